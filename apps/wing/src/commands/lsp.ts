@@ -99,8 +99,34 @@ export async function lsp() {
         codeActionProvider: true,
         hoverProvider: true,
         documentSymbolProvider: true,
+        documentFormattingProvider: true,
         definitionProvider: true,
+        referencesProvider: true,
+        documentHighlightProvider: true,
+        inlayHintProvider: true,
         renameProvider: { prepareProvider: true },
+        semanticTokensProvider: {
+          // must match the legend in libs/wingc/src/lsp/semantic_tokens.rs
+          legend: {
+            tokenTypes: [
+              "namespace",
+              "type",
+              "class",
+              "enum",
+              "interface",
+              "struct",
+              "parameter",
+              "variable",
+              "property",
+              "enumMember",
+              "function",
+              "method",
+            ],
+            tokenModifiers: ["declaration", "static", "preflight", "inflight", "reassignable"],
+          },
+          full: true,
+          range: true,
+        },
       },
     };
     return result;
@@ -145,7 +171,7 @@ export async function lsp() {
         const diag = Diagnostic.create(
           Range.create(rd.span.start.line, rd.span.start.col, rd.span.end.line, rd.span.end.col),
           message,
-          undefined,
+          rd.severity === "warning" ? DiagnosticSeverity.Warning : DiagnosticSeverity.Error,
           undefined,
           undefined,
           rd.annotations.map((a) => ({
@@ -213,15 +239,33 @@ export async function lsp() {
   connection.onDefinition(async (params) => {
     return callWing("wingc_on_goto_definition", params);
   });
+  connection.onReferences(async (params) => {
+    return callWing("wingc_on_references", params);
+  });
+  connection.onDocumentHighlight(async (params) => {
+    return callWing("wingc_on_document_highlight", params);
+  });
   connection.onDocumentSymbol(async (params) => {
     return callWing("wingc_on_document_symbol", params);
   });
+  connection.onDocumentFormatting(async (params) => {
+    return callWing("wingc_on_formatting", params);
+  });
   connection.onRenameRequest(async (params) => {
     return callWing("wingc_on_rename", params);
   });
   connection.onPrepareRename(async (params) => {
     return callWing("wingc_on_prepare_rename", params);
   });
+  connection.languages.semanticTokens.on(async (params) => {
+    return callWing("wingc_on_semantic_tokens", params);
+  });
+  connection.languages.semanticTokens.onRange(async (params) => {
+    return callWing("wingc_on_semantic_tokens_range", params);
+  });
+  connection.languages.inlayHint.on(async (params) => {
+    return callWing("wingc_on_inlay_hint", params);
+  });
   connection.onHover(async (params) => {
     return callWing("wingc_on_hover", params);
   });
@@ -4,15 +4,49 @@ use std::time::Duration;
 use termcolor::{BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
 
 pub(crate) fn print_compiling(text: &str) {
-	print_colourful_prefix("Compiling", text)
+	print_status("Compiling", text)
 }
 
 pub(crate) fn print_installing(text: &str) {
-	print_colourful_prefix("Installing", text)
+	print_status("Installing", text)
+}
+
+pub(crate) fn print_formatted(text: &str) {
+	print_colourful_prefix("Formatted", text)
+}
+
+pub(crate) fn print_unformatted(text: &str) {
+	print_colourful_prefix("Unformatted", text)
 }
 
 pub(crate) fn print_compiled(duration: Duration) {
-	print_colourful_prefix("Compiled", &format!("in {}", seconds(duration)))
+	print_status("Compiled", &format!("in {}", seconds(duration)))
+}
+
+pub(crate) fn print_watching(count: usize) {
+	print_status("Watching", &format!("{} file(s) for changes", count))
+}
+
+pub(crate) fn print_error(error: &str) {
+	let stderr = stderr_buffer_writer();
+	let mut buffer = stderr.buffer();
+	writeln!(buffer, "Error: {}", error).expect("Error writing");
+	stderr.print(&buffer).expect("Final result error writing");
+}
+
+pub(crate) fn print_test_passed(text: &str) {
+	print_prefix(stdout_buffer_writer(), "pass", Color::Green, text)
+}
+
+pub(crate) fn print_test_failed(text: &str) {
+	print_prefix(stdout_buffer_writer(), "fail", Color::Red, text)
+}
+
+pub(crate) fn print_tested(passed: usize, failed: usize, duration: Duration) {
+	print_colourful_prefix(
+		"Tested",
+		&format!("{} passed, {} failed in {}", passed, failed, seconds(duration)),
+	)
 }
 
 pub fn seconds(duration: Duration) -> String {
@@ -20,10 +54,18 @@ pub fn seconds(duration: Duration) -> String {
 }
 
 pub fn print_colourful_prefix(prefix: &str, text: &str) {
-	let buffer_writer = stdout_buffer_writer();
+	print_prefix(stdout_buffer_writer(), prefix, Color::Cyan, text)
+}
+
+/// Progress messages go to standard error (like cargo does), so standard output only has the command's results
+fn print_status(prefix: &str, text: &str) {
+	print_prefix(stderr_buffer_writer(), prefix, Color::Cyan, text)
+}
+
+fn print_prefix(buffer_writer: BufferWriter, prefix: &str, color: Color, text: &str) {
 	let mut buffer = buffer_writer.buffer();
 	buffer
-		.set_color(ColorSpec::new().set_intense(true).set_fg(Some(Color::Cyan)))
+		.set_color(ColorSpec::new().set_intense(true).set_fg(Some(color)))
 		.expect("print_cyan_prefix");
 	write!(buffer, "{prefix: >11}").expect("print_cyan_prefix");
 	buffer.set_color(&ColorSpec::new()).expect("print_cyan_prefix");
@@ -0,0 +1,395 @@
+use std::{collections::HashMap, io};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use termcolor::{Color, ColorSpec, WriteColor};
+use wingc::diagnostic::{Diagnostic, DiagnosticSeverity, WingSpan};
+
+use crate::cli::stderr_buffer_writer;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Lines of a multi-line span that are shown before and after the elided part of the span
+const CONTEXT_LINES: usize = 2;
+
+/// How compiler diagnostics are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DiagnosticsFormat {
+	/// Source snippets for humans, printed to standard error
+	Human,
+	/// A JSON array of diagnostics, printed to standard output
+	Json,
+	/// A SARIF 2.1.0 log for code scanning tools, printed to standard output
+	Sarif,
+}
+
+pub fn print_diagnostics(diagnostics: &[Diagnostic], format: DiagnosticsFormat) -> io::Result<()> {
+	match format {
+		DiagnosticsFormat::Human => {
+			let writer = stderr_buffer_writer();
+			let mut buffer = writer.buffer();
+			let mut sources = Sources::default();
+			for diagnostic in diagnostics {
+				write_human(&mut buffer, diagnostic, &mut sources)?;
+			}
+			writer.print(&buffer)
+		}
+		DiagnosticsFormat::Json => {
+			println!("{}", serde_json::to_string_pretty(diagnostics)?);
+			Ok(())
+		}
+		DiagnosticsFormat::Sarif => {
+			println!("{}", serde_json::to_string_pretty(&sarif_log(diagnostics))?);
+			Ok(())
+		}
+	}
+}
+
+/// Source files read while rendering diagnostics (`None` for files that couldn't be read)
+#[derive(Default)]
+struct Sources(HashMap<String, Option<String>>);
+
+impl Sources {
+	fn get(&mut self, file_id: &str) -> Option<&str> {
+		self
+			.0
+			.entry(file_id.to_string())
+			.or_insert_with(|| std::fs::read_to_string(file_id).ok())
+			.as_deref()
+	}
+}
+
+/// A span of source code to underline: the diagnostic's own span or one of its annotations
+struct Label<'a> {
+	span: &'a WingSpan,
+	message: &'a str,
+	primary: bool,
+}
+
+/// Writes a diagnostic like rustc does: the message, followed by a snippet of the source for each span
+/// (with the diagnostic's span underlined by `^` and its annotations by `-`) and the hints
+fn write_human(out: &mut impl WriteColor, diagnostic: &Diagnostic, sources: &mut Sources) -> io::Result<()> {
+	let (severity, color) = match diagnostic.severity {
+		DiagnosticSeverity::Error => ("error", Color::Red),
+		DiagnosticSeverity::Warning => ("warning", Color::Yellow),
+	};
+	out.set_color(ColorSpec::new().set_bold(true).set_fg(Some(color)))?;
+	write!(out, "{}", severity)?;
+	out.set_color(ColorSpec::new().set_bold(true))?;
+	writeln!(out, ": {}", diagnostic.message)?;
+	out.reset()?;
+
+	// Spans of code generated by the compiler don't have a file
+	let labels = diagnostic
+		.span
+		.iter()
+		.map(|span| Label {
+			span,
+			message: "",
+			primary: true,
+		})
+		.chain(diagnostic.annotations.iter().map(|annotation| Label {
+			span: &annotation.span,
+			message: &annotation.message,
+			primary: false,
+		}))
+		.filter(|label| !label.span.file_id.is_empty())
+		.collect::<Vec<_>>();
+	let width = labels
+		.iter()
+		.map(|label| (label.span.end.line + 1).to_string().len())
+		.max()
+		.unwrap_or(1);
+
+	for (i, label) in labels.iter().enumerate() {
+		let arrow = if i == 0 { "-->" } else { ":::" };
+		let marker_color = if label.primary { color } else { Color::Blue };
+		write_label(out, label, width, arrow, marker_color, sources)?;
+	}
+	if !labels.is_empty() && !diagnostic.hints.is_empty() {
+		write_margin(out, width, None, "|")?;
+		writeln!(out)?;
+	}
+	for hint in &diagnostic.hints {
+		write_margin(out, width, None, "=")?;
+		writeln!(out, " hint: {}", hint)?;
+	}
+	writeln!(out)
+}
+
+fn write_label(
+	out: &mut impl WriteColor,
+	label: &Label,
+	width: usize,
+	arrow: &str,
+	marker_color: Color,
+	sources: &mut Sources,
+) -> io::Result<()> {
+	let span = label.span;
+	out.set_color(ColorSpec::new().set_bold(true).set_fg(Some(Color::Blue)))?;
+	write!(out, "{:width$}{} ", "", arrow)?;
+	out.reset()?;
+	writeln!(
+		out,
+		"{}:{}:{}",
+		display_path(&span.file_id),
+		span.start.line + 1,
+		span.start.col + 1
+	)?;
+
+	let Some(source) = sources.get(&span.file_id) else {
+		if !label.message.is_empty() {
+			write_margin(out, width, None, "=")?;
+			writeln!(out, " {}", label.message)?;
+		}
+		return Ok(());
+	};
+	let lines = source.lines().collect::<Vec<_>>();
+
+	let first = span.start.line as usize;
+	let mut last = span.end.line as usize;
+	let mut end_col = span.end.col as usize;
+	// A span that ends at the start of a line doesn't cover anything on that line
+	if last > first && end_col == 0 {
+		last -= 1;
+		end_col = lines.get(last).map_or(0, |line| line.len());
+	}
+
+	write_margin(out, width, None, "|")?;
+	writeln!(out)?;
+	for line_index in first..=last {
+		let Some(line) = lines.get(line_index) else {
+			break;
+		};
+		// Only show the start and the end of long spans
+		if line_index >= first + CONTEXT_LINES && line_index + CONTEXT_LINES <= last {
+			if line_index == first + CONTEXT_LINES {
+				write_margin(out, width, None, "...")?;
+				writeln!(out)?;
+			}
+			continue;
+		}
+
+		write_margin(out, width, Some(line_index + 1), "|")?;
+		writeln!(out, " {}", line)?;
+
+		let start = if line_index == first {
+			span.start.col as usize
+		} else {
+			0
+		};
+		let end = if line_index == last { end_col } else { line.len() };
+		// Keep tabs so the markers line up with the code
+		let indent = line
+			.get(..start)
+			.unwrap_or("")
+			.chars()
+			.map(|c| if c == '\t' { '\t' } else { ' ' })
+			.collect::<String>();
+		let length = line.get(start..end).map_or(1, |text| text.chars().count().max(1));
+		let marker = if label.primary { "^" } else { "-" };
+
+		write_margin(out, width, None, "|")?;
+		write!(out, " {}", indent)?;
+		out.set_color(ColorSpec::new().set_bold(true).set_fg(Some(marker_color)))?;
+		write!(out, "{}", marker.repeat(length))?;
+		if line_index == last && !label.message.is_empty() {
+			write!(out, " {}", label.message)?;
+		}
+		out.reset()?;
+		writeln!(out)?;
+	}
+	Ok(())
+}
+
+/// Writes the left margin of a snippet, e.g. "12 |" or "   |"
+fn write_margin(out: &mut impl WriteColor, width: usize, line: Option<usize>, separator: &str) -> io::Result<()> {
+	out.set_color(ColorSpec::new().set_bold(true).set_fg(Some(Color::Blue)))?;
+	match line {
+		Some(line) => write!(out, "{:>width$} {}", line, separator)?,
+		None => write!(out, "{:width$} {}", "", separator)?,
+	}
+	out.reset()
+}
+
+/// Path of a source file relative to the current directory (if it's inside it)
+fn display_path(file_id: &str) -> String {
+	let path = Utf8Path::new(file_id);
+	std::env::current_dir()
+		.ok()
+		.and_then(|cwd| Utf8PathBuf::from_path_buf(cwd).ok())
+		.and_then(|cwd| path.strip_prefix(cwd).ok().map(|path| path.to_string()))
+		.unwrap_or_else(|| file_id.to_string())
+}
+
+fn sarif_log(diagnostics: &[Diagnostic]) -> Value {
+	let results = diagnostics
+		.iter()
+		.map(|diagnostic| {
+			let mut text = diagnostic.message.clone();
+			for hint in &diagnostic.hints {
+				text.push_str(&format!("\nhint: {}", hint));
+			}
+			let level = match diagnostic.severity {
+				DiagnosticSeverity::Error => "error",
+				DiagnosticSeverity::Warning => "warning",
+			};
+
+			let mut result = json!({
+				"level": level,
+				"message": { "text": text },
+			});
+			if let Some(span) = diagnostic.span.as_ref().filter(|span| !span.file_id.is_empty()) {
+				result["locations"] = json!([sarif_location(span)]);
+			}
+			let related_locations = diagnostic
+				.annotations
+				.iter()
+				.filter(|annotation| !annotation.span.file_id.is_empty())
+				.map(|annotation| {
+					let mut location = sarif_location(&annotation.span);
+					location["message"] = json!({ "text": annotation.message });
+					location
+				})
+				.collect::<Vec<_>>();
+			if !related_locations.is_empty() {
+				result["relatedLocations"] = Value::Array(related_locations);
+			}
+			result
+		})
+		.collect::<Vec<_>>();
+
+	json!({
+		"$schema": SARIF_SCHEMA,
+		"version": "2.1.0",
+		"runs": [{
+			"tool": {
+				"driver": {
+					"name": "wing",
+					"version": env!("CARGO_PKG_VERSION"),
+					"informationUri": "https://www.winglang.io",
+				},
+			},
+			"results": results,
+		}],
+	})
+}
+
+fn sarif_location(span: &WingSpan) -> Value {
+	json!({
+		"physicalLocation": {
+			"artifactLocation": { "uri": display_path(&span.file_id).replace('\\', "/") },
+			"region": {
+				"startLine": span.start.line + 1,
+				"startColumn": span.start.col + 1,
+				"endLine": span.end.line + 1,
+				"endColumn": span.end.col + 1,
+			},
+		},
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use termcolor::Buffer;
+	use wingc::diagnostic::{DiagnosticAnnotation, WingLocation};
+
+	use super::*;
+
+	const SOURCE: &str = "let y = 1;\nlet x: num = \"hello\";\n";
+
+	fn span(start: (u32, u32), end: (u32, u32)) -> WingSpan {
+		WingSpan {
+			start: WingLocation {
+				line: start.0,
+				col: start.1,
+			},
+			end: WingLocation {
+				line: end.0,
+				col: end.1,
+			},
+			file_id: "/project/main.w".to_string(),
+			start_offset: 0,
+			end_offset: 0,
+		}
+	}
+
+	fn diagnostic() -> Diagnostic {
+		Diagnostic {
+			message: "Expected type to be \"num\", but got \"str\" instead".to_string(),
+			span: Some(span((1, 13), (1, 20))),
+			annotations: vec![DiagnosticAnnotation {
+				message: "defined here".to_string(),
+				span: span((0, 4), (0, 5)),
+			}],
+			hints: vec!["convert it with num.fromStr()".to_string()],
+			severity: DiagnosticSeverity::Error,
+		}
+	}
+
+	#[test]
+	fn test_human_format() {
+		let mut sources = Sources::default();
+		sources
+			.0
+			.insert("/project/main.w".to_string(), Some(SOURCE.to_string()));
+		let mut buffer = Buffer::no_color();
+		write_human(&mut buffer, &diagnostic(), &mut sources).unwrap();
+
+		assert_eq!(
+			String::from_utf8(buffer.into_inner()).unwrap(),
+			r#"error: Expected type to be "num", but got "str" instead
+ --> /project/main.w:2:14
+  |
+2 | let x: num = "hello";
+  |              ^^^^^^^
+ ::: /project/main.w:1:5
+  |
+1 | let y = 1;
+  |     - defined here
+  |
+  = hint: convert it with num.fromStr()
+
+"#
+		);
+	}
+
+	#[test]
+	fn test_human_format_without_source() {
+		let mut sources = Sources::default();
+		sources.0.insert("/project/main.w".to_string(), None);
+		let mut buffer = Buffer::no_color();
+		write_human(&mut buffer, &diagnostic(), &mut sources).unwrap();
+
+		assert_eq!(
+			String::from_utf8(buffer.into_inner()).unwrap(),
+			r#"error: Expected type to be "num", but got "str" instead
+ --> /project/main.w:2:14
+ ::: /project/main.w:1:5
+  = defined here
+  |
+  = hint: convert it with num.fromStr()
+
+"#
+		);
+	}
+
+	#[test]
+	fn test_sarif_format() {
+		let log = sarif_log(&[diagnostic()]);
+		let result = &log["runs"][0]["results"][0];
+
+		assert_eq!(log["version"], "2.1.0");
+		assert_eq!(result["level"], "error");
+		assert_eq!(
+			result["message"]["text"],
+			"Expected type to be \"num\", but got \"str\" instead\nhint: convert it with num.fromStr()"
+		);
+		assert_eq!(
+			result["locations"][0]["physicalLocation"]["region"],
+			json!({ "startLine": 2, "startColumn": 14, "endLine": 2, "endColumn": 21 })
+		);
+		assert_eq!(result["relatedLocations"][0]["message"]["text"], "defined here");
+	}
+}
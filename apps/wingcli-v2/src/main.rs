@@ -1,14 +1,35 @@
 mod cli;
-
-use std::{error::Error, io::Write, path::PathBuf, time::Instant};
+mod diagnostics;
+mod platform;
+mod testing;
+mod watch;
+
+use std::{
+	error::Error,
+	io::Write,
+	path::PathBuf,
+	time::{Instant, SystemTime},
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
-use cli::{print_compiled, print_compiling, print_installing};
+use cli::{
+	print_compiled, print_compiling, print_error, print_formatted, print_installing, print_test_failed, print_tested,
+	print_unformatted, print_watching,
+};
+use diagnostics::{print_diagnostics, DiagnosticsFormat};
 use home::home_dir;
 use lazy_static::lazy_static;
-use strum::{Display, EnumString};
-use wingc::compile;
+use platform::{resolve_platforms, target_dir_name, Platform};
+use testing::{print_test_results, run_tests};
+use watch::wait_for_changes;
+use wingc::{
+	compile_incremental,
+	diagnostic::{get_diagnostics, reset_diagnostics},
+	format::format_source,
+	parser::is_test_file,
+};
+use wingii::type_system::TypeSystem;
 
 lazy_static! {
 	static ref HOME_PATH: PathBuf = home_dir().expect("Could not find home directory");
@@ -24,34 +45,68 @@ enum Command {
 		/// Source file or directory to compile
 		file: Utf8PathBuf,
 
-		/// The platform to target
+		/// Platforms to target: builtin platform names, scoped npm packages or paths to platform modules.
+		/// Can be repeated to stack platforms (defaults to "sim")
+		#[clap(short = 't', long, alias = "target")]
+		platform: Vec<Platform>,
+
+		/// Recompile whenever a source file changes
 		#[clap(short, long)]
-		target: Option<Target>,
+		watch: bool,
+
+		/// How to print errors and warnings
+		#[clap(long, value_enum, default_value = "human")]
+		diagnostics_format: DiagnosticsFormat,
+
+		/// Compile every file, instead of reusing the results of files that didn't change since the last compilation
+		#[clap(long)]
+		no_cache: bool,
 	},
-}
+	/// Compile and run Wing tests in the simulator
+	Test {
+		/// Test files or directories to search for `.test.w` files (defaults to the current directory)
+		files: Vec<Utf8PathBuf>,
 
-#[derive(Parser, Debug, EnumString, Display, Clone, Copy)]
-enum Target {
-	/// Local simulator
-	#[strum(serialize = "sim")]
-	Sim,
-	/// AWS with Terraform
-	#[strum(serialize = "tf-aws")]
-	TfAws,
-}
+		/// Platforms to target, the first one must be "sim". Can be repeated to stack platforms (defaults to "sim")
+		#[clap(short = 't', long, alias = "target")]
+		platform: Vec<Platform>,
 
-fn as_dir_prefix(target: &Target) -> &'static str {
-	match target {
-		Target::Sim => "sim",
-		Target::TfAws => "tfaws",
-	}
+		/// Only run tests whose name contains this text
+		#[clap(short, long)]
+		filter: Option<String>,
+
+		/// Compile every file, instead of reusing the results of files that didn't change since the last compilation
+		#[clap(long)]
+		no_cache: bool,
+	},
+	/// Format Wing source files
+	Fmt {
+		/// Files or directories to format (defaults to the current directory)
+		files: Vec<Utf8PathBuf>,
+
+		/// Only check that the files are formatted, without changing them
+		#[clap(long)]
+		check: bool,
+	},
 }
 
 fn main() {
 	initialize_logger();
-	let stderr = cli::stderr_buffer_writer();
 	let result = match Command::parse() {
-		Command::Compile { file, target } => command_build(file, target),
+		Command::Compile {
+			file,
+			platform,
+			watch,
+			diagnostics_format,
+			no_cache,
+		} => command_build(file, platform, watch, diagnostics_format, no_cache),
+		Command::Test {
+			files,
+			platform,
+			filter,
+			no_cache,
+		} => command_test(files, platform, filter, no_cache),
+		Command::Fmt { files, check } => command_fmt(files, check),
 	};
 
 	match result {
@@ -60,26 +115,191 @@ fn main() {
 		}
 		Err(error) => {
 			tracing::error!(error = ?error, "Failed");
-			let mut buffer = stderr.buffer();
-			writeln!(buffer, "Error: {}", error).expect("Error writing");
-			stderr.print(&buffer).expect("Final result error writing");
+			print_error(&error.to_string());
 			std::process::exit(1);
 		}
 	}
 }
 
-fn command_build(source_file: Utf8PathBuf, target: Option<Target>) -> Result<(), Box<dyn Error>> {
+fn command_build(
+	source_file: Utf8PathBuf,
+	platforms: Vec<Platform>,
+	watch: bool,
+	diagnostics_format: DiagnosticsFormat,
+	no_cache: bool,
+) -> Result<(), Box<dyn Error>> {
+	let platforms = if platforms.is_empty() {
+		vec![Platform::sim()]
+	} else {
+		platforms
+	};
+
+	let project_dir = Utf8PathBuf::from_path_buf(std::env::current_dir()?).expect("invalid utf8");
+	let target_dir = project_dir
+		.join("target")
+		.join(target_dir_name(&source_file, &platforms));
+
+	let options = CompileOptions {
+		project_dir: &project_dir,
+		platforms: &platforms,
+		target_dir: &target_dir,
+		is_test: false,
+		diagnostics_format,
+		use_cache: !no_cache,
+	};
+
+	// JSII assemblies are only loaded once, and reused by every compilation in watch mode
+	let mut jsii_types = TypeSystem::new();
+	loop {
+		let start = Instant::now();
+		let compile_time = SystemTime::now();
+		let mut source_paths = vec![];
+		let result = compile_and_synth(&source_file, &options, &mut jsii_types, &mut source_paths);
+		if !watch {
+			result?;
+			print_compiled(start.elapsed());
+			return Ok(());
+		}
+
+		match result {
+			Ok(()) => print_compiled(start.elapsed()),
+			Err(error) => print_error(&error.to_string()),
+		}
+		// Errors from this compilation shouldn't fail the next one
+		reset_diagnostics();
+
+		if source_paths.is_empty() {
+			source_paths.push(source_file.clone());
+		}
+		print_watching(source_paths.len());
+		wait_for_changes(&source_paths, compile_time);
+	}
+}
+
+fn command_test(
+	paths: Vec<Utf8PathBuf>,
+	platforms: Vec<Platform>,
+	filter: Option<String>,
+	no_cache: bool,
+) -> Result<(), Box<dyn Error>> {
 	let start = Instant::now();
-	let target = target.unwrap_or(Target::Sim);
+	let platforms = if platforms.is_empty() {
+		vec![Platform::sim()]
+	} else {
+		platforms
+	};
+	if platforms[0] != Platform::sim() {
+		return Err("Tests can only run on the \"sim\" platform".into());
+	}
+
+	let paths = if paths.is_empty() {
+		vec![Utf8PathBuf::from(".")]
+	} else {
+		paths
+	};
+
+	let mut files = vec![];
+	for path in &paths {
+		collect_wing_files(path, &mut files)?;
+	}
+	files.retain(|file| is_test_file(file));
+	if files.is_empty() {
+		return Err("No test files found".into());
+	}
 
 	let project_dir = Utf8PathBuf::from_path_buf(std::env::current_dir()?).expect("invalid utf8");
-	let target_dir = project_dir.join("target").join(format!(
-		"{}.{}",
-		source_file.file_stem().unwrap_or("main"),
-		as_dir_prefix(&target),
-	));
+	let mut jsii_types = TypeSystem::new();
+	let mut passed = 0;
+	let mut failed = 0;
+	let mut failed_files = 0;
+	for file in &files {
+		// Errors from the previous file shouldn't fail this one
+		reset_diagnostics();
+
+		let target_dir = project_dir
+			.join("target")
+			.join("test")
+			.join(target_dir_name(file, &platforms));
+		let options = CompileOptions {
+			project_dir: &project_dir,
+			platforms: &platforms,
+			target_dir: &target_dir,
+			is_test: true,
+			diagnostics_format: DiagnosticsFormat::Human,
+			use_cache: !no_cache,
+		};
+		let result = compile_and_synth(file, &options, &mut jsii_types, &mut vec![])
+			.and_then(|_| run_tests(node_command(), &target_dir, filter.as_deref()));
+
+		// A file that can't be compiled or run fails, but the remaining files are still tested
+		let results = match result {
+			Ok(results) => results,
+			Err(error) => {
+				print_error(&format!("{}: {}", file, error));
+				print_test_failed(file.as_str());
+				failed_files += 1;
+				continue;
+			}
+		};
+		let (file_passed, file_failed) = print_test_results(file, &results);
+		passed += file_passed;
+		failed += file_failed;
+	}
+
+	print_tested(passed, failed, start.elapsed());
+	if failed_files > 0 {
+		return Err(
+			format!(
+				"{} test(s) failed and {} file(s) could not be tested",
+				failed, failed_files
+			)
+			.into(),
+		);
+	}
+	if failed > 0 {
+		return Err(format!("{} test(s) failed", failed).into());
+	}
+	if let Some(filter) = filter.filter(|_| passed == 0) {
+		return Err(format!("No tests matched the filter \"{}\"", filter).into());
+	}
+	Ok(())
+}
+
+/// Where and how `compile_and_synth` builds an app
+struct CompileOptions<'a> {
+	project_dir: &'a Utf8Path,
+	/// Platforms the app is synthesized for
+	platforms: &'a [Platform],
+	/// Directory the app is synthesized into (compiled code goes in its `.wing` subdirectory)
+	target_dir: &'a Utf8Path,
+	/// Whether the app is synthesized for running its tests
+	is_test: bool,
+	diagnostics_format: DiagnosticsFormat,
+	/// Whether to reuse (and update) the results of compiling files that didn't change
+	use_cache: bool,
+}
+
+/// Compiles `source_file` and runs its preflight code, synthesizing the app for the `options` platforms into their
+/// target directory. The files and directories the app was compiled from are collected into `source_paths`.
+fn compile_and_synth(
+	source_file: &Utf8Path,
+	options: &CompileOptions,
+	jsii_types: &mut TypeSystem,
+	source_paths: &mut Vec<Utf8PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+	let CompileOptions {
+		project_dir,
+		platforms,
+		target_dir,
+		is_test,
+		diagnostics_format,
+		use_cache,
+	} = *options;
 	let work_dir = target_dir.join(".wing");
 
+	// Fail early if a platform can't be loaded by the preflight code
+	let wing_platforms = resolve_platforms(platforms, project_dir)?;
+
 	// Print that work is being done
 	print_compiling(source_file.as_str());
 
@@ -97,7 +317,16 @@ fn command_build(source_file: Utf8PathBuf, target: Option<Target>) -> Result<(),
 		std::env::set_var("WINGSDK_MANIFEST_ROOT", &sdk_root);
 	}
 
-	let result = compile(&project_dir, &source_file, None, &work_dir);
+	let result = compile_incremental(
+		project_dir,
+		source_file,
+		None,
+		&work_dir,
+		jsii_types,
+		source_paths,
+		use_cache,
+	);
+	print_diagnostics(&get_diagnostics(), diagnostics_format)?;
 
 	match result {
 		Ok(_) => {}
@@ -107,9 +336,79 @@ fn command_build(source_file: Utf8PathBuf, target: Option<Target>) -> Result<(),
 		}
 	}
 
-	run_javascript_node(&source_file, &target_dir, target)?;
+	run_javascript_node(source_file, target_dir, &wing_platforms, is_test)
+}
+
+fn command_fmt(paths: Vec<Utf8PathBuf>, check: bool) -> Result<(), Box<dyn Error>> {
+	let paths = if paths.is_empty() {
+		vec![Utf8PathBuf::from(".")]
+	} else {
+		paths
+	};
+
+	let mut files = vec![];
+	for path in &paths {
+		collect_wing_files(path, &mut files)?;
+	}
 
-	print_compiled(start.elapsed());
+	let mut unformatted = 0;
+	let mut failed = 0;
+	for file in &files {
+		let source = std::fs::read_to_string(file)?;
+		let formatted = match format_source(&source) {
+			Ok(formatted) => formatted,
+			Err(error) => {
+				tracing::error!(file = %file, error = %error, "Failed to format");
+				let stderr = cli::stderr_buffer_writer();
+				let mut buffer = stderr.buffer();
+				writeln!(buffer, "Error: {}: {}", file, error)?;
+				stderr.print(&buffer)?;
+				failed += 1;
+				continue;
+			}
+		};
+		if formatted == source {
+			continue;
+		}
+		if check {
+			print_unformatted(file.as_str());
+			unformatted += 1;
+		} else {
+			std::fs::write(file, formatted)?;
+			print_formatted(file.as_str());
+		}
+	}
+
+	if failed > 0 {
+		return Err(format!("{} file(s) could not be formatted", failed).into());
+	}
+	if unformatted > 0 {
+		return Err(format!("{} file(s) are not formatted", unformatted).into());
+	}
+	Ok(())
+}
+
+/// Finds all Wing files in `path` (recursively), skipping dependencies, build outputs and hidden directories
+fn collect_wing_files(path: &Utf8Path, files: &mut Vec<Utf8PathBuf>) -> Result<(), Box<dyn Error>> {
+	if path.is_file() {
+		files.push(path.to_path_buf());
+		return Ok(());
+	}
+
+	let mut entries = path.read_dir_utf8()?.collect::<Result<Vec<_>, _>>()?;
+	entries.sort_by(|a, b| a.file_name().cmp(b.file_name()));
+	for entry in entries {
+		let entry_path = entry.path();
+		let name = entry.file_name();
+		if entry_path.is_dir() {
+			if name.starts_with('.') || name == "node_modules" || name == "target" {
+				continue;
+			}
+			collect_wing_files(entry_path, files)?;
+		} else if entry_path.extension() == Some("w") {
+			files.push(entry_path.to_path_buf());
+		}
+	}
 	Ok(())
 }
 
@@ -141,12 +440,33 @@ fn install_sdk() -> Result<(), Box<dyn Error>> {
 	Ok(())
 }
 
-fn run_javascript_node(source_file: &Utf8Path, target_dir: &Utf8Path, target: Target) -> Result<(), Box<dyn Error>> {
+fn run_javascript_node(
+	source_file: &Utf8Path,
+	target_dir: &Utf8Path,
+	wing_platforms: &str,
+	is_test: bool,
+) -> Result<(), Box<dyn Error>> {
 	let source_file_canonical = source_file.canonicalize_utf8()?;
 	let source_dir = source_file_canonical.parent().expect("source file has no parent");
 
-	let mut command = std::process::Command::new("node");
+	let mut command = node_command();
 	command.arg(target_dir.join(".wing").join("preflight.cjs"));
+	command.env("WING_PLATFORMS", wing_platforms);
+	command.env("WING_SOURCE_DIR", source_dir);
+	command.env("WING_SYNTH_DIR", target_dir);
+	command.env("WING_IS_TEST", is_test.to_string());
+
+	tracing::info!("Running command: {:?}", command);
+	let status = command.status()?;
+	if !status.success() {
+		return Err("Node.js failed".into());
+	}
+	Ok(())
+}
+
+/// A `node` command that can load the SDK
+fn node_command() -> std::process::Command {
+	let mut command = std::process::Command::new("node");
 
 	let mut node_path = WING_CACHE_DIR.join("node_modules").to_string();
 
@@ -160,16 +480,7 @@ fn run_javascript_node(source_file: &Utf8Path, target_dir: &Utf8Path, target: Ta
 	}
 
 	command.env("NODE_PATH", node_path);
-	command.env("WING_PLATFORMS", target.to_string());
-	command.env("WING_SOURCE_DIR", source_dir);
-	command.env("WING_SYNTH_DIR", target_dir);
-
-	tracing::info!("Running command: {:?}", command);
-	let status = command.status()?;
-	if !status.success() {
-		return Err("Node.js failed".into());
-	}
-	Ok(())
+	command
 }
 
 fn initialize_logger() {
@@ -226,14 +537,93 @@ mod test {
 	#[test]
 	fn test_compile_sim() {
 		initialize();
-		let res = command_build("../../examples/tests/valid/hello.test.w".into(), Some(Target::Sim));
+		let res = command_build(
+			"../../examples/tests/valid/hello.test.w".into(),
+			vec![Platform::sim()],
+			false,
+			DiagnosticsFormat::Human,
+			false,
+		);
 		res.expect("Failed to compile to sim");
 	}
 
 	#[test]
 	fn test_compile_tfaws() {
 		initialize();
-		let res = command_build("../../examples/tests/valid/hello.test.w".into(), Some(Target::TfAws));
+		let res = command_build(
+			"../../examples/tests/valid/hello.test.w".into(),
+			vec!["tf-aws".into()],
+			false,
+			DiagnosticsFormat::Human,
+			false,
+		);
 		res.expect("Failed to compile to tf-aws");
 	}
+
+	#[test]
+	fn test_test() {
+		initialize();
+		let res = command_test(
+			vec!["../../examples/tests/valid/assert.test.w".into()],
+			vec![],
+			None,
+			false,
+		);
+		res.expect("Failed to run tests");
+	}
+
+	#[test]
+	fn test_test_continues_after_compile_error() {
+		initialize();
+		let res = command_test(
+			vec![
+				"../../examples/tests/invalid/void_in_expression_position.test.w".into(),
+				"../../examples/tests/valid/assert.test.w".into(),
+			],
+			vec![],
+			None,
+			false,
+		);
+		assert_eq!(
+			res.expect_err("Expected a file to fail").to_string(),
+			"0 test(s) failed and 1 file(s) could not be tested"
+		);
+	}
+
+	#[test]
+	fn test_test_filter() {
+		initialize();
+		let res = command_test(
+			vec!["../../examples/tests/valid/assert.test.w".into()],
+			vec![],
+			Some("does not exist".to_string()),
+			false,
+		);
+		assert_eq!(
+			res.expect_err("Expected no tests to match").to_string(),
+			"No tests matched the filter \"does not exist\""
+		);
+	}
+
+	#[test]
+	fn test_fmt() {
+		let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir().join("wingcli-test-fmt")).expect("invalid utf8");
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(dir.join("node_modules")).unwrap();
+		std::fs::write(dir.join("main.w"), "let x=1;\nif x>0 {\nlog(\"{x}\");}").unwrap();
+		std::fs::write(dir.join("node_modules").join("dep.w"), "let y=1;").unwrap();
+
+		command_fmt(vec![dir.clone()], true).expect_err("Expected unformatted files");
+		command_fmt(vec![dir.clone()], false).expect("Failed to format");
+		command_fmt(vec![dir.clone()], true).expect("Expected formatted files");
+
+		assert_eq!(
+			std::fs::read_to_string(dir.join("main.w")).unwrap(),
+			"let x = 1;\nif x > 0 {\n  log(\"{x}\");\n}\n"
+		);
+		assert_eq!(
+			std::fs::read_to_string(dir.join("node_modules").join("dep.w")).unwrap(),
+			"let y=1;"
+		);
+	}
 }
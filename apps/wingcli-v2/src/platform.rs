@@ -0,0 +1,165 @@
+use std::error::Error;
+
+use camino::Utf8Path;
+
+use crate::WING_CACHE_DIR;
+
+/// Platforms that ship with the SDK
+const BUILTIN_PLATFORMS: [&str; 4] = ["sim", "tf-aws", "tf-azure", "tf-gcp"];
+
+/// Separator used by the SDK when reading stacked platforms from `WING_PLATFORMS`
+const PLATFORMS_SEPARATOR: &str = ";";
+
+/// Number of hex digits of the path hash appended to the directory name of a platform given as a path
+const DIR_NAME_HASH_LEN: usize = 8;
+
+/// A platform to compile for: the name of a builtin platform, a scoped npm package (`@scope/platform`)
+/// or a path to a platform module (a `.js` file or a directory with an `index.js`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform(String);
+
+impl From<String> for Platform {
+	fn from(name: String) -> Self {
+		Self(name)
+	}
+}
+
+impl From<&str> for Platform {
+	fn from(name: &str) -> Self {
+		Self(name.to_string())
+	}
+}
+
+impl Platform {
+	pub fn sim() -> Self {
+		Self::from("sim")
+	}
+
+	fn is_builtin(&self) -> bool {
+		BUILTIN_PLATFORMS.contains(&self.0.as_str())
+	}
+
+	fn is_package(&self) -> bool {
+		self.0.starts_with('@')
+	}
+
+	/// Short name used in output directories, e.g. "tfaws" for "tf-aws" or "custom1a2b3c4d" for "./platforms/custom.js".
+	/// Platforms given as paths get a short hash of the full path so files with the same stem don't share a directory.
+	fn dir_name(&self) -> String {
+		let path = Utf8Path::new(&self.0);
+		let is_path = !self.is_builtin() && !self.is_package();
+		let name = if is_path { path.file_stem() } else { path.file_name() };
+		let mut name: String = name
+			.unwrap_or("platform")
+			.chars()
+			.filter(|c| c.is_ascii_alphanumeric())
+			.collect();
+		if is_path {
+			name.push_str(&blake3::hash(self.0.as_bytes()).to_hex()[..DIR_NAME_HASH_LEN]);
+		}
+		name
+	}
+
+	/// Checks that the platform's module exists and returns how the SDK should load it
+	fn resolve(&self, project_dir: &Utf8Path) -> Result<String, Box<dyn Error>> {
+		if self.is_builtin() {
+			return Ok(self.0.clone());
+		}
+
+		if self.is_package() {
+			let node_modules_dirs = [project_dir.join("node_modules"), WING_CACHE_DIR.join("node_modules")];
+			if node_modules_dirs.iter().any(|dir| dir.join(&self.0).is_dir()) {
+				return Ok(self.0.clone());
+			}
+			return Err(
+				format!(
+					"Unable to resolve platform \"{}\": package not found in {}",
+					self.0,
+					node_modules_dirs.map(|dir| dir.to_string()).join(" or ")
+				)
+				.into(),
+			);
+		}
+
+		let path = project_dir.join(&self.0);
+		let module = if path.extension() == Some("js") {
+			path.clone()
+		} else {
+			path.join("index.js")
+		};
+		if !module.is_file() {
+			return Err(format!("Unable to resolve platform \"{}\": {} does not exist", self.0, module).into());
+		}
+		Ok(path.to_string())
+	}
+}
+
+/// Name of the directory (relative to `target`) where `source_file` is compiled for `platforms`
+pub fn target_dir_name(source_file: &Utf8Path, platforms: &[Platform]) -> String {
+	let platforms = platforms.iter().map(Platform::dir_name).collect::<Vec<_>>().join("-");
+	format!("{}.{}", source_file.file_stem().unwrap_or("main"), platforms)
+}
+
+/// Resolves all platforms relative to `project_dir`, producing the value of the `WING_PLATFORMS` environment variable
+pub fn resolve_platforms(platforms: &[Platform], project_dir: &Utf8Path) -> Result<String, Box<dyn Error>> {
+	let resolved = platforms
+		.iter()
+		.map(|platform| platform.resolve(project_dir))
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok(resolved.join(PLATFORMS_SEPARATOR))
+}
+
+#[cfg(test)]
+mod test {
+	use camino::Utf8PathBuf;
+
+	use super::*;
+
+	#[test]
+	fn test_target_dir_name() {
+		let source_file = Utf8Path::new("examples/main.w");
+		assert_eq!(target_dir_name(source_file, &[Platform::sim()]), "main.sim");
+		assert_eq!(target_dir_name(source_file, &["tf-aws".into()]), "main.tfaws");
+		assert!(
+			target_dir_name(source_file, &["tf-aws".into(), "./platforms/custom.js".into()]).starts_with("main.tfaws-custom")
+		);
+		assert_eq!(
+			target_dir_name(source_file, &["sim".into(), "@acme/wing-platform".into()]),
+			"main.sim-wingplatform"
+		);
+	}
+
+	#[test]
+	fn test_target_dir_name_distinguishes_paths_with_the_same_stem() {
+		let source_file = Utf8Path::new("main.w");
+		let a = target_dir_name(source_file, &["./a/custom.js".into()]);
+		let b = target_dir_name(source_file, &["./b/custom.js".into()]);
+		assert_ne!(a, b);
+		assert_eq!(a, target_dir_name(source_file, &["./a/custom.js".into()]));
+		assert_eq!(a.len(), "main.custom".len() + DIR_NAME_HASH_LEN);
+	}
+
+	#[test]
+	fn test_resolve_platforms() {
+		let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir().join("wingcli-test-platforms")).expect("invalid utf8");
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(dir.join("node_modules").join("@acme").join("platform")).unwrap();
+		std::fs::create_dir_all(dir.join("local")).unwrap();
+		std::fs::write(dir.join("local").join("index.js"), "").unwrap();
+		std::fs::write(dir.join("custom.js"), "").unwrap();
+
+		let platforms: Vec<Platform> = vec![
+			"tf-aws".into(),
+			"@acme/platform".into(),
+			"local".into(),
+			"custom.js".into(),
+		];
+		assert_eq!(
+			resolve_platforms(&platforms, &dir).expect("Failed to resolve platforms"),
+			format!("tf-aws;@acme/platform;{};{}", dir.join("local"), dir.join("custom.js"))
+		);
+
+		resolve_platforms(&["missing.js".into()], &dir).expect_err("Expected missing platform file");
+		resolve_platforms(&["@acme/missing".into()], &dir).expect_err("Expected missing platform package");
+	}
+}
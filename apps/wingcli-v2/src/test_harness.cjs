@@ -0,0 +1,36 @@
+// Runs the tests of a simulator app and writes their results as JSON.
+// Usage: node test_harness.cjs <synth dir> <results file> [filter]
+const { writeFileSync } = require("fs");
+const { simulator } = require("@winglang/sdk");
+
+const [synthDir, resultsFile, filter] = process.argv.slice(1);
+
+// Test paths look like "root/env0/test:<name>"
+function testName(path) {
+  const part = path.split("/").find((p) => p.startsWith("test:"));
+  return part ? part.substring("test:".length) : path;
+}
+
+async function main() {
+  const s = new simulator.Simulator({ simfile: synthDir });
+  const tests = s
+    .tree()
+    .listTests()
+    .filter((t) => !filter || testName(t).includes(filter));
+
+  const results = [];
+  for (const t of tests) {
+    await s.start();
+    const testRunner = s.getResource("root/cloud.TestRunner");
+    results.push(await testRunner.runTest(t));
+    await s.stop();
+    await s.resetState();
+  }
+
+  writeFileSync(resultsFile, JSON.stringify(results));
+}
+
+main().catch((error) => {
+  console.error(error);
+  process.exit(1);
+});
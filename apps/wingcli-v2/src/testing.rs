@@ -0,0 +1,115 @@
+use std::{error::Error, process::Command};
+
+use camino::Utf8Path;
+use serde::Deserialize;
+
+use crate::cli::{print_test_failed, print_test_passed};
+
+/// Node script that runs the tests of a simulator app, see `run_tests`
+const TEST_HARNESS: &str = include_str!("test_harness.cjs");
+
+/// Result of a single test, as reported by the simulator's test runner
+#[derive(Debug, Deserialize)]
+pub struct TestResult {
+	path: String,
+	pass: bool,
+	error: Option<String>,
+	#[serde(default)]
+	traces: Vec<Trace>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Trace {
+	#[serde(rename = "type")]
+	kind: String,
+	data: TraceData,
+}
+
+#[derive(Debug, Deserialize)]
+struct TraceData {
+	message: Option<String>,
+}
+
+impl TestResult {
+	/// The name given to the test in the source code
+	fn name(&self) -> &str {
+		test_name(&self.path)
+	}
+}
+
+/// Extracts the test name from a test's construct path (`root/env0/test:<name>`)
+fn test_name(path: &str) -> &str {
+	path
+		.split('/')
+		.find_map(|part| part.strip_prefix("test:"))
+		.unwrap_or(path)
+}
+
+/// Runs the tests of the app synthesized to `synth_dir` in the simulator, using `node` (a command with
+/// the SDK in its `NODE_PATH`). Only tests whose name contains `filter` are run.
+pub fn run_tests(
+	mut node: Command,
+	synth_dir: &Utf8Path,
+	filter: Option<&str>,
+) -> Result<Vec<TestResult>, Box<dyn Error>> {
+	let results_file = synth_dir.join(".wing").join("test-results.json");
+	node.arg("-e").arg(TEST_HARNESS).arg(synth_dir).arg(&results_file);
+	if let Some(filter) = filter {
+		node.arg(filter);
+	}
+
+	tracing::info!("Running command: {:?}", node);
+	let status = node.status()?;
+	if !status.success() {
+		return Err("Failed to run tests".into());
+	}
+
+	let results = std::fs::read_to_string(&results_file)?;
+	Ok(serde_json::from_str(&results)?)
+}
+
+/// Prints the outcome, logs and errors of each test, returning the number of passed and failed tests
+pub fn print_test_results(source_file: &Utf8Path, results: &[TestResult]) -> (usize, usize) {
+	let mut failed = 0;
+	for result in results {
+		let name = format!("{} » {}", source_file, result.name());
+		if result.pass {
+			print_test_passed(&name);
+		} else {
+			print_test_failed(&name);
+			failed += 1;
+		}
+
+		let logs = result
+			.traces
+			.iter()
+			.filter(|trace| trace.kind == "log")
+			.filter_map(|trace| trace.data.message.as_deref());
+		for line in logs.chain(result.error.as_deref()).flat_map(str::lines) {
+			println!("{:>11} {}", "", line);
+		}
+	}
+	(results.len() - failed, failed)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_parse_results() {
+		let results: Vec<TestResult> = serde_json::from_str(
+			r#"[
+				{ "path": "root/env0/test:adds numbers", "pass": true, "traces": [{ "type": "log", "data": { "message": "1 + 1" } }] },
+				{ "path": "root/env1/test:main.w: fails", "pass": false, "error": "assertion failed", "traces": [] }
+			]"#,
+		)
+		.unwrap();
+
+		assert_eq!(results[0].name(), "adds numbers");
+		assert_eq!(results[0].traces[0].data.message.as_deref(), Some("1 + 1"));
+		assert_eq!(results[1].name(), "main.w: fails");
+		assert_eq!(results[1].error.as_deref(), Some("assertion failed"));
+		assert_eq!(print_test_results(Utf8Path::new("main.test.w"), &results), (1, 1));
+	}
+}
@@ -0,0 +1,58 @@
+use std::{thread::sleep, time::Duration, time::SystemTime};
+
+use camino::Utf8PathBuf;
+
+/// How often the watched paths are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Modification times of `paths` (`None` for paths that can't be read, e.g. because they were removed).
+/// The modification time of a directory changes when entries are added to it or removed from it.
+fn modified_times(paths: &[Utf8PathBuf]) -> Vec<Option<SystemTime>> {
+	paths
+		.iter()
+		.map(|path| path.metadata().and_then(|metadata| metadata.modified()).ok())
+		.collect()
+}
+
+/// Blocks until any of `paths` is modified, created or removed. Changes made after `since` (e.g. while
+/// the paths were being compiled) are picked up right away.
+pub fn wait_for_changes(paths: &[Utf8PathBuf], since: SystemTime) {
+	let initial = modified_times(paths);
+	if initial.iter().flatten().any(|modified| *modified > since) {
+		return;
+	}
+
+	loop {
+		sleep(POLL_INTERVAL);
+		if modified_times(paths) != initial {
+			return;
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_wait_for_changes() {
+		let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir().join("wingcli-test-watch")).expect("invalid utf8");
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(&dir).unwrap();
+		std::fs::write(dir.join("main.w"), "let x = 1;").unwrap();
+
+		// Files modified since the compilation started
+		let since = SystemTime::UNIX_EPOCH;
+		wait_for_changes(&[dir.join("main.w")], since);
+
+		// Files added to a watched directory
+		let since = SystemTime::now() + Duration::from_secs(60);
+		let new_file = dir.join("lib.w");
+		let writer = std::thread::spawn(move || {
+			sleep(POLL_INTERVAL * 2);
+			std::fs::write(new_file, "let y = 1;").unwrap();
+		});
+		wait_for_changes(&[dir.clone(), dir.join("main.w")], since);
+		writer.join().unwrap();
+	}
+}
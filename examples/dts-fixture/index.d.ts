@@ -0,0 +1,28 @@
+/** Options for creating a `Greeter` */
+export interface GreeterOptions {
+  readonly greeting: string;
+  readonly punctuation?: string;
+}
+
+export declare enum Shout {
+  YES = "yes",
+  NO = "no",
+}
+
+/** Greets people */
+export declare class Greeter {
+  constructor(options: GreeterOptions);
+  static withDefaults(): Greeter;
+  readonly greeting: string;
+  /** How many people were greeted */
+  count: number;
+  greet(name: string, shout?: Shout): string;
+  greetAll(...names: string[]): string[];
+}
+
+export declare function add(a: number, b: number): number;
+export declare function sum(values: number[]): number;
+export declare function toMap(keys: string[], value: number): Record<string, number>;
+export declare function parse(value: string): number | undefined;
+export declare function nextDay(date: Date): Date;
+export declare const VERSION: string;
@@ -0,0 +1,36 @@
+"use strict";
+
+const Shout = { YES: "yes", NO: "no" };
+
+class Greeter {
+  constructor(options) {
+    this.greeting = options.greeting;
+    this.punctuation = options.punctuation ?? "!";
+    this.count = 0;
+  }
+
+  static withDefaults() {
+    return new Greeter({ greeting: "Hello" });
+  }
+
+  greet(name, shout) {
+    this.count += 1;
+    const text = `${this.greeting}, ${name}${this.punctuation}`;
+    return shout === Shout.YES ? text.toUpperCase() : text;
+  }
+
+  greetAll(...names) {
+    return names.map((name) => this.greet(name));
+  }
+}
+
+const add = (a, b) => a + b;
+const sum = (values) => values.reduce((total, value) => total + value, 0);
+const toMap = (keys, value) => Object.fromEntries(keys.map((key) => [key, value]));
+const parse = (value) => {
+  const number = Number(value);
+  return Number.isNaN(number) ? undefined : number;
+};
+const nextDay = (date) => new Date(date.getTime() + 24 * 60 * 60 * 1000);
+
+module.exports = { Shout, Greeter, add, sum, toMap, parse, nextDay, VERSION: "1.0.0" };
@@ -38,6 +38,67 @@ export class JsiiClass {
   public methodWithStructParam(s: ClassWithInnerType.InnerStruct): string {
     return s.field;
   }
+
+  /** Set to a JS `Date` from Wing code */
+  public dateField?: Date;
+
+  public static nextDay(date: Date): Date {
+    assertDate(date);
+    return new Date(date.getTime() + 24 * 60 * 60 * 1000);
+  }
+
+  public methodWithDateStructParam(s: DateStruct): Date {
+    assertDate(s.date);
+    return s.date;
+  }
+
+  public static nextDays(dates: Date[]): Date[] {
+    return dates.map((date) => JsiiClass.nextDay(date));
+  }
+
+  public static nextDaysByKey(dates: {
+    [key: string]: Date;
+  }): { [key: string]: Date } {
+    const result: { [key: string]: Date } = {};
+    for (const [key, date] of Object.entries(dates)) {
+      result[key] = JsiiClass.nextDay(date);
+    }
+    return result;
+  }
+
+  public static applyDateClosure(date: Date, closure: IDateClosure): Date {
+    const result = closure(date);
+    assertDate(result);
+    return result;
+  }
+
+  public static callDateProvider(date: Date, provider: IDateProvider): Date {
+    const result = provider.nextDay(date);
+    assertDate(result);
+    return result;
+  }
+
+  public static describeValue(value: string | number | SomeStruct): string {
+    if (typeof value === "object") {
+      return `struct ${value.field}`;
+    }
+    return `${typeof value} ${value}`;
+  }
+
+  /** Like the list blocks of CDKTF providers, which also accept a token */
+  public static countStructs(structs: SomeStruct[] | ISomeInterface): number {
+    return Array.isArray(structs) ? structs.length : -1;
+  }
+
+  public static stringOrNumber(asString: boolean): string | number {
+    return asString ? "one" : 1;
+  }
+}
+
+function assertDate(date: Date) {
+  if (!(date instanceof Date)) {
+    throw new Error(`Expected a Date, got ${date}`);
+  }
 }
 
 export class JsiiClassWithPrivateConstructor {
@@ -58,10 +119,28 @@ export interface IFakeClosure {
   fn(x: number): number;
 }
 
+/**
+ * @callable
+ */
+export interface IDateClosure {
+  /** @internal */
+  (date: Date): Date;
+
+  fn(date: Date): Date;
+}
+
+export interface IDateProvider {
+  nextDay(date: Date): Date;
+}
+
 export interface SomeStruct {
   readonly field: string;
 }
 
+export interface DateStruct {
+  readonly date: Date;
+}
+
 export interface ISomeInterface {
   method(): void;
 }
@@ -1,7 +1,7 @@
 class Foo {
   instanceField: num;
 
-  pub static f: num;
+  pub static f: num = 0;
 
   pub static m() {
     this.instanceField = 1; // Can't access instance fields from static methods
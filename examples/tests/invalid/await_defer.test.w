@@ -0,0 +1,16 @@
+let f = inflight (): num => { return 1; };
+
+let x = defer 1;
+//      ^^^^^^^ "defer" can only be used in inflight code
+
+inflight () => {
+  let p = defer f();
+  let n: num = p;
+  //           ^ Expected type to be "num", but got "Promise<num>" instead
+
+  let y = await 1;
+  //            ^ Expected a "Promise" to await, but got "num" instead
+
+  let z = defer 1;
+  //            ^ Only function calls can be deferred
+};
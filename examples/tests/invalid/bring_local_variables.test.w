@@ -1,3 +1,6 @@
 bring "./file_with_variables.w" as stuff;
 
 new stuff.Bar();
+
+pub let x = 1;
+//      ^ Only top-level variables in module files can be public
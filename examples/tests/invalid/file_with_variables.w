@@ -4,7 +4,9 @@ bring cloud;
 
 let x = 5;
 let y = ["hello", "world"];
+let var w = 1;
 let z = new cloud.Bucket();
+pub let c = new cloud.Counter();
 
 pub class Bar {
   x: num;
@@ -0,0 +1,32 @@
+class Box<T> {
+  pub value: T;
+  new(value: T) {
+    this.value = value;
+  }
+}
+
+let b: Box = new Box<num>(1);
+//     ^^^ Missing type arguments for generic type "Box", expected 1
+
+let b2 = new Box<num, str>(1);
+//           ^^^^^^^^^^^^^ Type "Box" has 1 type parameters, but 2 were provided
+
+let b3 = new Box<num>("hello");
+//                    ^^^^^^^ Expected type to be "num", but got "str" instead
+
+class NotGeneric {}
+let ng = new NotGeneric<num>();
+//           ^^^^^^^^^^^^^^^ Type "NotGeneric" is not generic
+
+class Factory<T> {
+  pub make(): T {
+    return new T();
+  //       ^^^^^^^ Cannot instantiate type parameter "T"
+  }
+}
+
+let empty = <T>(): Array<T> => {
+  return [];
+};
+empty();
+//^^^^^ Unable to infer type argument "T" of "preflight <T>(): Array<T>", add type annotations to the arguments
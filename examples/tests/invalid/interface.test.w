@@ -28,9 +28,65 @@ interface IWithSameName {
  // ^^^ Symbol "foo" already defined in this scope
 }
 
-interface INoProps {
+interface IWithFields {
   bar: str;
-//^^^^^^^^ interfaces can't have properties 
+  var baz: num;
+  inflight qux: str;
+}
+
+interface IInvalidFields {
+  pub quux: str;
+//^^^ Access modifiers are not allowed in interfaces
+  static corge: str;
+//^^^^^^ Interface fields cannot be static
+  grault: str = "grault";
+//              ^^^^^^^^ Interface fields cannot have an initial value
+}
+
+class CMissingField impl IWithFields {
+   // ^^^^^^^^^^^^^ Class "CMissingField" does not implement field "bar" of interface "IWithFields"
+  pub var baz: num;
+  pub inflight qux: str;
+  new() { this.baz = 1; }
+}
+
+class CWrongFields impl IWithFields {
+  bar: str;
+//^^^ Field "bar" is private in "CWrongFields" but it's an implementation of "IWithFields". Interface members must be public.
+  pub baz: num;
+    //^^^ Field "baz" of "CWrongFields" must be reassignable to implement "IWithFields"
+  pub qux: str;
+    //^^^ Field "qux" is preflight in "CWrongFields" but inflight in "IWithFields"
+  new() {
+    this.bar = "bar";
+    this.baz = 1;
+    this.qux = "qux";
+  }
+}
+
+inflight interface IHasName {
+  name: str;
+}
+
+inflight interface IHasVarName {
+  var name: str;
+}
+
+struct Titled {
+  title: str;
+}
+
+struct Named {
+  name: str;
+}
+
+test "structural typing of interfaces with only fields" {
+  let a: IHasName = Titled { title: "title" };
+                  //^^^^^^^^^^^^^^^^^^^^^^^^^ Expected type to be "IHasName", but got "Titled" instead
+  let b: IHasName = { title: "title" };
+                  //^^^^^^^^^^^^^^^^^^ "title" is not a field of "IHasName", Missing required field "name" from "IHasName"
+  let c: IHasVarName = Named { name: "name" };
+                     //^^^^^^^^^^^^^^^^^^^^^^ Expected type to be "IHasVarName", but got "Named" instead
 }
 
 // interfaces can't have access modifiers in their method signatures
@@ -0,0 +1,53 @@
+enum Color {
+  RED,
+  GREEN,
+  BLUE,
+}
+
+let c = Color.RED;
+match c {
+//    ^ Match is not exhaustive, values not covered: Color.BLUE
+  Color.RED => {}
+  Color.GREEN => {}
+}
+
+let n: num? = 1;
+let x = match n {
+//            ^ Match is not exhaustive, values not covered: nil, _
+  1 => 1,
+};
+
+let y = match 1 {
+  nil => 1,
+//^^^ Cannot match nil against non-optional type "num"
+  _ => 2,
+};
+
+let arr = [1, 2];
+let z = match arr {
+  [1, 2] => 1,
+//^^^^^^ Expected a literal, nil or an enum variant as a pattern
+  _ => 2,
+};
+
+let w = match 1 {
+  1 | other => other,
+//^^^^^^^^^^^^^^^^^^ Alternative patterns cannot bind variables
+};
+
+struct Point {
+  x: num;
+  y: num;
+}
+
+let p = Point { x: 1, y: 2 };
+let q = match p {
+  { z } => z,
+//  ^ Struct "Point" does not have a field named "z"
+  _ => 0,
+};
+
+let s = match "hello" {
+//            ^^^^^^^ Match is not exhaustive, values not covered: _
+  "hello" => 1,
+};
@@ -0,0 +1,24 @@
+bring cloud;
+
+let bucketName = "my-bucket";
+
+class Foo {
+  static missing: num;
+//^^^^^^^^^^^^^^^^^^^^ Static class fields must be initialized
+
+  instanceField: num = 1;
+//                     ^ Only static class fields can be initialized in their declaration, initialize instance fields in the constructor
+
+  static wrongType: num = "hello";
+//                        ^^^^^^^ Expected type to be "num", but got "str" instead
+
+  static bucket: cloud.Bucket = new cloud.Bucket();
+//                              ^^^^^^^^^^^^^^^^^^ Static fields cannot create preflight objects
+
+  static inflight name: str = bucketName;
+//                            ^^^^^^^^^^ Inflight static fields cannot reference preflight values
+
+  new() {
+    this.instanceField = 1;
+  }
+}
@@ -0,0 +1,23 @@
+let a: str | num = true;
+//                 ^^^^ Expected type to be "str | num", but got "bool" instead
+
+let b: str | num = 1;
+let c: str = b;
+//           ^ Expected type to be "str", but got "str | num" instead
+
+if let x: bool = b {}
+//        ^^^^ "bool" is not one of the types of "str | num"
+
+let d: Array<str> | Array<num> = [1];
+if let x: Array<num> = d {}
+//        ^^^^^^^^^^ Cannot narrow "Array<str> | Array<num>" to "Array<num>", values of type "Array<str>" can't be told apart from it at runtime
+
+class Foo {}
+let e: Foo | str = "foo";
+if let x: Foo = e {}
+//        ^^^ Cannot narrow to "Foo", only str, num, bool, Array and MutArray can be checked at runtime
+
+enum Color { RED, GREEN }
+let f: Color | str = Color.RED;
+if let x: str = f {}
+//        ^^^ Cannot narrow "Color | str" to "str", values of type "Color" can't be told apart from it at runtime
@@ -0,0 +1,36 @@
+bring expect;
+
+// any value can be assigned to `any`
+let var a: any = 1;
+a = "hello";
+a = [1, 2, 3];
+a = { x: 1 };
+
+// `any` values can be used as any other type (without type checking)
+let n: any = 42;
+let doubled: num = n * 2;
+expect.equal(doubled, 84);
+
+let s: any = "wing";
+let upper: str = s.toUpperCase();
+expect.equal(upper, "WING");
+
+let identity = (x: any): any => {
+  return x;
+};
+expect.equal(identity(1), 1);
+expect.equal(identity("a"), "a");
+
+class Wrapper {
+  pub value: any;
+  new(value: any) {
+    this.value = value;
+  }
+}
+
+let w = new Wrapper([1, 2]);
+let items: Array<num> = w.value;
+expect.equal(items.length, 2);
+
+let obj: any = { nested: { value: 3 } };
+expect.equal(obj.nested.value, 3);
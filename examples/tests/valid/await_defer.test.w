@@ -0,0 +1,24 @@
+bring expect;
+bring util;
+
+let twice = inflight (x: num): num => {
+  util.sleep(10ms);
+  return x * 2;
+};
+
+test "deferred calls run concurrently" {
+  let p1 = defer twice(1);
+  let p2: Promise<num> = defer twice(2);
+  expect.equal(await p1 + await p2, 6);
+}
+
+test "promises can be passed around" {
+  let sum = inflight (promises: Array<Promise<num>>): num => {
+    let var total = 0;
+    for p in promises {
+      total += await p;
+    }
+    return total;
+  };
+  expect.equal(sum([defer twice(1), defer twice(2), defer twice(3)]), 12);
+}
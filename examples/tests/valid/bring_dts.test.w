@@ -0,0 +1,25 @@
+// npm packages without a JSII assembly are brought through their TypeScript declarations
+bring "dts-fixture" as dts;
+
+assert(dts.add(1, 2) == 3);
+assert(dts.sum([1, 2, 3]) == 6);
+assert(dts.toMap(["a", "b"], 1).get("b") == 1);
+assert((dts.parse("42") ?? 0) == 42);
+assert(dts.parse("forty-two") == nil);
+assert(dts.VERSION == "1.0.0");
+assert(dts.nextDay(datetime.fromIso("2024-01-01T00:00:00Z")).dayOfMonth == 2);
+
+let greeter = new dts.Greeter(greeting: "Hi", punctuation: "?");
+assert(greeter.greet("wingnuts") == "Hi, wingnuts?");
+assert(greeter.greet("wingnuts", dts.Shout.YES) == "HI, WINGNUTS?");
+assert(greeter.greetAll("a", "b") == ["Hi, a?", "Hi, b?"]);
+assert(greeter.count == 4);
+
+let options = dts.GreeterOptions { greeting: "Hey" };
+assert(new dts.Greeter(options).greet("you") == "Hey, you!");
+assert(dts.Greeter.withDefaults().greeting == "Hello");
+
+test "create a class of a brought npm package inflight" {
+  let inflightGreeter = new dts.Greeter(greeting: "Hello");
+  assert(inflightGreeter.greet("inflight") == "Hello, inflight!");
+}
@@ -10,6 +10,8 @@ bring expect;
 // classes from other files can be used
 let store = new file1.Store();
 let q = new file2.Q();
+expect.equal(file1.DEFAULT_DATA, "<empty>");
+expect.equal(file1.STORE_KEYS.length, 1);
 expect.equal(file2.Q.preflightGreet("foo"), "Hello foo");
 
 test "add data to store" {
@@ -0,0 +1,71 @@
+bring expect;
+
+// generic classes
+class Box<T> {
+  pub value: T;
+  new(value: T) {
+    this.value = value;
+  }
+
+  pub get(): T {
+    return this.value;
+  }
+
+  pub map<U>(f: (T): U): Box<U> {
+    return new Box<U>(f(this.value));
+  }
+}
+
+let b1 = new Box<num>(1);
+expect.equal(b1.get(), 1);
+
+// type arguments are inferred from the constructor arguments
+let b2 = new Box("hello") as "b2";
+let s: str = b2.value;
+expect.equal(s, "hello");
+
+let b3: Box<str> = b1.map((n: num): str => { return "{n + 1}"; });
+expect.equal(b3.get(), "2");
+
+// generic structs
+struct Pair<A, B> {
+  first: A;
+  second: B;
+}
+
+let p: Pair<num, str> = { first: 1, second: "one" };
+expect.equal(p.first, 1);
+expect.equal(p.second, "one");
+
+// generic interfaces
+interface IContainer<T> {
+  get(): T;
+}
+
+class Constant impl IContainer<str> {
+  pub get(): str {
+    return "constant";
+  }
+}
+
+let c: IContainer<str> = new Constant();
+expect.equal(c.get(), "constant");
+
+// generic functions
+let identity = <T>(value: T): T => {
+  return value;
+};
+let n: num = identity(42);
+expect.equal(n, 42);
+
+let first = <T>(items: Array<T>): T? => {
+  return items.tryAt(0);
+};
+expect.equal(first(["a", "b"]), "a");
+
+test "generic inflight closures" {
+  let wrap = <T>(value: T): Array<T> => {
+    return [value];
+  };
+  expect.equal(wrap("a"), ["a"]);
+}
@@ -50,3 +50,55 @@ interface IDocumentedInterface {
     /// blah blah blah
     method1(): void;
 }
+
+// interfaces can declare readonly and reassignable fields in both phases
+interface INamed {
+  /// Field documentation
+  name: str;
+  var counter: num;
+  inflight greeting: str;
+}
+
+interface INamedShape extends INamed, IShape {}
+
+class Named impl INamed {
+  pub name: str;
+  pub var counter: num;
+  pub inflight greeting: str;
+
+  new(name: str) {
+    this.name = name;
+    this.counter = 0;
+  }
+
+  inflight new() {
+    this.greeting = "hello";
+  }
+}
+
+let named: INamed = new Named("named");
+named.counter = named.counter + 1;
+assert(named.name == "named");
+assert(named.counter == 1);
+
+test "access interface fields inflight" {
+  assert(named.name == "named");
+  assert(named.greeting == "hello");
+}
+
+// interfaces that only declare fields are typed structurally (like JSII interfaces with properties)
+inflight interface IGreeting {
+  greeting: str;
+}
+
+struct Greeting {
+  greeting: str;
+  language: str;
+}
+
+test "structs and object literals can be used as interfaces with only fields" {
+  let g: IGreeting = Greeting { greeting: "hello", language: "en" };
+  let h: IGreeting = { greeting: "hi" };
+  assert(g.greeting == "hello");
+  assert(h.greeting == "hi");
+}
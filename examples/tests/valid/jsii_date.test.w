@@ -0,0 +1,46 @@
+bring "jsii-fixture" as jsii_fixture;
+
+// JSII `Date`s are `datetime`s in Wing, and are converted when passed to JSII code or returned from it
+let d = datetime.fromIso("2024-01-01T00:00:00Z");
+
+let nextDay = jsii_fixture.JsiiClass.nextDay(d);
+assert(nextDay.dayOfMonth == 2);
+assert(nextDay.timestampMs - d.timestampMs == 24 * 60 * 60 * 1000);
+
+let jsiiClass = new jsii_fixture.JsiiClass(10);
+assert(jsiiClass.methodWithDateStructParam(date: d).timestampMs == d.timestampMs);
+
+let s = jsii_fixture.DateStruct { date: d };
+assert(jsiiClass.methodWithDateStructParam(s).toIso() == d.toIso());
+assert(s.date.year == 2024);
+
+assert(jsiiClass.dateField == nil);
+jsiiClass.dateField = nextDay;
+assert(jsiiClass.dateField!.dayOfMonth == 2);
+
+// Arrays and maps of dates are converted item by item
+let nextDays = jsii_fixture.JsiiClass.nextDays([d, nextDay]);
+assert(nextDays.at(0).dayOfMonth == 2);
+assert(nextDays.at(1).dayOfMonth == 3);
+
+let nextDaysByKey = jsii_fixture.JsiiClass.nextDaysByKey({ "a": d, "b": nextDay });
+assert(nextDaysByKey.get("a").dayOfMonth == 2);
+assert(nextDaysByKey.get("b").dayOfMonth == 3);
+
+// Wing implementations of JSII callbacks and interfaces are called with `datetime`s and return `Date`s to JSII
+let fromClosure = jsii_fixture.JsiiClass.applyDateClosure(d, (date: datetime): datetime => {
+  assert(date.year == 2024);
+  return jsii_fixture.JsiiClass.nextDay(date);
+});
+assert(fromClosure.dayOfMonth == 2);
+
+class DateProvider impl jsii_fixture.IDateProvider {
+  pub nextDay(date: datetime): datetime {
+    assert(date.year == 2024);
+    return jsii_fixture.JsiiClass.nextDay(date);
+  }
+}
+
+let provider = new DateProvider();
+assert(jsii_fixture.JsiiClass.callDateProvider(d, provider).dayOfMonth == 2);
+assert(provider.nextDay(d).dayOfMonth == 2);
@@ -0,0 +1,25 @@
+bring "jsii-fixture" as jsii_fixture;
+bring expect;
+
+// union-typed parameters accept any of their members
+expect.equal(jsii_fixture.JsiiClass.describeValue("hello"), "string hello");
+expect.equal(jsii_fixture.JsiiClass.describeValue(42), "number 42");
+expect.equal(jsii_fixture.JsiiClass.describeValue({ field: "value" }), "struct value");
+expect.equal(jsii_fixture.JsiiClass.describeValue(jsii_fixture.SomeStruct { field: "other" }), "struct other");
+
+// struct literals are checked against the member of the union they can be used as
+expect.equal(jsii_fixture.JsiiClass.countStructs([{ field: "a" }, { field: "b" }]), 2);
+
+// union-typed results have to be narrowed
+let value = jsii_fixture.JsiiClass.stringOrNumber(false);
+if let n: num = value {
+  expect.equal(n + 1, 2);
+} else {
+  expect.fail("expected a number");
+}
+
+// `Json.keys` takes a `Json | MutJson`
+let mutable = MutJson { a: 1 };
+mutable.set("b", 2);
+expect.equal(Json.keys(mutable), ["a", "b"]);
+expect.equal(Json.keys(Json { c: 3 }), ["c"]);
@@ -0,0 +1,74 @@
+bring expect;
+
+enum Color {
+  RED,
+  GREEN,
+  BLUE,
+}
+
+// match statement over enum variants
+let describe = (c: Color): str => {
+  let var description = "";
+  match c {
+    Color.RED => { description = "warm"; }
+    Color.GREEN | Color.BLUE => { description = "cool"; }
+  }
+  return description;
+};
+expect.equal(describe(Color.RED), "warm");
+expect.equal(describe(Color.BLUE), "cool");
+
+// match expression with a catch-all binding
+let name = (n: num): str => {
+  return match n {
+    0 => "zero",
+    1 => "one",
+    other => "many ({other})",
+  };
+};
+expect.equal(name(0), "zero");
+expect.equal(name(1), "one");
+expect.equal(name(7), "many (7)");
+
+// optionals: after the `nil` arm, bindings have the unwrapped type
+let orZero = (x: num?): num => {
+  return match x {
+    nil => 0,
+    v => v + 1,
+  };
+};
+expect.equal(orZero(nil), 0);
+expect.equal(orZero(41), 42);
+
+// booleans
+let flag = match 1 > 0 {
+  true => "yes",
+  false => "no",
+};
+expect.equal(flag, "yes");
+
+// struct destructuring
+struct Shape {
+  kind: str;
+  size: num;
+}
+
+let area = (s: Shape): num => {
+  return match s {
+    { kind: "square", size } => size * size,
+    { kind: "line" } => 0,
+    _ => -1,
+  };
+};
+expect.equal(area({ kind: "square", size: 3 }), 9);
+expect.equal(area({ kind: "line", size: 3 }), 0);
+expect.equal(area({ kind: "circle", size: 3 }), -1);
+
+test "match inflight" {
+  let c = Color.GREEN;
+  let result = match c {
+    Color.RED => 1,
+    _ => 2,
+  };
+  expect.equal(result, 2);
+}
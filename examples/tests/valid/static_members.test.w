@@ -3,9 +3,9 @@ bring cloud;
 class Foo {
   pub instanceField: num;
 
-  // TODO: Static field initialization not supported yet (https://github.com/winglang/wing/issues/1668)
-  // static staticField: str = "Static resource value"; 
-  // static inflight inflightStaticField: str = "Inflight static resource value";
+  pub static staticField: str = "Static resource value";
+  pub static var counter: num = 0;
+  pub static inflight inflightStaticField: str = "Inflight static resource value";
 
   pub static m(): num { return 99; }
 
@@ -20,8 +20,9 @@ class Foo {
 
 let foo = new Foo();
 assert(foo.instanceField == 100);
-// TODO: Static field initialization not supported yet (https://github.com/winglang/wing/issues/1668)
-// assert(Foo.staticField == "Static resource value"); 
+assert(Foo.staticField == "Static resource value");
+Foo.counter = Foo.counter + 1;
+assert(Foo.counter == 1);
 assert(Foo.m() == 99);
 
 test "test" {
@@ -34,18 +35,19 @@ test "test" {
       return "Static inflight method";
     }
 
-    // TODO: Static field initialization not supported yet (https://github.com/winglang/wing/issues/1668)
-    // static staticInflightField: str = "Static inflight value";
+    pub static staticInflightField: str = "Static inflight value";
   }
 
   // TODO: acess to preflight types (`Foo`) not supported yet (https://github.com/winglang/wing/issues/1669)
   // assert(Foo.get123() == 123);
-  // TODO: Static field initialization not supported yet (https://github.com/winglang/wing/issues/1668)
+  // TODO: acess to preflight types (`Foo`) not supported yet (https://github.com/winglang/wing/issues/1669)
   // assert(Foo.inflightStaticField == "Inflight static resource value");
 
+  // preflight static fields are lifted like any other preflight value
+  assert(Foo.staticField == "Static resource value");
+
   let inflightClass = new InflightClass();
   assert(inflightClass.inflightMethod() == "Inflight method");
   assert(InflightClass.staticInflightMethod() == "Static inflight method");
-  // TODO: Static field initialization not supported yet (https://github.com/winglang/wing/issues/1668)
-  // assert(InflightClass.staticInflightField == "Static inflight value");
+  assert(InflightClass.staticInflightField == "Static inflight value");
 }
@@ -5,6 +5,9 @@ bring "./subdir/empty.w" as file3;
 bring math;
 bring cloud;
 
+pub let DEFAULT_DATA = "<empty>";
+pub let STORE_KEYS = ["data.txt"];
+
 pub class Util {}
 
 pub class Store {
@@ -13,7 +16,7 @@ pub class Store {
     this.b = new cloud.Bucket();
 
     let prefill = new cloud.OnDeploy(inflight () => {
-      this.b.put("data.txt", "<empty>");
+      this.b.put("data.txt", DEFAULT_DATA);
     });
   }
   pub inflight store(data: str) {
@@ -35,3 +38,7 @@ pub struct Point {
 pub interface Shape {
   area(): num;
 }
+
+test "default data is a constant" {
+  assert(DEFAULT_DATA == "<empty>");
+}
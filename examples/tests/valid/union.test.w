@@ -0,0 +1,51 @@
+bring expect;
+
+let describe = (value: str | num | Array<str>): str => {
+  if let s: str = value {
+    return "string {s}";
+  } else if let n: num = value {
+    return "number {n + 1}";
+  } else if let items: Array<str> = value {
+    return "array of {items.length}";
+  }
+  return "unreachable";
+};
+
+expect.equal(describe("hello"), "string hello");
+expect.equal(describe(41), "number 42");
+expect.equal(describe(["a", "b"]), "array of 2");
+
+// unions with an optional member are optional
+let maybe: str? | bool = nil;
+if let b: bool = maybe {
+  expect.fail("nil is not a bool");
+}
+
+// a member of a union is a subtype of it
+let values = Array<str | bool>["a", true];
+expect.equal(values.length, 2);
+
+// a union is a subtype of a union with more members
+let narrow: str | num = 1;
+let wide: str | num | bool = narrow;
+expect.equal("{wide}", "1");
+
+struct Options {
+  size: num | str;
+}
+
+let opts = Options { size: "large" };
+if let size: str = opts.size {
+  expect.equal(size, "large");
+}
+
+let fromJson = Options.fromJson({ size: 3 });
+if let size: num = fromJson.size {
+  expect.equal(size, 3);
+}
+
+// a struct literal can be used as the struct member of a union
+let sized: Options | num = { size: 2 };
+if let n: num = sized {
+  expect.fail("a struct is not a number");
+}
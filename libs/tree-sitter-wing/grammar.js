@@ -42,6 +42,9 @@ module.exports = grammar({
     // These modifier conflicts should be solved through GLR parsing
     [$.field_modifiers, $.method_modifiers],
     [$.class_modifiers, $.closure_modifiers, $.interface_modifiers],
+
+    // A statement starting with `match` is either a match statement or a match expression
+    [$.match_statement, $.match_expression],
   ],
 
   supertypes: ($) => [$.expression, $._literal],
@@ -82,10 +85,15 @@ module.exports = grammar({
         seq(
           field("object", $._type_identifier),
           // While the final "fields" identifier is optional in this grammar, upstream parsing will fail if it is not present
-          repeat(seq(".", optional(field("fields", $._type_identifier))))
+          repeat(seq(".", optional(field("fields", $._type_identifier)))),
+          optional(field("type_arguments", $.type_arguments))
         )
       ),
 
+    type_arguments: ($) => seq("<", commaSep1(field("type", $._type)), ">"),
+    type_parameters: ($) =>
+      seq("<", commaSep1(field("name", $.identifier)), ">"),
+
     nested_identifier: ($) =>
       prec(
         PREC.MEMBER,
@@ -125,6 +133,7 @@ module.exports = grammar({
         $.continue_statement,
         $.if_statement,
         $.if_let_statement,
+        $.match_statement,
         $.struct_definition,
         $.enum_definition,
         $.try_catch_statement,
@@ -146,6 +155,7 @@ module.exports = grammar({
         optional(field("access_modifier", $.access_modifier)),
         "struct",
         field("name", $.identifier),
+        optional(field("type_parameters", $.type_parameters)),
         optional(seq("extends", commaSep(field("extends", $.custom_type)))),
         braced(repeat(field("field", $.struct_field)))
       ),
@@ -204,6 +214,7 @@ module.exports = grammar({
 
     variable_definition_statement: ($) =>
       seq(
+        optional(field("access_modifier", $.access_modifier)),
         "let",
         optional(field("reassignable", $.reassignable)),
         field("name", $.identifier),
@@ -225,6 +236,7 @@ module.exports = grammar({
         optional(field("modifiers", $.class_modifiers)),
         "class",
         field("name", $.identifier),
+        optional(field("type_parameters", $.type_parameters)),
         optional(seq("extends", field("parent", $.custom_type))),
         optional(seq("impl", field("implements", commaSep1($.custom_type)))),
         field("implementation", $.class_implementation)
@@ -257,6 +269,7 @@ module.exports = grammar({
         optional(field("modifiers", $.interface_modifiers)),
         "interface",
         field("name", $.identifier),
+        optional(field("type_parameters", $.type_parameters)),
         optional(seq("extends", field("extends", commaSep1($.custom_type)))),
         field("implementation", $.interface_implementation)
       ),
@@ -299,6 +312,7 @@ module.exports = grammar({
         "let",
         optional(field("reassignable", $.reassignable)),
         field("name", $.identifier),
+        optional($._type_annotation),
         "=",
         field("value", $.expression),
         field("block", $.block),
@@ -318,6 +332,7 @@ module.exports = grammar({
         "let",
         optional(field("reassignable", $.reassignable)),
         field("name", $.identifier),
+        optional($._type_annotation),
         "=",
         field("value", $.expression),
         field("block", $.block)
@@ -340,6 +355,20 @@ module.exports = grammar({
         field("block", $.block)
       ),
 
+    match_statement: ($) =>
+      seq(
+        "match",
+        field("value", $.expression),
+        braced(repeat(field("arm", $.match_statement_arm)))
+      ),
+
+    match_statement_arm: ($) =>
+      seq(
+        pipeSep1(field("pattern", $.expression)),
+        "=>",
+        field("block", $.block)
+      ),
+
     try_catch_statement: ($) =>
       seq(
         "try",
@@ -378,7 +407,8 @@ module.exports = grammar({
         $.json_literal,
         $.struct_literal,
         $.optional_unwrap,
-        $.intrinsic
+        $.intrinsic,
+        $.match_expression
       ),
 
     intrinsic: ($) =>
@@ -512,6 +542,7 @@ module.exports = grammar({
         $.json_container_type,
         $.function_type,
         $.optional,
+        $.union_type,
         $._parenthesized_type
       ),
 
@@ -519,6 +550,10 @@ module.exports = grammar({
 
     optional: ($) => seq($._type, "?"),
 
+    // Binds tighter than function types: `(): str | num` returns a union
+    union_type: ($) =>
+      prec.left(1, seq(field("type", $._type), "|", field("type", $._type))),
+
     function_type: ($) =>
       prec.right(
         seq(
@@ -568,6 +603,7 @@ module.exports = grammar({
       seq(
         optional(field("modifiers", $.method_modifiers)),
         field("name", $.identifier),
+        optional(field("type_parameters", $.type_parameters)),
         field("parameter_list", $.parameter_list),
         optional($._return_type),
         choice(field("block", $.block), $._semicolon)
@@ -676,16 +712,31 @@ module.exports = grammar({
     closure: ($) =>
       seq(
         optional(field("modifiers", $.closure_modifiers)),
+        optional(field("type_parameters", $.type_parameters)),
         field("parameter_list", $.parameter_list),
         optional($._return_type),
         "=>",
         field("block", $.block)
       ),
 
-    await_expression: ($) => prec.right(seq("await", $.expression)),
-    defer_expression: ($) => prec.right(seq("defer", $.expression)),
+    await_expression: ($) => prec.right(PREC.UNARY, seq("await", $.expression)),
+    defer_expression: ($) => prec.right(PREC.UNARY, seq("defer", $.expression)),
     parenthesized_expression: ($) => seq("(", $.expression, ")"),
 
+    match_expression: ($) =>
+      seq(
+        "match",
+        field("value", $.expression),
+        braced(commaSep(field("arm", $.match_expression_arm)))
+      ),
+
+    match_expression_arm: ($) =>
+      seq(
+        pipeSep1(field("pattern", $.expression)),
+        "=>",
+        field("value", $.expression)
+      ),
+
     _collection_literal: ($) => choice($.array_literal, $.map_literal),
     array_literal: ($) =>
       seq(
@@ -764,3 +815,10 @@ function commaSep1(rule) {
 function commaSep(rule) {
   return optional(commaSep1(rule));
 }
+
+/**
+ * @param {Rule} rule
+ */
+function pipeSep1(rule) {
+  return seq(rule, repeat(seq("|", rule)));
+}
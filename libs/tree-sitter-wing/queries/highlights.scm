@@ -13,6 +13,9 @@
 (class_definition
   name: (identifier) @type)
 
+(type_parameters
+  name: (identifier) @type)
+
 (method_definition
   name: (identifier) @function.method)
 
@@ -93,6 +96,7 @@
 [
   "if"
   "else"
+  "match"
 ] @keyword.conditional
 
 [
@@ -101,4 +105,9 @@
   "internal"
 ] @keyword.modifier
 
+[
+  "await"
+  "defer"
+] @keyword.coroutine
+
 "return" @keyword.return
@@ -5,6 +5,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use camino::Utf8PathBuf;
 use indexmap::{Equivalent, IndexMap};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::diagnostic::WingSpan;
 
@@ -14,7 +15,7 @@ static EXPR_COUNTER: AtomicUsize = AtomicUsize::new(0);
 static SCOPE_COUNTER: AtomicUsize = AtomicUsize::new(0);
 static ARGLIST_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
-#[derive(Debug, Eq, Clone)]
+#[derive(Debug, Eq, Clone, Serialize, Deserialize)]
 pub struct Symbol {
 	pub name: String,
 	pub span: WingSpan,
@@ -84,7 +85,7 @@ impl From<&str> for Symbol {
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Phase {
 	Inflight,
 	Preflight,
@@ -132,6 +133,7 @@ pub enum TypeAnnotationKind {
 	Datetime,
 	Regex,
 	Void,
+	Any,
 	Json,
 	MutJson,
 	Optional(Box<TypeAnnotation>),
@@ -141,16 +143,21 @@ pub enum TypeAnnotationKind {
 	MutMap(Box<TypeAnnotation>),
 	Set(Box<TypeAnnotation>),
 	MutSet(Box<TypeAnnotation>),
+	Promise(Box<TypeAnnotation>),
+	/// `A | B`, always has at least two members
+	Union(Vec<TypeAnnotation>),
 	Function(FunctionSignature),
 	UserDefined(UserDefinedType),
 }
 
 // In the future this may be an enum for type-alias, class, etc. For now its just a nested name.
 // Also this root,fields thing isn't really useful, should just turn in to a Vec<Symbol>.
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone)]
 pub struct UserDefinedType {
 	pub root: Symbol,
 	pub fields: Vec<Symbol>,
+	/// Type arguments of a generic type (e.g. `Box<num>`), empty if none are given
+	pub type_arguments: Vec<TypeAnnotation>,
 	pub span: WingSpan,
 }
 
@@ -167,11 +174,14 @@ impl PartialEq for UserDefinedType {
 	}
 }
 
+impl Eq for UserDefinedType {}
+
 impl UserDefinedType {
 	pub fn for_class(class: &Class) -> Self {
 		Self {
 			root: class.name.clone(),
 			fields: vec![],
+			type_arguments: vec![],
 			span: class.name.span.clone(),
 		}
 	}
@@ -193,7 +203,11 @@ impl UserDefinedType {
 
 impl Display for UserDefinedType {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}", self.full_path_str())
+		write!(f, "{}", self.full_path_str())?;
+		if !self.type_arguments.is_empty() {
+			write!(f, "<{}>", self.type_arguments.iter().join(", "))?;
+		}
+		Ok(())
 	}
 }
 
@@ -208,6 +222,7 @@ impl Display for TypeAnnotationKind {
 			TypeAnnotationKind::Datetime => write!(f, "datetime"),
 			TypeAnnotationKind::Regex => write!(f, "regex"),
 			TypeAnnotationKind::Void => write!(f, "void"),
+			TypeAnnotationKind::Any => write!(f, "any"),
 			TypeAnnotationKind::Json => write!(f, "Json"),
 			TypeAnnotationKind::MutJson => write!(f, "MutJson"),
 			TypeAnnotationKind::Optional(t) => write!(f, "{}?", t),
@@ -217,6 +232,8 @@ impl Display for TypeAnnotationKind {
 			TypeAnnotationKind::MutMap(t) => write!(f, "MutMap<{}>", t),
 			TypeAnnotationKind::Set(t) => write!(f, "Set<{}>", t),
 			TypeAnnotationKind::MutSet(t) => write!(f, "MutSet<{}>", t),
+			TypeAnnotationKind::Promise(t) => write!(f, "Promise<{}>", t),
+			TypeAnnotationKind::Union(members) => write!(f, "{}", members.iter().join(" | ")),
 			TypeAnnotationKind::Function(t) => write!(f, "{}", t),
 			TypeAnnotationKind::UserDefined(user_defined_type) => write!(f, "{}", user_defined_type),
 		}
@@ -249,13 +266,21 @@ impl Display for FunctionSignature {
 			.collect::<Vec<String>>()
 			.join(", ");
 
+		let type_params_str = if self.type_parameters.is_empty() {
+			"".to_string()
+		} else {
+			format!("<{}>", self.type_parameters.iter().join(", "))
+		};
+
 		let ret_type_str = format!("{}", &self.return_type);
-		write!(f, "{phase_str}({params_str}): {ret_type_str}")
+		write!(f, "{phase_str}{type_params_str}({params_str}): {ret_type_str}")
 	}
 }
 
 #[derive(Debug, Clone)]
 pub struct FunctionSignature {
+	/// Type parameters of a generic function (e.g. `<T>` in `fn<T>(x: T): T`)
+	pub type_parameters: Vec<Symbol>,
 	pub parameters: Vec<FunctionParameter>,
 	pub return_type: Box<TypeAnnotation>,
 	pub phase: Phase,
@@ -322,6 +347,8 @@ pub struct ElseIfBlock {
 pub struct ElseIfLetBlock {
 	pub reassignable: bool,
 	pub var_name: Symbol,
+	/// The type the value is narrowed to (`else if let x: str = value`), if any
+	pub type_: Option<TypeAnnotation>,
 	pub value: Expr,
 	pub statements: Scope,
 }
@@ -329,6 +356,7 @@ pub struct ElseIfLetBlock {
 #[derive(Debug)]
 pub struct Class {
 	pub name: Symbol,
+	pub type_parameters: Vec<Symbol>,
 	pub span: WingSpan,
 	pub fields: Vec<ClassField>,
 	pub methods: Vec<(Symbol, FunctionDefinition)>,
@@ -398,8 +426,11 @@ impl Class {
 #[derive(Debug)]
 pub struct Interface {
 	pub name: Symbol,
+	pub type_parameters: Vec<Symbol>,
 	// Each method has a symbol, a signature, and an optional documentation string
 	pub methods: Vec<(Symbol, FunctionSignature, Option<String>)>,
+	/// Interface fields are always public, non-static and have no initial value
+	pub fields: Vec<ClassField>,
 	pub extends: Vec<UserDefinedType>,
 	pub access: AccessModifier,
 	pub phase: Phase,
@@ -408,6 +439,7 @@ pub struct Interface {
 #[derive(Debug)]
 pub struct Struct {
 	pub name: Symbol,
+	pub type_parameters: Vec<Symbol>,
 	pub extends: Vec<UserDefinedType>,
 	pub fields: Vec<StructField>,
 	pub access: AccessModifier,
@@ -446,6 +478,8 @@ pub enum AssignmentKind {
 pub struct IfLet {
 	pub reassignable: bool,
 	pub var_name: Symbol,
+	/// The type the value is narrowed to (`if let x: str = value`), if any
+	pub type_: Option<TypeAnnotation>,
 	pub value: Expr,
 	pub statements: Scope,
 	pub else_if_statements: Vec<ElseIfs>,
@@ -458,6 +492,48 @@ pub enum ElseIfs {
 	ElseIfLetBlock(ElseIfLetBlock),
 }
 
+/// A `match` statement or expression, the arms are tried in order and the first one with a matching pattern is taken.
+#[derive(Debug)]
+pub struct Match {
+	pub value: Box<Expr>,
+	pub arms: Vec<MatchArm>,
+}
+
+#[derive(Debug)]
+pub struct MatchArm {
+	/// The arm is taken if the value matches any of these patterns (`A | B => ...`)
+	pub patterns: Vec<MatchPattern>,
+	pub body: MatchArmBody,
+	pub span: WingSpan,
+}
+
+#[derive(Debug)]
+pub enum MatchArmBody {
+	/// The block of an arm in a `match` statement.
+	Statements(Scope),
+	/// The value of an arm in a `match` expression.
+	Expression(Expr),
+}
+
+#[derive(Debug)]
+pub struct MatchPattern {
+	pub kind: MatchPatternKind,
+	pub span: WingSpan,
+}
+
+#[derive(Debug)]
+pub enum MatchPatternKind {
+	/// `_`: matches any value
+	Wildcard,
+	/// `x`: matches any value and binds it to a new variable
+	Binding(Symbol),
+	/// A literal, `nil` or an enum variant: matches values equal to it
+	Value(Expr),
+	/// `{ a, b: <pattern> }`: matches a struct whose fields match the given patterns, a field without a pattern is
+	/// bound to a new variable with the same name
+	Struct(Vec<(Symbol, MatchPattern)>),
+}
+
 #[derive(Debug)]
 pub enum StmtKind {
 	Bring {
@@ -472,6 +548,8 @@ pub enum StmtKind {
 		var_name: Symbol,
 		initial_value: Expr,
 		type_: Option<TypeAnnotation>,
+		/// Only top-level variables of module files can be exported
+		access: AccessModifier,
 	},
 	ForLoop {
 		iterator: Symbol,
@@ -489,6 +567,7 @@ pub enum StmtKind {
 		else_if_statements: Vec<ElseIfBlock>,
 		else_statements: Option<Scope>,
 	},
+	Match(Match),
 	Break,
 	Continue,
 	Return(Option<Expr>),
@@ -548,9 +627,11 @@ pub struct ClassField {
 	pub is_static: bool,
 	pub access: AccessModifier,
 	pub doc: Option<String>,
+	/// Static fields are initialized where they're declared
+	pub initial_value: Option<Expr>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum AccessModifier {
 	Private,
 	Public,
@@ -675,6 +756,11 @@ pub enum ExprKind {
 		element: Box<Expr>,
 	},
 	FunctionClosure(FunctionDefinition),
+	Match(Match),
+	/// Waits for a deferred (`Promise`) value to resolve
+	Await(Box<Expr>),
+	/// Calls an inflight function without waiting for its result, which is returned as a `Promise`
+	Defer(Box<Expr>),
 }
 
 #[derive(Debug)]
@@ -711,8 +797,16 @@ pub struct Expr {
 
 impl Expr {
 	pub fn new(kind: ExprKind, span: WingSpan) -> Self {
-		let id = EXPR_COUNTER.fetch_add(1, Ordering::SeqCst);
-		Self { id, kind, span }
+		Self {
+			id: Self::new_id(),
+			kind,
+			span,
+		}
+	}
+
+	/// Generates an id no other expression has (for types that refer to expressions outside of the AST)
+	pub fn new_id() -> ExprId {
+		EXPR_COUNTER.fetch_add(1, Ordering::SeqCst)
 	}
 }
 
@@ -946,6 +1040,12 @@ impl Spanned for UserDefinedType {
 	}
 }
 
+impl Spanned for MatchPattern {
+	fn span(&self) -> WingSpan {
+		self.span.clone()
+	}
+}
+
 impl Spanned for Scope {
 	fn span(&self) -> WingSpan {
 		self.span.clone()
@@ -145,6 +145,7 @@ impl Fold for ClosureTransformer {
 				let class_udt = UserDefinedType {
 					root: new_class_name.clone(),
 					fields: vec![],
+					type_arguments: vec![],
 					span: WingSpan::for_file(file_id),
 				};
 
@@ -234,6 +235,7 @@ impl Fold for ClosureTransformer {
 								WingSpan::for_file(file_id),
 							),
 							type_: None,
+							access: AccessModifier::Private,
 						},
 						span: WingSpan::for_file(file_id),
 						idx: 0,
@@ -254,11 +256,13 @@ impl Fold for ClosureTransformer {
 				let class_def = Stmt {
 					kind: StmtKind::Class(Class {
 						name: new_class_name.clone(),
+						type_parameters: vec![],
 						span: new_func_def.span.clone(),
 						phase: Phase::Preflight,
 						initializer: FunctionDefinition {
 							name: Some(CLASS_INIT_NAME.into()),
 							signature: FunctionSignature {
+								type_parameters: vec![],
 								parameters: class_init_params,
 								return_type: Box::new(class_type_annotation.clone()),
 								phase: Phase::Preflight,
@@ -276,6 +280,7 @@ impl Fold for ClosureTransformer {
 						inflight_initializer: FunctionDefinition {
 							name: Some(CLASS_INFLIGHT_INIT_NAME.into()),
 							signature: FunctionSignature {
+								type_parameters: vec![],
 								parameters: vec![],
 								return_type: Box::new(TypeAnnotation {
 									kind: TypeAnnotationKind::Void,
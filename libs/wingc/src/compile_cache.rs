@@ -0,0 +1,702 @@
+use std::{collections::HashSet, fs, io, ops::Range};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use wingii::type_system::TypeSystem;
+
+use crate::{
+	ast::Scope,
+	diagnostic::{report_diagnostic, Diagnostic},
+	file_graph::{File, FileGraph},
+	files::Files,
+	parser::is_entrypoint_file,
+	type_check::{cached_exports::CachedExports, jsii_importer::JsiiImportSpec, Types},
+};
+
+const CACHE_DIR_NAME: &str = ".wingc-cache";
+
+/// Version of the format of the cached results, bump it when changing what's cached (or how it's compiled) without
+/// changing the compiler's version
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// Results of compiling each file of a project, stored in its output directory.
+///
+/// When the project is compiled again, files that didn't change (and whose dependencies didn't change) reuse their
+/// cached results instead of being type checked and jsified again. The symbols they export are only restored if a
+/// file that changed brings them, so compiling a project where nothing changed doesn't type check anything.
+pub struct CompileCache {
+	/// Directory the cache is stored in, `None` if the cache is disabled
+	dir: Option<Utf8PathBuf>,
+	fingerprint: String,
+	/// Files whose cached results can be reused, along with their final keys (see `CachedFile::final_key`)
+	hits: IndexMap<Utf8PathBuf, (CachedFile, String)>,
+	/// Hits whose exported symbols were restored into the type system
+	restored: HashSet<Utf8PathBuf>,
+	/// Results of compiling the files that weren't cached
+	compiled: IndexMap<Utf8PathBuf, CompiledFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFile {
+	/// Hash of the compiler's fingerprint, the file's path, package and source, and the final keys of the files
+	/// it depends on
+	key: String,
+	/// Content hash of other files the compilation depended on (like JSII assemblies and extern JS files)
+	externals: IndexMap<String, String>,
+	/// Symbols exported by the file (directories are type checked from their children instead)
+	exports: Option<CachedExports>,
+	js: CachedJsFiles,
+	/// Unique ids of the classes defined by the file
+	class_uids: Range<usize>,
+	/// Diagnostics (warnings) reported while compiling the file, including the ones without a span
+	diagnostics: Vec<Diagnostic>,
+}
+
+/// The JS files emitted for a source file (including its type declarations)
+#[derive(Serialize, Deserialize, Default)]
+pub struct CachedJsFiles {
+	pub preflight_file_name: String,
+	/// The number in the name of the preflight file, if it has one
+	pub preflight_file_id: Option<usize>,
+	/// The number in the names of the inflight files of its classes, if it has any
+	pub inflight_file_id: Option<usize>,
+	pub has_tests: bool,
+	/// Contents of the emitted files by their path relative to the output directory
+	pub files: IndexMap<String, String>,
+}
+
+#[derive(Default)]
+struct CompiledFile {
+	class_uids: Range<usize>,
+	js: CachedJsFiles,
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl CachedFile {
+	/// The key files that depend on this one are cached with, which also changes if its externals change
+	fn final_key(&self) -> String {
+		let mut hasher = blake3::Hasher::new();
+		hasher.update(self.key.as_bytes());
+		for (path, hash) in &self.externals {
+			hasher.update(path.as_bytes());
+			hasher.update(hash.as_bytes());
+		}
+		hasher.finalize().to_string()
+	}
+}
+
+impl CompileCache {
+	/// Loads the cached results of `topo_sorted_files` from `out_dir`, keeping the ones that are still valid.
+	/// If `fingerprint` is `None` the cache is disabled: nothing is loaded and nothing will be saved. If any of the
+	/// results can't be read the whole cache is ignored, so everything is compiled (and cached) again.
+	pub fn load(
+		out_dir: &Utf8Path,
+		fingerprint: Option<String>,
+		topo_sorted_files: &[File],
+		file_graph: &FileGraph,
+		files: &Files,
+	) -> Self {
+		let mut cache = Self {
+			dir: fingerprint.as_ref().map(|_| out_dir.join(CACHE_DIR_NAME)),
+			fingerprint: fingerprint.unwrap_or_default(),
+			hits: IndexMap::new(),
+			restored: HashSet::new(),
+			compiled: IndexMap::new(),
+		};
+		let Some(dir) = &cache.dir else {
+			return cache;
+		};
+
+		for (idx, file) in topo_sorted_files.iter().enumerate() {
+			let deps = key_dependencies(file, &topo_sorted_files[..idx], file_graph);
+			// A file is only reused if all the files it depends on are
+			let Some(dep_keys) = deps
+				.iter()
+				.map(|dep| Some((dep.path.as_path(), cache.hits.get(&dep.path)?.1.as_str())))
+				.collect::<Option<Vec<_>>>()
+			else {
+				continue;
+			};
+			let key = cache.key(file, files, dep_keys);
+			let entry = match fs::read_to_string(entry_path(dir, &file.path)) {
+				Ok(json) => serde_json::from_str::<CachedFile>(&json).ok(),
+				Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+				Err(_) => None,
+			};
+			let Some(entry) = entry else {
+				cache.hits.clear();
+				break;
+			};
+			let is_valid = entry.key == key
+				&& entry
+					.externals
+					.iter()
+					.all(|(path, hash)| hash_path(Utf8Path::new(path)).as_ref() == Some(hash));
+			if is_valid {
+				let final_key = entry.final_key();
+				cache.hits.insert(file.path.clone(), (entry, final_key));
+			}
+		}
+		cache
+	}
+
+	fn key<'a>(&self, file: &File, files: &Files, dep_keys: impl IntoIterator<Item = (&'a Utf8Path, &'a str)>) -> String {
+		let mut hasher = blake3::Hasher::new();
+		hasher.update(self.fingerprint.as_bytes());
+		hasher.update(file.path.as_str().as_bytes());
+		hasher.update(file.package.as_bytes());
+		match files.get_file(&file.path) {
+			Some(text) => hasher.update(text.as_bytes()),
+			None => hasher.update(hash_path(&file.path).unwrap_or_default().as_bytes()),
+		};
+		let mut dep_keys = dep_keys.into_iter().collect::<Vec<_>>();
+		dep_keys.sort();
+		for (path, key) in dep_keys {
+			hasher.update(path.as_str().as_bytes());
+			hasher.update(key.as_bytes());
+		}
+		hasher.finalize().to_string()
+	}
+
+	/// Whether the file's cached results are reused (so it doesn't need to be compiled)
+	pub fn contains(&self, path: &Utf8Path) -> bool {
+		self.hits.contains_key(path)
+	}
+
+	/// Reports the cached diagnostics of the files that aren't compiled again
+	pub fn replay_diagnostics(&self) {
+		for (entry, _) in self.hits.values() {
+			for diagnostic in &entry.diagnostics {
+				report_diagnostic(diagnostic.clone());
+			}
+		}
+	}
+
+	/// The first class uid that isn't used by the cached files
+	pub fn next_class_uid(&self) -> usize {
+		self
+			.hits
+			.values()
+			.map(|(entry, _)| entry.class_uids.end)
+			.max()
+			.unwrap_or_default()
+	}
+
+	/// The cached JS files of the files that aren't compiled again
+	pub fn cached_js(&self) -> impl Iterator<Item = (&Utf8Path, &CachedJsFiles)> {
+		self.hits.iter().map(|(path, (entry, _))| (path.as_path(), &entry.js))
+	}
+
+	/// Restores the exported symbols of a cached file (and of the cached files it depends on) so files that bring it
+	/// can be type checked. Does nothing if the file isn't cached or was already restored. Fails if the cached
+	/// symbols can't be restored, in which case the project should be compiled without the cache (see `clear`).
+	pub fn restore(
+		&mut self,
+		file: &File,
+		asts: &mut IndexMap<Utf8PathBuf, Scope>,
+		types: &mut Types,
+		file_graph: &FileGraph,
+		jsii_types: &mut TypeSystem,
+		jsii_imports: &mut Vec<JsiiImportSpec>,
+	) -> Result<(), String> {
+		if !self.hits.contains_key(&file.path) || !self.restored.insert(file.path.clone()) {
+			return Ok(());
+		}
+		for dep in file_graph.dependencies_of(file) {
+			self.restore(dep, asts, types, file_graph, jsii_types, jsii_imports)?;
+		}
+
+		if let Some(exports) = &self.hits[&file.path].0.exports {
+			crate::restore_exports(types, file, file_graph, jsii_types, jsii_imports, exports)
+				.map_err(|error| format!("Cannot restore the cached symbols of \"{}\": {error}", file.path))?;
+		} else {
+			let scope = asts.get_mut(&file.path).expect("matching AST not found");
+			crate::type_check(scope, types, file, file_graph, jsii_types, jsii_imports);
+		}
+		Ok(())
+	}
+
+	/// Restores the cached files that declare functions in the given extern files, so their type declarations
+	/// include all of them
+	pub fn restore_extern_declarations(
+		&mut self,
+		extern_files: &[Utf8PathBuf],
+		asts: &mut IndexMap<Utf8PathBuf, Scope>,
+		types: &mut Types,
+		file_graph: &FileGraph,
+		jsii_types: &mut TypeSystem,
+		jsii_imports: &mut Vec<JsiiImportSpec>,
+	) -> Result<(), String> {
+		let files = self
+			.hits
+			.iter()
+			.filter(|(_, (entry, _))| {
+				entry.exports.as_ref().map_or(false, |exports| {
+					exports
+						.extern_files()
+						.any(|path| extern_files.iter().any(|extern_file| extern_file == path))
+				})
+			})
+			.map(|(path, _)| path.clone())
+			.collect::<Vec<_>>();
+		for path in files {
+			let file = file_graph
+				.iter_files()
+				.find(|file| file.path == path)
+				.expect("cached file not in graph")
+				.clone();
+			self.restore(&file, asts, types, file_graph, jsii_types, jsii_imports)?;
+		}
+		Ok(())
+	}
+
+	/// Records the class uids used by a file that was type checked in this compilation
+	pub fn add_compiled_file(&mut self, path: &Utf8Path, class_uids: Range<usize>) {
+		self.compiled.entry(path.to_path_buf()).or_default().class_uids = class_uids;
+	}
+
+	/// Records the JS files emitted for a file compiled in this compilation
+	pub fn add_js(&mut self, path: &Utf8Path, js: CachedJsFiles) {
+		self.compiled.entry(path.to_path_buf()).or_default().js = js;
+	}
+
+	/// Records diagnostics reported while compiling a file in this compilation, so they're reported again when its
+	/// cached results are reused
+	pub fn add_diagnostics(&mut self, path: &Utf8Path, diagnostics: Vec<Diagnostic>) {
+		self
+			.compiled
+			.entry(path.to_path_buf())
+			.or_default()
+			.diagnostics
+			.extend(diagnostics);
+	}
+
+	/// Records a type declarations file emitted for a file compiled in this compilation
+	pub fn add_dts(&mut self, path: &Utf8Path, dts_path: &Utf8Path, content: &str) {
+		self
+			.compiled
+			.entry(path.to_path_buf())
+			.or_default()
+			.js
+			.files
+			.insert(dts_path.to_string(), content.to_string());
+	}
+
+	/// Saves the results of the files compiled in this compilation to the cache, so the next compilation can reuse
+	/// them. The cache is only an optimization, so failures are ignored.
+	pub fn save(
+		mut self,
+		project_dir: &Utf8Path,
+		topo_sorted_files: &[File],
+		file_graph: &FileGraph,
+		files: &Files,
+		types: &Types,
+		jsii_types: &TypeSystem,
+	) {
+		let Some(dir) = self.dir.take() else {
+			return;
+		};
+		if fs::create_dir_all(&dir).is_err() {
+			return;
+		}
+
+		// JSII assemblies (and TypeScript typings) loaded by the compilation, which any of the files may depend on
+		let mut module_files = vec![project_dir.join("package.json")];
+		for module_dir in jsii_types.assembly_directories() {
+			module_files.push(module_dir.join(".jsii"));
+			module_files.push(module_dir.join("package.json"));
+		}
+		let mut hashes = IndexMap::new();
+		let mut hash = |path: &Utf8Path| -> Option<(String, String)> {
+			let hash = hashes
+				.entry(path.to_path_buf())
+				.or_insert_with(|| hash_path(path))
+				.clone()?;
+			Some((path.to_string(), hash))
+		};
+		let module_hashes = module_files.iter().filter_map(|path| hash(path)).collect::<Vec<_>>();
+
+		let mut final_keys = self
+			.hits
+			.iter()
+			.map(|(path, (_, final_key))| (path.clone(), final_key.clone()))
+			.collect::<IndexMap<_, _>>();
+		for (idx, file) in topo_sorted_files.iter().enumerate() {
+			let Some(compiled) = self.compiled.swap_remove(&file.path) else {
+				continue;
+			};
+			let deps = key_dependencies(file, &topo_sorted_files[..idx], file_graph);
+			let Some(dep_keys) = deps
+				.iter()
+				.map(|dep| Some((dep.path.as_path(), final_keys.get(&dep.path)?.as_str())))
+				.collect::<Option<Vec<_>>>()
+			else {
+				continue;
+			};
+			let key = self.key(file, files, dep_keys);
+
+			let exports = if file.path.is_dir() {
+				None
+			} else {
+				// Files that export symbols that can't be cached are compiled every time
+				let Some(exports) = CachedExports::new(types, jsii_types, &file.path) else {
+					continue;
+				};
+				Some(exports)
+			};
+			let mut externals = module_hashes.iter().cloned().collect::<IndexMap<_, _>>();
+			for extern_file in exports.iter().flat_map(|exports| exports.extern_files()) {
+				externals.extend(hash(extern_file));
+			}
+			let entry = CachedFile {
+				key,
+				externals,
+				exports,
+				js: compiled.js,
+				class_uids: compiled.class_uids,
+				diagnostics: compiled.diagnostics,
+			};
+
+			if let Ok(json) = serde_json::to_string(&entry) {
+				if fs::write(entry_path(&dir, &file.path), json).is_ok() {
+					final_keys.insert(file.path.clone(), entry.final_key());
+				}
+			}
+		}
+	}
+}
+
+/// The files a file's cached results depend on: the files it brings, or for entrypoints (which register the tests
+/// of all modules compiled before them) all the files before it
+fn key_dependencies<'a>(file: &File, preceding_files: &'a [File], file_graph: &'a FileGraph) -> Vec<&'a File> {
+	if is_entrypoint_file(&file.path) {
+		preceding_files.iter().collect()
+	} else {
+		file_graph.dependencies_of(file)
+	}
+}
+
+fn entry_path(dir: &Utf8Path, path: &Utf8Path) -> Utf8PathBuf {
+	dir.join(format!("{}.json", blake3::hash(path.as_str().as_bytes())))
+}
+
+/// Fingerprint of a compilation's options and of the compiler's version, so upgrading the compiler invalidates
+/// the cache. The content of the sources and of the JSII assemblies they use is part of each file's key instead.
+pub fn fingerprint(project_dir: &Utf8Path, source_path: &Utf8Path, out_dir: &Utf8Path) -> String {
+	let fp_raw_str = format!(
+		"{}-{CACHE_FORMAT_VERSION}-{project_dir}-{source_path}-{out_dir}-{}",
+		env!("CARGO_PKG_VERSION"),
+		std::env::var("WINGSDK_MANIFEST_ROOT").unwrap_or_default(),
+	);
+	blake3::hash(fp_raw_str.as_bytes()).to_string()
+}
+
+/// Deletes the cache in `out_dir`, so the next compilation compiles every file
+pub fn clear(out_dir: &Utf8Path) -> io::Result<()> {
+	match fs::remove_dir_all(out_dir.join(CACHE_DIR_NAME)) {
+		Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+		_ => Ok(()),
+	}
+}
+
+/// Hash of a file's content, or of the names of a directory's entries (so adding or removing files changes it)
+fn hash_path(path: &Utf8Path) -> Option<String> {
+	if path.is_dir() {
+		let mut names = path
+			.read_dir_utf8()
+			.ok()?
+			.filter_map(|entry| Some(entry.ok()?.file_name().to_string()))
+			.collect::<Vec<_>>();
+		names.sort();
+		Some(blake3::hash(names.join("\n").as_bytes()).to_string())
+	} else {
+		Some(blake3::hash(&fs::read(path).ok()?).to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+
+	use super::*;
+	use crate::{
+		ast::Phase,
+		compile, compile_incremental,
+		diagnostic::{get_diagnostics, reset_diagnostics, DiagnosticSeverity},
+		parser::parse_wing_project,
+		type_check::{
+			symbol_env::{SymbolEnv, SymbolEnvKind},
+			SymbolEnvOrNamespace,
+		},
+		DEFAULT_PACKAGE_NAME, WINGSDK_ASSEMBLY_NAME,
+	};
+
+	const LIB: &str = r#"
+pub struct Options { name: str; }
+pub class Greeter {
+  pub prefix: str;
+  new(opts: Options) { this.prefix = opts.name; }
+  pub greet(name: str): str { return "{this.prefix} {name}"; }
+  pub inflight hello(): str { return "hello {this.prefix}"; }
+}
+pub class Box<T> {
+  pub item: T;
+  new(item: T) { this.item = item; }
+}
+"#;
+
+	const MAIN: &str = r#"
+bring "./lib.w" as lib;
+let g = new lib.Greeter({ name: "a" });
+log(g.greet("b"));
+"#;
+
+	/// The emitted files, except for source maps (which refer to the sources relative to the output directory)
+	fn read_outputs(out_dir: &Utf8Path) -> BTreeMap<String, String> {
+		out_dir
+			.read_dir_utf8()
+			.unwrap()
+			.map(|entry| entry.unwrap().path().to_path_buf())
+			.filter(|path| path.is_file() && path.extension() != Some("map"))
+			.map(|path| {
+				(
+					path.file_name().unwrap().to_string(),
+					fs::read_to_string(&path).unwrap(),
+				)
+			})
+			.collect()
+	}
+
+	fn compile_and_compare(project_dir: &Utf8Path, out_dir: &Utf8Path, jsii_types: &mut TypeSystem) {
+		let main = project_dir.join("main.w");
+		compile_incremental(project_dir, &main, None, out_dir, jsii_types, &mut vec![], true).expect("compilation failed");
+		let clean_out_dir = project_dir.join("clean");
+		let _ = fs::remove_dir_all(&clean_out_dir);
+		compile(project_dir, &main, None, &clean_out_dir).expect("compilation failed");
+		assert_eq!(read_outputs(out_dir), read_outputs(&clean_out_dir));
+	}
+
+	#[test]
+	fn compile_incremental_reuses_cached_files() {
+		let temp_dir = tempfile::tempdir().expect("Failed to create temporary directory");
+		let project_dir = Utf8Path::from_path(temp_dir.path()).expect("invalid unicode path");
+		let out_dir = project_dir.join("target");
+		let main = project_dir.join("main.w");
+		fs::write(project_dir.join("lib.w"), LIB).unwrap();
+		fs::write(&main, MAIN).unwrap();
+		compile_and_compare(project_dir, &out_dir, &mut TypeSystem::new());
+
+		// Nothing changed, so nothing is type checked (the SDK isn't even loaded)
+		let mut jsii_types = TypeSystem::new();
+		let mut source_paths = vec![];
+		compile_incremental(
+			project_dir,
+			&main,
+			None,
+			&out_dir,
+			&mut jsii_types,
+			&mut source_paths,
+			true,
+		)
+		.expect("compilation failed");
+		assert!(!jsii_types.includes_assembly(WINGSDK_ASSEMBLY_NAME));
+		source_paths.sort();
+		assert_eq!(source_paths, [project_dir.join("lib.w"), main.clone()]);
+
+		// Only the entrypoint changed, so the library's symbols and JS are restored from the cache
+		fs::write(
+			&main,
+			format!("{MAIN}let b = new lib.Box<str>(\"c\");\nlog(b.item);\ntest \"hello\" {{ log(g.hello()); }}\n"),
+		)
+		.unwrap();
+		compile_and_compare(project_dir, &out_dir, &mut TypeSystem::new());
+
+		// The library changed, so both files are compiled again
+		fs::write(project_dir.join("lib.w"), LIB.replace("hello {", "hi {")).unwrap();
+		compile_and_compare(project_dir, &out_dir, &mut TypeSystem::new());
+		assert!(fs::read_to_string(out_dir.join("inflight.Greeter-1.cjs"))
+			.unwrap()
+			.contains("hi "));
+	}
+
+	#[test]
+	fn compile_incremental_falls_back_to_compiling_everything() {
+		let temp_dir = tempfile::tempdir().expect("Failed to create temporary directory");
+		let project_dir = Utf8Path::from_path(temp_dir.path()).expect("invalid unicode path");
+		let out_dir = project_dir.join("target");
+		let main = project_dir.join("main.w");
+		fs::write(project_dir.join("lib.w"), LIB).unwrap();
+		fs::write(&main, MAIN).unwrap();
+
+		// Nothing is cached when the cache is disabled
+		compile_incremental(
+			project_dir,
+			&main,
+			None,
+			&out_dir,
+			&mut TypeSystem::new(),
+			&mut vec![],
+			false,
+		)
+		.expect("compilation failed");
+		assert!(!out_dir.join(CACHE_DIR_NAME).exists());
+		compile_and_compare(project_dir, &out_dir, &mut TypeSystem::new());
+
+		// The library's cached symbols refer to types that aren't cached, so they can't be restored
+		let entry_path = entry_path(&out_dir.join(CACHE_DIR_NAME), &project_dir.join("lib.w"));
+		let mut entry: serde_json::Value = serde_json::from_str(&fs::read_to_string(&entry_path).unwrap()).unwrap();
+		entry["exports"]["types"] = serde_json::json!([]);
+		fs::write(&entry_path, entry.to_string()).unwrap();
+		fs::write(&main, format!("{MAIN}log(g.prefix);\n")).unwrap();
+		reset_diagnostics();
+		compile_and_compare(project_dir, &out_dir, &mut TypeSystem::new());
+		assert!(get_diagnostics()
+			.iter()
+			.any(|diagnostic| diagnostic.message.starts_with("Ignoring the compile cache")));
+	}
+
+	/// Files brought by the entrypoint (`main.w`): `lib.w` brings `util.w`, and `other.w` doesn't bring anything
+	const PROJECT: [(&str, &str); 4] = [
+		("main.w", "bring \"./lib.w\" as lib;\nbring \"./other.w\" as other;\n"),
+		("lib.w", "bring \"./util.w\" as util;\n"),
+		("util.w", "let x = 1;\n"),
+		("other.w", "let y = 2;\n"),
+	];
+
+	fn parse_project(project_dir: &Utf8Path) -> (Files, FileGraph, Vec<File>) {
+		let mut files = Files::new();
+		let mut file_graph = FileGraph::default();
+		let topo_sorted_files = parse_wing_project(
+			&File::new(project_dir.join("main.w"), DEFAULT_PACKAGE_NAME),
+			None,
+			&mut files,
+			&mut file_graph,
+			&mut IndexMap::new(),
+			&mut IndexMap::new(),
+		);
+		(files, file_graph, topo_sorted_files)
+	}
+
+	fn load_cache(project_dir: &Utf8Path, out_dir: &Utf8Path) -> CompileCache {
+		let (files, file_graph, topo_sorted_files) = parse_project(project_dir);
+		let fingerprint = fingerprint(project_dir, &project_dir.join("main.w"), out_dir);
+		CompileCache::load(out_dir, Some(fingerprint), &topo_sorted_files, &file_graph, &files)
+	}
+
+	/// Caches the files that aren't cached as if they were compiled (without type checking them, so the SDK isn't
+	/// needed), each with a warning that has no span. Returns their names.
+	fn fake_compile(project_dir: &Utf8Path, out_dir: &Utf8Path) -> Vec<String> {
+		let (files, file_graph, topo_sorted_files) = parse_project(project_dir);
+		let mut cache = load_cache(project_dir, out_dir);
+		let mut types = Types::new();
+		let mut compiled = vec![];
+		for file in &topo_sorted_files {
+			if cache.contains(&file.path) {
+				continue;
+			}
+			let env = types.add_symbol_env(SymbolEnv::new(
+				None,
+				SymbolEnvKind::Scope,
+				Phase::Preflight,
+				0,
+				file.package.clone(),
+			));
+			types
+				.source_file_envs
+				.insert(file.path.clone(), SymbolEnvOrNamespace::SymbolEnv(env));
+			let name = file.path.file_name().unwrap().to_string();
+			cache.add_compiled_file(&file.path, 0..0);
+			cache.add_diagnostics(
+				&file.path,
+				vec![Diagnostic {
+					message: format!("warning from {name}"),
+					span: None,
+					annotations: vec![],
+					hints: vec![],
+					severity: DiagnosticSeverity::Warning,
+				}],
+			);
+			compiled.push(name);
+		}
+		cache.save(
+			project_dir,
+			&topo_sorted_files,
+			&file_graph,
+			&files,
+			&types,
+			&TypeSystem::new(),
+		);
+		compiled.sort();
+		compiled
+	}
+
+	fn create_project() -> (tempfile::TempDir, Utf8PathBuf, Utf8PathBuf) {
+		let temp_dir = tempfile::tempdir().expect("Failed to create temporary directory");
+		let project_dir = Utf8Path::from_path(temp_dir.path())
+			.expect("invalid unicode path")
+			.to_path_buf();
+		let out_dir = project_dir.join("target");
+		for (name, source) in PROJECT {
+			fs::write(project_dir.join(name), source).unwrap();
+		}
+		(temp_dir, project_dir, out_dir)
+	}
+
+	#[test]
+	fn cache_invalidates_files_that_depend_on_changed_files() {
+		let (_temp_dir, project_dir, out_dir) = create_project();
+		assert_eq!(
+			fake_compile(&project_dir, &out_dir),
+			["lib.w", "main.w", "other.w", "util.w"]
+		);
+		assert!(fake_compile(&project_dir, &out_dir).is_empty());
+
+		// Files that bring a changed file (directly or not) are compiled again
+		fs::write(project_dir.join("util.w"), "let x = 3;\n").unwrap();
+		assert_eq!(fake_compile(&project_dir, &out_dir), ["lib.w", "main.w", "util.w"]);
+
+		fs::write(project_dir.join("lib.w"), "bring \"./util.w\" as util;\nlet z = 4;\n").unwrap();
+		assert_eq!(fake_compile(&project_dir, &out_dir), ["lib.w", "main.w"]);
+
+		fs::write(project_dir.join("main.w"), format!("{}log(\"hi\");\n", PROJECT[0].1)).unwrap();
+		assert_eq!(fake_compile(&project_dir, &out_dir), ["main.w"]);
+
+		// Changing the compiler's options invalidates every file
+		let other_out_dir = project_dir.join("other-target");
+		fs::create_dir_all(&other_out_dir).unwrap();
+		fs::rename(out_dir.join(CACHE_DIR_NAME), other_out_dir.join(CACHE_DIR_NAME)).unwrap();
+		assert_eq!(fake_compile(&project_dir, &other_out_dir).len(), PROJECT.len());
+	}
+
+	#[test]
+	fn cache_replays_diagnostics_without_span() {
+		let (_temp_dir, project_dir, out_dir) = create_project();
+		fake_compile(&project_dir, &out_dir);
+		fs::write(project_dir.join("other.w"), "let y = 3;\n").unwrap();
+
+		reset_diagnostics();
+		load_cache(&project_dir, &out_dir).replay_diagnostics();
+		let mut messages = get_diagnostics()
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect::<Vec<_>>();
+		messages.sort();
+		assert_eq!(messages, ["warning from lib.w", "warning from util.w"]);
+	}
+
+	#[test]
+	fn cache_is_ignored_when_an_entry_cant_be_read() {
+		let (_temp_dir, project_dir, out_dir) = create_project();
+		fake_compile(&project_dir, &out_dir);
+		fs::write(
+			entry_path(&out_dir.join(CACHE_DIR_NAME), &project_dir.join("lib.w")),
+			"{",
+		)
+		.unwrap();
+
+		// Even the files whose entries can be read are compiled again
+		assert_eq!(fake_compile(&project_dir, &out_dir).len(), PROJECT.len());
+		assert!(fake_compile(&project_dir, &out_dir).is_empty());
+	}
+}
@@ -5,7 +5,7 @@ use tree_sitter::Point;
 
 use lsp_types::{Position, Range};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::ast::Spanned;
 
@@ -17,7 +17,7 @@ pub type DiagnosticResult<T> = Result<T, ()>;
 pub const ERR_EXPECTED_SEMICOLON: &str = "Expected ';'";
 
 /// Line and character location in a UTF8 Wing source file
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct WingLocation {
 	pub line: u32,
 	pub col: u32,
@@ -86,7 +86,7 @@ impl Display for WingLocation {
 }
 
 /// A span of text in a Wing source file
-#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Serialize)]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct WingSpan {
 	pub start: WingLocation,
 	pub end: WingLocation,
@@ -275,7 +275,7 @@ impl PartialOrd for WingSpan {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Diagnostic {
 	pub message: String,
 	pub annotations: Vec<DiagnosticAnnotation>,
@@ -328,7 +328,7 @@ impl Diagnostic {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DiagnosticAnnotation {
 	pub message: String,
 	pub span: WingSpan,
@@ -343,7 +343,7 @@ impl DiagnosticAnnotation {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DiagnosticSeverity {
 	Error,
@@ -452,6 +452,21 @@ pub fn get_diagnostics() -> Vec<Diagnostic> {
 	})
 }
 
+/// Returns the number of diagnostics reported so far (see `get_diagnostics_since`)
+pub fn diagnostics_count() -> usize {
+	DIAGNOSTICS.with(|diagnostics| diagnostics.borrow().len())
+}
+
+/// Returns the diagnostics reported after the first `count` ones
+pub fn get_diagnostics_since(count: usize) -> Vec<Diagnostic> {
+	DIAGNOSTICS.with(|diagnostics| diagnostics.borrow().iter().skip(count).cloned().collect())
+}
+
+/// Discards the diagnostics reported after the first `count` ones
+pub fn truncate_diagnostics(count: usize) {
+	DIAGNOSTICS.with(|diagnostics| diagnostics.borrow_mut().truncate(count))
+}
+
 /// Reset diagnostics, this is useful if we perform more than one compilation
 /// in a single session
 pub fn reset_diagnostics() {
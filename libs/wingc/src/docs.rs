@@ -2,6 +2,7 @@ use std::{collections::BTreeMap, sync::OnceLock};
 
 use itertools::Itertools;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 use crate::{
 	ast::{AccessModifier, Phase},
@@ -14,7 +15,7 @@ use crate::{
 	},
 };
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Docs {
 	pub summary: Option<String>,
 	pub remarks: Option<String>,
@@ -170,7 +171,9 @@ impl Documented for TypeRef {
 			| Type::Map(_)
 			| Type::MutMap(_)
 			| Type::Set(_)
-			| Type::MutSet(_) => None,
+			| Type::MutSet(_)
+			| Type::Promise(_)
+			| Type::Union(_) => None,
 		}
 	}
 
@@ -207,7 +210,9 @@ impl Documented for TypeRef {
 			| Type::MutMap(_)
 			| Type::Set(_)
 			| Type::Stringable
-			| Type::MutSet(_) => "".to_string(),
+			| Type::MutSet(_)
+			| Type::Promise(_)
+			| Type::Union(_) => "".to_string(),
 		}
 	}
 }
@@ -165,6 +165,11 @@ impl<'a> ExternDTSifier<'a> {
 			Type::MutMap(t) => format!("Record<string, {}>", self.dtsify_type(*t, is_inflight)),
 			Type::Set(t) => format!("Readonly<Set<{}>>", self.dtsify_type(*t, is_inflight)),
 			Type::MutSet(t) => format!("Set<{}>", self.dtsify_type(*t, is_inflight)),
+			Type::Promise(t) => format!("Promise<{}>", self.dtsify_type(*t, is_inflight)),
+			Type::Union(members) => members
+				.iter()
+				.map(|t| format!("({})", self.dtsify_type(*t, is_inflight)))
+				.join(" | "),
 			Type::Function(f) => self.dtsify_function_signature(&f, is_inflight),
 			Type::Class(_) | Type::Interface(_) | Type::Struct(_) | Type::Enum(_) => {
 				self.resolve_named_type(type_, is_inflight)
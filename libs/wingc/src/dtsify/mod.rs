@@ -10,7 +10,7 @@ use crate::{
 	file_graph::{File, FileGraph},
 	files::Files,
 	jsify::codemaker::CodeMaker,
-	type_check::Types,
+	type_check::{Type, TypeRef, Types},
 	WINGSDK_ASSEMBLY_NAME,
 };
 pub mod extern_dtsify;
@@ -19,7 +19,7 @@ pub const TYPE_INFLIGHT_POSTFIX: &str = "$Inflight";
 const TYPE_INTERNAL_NAMESPACE: &str = "$internal";
 const TYPE_STD: &str = "std";
 const EMIT_FILE_EXTENSION: &str = "cjs";
-const EMIT_TYPE_FILE_EXTENSION: &str = "d.cts";
+pub const EMIT_TYPE_FILE_EXTENSION: &str = "d.cts";
 
 pub struct DTSifier<'a> {
 	preflight_file_map: &'a IndexMap<Utf8PathBuf, String>,
@@ -95,7 +95,11 @@ impl<'a> DTSifier<'a> {
 			return_type
 		};
 
-		let func = format!("({}) => {return_type}", args,);
+		let func = format!(
+			"{}({}) => {return_type}",
+			self.dtsify_type_parameters(&f.type_parameters),
+			args,
+		);
 
 		if is_inflight && !as_inflight {
 			// this is the preflight side of an inflight function
@@ -113,7 +117,10 @@ impl<'a> DTSifier<'a> {
 			interface.name.name.to_string()
 		};
 
-		code.line(format!("export interface {interface_name}"));
+		code.line(format!(
+			"export interface {interface_name}{}",
+			self.dtsify_type_parameters(&interface.type_parameters)
+		));
 
 		if !interface.extends.is_empty() {
 			code.append(" extends ");
@@ -121,13 +128,7 @@ impl<'a> DTSifier<'a> {
 				&interface
 					.extends
 					.iter()
-					.map(|udt| {
-						if as_inflight {
-							format!("{udt}{TYPE_INFLIGHT_POSTFIX}")
-						} else {
-							udt.to_string()
-						}
-					})
+					.map(|udt| self.dtsify_user_defined_type(udt, as_inflight))
 					.join(", "),
 			);
 		}
@@ -145,6 +146,23 @@ impl<'a> DTSifier<'a> {
 				self.dtsify_function_signature(&method.1, as_inflight)
 			));
 		}
+		for field in interface
+			.fields
+			.iter()
+			.filter(|f| !ignore_member_phase(f.phase, as_inflight))
+		{
+			code.line(format!(
+				"{}{}{}: {};",
+				if field.reassignable { "" } else { "readonly " },
+				field.name,
+				if matches!(field.member_type.kind, TypeAnnotationKind::Optional(_)) {
+					"?"
+				} else {
+					""
+				},
+				self.dtsify_type_annotation(&field.member_type, true)
+			));
+		}
 
 		code.close("}");
 
@@ -159,33 +177,27 @@ impl<'a> DTSifier<'a> {
 			class.name.name.to_string()
 		};
 
+		let type_parameters = self.dtsify_type_parameters(&class.type_parameters);
+
 		code.line("export class ");
 
-		code.append(&class_name);
+		code.append(format!("{class_name}{type_parameters}"));
 		if let Some(parent) = &class.parent {
 			code.append(" extends ");
-			if as_inflight {
-				code.append(format!("{parent}{TYPE_INFLIGHT_POSTFIX}"));
-			} else {
-				code.append(parent.to_string());
-			}
+			code.append(self.dtsify_user_defined_type(parent, as_inflight));
 		} else if !as_inflight && matches!(class.phase, Phase::Preflight) {
 			code.append(format!(" extends {TYPE_STD}.Resource"));
 		}
 
 		if !class.implements.is_empty() {
 			code.append(" implements ");
-			if as_inflight {
-				code.append(
-					&class
-						.implements
-						.iter()
-						.map(|udt| format!("{udt}{TYPE_INFLIGHT_POSTFIX}"))
-						.join(", "),
-				);
-			} else {
-				code.append(&class.implements.iter().map(|udt| udt.to_string()).join(", "));
-			}
+			code.append(
+				&class
+					.implements
+					.iter()
+					.map(|udt| self.dtsify_user_defined_type(udt, as_inflight))
+					.join(", "),
+			);
 		}
 
 		code.open("{");
@@ -212,7 +224,7 @@ impl<'a> DTSifier<'a> {
 
 		if !as_inflight {
 			// Emit special preflight marks to tie the inflight side together
-			let inflight_class_name = format!("{class_name}{TYPE_INFLIGHT_POSTFIX}");
+			let inflight_class_name = format!("{class_name}{TYPE_INFLIGHT_POSTFIX}{type_parameters}");
 			code.line(format!(
 				"[{TYPE_INTERNAL_NAMESPACE}.INFLIGHT_SYMBOL]?: {inflight_class_name};"
 			));
@@ -225,7 +237,8 @@ impl<'a> DTSifier<'a> {
 			.filter(|f| !ignore_member_phase(f.phase, as_inflight))
 		{
 			code.line(format!(
-				"{}{}: {};",
+				"{}{}{}: {};",
+				if field.is_static { "static " } else { "" },
 				field.name,
 				if matches!(field.member_type.kind, TypeAnnotationKind::Optional(_)) {
 					"?"
@@ -261,14 +274,18 @@ impl<'a> DTSifier<'a> {
 				code.line(self.dtsify_interface(interface, true));
 			}
 			StmtKind::Struct(st) => {
+				let type_parameters = self.dtsify_type_parameters(&st.type_parameters);
 				if !st.extends.is_empty() {
 					code.open(format!(
-						"export interface {} extends {} {{",
+						"export interface {}{type_parameters} extends {} {{",
 						st.name.name,
-						st.extends.iter().map(|udt| udt.to_string()).join(", ")
+						st.extends
+							.iter()
+							.map(|udt| self.dtsify_user_defined_type(udt, false))
+							.join(", ")
 					));
 				} else {
-					code.open(format!("export interface {} {{", st.name.name));
+					code.open(format!("export interface {}{type_parameters} {{", st.name.name));
 				}
 
 				for field in &st.fields {
@@ -320,6 +337,24 @@ impl<'a> DTSifier<'a> {
 				code.line(self.dtsify_class(class, false));
 				code.line(self.dtsify_class(class, true));
 			}
+			StmtKind::Let {
+				var_name,
+				initial_value,
+				type_,
+				access: AccessModifier::Public | AccessModifier::Internal,
+				..
+			} => {
+				let type_ = if let Some(type_) = type_ {
+					self.dtsify_type_annotation(type_, false)
+				} else {
+					Self::dtsify_inferred_type(
+						self
+							.types
+							.maybe_unwrap_inference(self.types.get_expr_type(initial_value)),
+					)
+				};
+				code.line(format!("export const {}: {type_};", var_name.name));
+			}
 
 			// No need to emit anything for these
 			StmtKind::SuperConstructor { .. }
@@ -328,6 +363,7 @@ impl<'a> DTSifier<'a> {
 			| StmtKind::While { .. }
 			| StmtKind::IfLet(_)
 			| StmtKind::If { .. }
+			| StmtKind::Match(_)
 			| StmtKind::Break
 			| StmtKind::Continue
 			| StmtKind::Return(_)
@@ -373,6 +409,7 @@ impl<'a> DTSifier<'a> {
 			TypeAnnotationKind::String => "string".to_string(),
 			TypeAnnotationKind::Bool => "boolean".to_string(),
 			TypeAnnotationKind::Void => "void".to_string(),
+			TypeAnnotationKind::Any => "any".to_string(),
 			TypeAnnotationKind::Json => format!("Readonly<{TYPE_INTERNAL_NAMESPACE}.Json>"),
 			TypeAnnotationKind::MutJson => format!("{TYPE_INTERNAL_NAMESPACE}.Json"),
 			TypeAnnotationKind::Duration => format!("{TYPE_STD}.Duration"),
@@ -392,9 +429,67 @@ impl<'a> DTSifier<'a> {
 			}
 			TypeAnnotationKind::Set(t) => format!("Readonly<Set<{}>>", self.dtsify_type_annotation(&t, ignore_phase)),
 			TypeAnnotationKind::MutSet(t) => format!("Set<{}>", self.dtsify_type_annotation(&t, ignore_phase)),
+			TypeAnnotationKind::Promise(t) => format!("Promise<{}>", self.dtsify_type_annotation(&t, ignore_phase)),
+			TypeAnnotationKind::Union(members) => members
+				.iter()
+				.map(|t| format!("({})", self.dtsify_type_annotation(t, ignore_phase)))
+				.join(" | "),
 			TypeAnnotationKind::Function(f) => self.dtsify_function_signature(f, ignore_phase),
-			TypeAnnotationKind::UserDefined(udt) => udt.to_string(),
+			TypeAnnotationKind::UserDefined(udt) => self.dtsify_user_defined_type(udt, false),
+		}
+	}
+
+	/// The declaration of a type that has no annotation in the source (like the type of `let x = 1;`)
+	fn dtsify_inferred_type(type_: TypeRef) -> String {
+		match &*type_ {
+			Type::Number => "number".to_string(),
+			Type::String => "string".to_string(),
+			Type::Boolean => "boolean".to_string(),
+			Type::Void => "void".to_string(),
+			Type::Anything => "any".to_string(),
+			Type::Json(_) => format!("Readonly<{TYPE_INTERNAL_NAMESPACE}.Json>"),
+			Type::MutJson => format!("{TYPE_INTERNAL_NAMESPACE}.Json"),
+			Type::Duration => format!("{TYPE_STD}.Duration"),
+			Type::Datetime => format!("{TYPE_STD}.Datetime"),
+			Type::Regex => format!("{TYPE_STD}.Regex"),
+			Type::Optional(t) => format!("({}) | undefined", Self::dtsify_inferred_type(*t)),
+			Type::Array(t) => format!("(readonly ({})[])", Self::dtsify_inferred_type(*t)),
+			Type::MutArray(t) => format!("({})[]", Self::dtsify_inferred_type(*t)),
+			Type::Map(t) => format!("Readonly<Record<string, {}>>", Self::dtsify_inferred_type(*t)),
+			Type::MutMap(t) => format!("Record<string, {}>", Self::dtsify_inferred_type(*t)),
+			Type::Set(t) => format!("Readonly<Set<{}>>", Self::dtsify_inferred_type(*t)),
+			Type::MutSet(t) => format!("Set<{}>", Self::dtsify_inferred_type(*t)),
+			Type::Union(members) => members
+				.iter()
+				.map(|t| format!("({})", Self::dtsify_inferred_type(*t)))
+				.join(" | "),
+			Type::Struct(_) | Type::Enum(_) | Type::Class(_) | Type::Interface(_) => type_.to_string(),
+			_ => "any".to_string(),
+		}
+	}
+
+	fn dtsify_type_parameters(&self, type_parameters: &[Symbol]) -> String {
+		if type_parameters.is_empty() {
+			return String::new();
+		}
+		format!("<{}>", type_parameters.iter().map(|p| &p.name).join(", "))
+	}
+
+	/// The type arguments of a user defined type follow the inflight postfix (e.g. `Box$Inflight<number>`)
+	fn dtsify_user_defined_type(&self, udt: &UserDefinedType, as_inflight: bool) -> String {
+		let postfix = if as_inflight { TYPE_INFLIGHT_POSTFIX } else { "" };
+		if udt.type_arguments.is_empty() {
+			return format!("{}{postfix}", udt.full_path_str());
 		}
+		format!(
+			"{}{postfix}<{}>",
+			udt.full_path_str(),
+			udt
+				.type_arguments
+				.iter()
+				.map(|t| self.dtsify_type_annotation(t, as_inflight))
+				.join(", ")
+		)
 	}
 }
 
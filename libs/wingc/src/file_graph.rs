@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
 use camino::Utf8PathBuf;
-use indexmap::IndexMap;
-use petgraph::visit::EdgeRef;
+use indexmap::{IndexMap, IndexSet};
+use petgraph::{visit::EdgeRef, Direction};
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct File {
@@ -79,6 +79,25 @@ impl FileGraph {
 			.collect::<Vec<_>>()
 	}
 
+	/// Returns a list of all files that depend on the given file, directly or transitively.
+	/// The given file itself is not included (unless it is part of a cycle).
+	/// Returns an empty list if the file is not in the graph.
+	pub fn dependents_of(&self, file: &File) -> Vec<&File> {
+		let Some(node_index) = self.path_to_node_index.get(file) else {
+			return vec![];
+		};
+		let mut dependents = IndexSet::new();
+		let mut stack = vec![*node_index];
+		while let Some(node_index) = stack.pop() {
+			for dependent in self.graph.neighbors_directed(node_index, Direction::Incoming) {
+				if dependents.insert(dependent) {
+					stack.push(dependent);
+				}
+			}
+		}
+		dependents.into_iter().map(|n| &self.graph[n]).collect::<Vec<_>>()
+	}
+
 	/// Returns a list of files in the order they should be compiled, if a topological sort exists.
 	/// If there is a cycle in the graph, returns an error with a list of files that are part of a cycle.
 	/// (Note that there might be more than one cycle.)
@@ -283,6 +302,31 @@ mod tests {
 		assert_eq!(file_paths(&graph.toposort().unwrap()), ["c", "a", "b"]);
 	}
 
+	#[test]
+	fn dependents_transitive() {
+		// graph with 4 nodes, where A depends on B, B depends on C, and D depends on C
+		let mut graph = FileGraph::default();
+		let a = File::new("a", "pkg");
+		let b = File::new("b", "pkg");
+		let c = File::new("c", "pkg");
+		let d = File::new("d", "pkg");
+		graph.set_file_deps(&a, [&b]);
+		graph.set_file_deps(&b, [&c]);
+		graph.set_file_deps(&c, []);
+		graph.set_file_deps(&d, [&c]);
+
+		let mut dependents = graph
+			.dependents_of(&c)
+			.into_iter()
+			.map(|f| f.path.as_str())
+			.collect_vec();
+		dependents.sort();
+		assert_eq!(dependents, ["a", "b", "d"]);
+		assert_eq!(graph.dependents_of(&b), [&a]);
+		assert!(graph.dependents_of(&a).is_empty());
+		assert!(graph.dependents_of(&File::new("e", "pkg")).is_empty());
+	}
+
 	fn file_paths(files: &Vec<File>) -> Vec<&str> {
 		files.iter().map(|x| x.path.as_str()).collect_vec()
 	}
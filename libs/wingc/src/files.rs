@@ -75,6 +75,11 @@ impl Files {
 		self.data.contains_key(path.as_ref())
 	}
 
+	/// The paths of all files.
+	pub fn paths(&self) -> impl Iterator<Item = &Utf8Path> {
+		self.data.keys().map(|path| path.as_path())
+	}
+
 	/// Write all files to the given directory.
 	pub fn emit_files(&self, out_dir: &Utf8Path) -> Result<(), FilesError> {
 		for (path, content) in &self.data {
@@ -1,8 +1,9 @@
 use crate::ast::{
 	ArgList, BringSource, CalleeKind, CatchBlock, Class, ClassField, ElseIfBlock, ElseIfLetBlock, ElseIfs, Enum,
 	ExplicitLift, Expr, ExprKind, FunctionBody, FunctionDefinition, FunctionParameter, FunctionSignature, IfLet,
-	Interface, InterpolatedString, InterpolatedStringPart, Intrinsic, LiftQualification, Literal, New, Reference, Scope,
-	Stmt, StmtKind, Struct, StructField, Symbol, TypeAnnotation, TypeAnnotationKind, UserDefinedType,
+	Interface, InterpolatedString, InterpolatedStringPart, Intrinsic, LiftQualification, Literal, Match, MatchArm,
+	MatchArmBody, MatchPattern, MatchPatternKind, New, Reference, Scope, Stmt, StmtKind, Struct, StructField, Symbol,
+	TypeAnnotation, TypeAnnotationKind, UserDefinedType,
 };
 
 /// Similar to the `visit` module in `wingc` except each method takes ownership of an
@@ -39,6 +40,12 @@ pub trait Fold {
 	fn fold_new_expr(&mut self, node: New) -> New {
 		fold_new_expr(self, node)
 	}
+	fn fold_match(&mut self, node: Match) -> Match {
+		fold_match(self, node)
+	}
+	fn fold_match_pattern(&mut self, node: MatchPattern) -> MatchPattern {
+		fold_match_pattern(self, node)
+	}
 	fn fold_literal(&mut self, node: Literal) -> Literal {
 		fold_literal(self, node)
 	}
@@ -100,11 +107,13 @@ where
 			var_name,
 			initial_value,
 			type_,
+			access,
 		} => StmtKind::Let {
 			reassignable,
 			var_name: f.fold_symbol(var_name),
 			initial_value: f.fold_expr(initial_value),
 			type_: type_.map(|type_| f.fold_type_annotation(type_)),
+			access,
 		},
 		StmtKind::ForLoop {
 			iterator,
@@ -124,6 +133,7 @@ where
 			statements,
 			reassignable,
 			var_name,
+			type_,
 			else_if_statements,
 			else_statements,
 		}) => StmtKind::IfLet(IfLet {
@@ -131,6 +141,7 @@ where
 			statements: f.fold_scope(statements),
 			reassignable,
 			var_name: f.fold_symbol(var_name),
+			type_: type_.map(|t| f.fold_type_annotation(t)),
 			else_if_statements: else_if_statements
 				.into_iter()
 				.map(|else_if_block| match else_if_block {
@@ -146,6 +157,7 @@ where
 							statements: f.fold_scope(else_if_let_block.statements),
 							value: f.fold_expr(else_if_let_block.value),
 							var_name: f.fold_symbol(else_if_let_block.var_name),
+							type_: else_if_let_block.type_.map(|t| f.fold_type_annotation(t)),
 						});
 					}
 				})
@@ -169,6 +181,7 @@ where
 				.collect(),
 			else_statements: else_statements.map(|statements| f.fold_scope(statements)),
 		},
+		StmtKind::Match(match_) => StmtKind::Match(f.fold_match(match_)),
 		StmtKind::Break => StmtKind::Break,
 		StmtKind::Continue => StmtKind::Continue,
 		StmtKind::Return(value) => StmtKind::Return(value.map(|value| f.fold_expr(value))),
@@ -225,6 +238,11 @@ where
 {
 	Class {
 		name: f.fold_symbol(node.name),
+		type_parameters: node
+			.type_parameters
+			.into_iter()
+			.map(|param| f.fold_symbol(param))
+			.collect(),
 		span: node.span,
 		fields: node.fields.into_iter().map(|field| f.fold_class_field(field)).collect(),
 		methods: node
@@ -258,6 +276,7 @@ where
 		is_static: node.is_static,
 		access: node.access,
 		doc: node.doc,
+		initial_value: node.initial_value.map(|v| f.fold_expr(v)),
 	}
 }
 
@@ -278,11 +297,17 @@ where
 {
 	Interface {
 		name: f.fold_symbol(node.name),
+		type_parameters: node
+			.type_parameters
+			.into_iter()
+			.map(|param| f.fold_symbol(param))
+			.collect(),
 		methods: node
 			.methods
 			.into_iter()
 			.map(|(name, sig, doc)| (f.fold_symbol(name), f.fold_function_signature(sig), doc))
 			.collect(),
+		fields: node.fields.into_iter().map(|field| f.fold_class_field(field)).collect(),
 		extends: node
 			.extends
 			.into_iter()
@@ -299,6 +324,11 @@ where
 {
 	Struct {
 		name: f.fold_symbol(node.name),
+		type_parameters: node
+			.type_parameters
+			.into_iter()
+			.map(|param| f.fold_symbol(param))
+			.collect(),
 		extends: node.extends.into_iter().map(|e| f.fold_user_defined_type(e)).collect(),
 		fields: node
 			.fields
@@ -387,6 +417,9 @@ where
 			element: Box::new(f.fold_expr(*element)),
 		},
 		ExprKind::FunctionClosure(def) => ExprKind::FunctionClosure(f.fold_function_definition(def)),
+		ExprKind::Match(match_) => ExprKind::Match(f.fold_match(match_)),
+		ExprKind::Await(exp) => ExprKind::Await(Box::new(f.fold_expr(*exp))),
+		ExprKind::Defer(exp) => ExprKind::Defer(Box::new(f.fold_expr(*exp))),
 	};
 	Expr {
 		id: node.id,
@@ -395,6 +428,45 @@ where
 	}
 }
 
+pub fn fold_match<F>(f: &mut F, node: Match) -> Match
+where
+	F: Fold + ?Sized,
+{
+	Match {
+		value: Box::new(f.fold_expr(*node.value)),
+		arms: node
+			.arms
+			.into_iter()
+			.map(|arm| MatchArm {
+				patterns: arm.patterns.into_iter().map(|p| f.fold_match_pattern(p)).collect(),
+				body: match arm.body {
+					MatchArmBody::Statements(scope) => MatchArmBody::Statements(f.fold_scope(scope)),
+					MatchArmBody::Expression(expr) => MatchArmBody::Expression(f.fold_expr(expr)),
+				},
+				span: arm.span,
+			})
+			.collect(),
+	}
+}
+
+pub fn fold_match_pattern<F>(f: &mut F, node: MatchPattern) -> MatchPattern
+where
+	F: Fold + ?Sized,
+{
+	let kind = match node.kind {
+		MatchPatternKind::Wildcard => MatchPatternKind::Wildcard,
+		MatchPatternKind::Binding(name) => MatchPatternKind::Binding(f.fold_symbol(name)),
+		MatchPatternKind::Value(value) => MatchPatternKind::Value(f.fold_expr(value)),
+		MatchPatternKind::Struct(fields) => MatchPatternKind::Struct(
+			fields
+				.into_iter()
+				.map(|(name, pattern)| (f.fold_symbol(name), f.fold_match_pattern(pattern)))
+				.collect(),
+		),
+	};
+	MatchPattern { kind, span: node.span }
+}
+
 pub fn fold_new_expr<F>(f: &mut F, node: New) -> New
 where
 	F: Fold + ?Sized,
@@ -487,6 +559,11 @@ where
 	F: Fold + ?Sized,
 {
 	FunctionSignature {
+		type_parameters: node
+			.type_parameters
+			.into_iter()
+			.map(|param| f.fold_symbol(param))
+			.collect(),
 		parameters: node
 			.parameters
 			.into_iter()
@@ -537,6 +614,7 @@ where
 		TypeAnnotationKind::Datetime => TypeAnnotationKind::Datetime,
 		TypeAnnotationKind::Regex => TypeAnnotationKind::Regex,
 		TypeAnnotationKind::Void => TypeAnnotationKind::Void,
+		TypeAnnotationKind::Any => TypeAnnotationKind::Any,
 		TypeAnnotationKind::Json => TypeAnnotationKind::Json,
 		TypeAnnotationKind::MutJson => TypeAnnotationKind::MutJson,
 		TypeAnnotationKind::Optional(t) => TypeAnnotationKind::Optional(Box::new(f.fold_type_annotation(*t))),
@@ -546,7 +624,12 @@ where
 		TypeAnnotationKind::MutMap(t) => TypeAnnotationKind::MutMap(Box::new(f.fold_type_annotation(*t))),
 		TypeAnnotationKind::Set(t) => TypeAnnotationKind::Set(Box::new(f.fold_type_annotation(*t))),
 		TypeAnnotationKind::MutSet(t) => TypeAnnotationKind::MutSet(Box::new(f.fold_type_annotation(*t))),
+		TypeAnnotationKind::Promise(t) => TypeAnnotationKind::Promise(Box::new(f.fold_type_annotation(*t))),
+		TypeAnnotationKind::Union(members) => {
+			TypeAnnotationKind::Union(members.into_iter().map(|t| f.fold_type_annotation(t)).collect())
+		}
 		TypeAnnotationKind::Function(t) => TypeAnnotationKind::Function(FunctionSignature {
+			type_parameters: t.type_parameters.into_iter().map(|p| f.fold_symbol(p)).collect(),
 			parameters: t.parameters.into_iter().map(|p| f.fold_function_parameter(p)).collect(),
 			return_type: Box::new(f.fold_type_annotation(*t.return_type)),
 			phase: t.phase,
@@ -565,6 +648,11 @@ where
 	UserDefinedType {
 		root: f.fold_symbol(node.root),
 		fields: node.fields.into_iter().map(|s| f.fold_symbol(s)).collect(),
+		type_arguments: node
+			.type_arguments
+			.into_iter()
+			.map(|t| f.fold_type_annotation(t))
+			.collect(),
 		span: node.span,
 	}
 }
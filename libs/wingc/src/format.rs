@@ -0,0 +1,511 @@
+use std::fmt::Display;
+
+use tree_sitter::{Node, Tree};
+
+const INDENT: &str = "  ";
+
+/// Nodes that are printed exactly as they appear in the source
+const VERBATIM_NODES: [&str; 4] = ["string", "non_interpolated_string", "comment", "doc"];
+
+/// Braces of these nodes always contain one statement (or member) per line
+const BLOCK_NODES: [&str; 4] = [
+	"block",
+	"class_implementation",
+	"interface_implementation",
+	"struct_definition",
+];
+
+const IDENTIFIER_NODES: [&str; 6] = [
+	"identifier",
+	"reference_identifier",
+	"member_identifier",
+	"type_identifier",
+	"intrinsic_identifier",
+	"keyword_argument_key",
+];
+
+const CONTAINER_TYPE_NODES: [&str; 2] = ["immutable_container_type", "mutable_container_type"];
+
+const BINARY_OPERATORS: [&str; 16] = [
+	"+", "-", "*", "/", "\\", "%", "**", "||", "&&", "==", "!=", ">", ">=", "<=", "<", "??",
+];
+
+/// Operators that two adjacent tokens would turn into if printed without a space between them (`- -x` isn't `--x`)
+const MERGEABLE_OPERATORS: [&str; 12] = ["--", "-=", "+=", "==", "!=", ">=", "<=", "**", "||", "&&", "??", "=>"];
+
+#[derive(Debug, PartialEq)]
+pub enum FormatError {
+	/// The source has syntax errors, formatting it could change its meaning
+	SyntaxError,
+	/// The formatted output doesn't contain the same tokens as the source (this is a bug in the formatter)
+	TokenMismatch,
+}
+
+impl Display for FormatError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FormatError::SyntaxError => write!(f, "Cannot format a file with syntax errors"),
+			FormatError::TokenMismatch => write!(f, "Formatting failed to preserve the source tokens"),
+		}
+	}
+}
+
+/// Parses `source` and formats it. See [format_tree].
+pub fn format_source(source: &str) -> Result<String, FormatError> {
+	let mut tree_sitter_parser = tree_sitter::Parser::new();
+	tree_sitter_parser.set_language(&tree_sitter_wing::language()).unwrap();
+	let tree = tree_sitter_parser
+		.parse(source.as_bytes(), None)
+		.ok_or(FormatError::SyntaxError)?;
+	format_tree(source, &tree)
+}
+
+/// Formats Wing source code in the canonical style, given its tree-sitter CST.
+///
+/// The formatter only changes whitespace between tokens: indentation is normalized, braces of
+/// blocks are kept on the line that opens them, every statement goes on its own line, spacing
+/// within a line is made consistent and consecutive blank lines are collapsed into one.
+/// Comments and doc comments are kept in place, and other line breaks are left up to the author.
+pub fn format_tree(source: &str, tree: &Tree) -> Result<String, FormatError> {
+	let root = tree.root_node();
+	if root.has_error() {
+		return Err(FormatError::SyntaxError);
+	}
+
+	let mut tokens = vec![];
+	let mut offset = 0;
+	collect_tokens(root, source, &mut offset, &mut tokens)?;
+
+	let mut printer = Printer::new();
+	for (i, token) in tokens.iter().enumerate() {
+		let prev = if i > 0 { Some(&tokens[i - 1]) } else { None };
+		printer.print(prev, token);
+	}
+	let formatted = printer.finish();
+
+	// Sanity check: only whitespace should ever change
+	let non_whitespace = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+	if non_whitespace(source) != non_whitespace(&formatted) {
+		return Err(FormatError::TokenMismatch);
+	}
+
+	Ok(formatted)
+}
+
+struct Token<'a> {
+	text: &'a str,
+	kind: &'static str,
+	parent_kind: &'static str,
+	/// Number of line breaks between the previous token and this one in the source
+	newlines_before: usize,
+}
+
+impl Token<'_> {
+	fn is(&self, text: &str) -> bool {
+		self.text == text
+	}
+
+	fn is_line_comment(&self) -> bool {
+		self.kind == "doc" || (self.kind == "comment" && self.text.starts_with("//"))
+	}
+
+	fn is_comment(&self) -> bool {
+		self.kind == "doc" || self.kind == "comment"
+	}
+
+	fn is_block_open(&self) -> bool {
+		self.is("{") && BLOCK_NODES.contains(&self.parent_kind)
+	}
+
+	fn is_block_close(&self) -> bool {
+		self.is("}") && BLOCK_NODES.contains(&self.parent_kind)
+	}
+
+	fn is_opener(&self) -> bool {
+		self.kind != "" && matches!(self.text, "{" | "[" | "(")
+	}
+
+	fn is_closer(&self) -> bool {
+		self.kind != "" && matches!(self.text, "}" | "]" | ")")
+	}
+
+	fn is_type_bracket(&self) -> bool {
+		matches!(self.text, "<" | ">") && CONTAINER_TYPE_NODES.contains(&self.parent_kind)
+	}
+
+	fn is_binary_operator(&self) -> bool {
+		self.parent_kind == "binary_expression" && BINARY_OPERATORS.contains(&self.text)
+	}
+
+	fn is_unary_operator(&self) -> bool {
+		self.parent_kind == "unary_expression" && matches!(self.text, "-" | "!" | "--")
+	}
+
+	fn is_assignment(&self) -> bool {
+		matches!(self.text, "=" | "+=" | "-=") && !self.is_inclusive_range()
+	}
+
+	/// The `=` in `0..=10`
+	fn is_inclusive_range(&self) -> bool {
+		self.kind == "inclusive_range" || self.parent_kind == "inclusive_range"
+	}
+}
+
+/// Collects the tokens of `node` in source order. Text between tokens that isn't whitespace (which
+/// happens for terminals that are hidden in the grammar) is kept as its own token.
+fn collect_tokens<'a>(
+	node: Node,
+	source: &'a str,
+	offset: &mut usize,
+	tokens: &mut Vec<Token<'a>>,
+) -> Result<(), FormatError> {
+	let is_leaf = node.child_count() == 0 || VERBATIM_NODES.contains(&node.kind());
+	if !is_leaf {
+		let mut cursor = node.walk();
+		for child in node.children(&mut cursor) {
+			collect_tokens(child, source, offset, tokens)?;
+		}
+		return Ok(());
+	}
+
+	if node.start_byte() == node.end_byte() {
+		// Zero width tokens are inserted by the scanner for missing semicolons and blocks
+		if node.kind() == "AUTOMATIC_SEMICOLON" || node.kind() == "AUTOMATIC_BLOCK" || node.is_missing() {
+			return Err(FormatError::SyntaxError);
+		}
+		return Ok(());
+	}
+
+	let mut newlines_before = 0;
+	for piece in split_whitespace_runs(&source[*offset..node.start_byte()]) {
+		if piece.trim().is_empty() {
+			newlines_before += piece.matches('\n').count();
+		} else {
+			tokens.push(Token {
+				text: piece,
+				kind: "",
+				parent_kind: "",
+				newlines_before,
+			});
+			newlines_before = 0;
+		}
+	}
+
+	let text = &source[node.start_byte()..node.end_byte()];
+	tokens.push(Token {
+		text: if node.kind() == "comment" || node.kind() == "doc" {
+			text.trim_end()
+		} else {
+			text
+		},
+		kind: node.kind(),
+		parent_kind: node.parent().map(|p| p.kind()).unwrap_or(""),
+		newlines_before,
+	});
+	*offset = node.end_byte();
+
+	Ok(())
+}
+
+/// Splits a string into alternating runs of whitespace and non whitespace characters
+fn split_whitespace_runs(s: &str) -> Vec<&str> {
+	let mut runs = vec![];
+	let mut start = 0;
+	let mut in_whitespace = None;
+	for (i, c) in s.char_indices() {
+		let ws = c.is_whitespace();
+		if in_whitespace.is_some() && in_whitespace != Some(ws) {
+			runs.push(&s[start..i]);
+			start = i;
+		}
+		in_whitespace = Some(ws);
+	}
+	if start < s.len() {
+		runs.push(&s[start..]);
+	}
+	runs
+}
+
+enum Separator {
+	None,
+	Space,
+	/// A line break, optionally followed by an empty line
+	Newline {
+		blank_line: bool,
+	},
+}
+
+struct OpenBracket {
+	/// The output line the bracket was opened on
+	line: usize,
+	/// Whether the contents of the bracket are indented (the bracket is followed by a line break)
+	indented: bool,
+}
+
+struct Printer {
+	out: String,
+	line: usize,
+	brackets: Vec<OpenBracket>,
+}
+
+impl Printer {
+	fn new() -> Self {
+		Self {
+			out: String::new(),
+			line: 0,
+			brackets: vec![],
+		}
+	}
+
+	fn print(&mut self, prev: Option<&Token>, token: &Token) {
+		if let Some(prev) = prev {
+			match separator(prev, token) {
+				Separator::None => {}
+				Separator::Space => self.out.push(' '),
+				Separator::Newline { blank_line } => self.newline(prev, token, blank_line),
+			}
+		}
+
+		if token.is_closer() {
+			self.brackets.pop();
+		}
+		self.out.push_str(token.text);
+		self.line += token.text.matches('\n').count();
+		if token.is_opener() {
+			self.brackets.push(OpenBracket {
+				line: self.line,
+				indented: false,
+			});
+		}
+	}
+
+	fn newline(&mut self, prev: &Token, token: &Token, blank_line: bool) {
+		// The innermost bracket opened on the line we're breaking gets its contents indented
+		if let Some(bracket) = self.brackets.last_mut() {
+			if bracket.line == self.line {
+				bracket.indented = true;
+			}
+		}
+
+		self.out.push('\n');
+		self.line += 1;
+		if blank_line && !prev.is_opener() && !token.is_closer() {
+			self.out.push('\n');
+			self.line += 1;
+		}
+
+		let mut indent = self.brackets.iter().filter(|b| b.indented).count();
+		if token.is_closer() && self.brackets.last().map_or(false, |b| b.indented) {
+			indent -= 1;
+		}
+		let continuation = token.is(".")
+			|| token.is("?.")
+			|| token.is_binary_operator()
+			|| prev.is_binary_operator()
+			|| prev.is_assignment();
+		if continuation && !token.is_comment() {
+			indent += 1;
+		}
+		self.out.push_str(&INDENT.repeat(indent));
+	}
+
+	fn finish(self) -> String {
+		let mut formatted = self.out.lines().map(|l| l.trim_end()).collect::<Vec<_>>().join("\n");
+		if !formatted.is_empty() {
+			formatted.push('\n');
+		}
+		formatted
+	}
+}
+
+fn separator(prev: &Token, token: &Token) -> Separator {
+	let original_newline = Separator::Newline {
+		blank_line: token.newlines_before > 1,
+	};
+	let trailing_comment = token.is_comment() && token.newlines_before == 0;
+
+	if prev.is_line_comment() {
+		return original_newline;
+	}
+
+	if token.is_block_open() {
+		return Separator::Space;
+	}
+
+	if prev.is_block_open() {
+		return if token.is_block_close() {
+			Separator::None
+		} else if trailing_comment {
+			Separator::Space
+		} else {
+			Separator::Newline { blank_line: false }
+		};
+	}
+
+	if token.is_block_close() {
+		return Separator::Newline { blank_line: false };
+	}
+
+	if prev.is_block_close() {
+		return if matches!(token.text, "else" | "catch" | "finally") || trailing_comment {
+			Separator::Space
+		} else if matches!(token.text, ")" | "]" | "," | ";" | "." | "?.") {
+			Separator::None
+		} else {
+			original_newline
+		};
+	}
+
+	if prev.is(";") {
+		return if trailing_comment {
+			Separator::Space
+		} else {
+			original_newline
+		};
+	}
+
+	if token.newlines_before > 0 {
+		return original_newline;
+	}
+
+	if needs_space(prev, token) {
+		Separator::Space
+	} else {
+		Separator::None
+	}
+}
+
+/// Whether two tokens on the same line should be separated by a space
+fn needs_space(prev: &Token, token: &Token) -> bool {
+	if token.is_comment() || would_merge(prev, token) {
+		return true;
+	}
+
+	if matches!(prev.text, "(" | "[" | "." | "?." | ".." | "...") || prev.is_unary_operator() || prev.is_inclusive_range()
+	{
+		return false;
+	}
+
+	if matches!(token.text, ")" | "]" | "," | ";" | "." | "?." | ".." | ":") {
+		return false;
+	}
+
+	// Postfix operators: `x!` and `str?`
+	if (token.is("!") && token.parent_kind == "optional_unwrap") || (token.is("?") && token.parent_kind == "optional") {
+		return false;
+	}
+
+	if token.is_type_bracket() || (prev.is("<") && prev.is_type_bracket()) {
+		return false;
+	}
+
+	let prev_is_callee = IDENTIFIER_NODES.contains(&prev.kind)
+		|| matches!(prev.text, ")" | "]" | "new" | "super")
+		|| (prev.is(">") && prev.is_type_bracket());
+	if matches!(token.text, "(" | "[") && prev_is_callee {
+		return false;
+	}
+
+	// Typed collection literals: `MutMap<num>{}`
+	if token.is("{") && prev.is(">") && prev.is_type_bracket() {
+		return false;
+	}
+
+	if prev.is("{") && token.is("}") {
+		return false;
+	}
+
+	true
+}
+
+/// Whether printing `token` right after `prev` would read as a different operator
+fn would_merge(prev: &Token, token: &Token) -> bool {
+	match (prev.text.chars().last(), token.text.chars().next()) {
+		(Some(last), Some(first)) => MERGEABLE_OPERATORS
+			.iter()
+			.any(|op| op.starts_with(last) && op[1..].starts_with(first)),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_formats(input: &str, expected: &str) {
+		let formatted = format_source(input).expect("formatting failed");
+		assert_eq!(formatted, expected);
+		// formatting should be idempotent
+		assert_eq!(format_source(&formatted).expect("formatting failed"), expected);
+	}
+
+	#[test]
+	fn indents_blocks() {
+		assert_formats(
+			"class Foo {\npub bar(x:num):str{\nif x>1{\nreturn \"big\";\n}\nreturn \"small\";\n}\n}\n",
+			"class Foo {\n  pub bar(x: num): str {\n    if x > 1 {\n      return \"big\";\n    }\n    return \"small\";\n  }\n}\n",
+		);
+	}
+
+	#[test]
+	fn moves_braces_and_statements() {
+		assert_formats(
+			"if true\n{ log(\"a\"); log(\"b\"); }\nelse {}",
+			"if true {\n  log(\"a\");\n  log(\"b\");\n} else {}\n",
+		);
+	}
+
+	#[test]
+	fn keeps_comments_and_docs() {
+		assert_formats(
+			"// leading comment\n\n\n\n/// Docs for Foo\nstruct Foo {\n    /// Docs for bar\n    bar: str;   // trailing comment\n}\n",
+			"// leading comment\n\n/// Docs for Foo\nstruct Foo {\n  /// Docs for bar\n  bar: str; // trailing comment\n}\n",
+		);
+	}
+
+	#[test]
+	fn keeps_strings_verbatim() {
+		assert_formats(
+			"let  x  =  \"a   {1+2}   b\";\nlet y = #\"  c  \";\n",
+			"let x = \"a   {1+2}   b\";\nlet y = #\"  c  \";\n",
+		);
+	}
+
+	#[test]
+	fn formats_expressions() {
+		assert_formats(
+			"let a = MutArray<num> [1,2 ,3];\nlet b = a.at( 0 )!;\nlet c: str? = nil;\nfor i in 0..=10 { log(\"{-i}\"); }\nlet f = inflight (x: num, ...rest: Array<str>): void => {};",
+			"let a = MutArray<num>[1, 2, 3];\nlet b = a.at(0)!;\nlet c: str? = nil;\nfor i in 0..=10 {\n  log(\"{-i}\");\n}\nlet f = inflight (x: num, ...rest: Array<str>): void => {};\n",
+		);
+	}
+
+	#[test]
+	fn keeps_operators_apart() {
+		assert_formats(
+			"let a = - -1;\nlet b = -(-1);\nlet c = !!true;\nlet d = - --1;\n",
+			"let a = - -1;\nlet b = -(-1);\nlet c = !!true;\nlet d = - --1;\n",
+		);
+	}
+
+	#[test]
+	fn keeps_user_line_breaks_in_expressions() {
+		assert_formats(
+			"let x = foo(\n1,\n2\n);\nlet y = bar\n.baz()\n.qux();\n",
+			"let x = foo(\n  1,\n  2\n);\nlet y = bar\n  .baz()\n  .qux();\n",
+		);
+	}
+
+	#[test]
+	fn closures_as_arguments() {
+		assert_formats(
+			"test \"foo\" {\nlet b = new cloud.Bucket();\nb.onCreate(inflight (key) => {\nlog(key);\n});\n}\n",
+			"test \"foo\" {\n  let b = new cloud.Bucket();\n  b.onCreate(inflight (key) => {\n    log(key);\n  });\n}\n",
+		);
+	}
+
+	#[test]
+	fn rejects_syntax_errors() {
+		assert_eq!(format_source("let x = ;"), Err(FormatError::SyntaxError));
+		assert_eq!(format_source("let x = 1"), Err(FormatError::SyntaxError));
+	}
+}
@@ -13,20 +13,22 @@ use std::{borrow::Borrow, cell::RefCell, cmp::Ordering, collections::BTreeMap, v
 use crate::{
 	ast::{
 		AccessModifier, ArgList, AssignmentKind, BinaryOperator, BringSource, CalleeKind, Class as AstClass, ElseIfs, Enum,
-		Expr, ExprKind, FunctionBody, FunctionDefinition, IfLet, InterpolatedStringPart, IntrinsicKind, Literal, New,
-		Phase, Reference, Scope, Stmt, StmtKind, Symbol, UnaryOperator, UserDefinedType,
+		Expr, ExprKind, FunctionBody, FunctionDefinition, IfLet, InterpolatedStringPart, IntrinsicKind, Literal, Match,
+		MatchArmBody, MatchPattern, MatchPatternKind, New, Phase, Reference, Scope, Stmt, StmtKind, Symbol, TypeAnnotation,
+		TypeAnnotationKind, UnaryOperator, UserDefinedType,
 	},
 	comp_ctx::{CompilationContext, CompilationPhase},
+	compile_cache::CachedJsFiles,
 	diagnostic::{report_diagnostic, Diagnostic, DiagnosticSeverity, WingSpan},
 	file_graph::{File, FileGraph},
 	files::Files,
-	parser::is_entrypoint_file,
+	parser::{is_entrypoint_file, is_test_statement},
 	type_check::{
 		is_udt_struct_type,
 		lifts::{LiftQualification, Liftable, Lifts},
 		resolve_super_method, resolve_user_defined_type,
 		symbol_env::{SymbolEnv, SymbolEnvKind},
-		ClassLike, Type, TypeRef, Types, CLASS_INFLIGHT_INIT_NAME,
+		ClassLike, FunctionSignature, Type, TypeRef, Types, CLASS_INFLIGHT_INIT_NAME, CLASS_INIT_NAME,
 	},
 	visit_context::{VisitContext, VisitorWithContext},
 	MACRO_REPLACE_ARGS, MACRO_REPLACE_ARGS_TEXT, MACRO_REPLACE_SELF, WINGSDK_ASSEMBLY_NAME, WINGSDK_AUTOID_RESOURCE,
@@ -49,6 +51,7 @@ const PLATFORMS_VAR: &str = "$platforms";
 const HELPERS_VAR: &str = "$helpers";
 const MACROS_VAR: &str = "$macros";
 const EXTERN_VAR: &str = "$extern";
+const MATCH_VALUE: &str = "$match_value";
 
 const ROOT_CLASS: &str = "$Root";
 const JS_CONSTRUCTOR: &str = "constructor";
@@ -60,6 +63,7 @@ const SUPER_CLASS_INFLIGHT_INIT_NAME: &str = formatcp!("super_{CLASS_INFLIGHT_IN
 
 const PREFLIGHT_TYPES_MAP: &str = "$helpers.nodeof(this).root.$preflightTypesMap";
 const MODULE_PREFLIGHT_TYPES_MAP: &str = "$preflightTypesMap";
+const MODULE_TESTS_FN: &str = "$tests";
 
 const SCOPE_PARAM: &str = "$scope";
 
@@ -86,6 +90,10 @@ pub struct JSifier<'a> {
 	/// Map from source file paths to the JS file names they are emitted to.
 	/// e.g. "bucket.w" -> "preflight.bucket-1.cjs"
 	pub preflight_file_map: RefCell<IndexMap<Utf8PathBuf, String>>,
+	/// Map from source file paths to the numbers in the names of their preflight files.
+	preflight_file_ids: RefCell<IndexMap<Utf8PathBuf, usize>>,
+	/// Module files that define tests. Their tests are registered by the entrypoint.
+	modules_with_tests: RefCell<Vec<Utf8PathBuf>>,
 	source_files: &'a Files,
 	source_file_graph: &'a FileGraph,
 	/// The path that compilation started at (file or directory)
@@ -128,6 +136,8 @@ impl<'a> JSifier<'a> {
 			inflight_file_map: RefCell::new(IndexMap::new()),
 			preflight_file_counter: RefCell::new(0),
 			preflight_file_map: RefCell::new(IndexMap::new()),
+			preflight_file_ids: RefCell::new(IndexMap::new()),
+			modules_with_tests: RefCell::new(vec![]),
 			output_files: RefCell::new(output_files),
 		}
 	}
@@ -136,6 +146,8 @@ impl<'a> JSifier<'a> {
 		CompilationContext::set(CompilationPhase::Jsifying, &scope.span);
 		let mut js = CodeMaker::default();
 		let mut imports = CodeMaker::default();
+		let mut tests = CodeMaker::default();
+		let is_entrypoint = is_entrypoint_file(&source_file.path);
 
 		let mut visit_ctx = VisitContext::new();
 		let mut jsify_context = JSifyContext {
@@ -158,6 +170,8 @@ impl<'a> JSifier<'a> {
 			let s = self.jsify_statement(&scope_env, statement, &mut jsify_context); // top level statements are always preflight
 			if matches!(statement.kind, StmtKind::Bring { .. }) {
 				imports.add_code(s);
+			} else if !is_entrypoint && is_test_statement(statement) {
+				tests.add_code(s);
 			} else {
 				js.add_code(s);
 			}
@@ -166,7 +180,6 @@ impl<'a> JSifier<'a> {
 		let mut output = CodeMaker::default();
 
 		let is_compilation_init = source_file.path == self.compilation_init_path;
-		let is_entrypoint = is_entrypoint_file(&source_file.path);
 		let is_directory = source_file.path.is_dir();
 
 		output.line("\"use strict\";");
@@ -215,6 +228,15 @@ impl<'a> JSifier<'a> {
 			root_class.line(format!("{PREFLIGHT_TYPES_MAP} = {MODULE_PREFLIGHT_TYPES_MAP};"));
 
 			root_class.add_code(js);
+
+			// Register the tests of all modules used by the app
+			let preflight_file_map = self.preflight_file_map.borrow();
+			for module in self.modules_with_tests.borrow().iter() {
+				let preflight_file_name = preflight_file_map.get(module).expect("no emitted JS file found");
+				root_class.line(format!(
+					"{HELPERS_VAR}.bringJs(`${{__dirname}}/{preflight_file_name}`, {MODULE_PREFLIGHT_TYPES_MAP}).{MODULE_TESTS_FN}.call(this);"
+				));
+			}
 			root_class.close("}");
 			root_class.close("}");
 
@@ -263,10 +285,24 @@ impl<'a> JSifier<'a> {
 			output.add_code(imports);
 			output.add_code(self.jsify_struct_schemas(source_file));
 			output.add_code(js);
-			let exports = get_exported_symbols(&scope);
+			let mut exports = get_exported_symbols(&scope)
+				.iter()
+				.map(ToString::to_string)
+				.collect_vec();
+			if !tests.is_empty() {
+				// Tests need a scope, so they're only created when the entrypoint calls this function with its root
+				output.open(format!("function {MODULE_TESTS_FN}() {{"));
+				output.add_code(tests);
+				output.close("}");
+				exports.push(MODULE_TESTS_FN.to_string());
+				self
+					.modules_with_tests
+					.borrow_mut()
+					.push(source_file.path.to_path_buf());
+			}
 			output.line(format!(
 				"module.exports = {{ {MODULE_PREFLIGHT_TYPES_MAP}, {} }};",
-				exports.iter().map(ToString::to_string).join(", ")
+				exports.join(", ")
 			));
 		}
 
@@ -285,6 +321,10 @@ impl<'a> JSifier<'a> {
 			// add a number to the end to avoid name collisions
 			let mut preflight_file_counter = self.preflight_file_counter.borrow_mut();
 			*preflight_file_counter += 1;
+			self
+				.preflight_file_ids
+				.borrow_mut()
+				.insert(source_file.path.to_path_buf(), *preflight_file_counter);
 			format!("preflight.{}-{}.cjs", sanitized_name, preflight_file_counter)
 		};
 
@@ -325,6 +365,65 @@ impl<'a> JSifier<'a> {
 		}
 	}
 
+	/// Makes sure files jsified from now on don't use the file names of cached JS files
+	pub fn reserve_file_ids(&self, js: &CachedJsFiles) {
+		let mut preflight_file_counter = self.preflight_file_counter.borrow_mut();
+		*preflight_file_counter = (*preflight_file_counter).max(js.preflight_file_id.unwrap_or_default());
+		let mut inflight_file_counter = self.inflight_file_counter.borrow_mut();
+		*inflight_file_counter = (*inflight_file_counter).max(js.inflight_file_id.unwrap_or_default());
+	}
+
+	/// Adds the cached JS files of a source file instead of jsifying it
+	pub fn add_cached_files(&self, source_path: &Utf8Path, js: &CachedJsFiles) {
+		self
+			.preflight_file_map
+			.borrow_mut()
+			.insert(source_path.to_path_buf(), js.preflight_file_name.clone());
+		if let Some(id) = js.preflight_file_id {
+			self
+				.preflight_file_ids
+				.borrow_mut()
+				.insert(source_path.to_path_buf(), id);
+		}
+		if let Some(id) = js.inflight_file_id {
+			self.inflight_file_map.borrow_mut().insert(source_path.to_string(), id);
+		}
+		if js.has_tests {
+			self.modules_with_tests.borrow_mut().push(source_path.to_path_buf());
+		}
+		let mut output_files = self.output_files.borrow_mut();
+		for (path, content) in &js.files {
+			match output_files.add_file(path, content.clone()) {
+				Ok(()) => {}
+				Err(err) => report_diagnostic(err.into()),
+			}
+		}
+	}
+
+	/// The JS files emitted for a source file, so they can be cached
+	pub fn cached_files<'p>(
+		&self,
+		source_path: &Utf8Path,
+		paths: impl IntoIterator<Item = &'p Utf8Path>,
+	) -> CachedJsFiles {
+		let output_files = self.output_files.borrow();
+		CachedJsFiles {
+			preflight_file_name: self.preflight_file_map.borrow()[source_path].clone(),
+			preflight_file_id: self.preflight_file_ids.borrow().get(source_path).copied(),
+			inflight_file_id: self.inflight_file_map.borrow().get(source_path.as_str()).copied(),
+			has_tests: self.modules_with_tests.borrow().iter().any(|path| path == source_path),
+			files: paths
+				.into_iter()
+				.map(|path| {
+					(
+						path.to_string(),
+						output_files.get_file(path).cloned().unwrap_or_default(),
+					)
+				})
+				.collect(),
+		}
+	}
+
 	fn jsify_struct_schemas(&self, source_file: &File) -> CodeMaker {
 		// For each struct schema that is referenced in the code
 		// (this is determined by the StructSchemaVisitor before jsification starts)
@@ -396,6 +495,7 @@ impl<'a> JSifier<'a> {
 	fn jsify_arg_list(
 		&self,
 		arg_list: &ArgList,
+		sig: Option<&FunctionSignature>,
 		scope: Option<String>,
 		id: Option<String>,
 		ctx: &mut JSifyContext,
@@ -411,16 +511,22 @@ impl<'a> JSifier<'a> {
 			args.push(new_code!(&arg_list.span, id_str));
 		}
 
-		for arg in arg_list.pos_args.iter() {
-			args.push(self.jsify_expression(arg, ctx));
+		for (i, arg) in arg_list.pos_args.iter().enumerate() {
+			let param_type = sig.and_then(|sig| sig.parameters.get(i)).map(|p| p.typeref);
+			args.push(self.jsify_to_jsii_date(self.jsify_expression(arg, ctx), param_type, &arg.span));
 		}
 
+		// Named args are fields of the struct expected as the last parameter
+		let struct_type = sig
+			.and_then(|sig| sig.parameters.last())
+			.and_then(|p| p.typeref.maybe_unwrap_option().as_struct());
 		for arg in arg_list.named_args.iter() {
+			let field_type = struct_type.and_then(|s| s.get_field(arg.0)).map(|v| v.type_);
 			structure_args.push(new_code!(
 				&arg_list.span,
 				&arg.0.name,
 				": ",
-				self.jsify_expression(arg.1, ctx)
+				self.jsify_to_jsii_date(self.jsify_expression(arg.1, ctx), field_type, &arg.1.span)
 			));
 		}
 
@@ -431,6 +537,66 @@ impl<'a> JSifier<'a> {
 		new_code!(&arg_list.span, args)
 	}
 
+	/// Converts `datetime`s to JS `Date`s if they're passed where JSII code expects them (see `Types::jsii_date`).
+	/// Functions passed as JSII callbacks are wrapped to convert the dates JSII calls them with and they return.
+	fn jsify_to_jsii_date(&self, code: CodeMaker, target_type: Option<TypeRef>, span: &WingSpan) -> CodeMaker {
+		let Some(target_type) = target_type else {
+			return code;
+		};
+		if self.types.has_jsii_date(target_type) {
+			return new_code!(span, HELPERS_VAR, ".toJsiiDate(", code, ")");
+		}
+		let Some(sig) = target_type.maybe_unwrap_option().as_function_sig() else {
+			return code;
+		};
+		let date_params = sig
+			.parameters
+			.iter()
+			.enumerate()
+			.filter(|(_, p)| self.types.has_jsii_date(p.typeref))
+			.map(|(i, _)| i.to_string())
+			.collect_vec();
+		let date_return = self.types.has_jsii_date(sig.return_type);
+		if date_params.is_empty() && !date_return {
+			return code;
+		}
+		new_code!(
+			span,
+			HELPERS_VAR,
+			".toJsiiCallback(",
+			code,
+			format!(", [{}], {date_return})", date_params.join(", "))
+		)
+	}
+
+	/// If `callee` is a Wing method that implements a JSII method, returns the JSII method's return type, since the
+	/// Wing method returns the JSII value (see `jsify_jsii_method_body`)
+	fn implemented_jsii_method_return_type(&self, callee: &CalleeKind, ctx: &JSifyContext) -> Option<TypeRef> {
+		let (class_type, method) = match callee {
+			CalleeKind::Expr(expr) => match &expr.kind {
+				ExprKind::Reference(Reference::InstanceMember { object, property, .. }) => {
+					(self.types.get_expr_type(object), property)
+				}
+				_ => return None,
+			},
+			CalleeKind::SuperCall(method) => (parent_class_type(ctx), method),
+		};
+		let class = class_type
+			.maybe_unwrap_option()
+			.as_class()
+			.filter(|c| c.fqn.is_none())?;
+		let jsii_method_type = class.jsii_method_type(method)?;
+		Some(jsii_method_type.as_function_sig()?.return_type)
+	}
+
+	/// Converts JS `Date`s returned from JSII code to `datetime`s (see `Types::jsii_date`)
+	fn jsify_from_jsii_date(&self, code: CodeMaker, source_type: Option<TypeRef>, span: &WingSpan) -> CodeMaker {
+		match source_type {
+			Some(t) if self.types.has_jsii_date(t) => new_code!(span, HELPERS_VAR, ".fromJsiiDate(", code, ")"),
+			_ => code,
+		}
+	}
+
 	pub fn jsify_type(typ: &Type) -> Option<String> {
 		match typ {
 			Type::Struct(t) => Some(t.name.name.clone()),
@@ -578,7 +744,10 @@ impl<'a> JSifier<'a> {
 
 				let scope_arg = if fqn.is_none() { scope.clone() } else { scope.clone() };
 
-				let args = self.jsify_arg_list(&arg_list, scope_arg, id, ctx);
+				let ctor_sig = class_type
+					.get_method(&Symbol::global(CLASS_INIT_NAME))
+					.and_then(|v| v.type_.as_function_sig());
+				let args = self.jsify_arg_list(&arg_list, ctor_sig, scope_arg, id, ctx);
 
 				if let (true, Some(fqn)) = (is_preflight_class, fqn) {
 					new_code!(
@@ -675,7 +844,15 @@ impl<'a> JSifier<'a> {
 				inclusive.unwrap().to_string(),
 				")"
 			),
-			ExprKind::Reference(_ref) => new_code!(expr_span, self.jsify_reference(&_ref, ctx)),
+			ExprKind::Reference(_ref) => {
+				let code = new_code!(expr_span, self.jsify_reference(&_ref, ctx));
+				match _ref {
+					Reference::InstanceMember { .. } | Reference::TypeMember { .. } => {
+						self.jsify_from_jsii_date(code, Some(self.types.get_expr_type(expression)), expr_span)
+					}
+					_ => code,
+				}
+			}
 			ExprKind::Intrinsic(intrinsic) => match intrinsic.kind {
 				IntrinsicKind::Unknown => new_code!(expr_span, ""),
 				IntrinsicKind::Dirname => {
@@ -702,122 +879,14 @@ impl<'a> JSifier<'a> {
 					)
 				}
 			},
-			ExprKind::Call { callee, arg_list } => {
-				let function_type = match callee {
-					CalleeKind::Expr(expr) => self.types.get_expr_type(expr),
-					CalleeKind::SuperCall(method) => {
-						resolve_super_method(method, ctx.visit_ctx.current_env().expect("an env"), self.types)
-							.expect("valid super method")
-							.0
-					}
-				};
-				let is_option = function_type.is_option();
-				let function_type = function_type.maybe_unwrap_option();
-				let function_sig = function_type.as_function_sig();
-
-				let expr_string = match callee {
-					CalleeKind::Expr(expr) => self.jsify_expression(expr, ctx).to_string(),
-					CalleeKind::SuperCall(method) => format!("super.{}", method),
+			ExprKind::Call { callee, arg_list } => self.jsify_call(expression, callee, arg_list, auto_await, ctx),
+			ExprKind::Await(awaited) => new_code!(expr_span, "(await ", self.jsify_expression(awaited, ctx), ")"),
+			ExprKind::Defer(deferred) => {
+				// The call returns its promise instead of being awaited
+				let ExprKind::Call { callee, arg_list } = &deferred.kind else {
+					panic!("only calls can be deferred");
 				};
-				let mut args_string = self.jsify_arg_list(&arg_list, None, None, ctx).to_string();
-
-				let mut args_text_string = lookup_span(&arg_list.span, &self.source_files);
-				if args_text_string.len() > 0 {
-					// remove the parens
-					args_text_string = args_text_string[1..args_text_string.len() - 1].to_string();
-				}
-				let args_text_string = escape_javascript_string(&args_text_string);
-				let mut is_optional = false;
-
-				if let Some(function_sig) = function_sig {
-					if let Some(js_override) = &function_sig.js_override {
-						let self_string = match callee {
-							CalleeKind::Expr(expr) => match &expr.kind {
-								// for "loose" macros, e.g. `print()`, $self$ is the global object
-								ExprKind::Reference(Reference::Identifier(_)) => "global".to_string(),
-								ExprKind::Reference(Reference::InstanceMember {
-									object,
-									optional_accessor,
-									..
-								}) => {
-									is_optional = *optional_accessor;
-									self.jsify_expression(&object, ctx).to_string()
-								}
-								ExprKind::Reference(Reference::TypeMember { property, .. }) => {
-									// remove the property name from the expression string
-									expr_string.split(".").filter(|s| s != &property.name).join(".")
-								}
-								_ => expr_string,
-							},
-							CalleeKind::SuperCall { .. } =>
-							// Note: in case of a $self$ macro override of a super call there's no clear definition of what $self$ should be,
-							// "this" is a decent option because it'll refer to the object where "super" was used, but depending on how
-							// $self$ is used in the macro it might lead to unexpected results if $self$.some_method() is called and is
-							// defined differently in the parent class of "this".
-							{
-								"this".to_string()
-							}
-						};
-						if function_sig.is_macro {
-							return new_code!(
-								expr_span,
-								format!(
-									"{}.{}({}, {}, {})",
-									MACROS_VAR,
-									js_override,
-									is_optional.to_string(),
-									self_string,
-									args_string
-								)
-							);
-						} else {
-							let patterns = &[MACRO_REPLACE_SELF, MACRO_REPLACE_ARGS, MACRO_REPLACE_ARGS_TEXT];
-							let replace_with = &[self_string, args_string, args_text_string];
-							let ac = AhoCorasick::new(patterns).expect("Failed to create macro pattern");
-							return new_code!(expr_span, ac.replace_all(js_override, replace_with));
-						}
-					}
-
-					// If this function requires an implicit scope argument, we need to add it to the args string
-					if function_sig.implicit_scope_param {
-						// If the current function we're in also has an implicit scope parameter then use it
-						// TODO: make a helper function to get the `current_function_type`
-						let implicit_scope_arg_available = ctx.visit_ctx.current_function_env().map_or(false, |e| {
-							if let SymbolEnvKind::Function { sig, .. } = e.kind {
-								sig.as_function_sig().expect("a function sig").implicit_scope_param
-							} else {
-								false
-							}
-						});
-
-						let prepend_scope_arg = if implicit_scope_arg_available {
-							SCOPE_PARAM.to_string()
-						} else {
-							// Otherwise, we can just use `this`. We can assume `this` is available since othesize we should have had an implicit scope arg available.
-							"this".to_string()
-						};
-						if args_string.len() > 0 {
-							args_string = format!("{}, {}", prepend_scope_arg, args_string);
-						} else {
-							args_string = prepend_scope_arg;
-						}
-					}
-				}
-
-				let optional_access = if is_option { "?." } else { "" };
-
-				// NOTE: if the expression is a "handle" class, the object itself is callable (see
-				// `jsify_class_inflight` below), so we can just call it as-is.
-				new_code!(
-					expr_span,
-					"(",
-					auto_await,
-					expr_string,
-					optional_access,
-					"(",
-					args_string,
-					"))"
-				)
+				self.jsify_call(deferred, callee, arg_list, "", ctx)
 			}
 			ExprKind::Unary { op, exp } => {
 				let js_exp = self.jsify_expression(exp, ctx);
@@ -863,12 +932,18 @@ impl<'a> JSifier<'a> {
 				new_code!(expr_span, "[", item_list, "]")
 			}
 			ExprKind::StructLiteral { fields, .. } => {
+				let struct_type = self.types.get_expr_type(expression);
+				let struct_type = struct_type.maybe_unwrap_option().as_struct();
 				new_code!(
 					expr_span,
 					"({",
 					fields
 						.iter()
-						.map(|(name, expr)| new_code!(expr_span, "\"", &name.name, "\": ", self.jsify_expression(expr, ctx)))
+						.map(|(name, expr)| {
+							let field_type = struct_type.and_then(|s| s.get_field(name)).map(|v| v.type_);
+							let value = self.jsify_to_jsii_date(self.jsify_expression(expr, ctx), field_type, &expr.span);
+							new_code!(expr_span, "\"", &name.name, "\": ", value)
+						})
 						.collect_vec(),
 					"})"
 				)
@@ -902,9 +977,279 @@ impl<'a> JSifier<'a> {
 				new_code!(expr_span, "new Set([", item_list, "])")
 			}
 			ExprKind::FunctionClosure(func_def) => self.jsify_function(None, func_def, true, ctx),
+			ExprKind::Match(match_) => {
+				// A match expression is emitted as an immediately invoked function that returns the value of the
+				// matching arm, for example:
+				// ((($match_value) => {
+				//   if ($helpers.eq($match_value, 1)) {
+				//     return "one";
+				//   }
+				//   if (true) {
+				//     const n = $match_value;
+				//     return `${n}`;
+				//   }
+				// })(x))
+				let mut code = CodeMaker::with_source(expr_span);
+				let async_ = if ctx.visit_ctx.current_phase() == Phase::Inflight {
+					"async "
+				} else {
+					""
+				};
+				code.open(format!("({auto_await}({async_}({MATCH_VALUE}) => {{"));
+				for arm in &match_.arms {
+					let MatchArmBody::Expression(value) = &arm.body else {
+						panic!("match expression arms must have an expression body");
+					};
+					code.open(new_code!(
+						&arm.span,
+						"if (",
+						self.jsify_match_arm_condition(arm.patterns.as_slice(), ctx),
+						") {"
+					));
+					Self::jsify_match_bindings(&mut code, &arm.patterns[0], MATCH_VALUE);
+					code.line(new_code!(
+						&value.span,
+						"return (",
+						self.jsify_expression(value, ctx),
+						");"
+					));
+					code.close("}");
+				}
+				code.close(new_code!(
+					&match_.value.span,
+					"})(",
+					self.jsify_expression(&match_.value, ctx),
+					"))"
+				));
+				code
+			}
 		}
 	}
 
+	/// Emits a call expression. Calls of async (inflight) functions are awaited using `auto_await`, unless the call
+	/// is deferred.
+	fn jsify_call(
+		&self,
+		expression: &Expr,
+		callee: &CalleeKind,
+		arg_list: &ArgList,
+		auto_await: &str,
+		ctx: &mut JSifyContext,
+	) -> CodeMaker {
+		let expr_span = &expression.span;
+		let function_type = match callee {
+			CalleeKind::Expr(expr) => self.types.get_expr_type(expr),
+			CalleeKind::SuperCall(method) => {
+				resolve_super_method(method, ctx.visit_ctx.current_env().expect("an env"), self.types)
+					.expect("valid super method")
+					.0
+			}
+		};
+		let is_option = function_type.is_option();
+		let function_type = function_type.maybe_unwrap_option();
+		let function_sig = function_type.as_function_sig();
+
+		let expr_string = match callee {
+			CalleeKind::Expr(expr) => self.jsify_expression(expr, ctx).to_string(),
+			CalleeKind::SuperCall(method) => format!("super.{}", method),
+		};
+		let mut args_string = self
+			.jsify_arg_list(&arg_list, function_sig, None, None, ctx)
+			.to_string();
+
+		let mut args_text_string = lookup_span(&arg_list.span, &self.source_files);
+		if args_text_string.len() > 0 {
+			// remove the parens
+			args_text_string = args_text_string[1..args_text_string.len() - 1].to_string();
+		}
+		let args_text_string = escape_javascript_string(&args_text_string);
+		let mut is_optional = false;
+
+		if let Some(function_sig) = function_sig {
+			if let Some(js_override) = &function_sig.js_override {
+				let self_string = match callee {
+					CalleeKind::Expr(expr) => match &expr.kind {
+						// for "loose" macros, e.g. `print()`, $self$ is the global object
+						ExprKind::Reference(Reference::Identifier(_)) => "global".to_string(),
+						ExprKind::Reference(Reference::InstanceMember {
+							object,
+							optional_accessor,
+							..
+						}) => {
+							is_optional = *optional_accessor;
+							self.jsify_expression(&object, ctx).to_string()
+						}
+						ExprKind::Reference(Reference::TypeMember { property, .. }) => {
+							// remove the property name from the expression string
+							expr_string.split(".").filter(|s| s != &property.name).join(".")
+						}
+						_ => expr_string,
+					},
+					CalleeKind::SuperCall { .. } =>
+					// Note: in case of a $self$ macro override of a super call there's no clear definition of what $self$ should be,
+					// "this" is a decent option because it'll refer to the object where "super" was used, but depending on how
+					// $self$ is used in the macro it might lead to unexpected results if $self$.some_method() is called and is
+					// defined differently in the parent class of "this".
+					{
+						"this".to_string()
+					}
+				};
+				if function_sig.is_macro {
+					return new_code!(
+						expr_span,
+						format!(
+							"{}.{}({}, {}, {})",
+							MACROS_VAR,
+							js_override,
+							is_optional.to_string(),
+							self_string,
+							args_string
+						)
+					);
+				} else {
+					let patterns = &[MACRO_REPLACE_SELF, MACRO_REPLACE_ARGS, MACRO_REPLACE_ARGS_TEXT];
+					let replace_with = &[self_string, args_string, args_text_string];
+					let ac = AhoCorasick::new(patterns).expect("Failed to create macro pattern");
+					return new_code!(expr_span, ac.replace_all(js_override, replace_with));
+				}
+			}
+
+			// If this function requires an implicit scope argument, we need to add it to the args string
+			if function_sig.implicit_scope_param {
+				// If the current function we're in also has an implicit scope parameter then use it
+				// TODO: make a helper function to get the `current_function_type`
+				let implicit_scope_arg_available = ctx.visit_ctx.current_function_env().map_or(false, |e| {
+					if let SymbolEnvKind::Function { sig, .. } = e.kind {
+						sig.as_function_sig().expect("a function sig").implicit_scope_param
+					} else {
+						false
+					}
+				});
+
+				let prepend_scope_arg = if implicit_scope_arg_available {
+					SCOPE_PARAM.to_string()
+				} else {
+					// Otherwise, we can just use `this`. We can assume `this` is available since othesize we should have had an implicit scope arg available.
+					"this".to_string()
+				};
+				if args_string.len() > 0 {
+					args_string = format!("{}, {}", prepend_scope_arg, args_string);
+				} else {
+					args_string = prepend_scope_arg;
+				}
+			}
+		}
+
+		let optional_access = if is_option { "?." } else { "" };
+
+		// NOTE: if the expression is a "handle" class, the object itself is callable (see
+		// `jsify_class_inflight` below), so we can just call it as-is.
+		self.jsify_from_jsii_date(
+			new_code!(
+				expr_span,
+				"(",
+				auto_await,
+				expr_string,
+				optional_access,
+				"(",
+				args_string,
+				"))"
+			),
+			self
+				.implemented_jsii_method_return_type(callee, ctx)
+				.or(function_sig.map(|sig| sig.return_type)),
+			expr_span,
+		)
+	}
+
+	/// Emits the condition under which any of an arm's patterns matches the value of a match
+	fn jsify_match_arm_condition(&self, patterns: &[MatchPattern], ctx: &mut JSifyContext) -> String {
+		patterns
+			.iter()
+			.map(|pattern| self.jsify_match_pattern_condition(pattern, MATCH_VALUE, ctx))
+			.join(" || ")
+	}
+
+	/// Emits the condition under which `pattern` matches the value at `path`
+	fn jsify_match_pattern_condition(&self, pattern: &MatchPattern, path: &str, ctx: &mut JSifyContext) -> String {
+		match &pattern.kind {
+			MatchPatternKind::Wildcard | MatchPatternKind::Binding(_) => "true".to_string(),
+			MatchPatternKind::Value(Expr {
+				kind: ExprKind::Literal(Literal::Nil),
+				..
+			}) => format!("{path} === undefined"),
+			MatchPatternKind::Value(value) => {
+				format!(
+					"{HELPERS_VAR}.eq({path}, {})",
+					self.jsify_expression(value, ctx).to_string()
+				)
+			}
+			MatchPatternKind::Struct(fields) => {
+				let mut conditions = vec![format!("{path} !== undefined")];
+				for (name, field_pattern) in fields {
+					let condition = self.jsify_match_pattern_condition(field_pattern, &format!("{path}.{name}"), ctx);
+					if condition != "true" {
+						conditions.push(format!("({condition})"));
+					}
+				}
+				conditions.join(" && ")
+			}
+		}
+	}
+
+	/// Emits the declarations of the variables `pattern` binds from the value at `path`
+	fn jsify_match_bindings(code: &mut CodeMaker, pattern: &MatchPattern, path: &str) {
+		match &pattern.kind {
+			MatchPatternKind::Binding(name) => code.line(new_code!(&name.span, "const ", &name.name, " = ", path, ";")),
+			MatchPatternKind::Struct(fields) => {
+				for (name, field_pattern) in fields {
+					Self::jsify_match_bindings(code, field_pattern, &format!("{path}.{name}"));
+				}
+			}
+			MatchPatternKind::Wildcard | MatchPatternKind::Value(_) => {}
+		}
+	}
+
+	fn jsify_match_statement(&self, code: &mut CodeMaker, match_: &Match, ctx: &mut JSifyContext) {
+		// The matched value is stored in a temporary scope so that it's only evaluated once:
+		// {
+		//   const $match_value = x;
+		//   if ($helpers.eq($match_value, 1)) {
+		//     ...
+		//   }
+		//   else if (true) {
+		//     const n = $match_value;
+		//     ...
+		//   }
+		// }
+		code.open("{");
+		code.line(new_code!(
+			&match_.value.span,
+			"const ",
+			MATCH_VALUE,
+			" = ",
+			self.jsify_expression(&match_.value, ctx),
+			";"
+		));
+		for (i, arm) in match_.arms.iter().enumerate() {
+			let MatchArmBody::Statements(scope) = &arm.body else {
+				panic!("match statement arms must have a block");
+			};
+			let else_ = if i == 0 { "" } else { "else " };
+			code.open(new_code!(
+				&arm.span,
+				else_,
+				"if (",
+				self.jsify_match_arm_condition(arm.patterns.as_slice(), ctx),
+				") {"
+			));
+			Self::jsify_match_bindings(code, &arm.patterns[0], MATCH_VALUE);
+			code.add_code(self.jsify_scope_body(scope, ctx));
+			code.close("}");
+		}
+		code.close("}");
+	}
+
 	// To avoid a performance penalty when evaluating assignments made in the else if statement,
 	// it was necessary to nest the if statements.
 	//
@@ -964,7 +1309,10 @@ impl<'a> JSifier<'a> {
 					";"
 				));
 				let value = format!("{}{}", else_if_let_value, index);
-				code.open(format!("if ({value} != undefined) {{"));
+				code.open(format!(
+					"if ({}) {{",
+					jsify_if_let_condition(&value, &else_if_let_to_jsify.type_)
+				));
 				if else_if_let_to_jsify.reassignable {
 					code.line(format!("let {} = {};", else_if_let_to_jsify.var_name, value));
 				} else {
@@ -1026,7 +1374,7 @@ impl<'a> JSifier<'a> {
 				}
 			},
 			StmtKind::SuperConstructor { arg_list } => {
-				let args = self.jsify_arg_list(&arg_list, None, None, ctx);
+				let args = self.jsify_arg_list(&arg_list, None, None, None, ctx);
 				match ctx.visit_ctx.current_phase() {
 					// does class have any inflight constructor?
 					Phase::Inflight => {
@@ -1071,6 +1419,7 @@ impl<'a> JSifier<'a> {
 				var_name,
 				initial_value,
 				type_: _,
+				access: _,
 			} => {
 				let initial_value = self.jsify_expression(initial_value, ctx);
 				if *reassignable {
@@ -1127,6 +1476,7 @@ impl<'a> JSifier<'a> {
 				value,
 				statements,
 				var_name,
+				type_,
 				else_if_statements,
 				else_statements,
 			}) => {
@@ -1168,7 +1518,7 @@ impl<'a> JSifier<'a> {
 					";"
 				));
 
-				code.open(format!("if ({if_let_value} != undefined) {{"));
+				code.open(format!("if ({}) {{", jsify_if_let_condition(if_let_value, type_)));
 				if *reassignable {
 					code.line(format!("let {} = {};", var_name, if_let_value));
 				} else {
@@ -1195,6 +1545,7 @@ impl<'a> JSifier<'a> {
 
 				code.close("}");
 			}
+			StmtKind::Match(match_) => self.jsify_match_statement(&mut code, match_, ctx),
 			StmtKind::If {
 				condition,
 				statements,
@@ -1252,13 +1603,23 @@ impl<'a> JSifier<'a> {
 						));
 					}
 					_ => {
+						let variable_type = match variable {
+							Reference::InstanceMember { object, property, .. } => self
+								.types
+								.get_expr_type(object)
+								.maybe_unwrap_option()
+								.as_class()
+								.and_then(|c| c.get_field(property))
+								.map(|v| v.type_),
+							_ => None,
+						};
 						code.line(new_code!(
 							&statement.span,
 							self.jsify_reference(variable, ctx),
 							" ",
 							operator,
 							" ",
-							self.jsify_expression(value, ctx),
+							self.jsify_to_jsii_date(self.jsify_expression(value, ctx), variable_type, &value.span),
 							";"
 						));
 					}
@@ -1422,7 +1783,7 @@ impl<'a> JSifier<'a> {
 				..
 			}) = body_scope.statements.iter().next()
 			{
-				let args = self.jsify_arg_list(&arg_list, None, None, ctx);
+				let args = self.jsify_arg_list(&arg_list, None, None, None, ctx);
 				code.line("super(");
 				code.append(args);
 				code.append(");");
@@ -1485,13 +1846,20 @@ impl<'a> JSifier<'a> {
 		let body = match &func_def.body {
 			FunctionBody::Statements(scope) => {
 				let function_env = self.types.get_scope_env(&scope);
-				ctx.with_function_def(
+				let body = ctx.with_function_def(
 					func_def.name.as_ref(),
 					&func_def.signature,
 					func_def.is_static,
 					function_env,
 					|ctx| self.jsify_scope_body(scope, ctx),
-				)
+				);
+				let jsii_method_type = class_type
+					.zip(func_def.name.as_ref())
+					.and_then(|(t, name)| t.as_class()?.jsii_method_type(name));
+				match jsii_method_type.as_ref().and_then(|t| t.as_function_sig()) {
+					Some(jsii_sig) => self.jsify_jsii_method_body(body, func_def, jsii_sig),
+					None => body,
+				}
 			}
 			FunctionBody::External(extern_path) => {
 				// check if the first part of the path is the node module directory
@@ -1547,6 +1915,50 @@ impl<'a> JSifier<'a> {
 		}
 	}
 
+	/// Wraps the body of a method that implements a JSII method: JSII code calls it with JS `Date`s and expects
+	/// `Date`s back (see `Types::jsii_date`), e.g.:
+	/// ```js
+	/// date = $helpers.fromJsiiDate(date);
+	/// return $helpers.toJsiiDate((() => {
+	///   <body>
+	/// })());
+	/// ```
+	fn jsify_jsii_method_body(
+		&self,
+		body: CodeMaker,
+		func_def: &FunctionDefinition,
+		jsii_sig: &FunctionSignature,
+	) -> CodeMaker {
+		let mut code = CodeMaker::default();
+		for (param, jsii_param) in func_def.signature.parameters.iter().zip(&jsii_sig.parameters) {
+			if self.types.has_jsii_date(jsii_param.typeref) {
+				code.line(new_code!(
+					&param.name.span,
+					jsify_symbol(&param.name),
+					" = ",
+					HELPERS_VAR,
+					".fromJsiiDate(",
+					jsify_symbol(&param.name),
+					");"
+				));
+			}
+		}
+
+		if !self.types.has_jsii_date(jsii_sig.return_type) {
+			code.add_code(body);
+			return code;
+		}
+
+		if func_def.signature.phase == Phase::Inflight {
+			code.open(format!("return {HELPERS_VAR}.toJsiiDate(await (async () => {{"));
+		} else {
+			code.open(format!("return {HELPERS_VAR}.toJsiiDate((() => {{"));
+		}
+		code.add_code(body);
+		code.close("})());");
+		code
+	}
+
 	fn get_require_path(&self, absolute_target: &Utf8PathBuf, span: &WingSpan) -> Option<String> {
 		let entrypoint_is_file = self.compilation_init_path.is_file();
 		let entrypoint_dir = if entrypoint_is_file {
@@ -1686,6 +2098,9 @@ impl<'a> JSifier<'a> {
 				code.line(format!("_id = {STDLIB_CORE}.closureId();"))
 			}
 
+			// emit preflight static fields
+			code.add_code(self.jsify_static_fields(class, Phase::Preflight, ctx));
+
 			// emit the preflight constructor
 			code.add_code(self.jsify_preflight_constructor(&class, ctx));
 
@@ -1725,6 +2140,23 @@ impl<'a> JSifier<'a> {
 		})
 	}
 
+	fn jsify_static_fields(&self, class: &AstClass, phase: Phase, ctx: &mut JSifyContext) -> CodeMaker {
+		let mut code = CodeMaker::default();
+		for field in class.fields.iter().filter(|f| f.is_static && f.phase == phase) {
+			if let Some(initial_value) = &field.initial_value {
+				code.line(new_code!(
+					&field.name.span,
+					"static ",
+					jsify_symbol(&field.name),
+					" = ",
+					self.jsify_expression(initial_value, ctx),
+					";"
+				));
+			}
+		}
+		code
+	}
+
 	pub fn class_singleton(&self, type_: TypeRef) -> String {
 		let c = type_.as_class().unwrap();
 		format!("$helpers.preflightClassSingleton(this, {})", c.uid)
@@ -1859,6 +2291,8 @@ impl<'a> JSifier<'a> {
 		class_code.append(" {");
 		class_code.indent();
 
+		class_code.add_code(self.jsify_static_fields(class, Phase::Inflight, ctx));
+
 		// if this is a preflight class, emit the binding constructor
 		if class.phase == Phase::Preflight {
 			self.jsify_inflight_binding_constructor(class, class_type, &mut class_code);
@@ -2137,6 +2571,21 @@ fn jsify_function_parameters(func_def: &FunctionDefinition) -> CodeMaker {
 	new_code!(&func_def.span, parameter_list)
 }
 
+/// The condition of an `if let` block: whether the value isn't `nil`, or whether it has the type it's narrowed to
+fn jsify_if_let_condition(value: &str, type_: &Option<TypeAnnotation>) -> String {
+	let Some(type_) = type_ else {
+		return format!("{value} != undefined");
+	};
+	match type_.kind {
+		TypeAnnotationKind::Number => format!("typeof {value} === \"number\""),
+		TypeAnnotationKind::String => format!("typeof {value} === \"string\""),
+		TypeAnnotationKind::Bool => format!("typeof {value} === \"boolean\""),
+		TypeAnnotationKind::Array(_) | TypeAnnotationKind::MutArray(_) => format!("Array.isArray({value})"),
+		// Narrowing to other types is a type error, so just check that there's a value
+		_ => format!("{value} != undefined"),
+	}
+}
+
 fn jsify_symbol(symbol: &Symbol) -> CodeMaker {
 	new_code!(&symbol.span, &symbol.name)
 }
@@ -2172,11 +2621,16 @@ fn get_exported_symbols(scope: &Scope) -> Vec<Symbol> {
 		match &stmt.kind {
 			StmtKind::Bring { .. } => {}
 			StmtKind::SuperConstructor { .. } => {}
-			StmtKind::Let { .. } => {}
+			StmtKind::Let { var_name, access, .. } => {
+				if *access == AccessModifier::Public || *access == AccessModifier::Internal {
+					symbols.push(var_name.clone());
+				}
+			}
 			StmtKind::ForLoop { .. } => {}
 			StmtKind::While { .. } => {}
 			StmtKind::IfLet(IfLet { .. }) => {}
 			StmtKind::If { .. } => {}
+			StmtKind::Match(_) => {}
 			StmtKind::Break => {}
 			StmtKind::Continue => {}
 			StmtKind::Return(_) => {}
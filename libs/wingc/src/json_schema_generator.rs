@@ -79,7 +79,16 @@ impl JsonSchemaGenerator {
 				code.to_string()
 			}
 			Type::Optional(t) => self.get_struct_schema_field(&t),
+			Type::Union(ref members) => {
+				let choices = members
+					.iter()
+					.map(|t| self.get_struct_schema_field(t))
+					.collect::<Vec<String>>()
+					.join(", ");
+				format!("{{ anyOf: [{}] }}", choices)
+			}
 			Type::Json(_) => "{ type: [\"object\", \"string\", \"boolean\", \"number\", \"array\"] }".to_string(),
+			Type::Anything => "{}".to_string(),
 			Type::Enum(ref enu) => {
 				let choices = enu
 					.values
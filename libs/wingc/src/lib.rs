@@ -11,8 +11,12 @@ use ast::{Scope, Symbol};
 use camino::{Utf8Path, Utf8PathBuf};
 use closure_transform::ClosureTransformer;
 use comp_ctx::set_custom_panic_hook;
+use compile_cache::CompileCache;
 use const_format::formatcp;
-use diagnostic::{found_errors, report_diagnostic, Diagnostic, DiagnosticSeverity};
+use diagnostic::{
+	diagnostics_count, found_errors, get_diagnostics_since, report_diagnostic, truncate_diagnostics, Diagnostic,
+	DiagnosticSeverity,
+};
 use dtsify::extern_dtsify::{is_extern_file, ExternDTSifier};
 use file_graph::{File, FileGraph};
 use files::Files;
@@ -21,6 +25,7 @@ use indexmap::IndexMap;
 use jsify::JSifier;
 
 use lifting::LiftVisitor;
+use lint::{lint, LintConfig};
 use parser::{as_wing_library, is_entrypoint_file, parse_wing_project};
 use serde::Serialize;
 use struct_schema::StructSchemaVisitor;
@@ -35,12 +40,14 @@ use wingii::type_system::TypeSystem;
 
 use crate::parser::normalize_path;
 use std::alloc::{alloc, dealloc, Layout};
+use std::collections::HashSet;
 
 use std::mem;
 
 use crate::ast::Phase;
+use crate::type_check::cached_exports::CachedExports;
 use crate::type_check::symbol_env::SymbolEnv;
-use crate::type_check::{SymbolEnvOrNamespace, TypeChecker, Types};
+use crate::type_check::{TypeChecker, Types};
 
 #[macro_use]
 #[cfg(test)]
@@ -49,6 +56,7 @@ mod test_utils;
 pub mod ast;
 pub mod closure_transform;
 mod comp_ctx;
+mod compile_cache;
 pub mod debug;
 pub mod diagnostic;
 mod docs;
@@ -56,9 +64,11 @@ mod dtsify;
 mod file_graph;
 mod files;
 pub mod fold;
+pub mod format;
 pub mod jsify;
 pub mod json_schema_generator;
 mod lifting;
+mod lint;
 pub mod lsp;
 pub mod parser;
 pub mod struct_schema;
@@ -286,12 +296,129 @@ pub fn type_check(
 	tc.type_check_file_or_dir(file, scope);
 }
 
+/// Restores the symbols a file exported when it was cached, instead of type checking it (see `type_check`)
+pub fn restore_exports(
+	types: &mut Types,
+	file: &File,
+	file_graph: &FileGraph,
+	jsii_types: &mut TypeSystem,
+	jsii_imports: &mut Vec<JsiiImportSpec>,
+	exports: &CachedExports,
+) -> Result<(), String> {
+	let mut env = types.add_symbol_env(SymbolEnv::new(
+		None,
+		SymbolEnvKind::Scope,
+		Phase::Preflight,
+		0,
+		file.package.clone(),
+	));
+
+	let mut tc = TypeChecker::new(types, file, file_graph, jsii_types, jsii_imports);
+	tc.add_jsii_module_to_env(
+		&mut env,
+		WINGSDK_ASSEMBLY_NAME.to_string(),
+		vec![WINGSDK_STD_MODULE.to_string()],
+		&Symbol::global(WINGSDK_STD_MODULE),
+		None,
+	);
+	tc.patch_constructs();
+	tc.restore_exports(env, exports)
+}
+
 pub fn compile(
 	project_dir: &Utf8Path,
 	source_path: &Utf8Path,
 	source_text: Option<String>,
 	out_dir: &Utf8Path,
 ) -> Result<CompilerOutput, ()> {
+	compile_project(
+		project_dir,
+		source_path,
+		source_text,
+		out_dir,
+		&mut TypeSystem::new(),
+		&mut vec![],
+		None,
+	)
+	.map_err(|_| ())
+}
+
+/// Like `compile`, but reuses the JSII assemblies already loaded into `jsii_types` by previous compilations
+/// (e.g. when recompiling in watch mode). The files and directories the project was read from are collected
+/// into `source_paths`, even if compilation fails.
+///
+/// Unless `use_cache` is false, the results of compiling each file are cached in `out_dir`, and compiling the project
+/// again only compiles the files that changed (or depend on files that changed) since. If the cached results can't
+/// be used the project is compiled from scratch.
+pub fn compile_incremental(
+	project_dir: &Utf8Path,
+	source_path: &Utf8Path,
+	source_text: Option<String>,
+	out_dir: &Utf8Path,
+	jsii_types: &mut TypeSystem,
+	source_paths: &mut Vec<Utf8PathBuf>,
+	use_cache: bool,
+) -> Result<CompilerOutput, ()> {
+	// Source text that isn't read from disk can't be checked against the cache
+	let fingerprint =
+		(use_cache && source_text.is_none()).then(|| compile_cache::fingerprint(project_dir, source_path, out_dir));
+
+	let diagnostics_count = diagnostics_count();
+	let source_paths_count = source_paths.len();
+	match compile_project(
+		project_dir,
+		source_path,
+		source_text.clone(),
+		out_dir,
+		jsii_types,
+		source_paths,
+		fingerprint.clone(),
+	) {
+		Err(ProjectError::InvalidCache(error)) => {
+			// Everything reported by the failed compilation is reported again by the next one
+			truncate_diagnostics(diagnostics_count);
+			source_paths.truncate(source_paths_count);
+			report_diagnostic(Diagnostic {
+				message: format!("Ignoring the compile cache in \"{out_dir}\": {error}"),
+				span: None,
+				annotations: vec![],
+				hints: vec![],
+				severity: DiagnosticSeverity::Warning,
+			});
+			let fingerprint = fingerprint.filter(|_| compile_cache::clear(out_dir).is_ok());
+			compile_project(
+				project_dir,
+				source_path,
+				source_text,
+				out_dir,
+				jsii_types,
+				source_paths,
+				fingerprint,
+			)
+			.map_err(|_| ())
+		}
+		result => result.map_err(|_| ()),
+	}
+}
+
+/// Why compiling a project failed
+enum ProjectError {
+	/// Errors were reported as diagnostics
+	Diagnostics,
+	/// The cached results of a file couldn't be restored, so the project needs to be compiled without the cache
+	InvalidCache(String),
+}
+
+/// Compiles the project, reusing and updating the compile cache in `out_dir` if `cache_fingerprint` is given
+fn compile_project(
+	project_dir: &Utf8Path,
+	source_path: &Utf8Path,
+	source_text: Option<String>,
+	out_dir: &Utf8Path,
+	jsii_types: &mut TypeSystem,
+	source_paths: &mut Vec<Utf8PathBuf>,
+	cache_fingerprint: Option<String>,
+) -> Result<CompilerOutput, ProjectError> {
 	let source_package = as_wing_library(project_dir).unwrap_or_else(|| DEFAULT_PACKAGE_NAME.to_string());
 	let source_path = normalize_path(source_path, None);
 	let source_file = File::new(&source_path, source_package);
@@ -309,15 +436,22 @@ pub fn compile(
 		&mut tree_sitter_trees,
 		&mut asts,
 	);
+	source_paths.extend(file_graph.iter_files().map(|file| file.path.clone()));
 
 	emit_warning_for_unsupported_package_managers(&project_dir);
 
+	let mut cache = CompileCache::load(out_dir, cache_fingerprint, &topo_sorted_files, &file_graph, &files);
+	cache.replay_diagnostics();
+
 	// -- DESUGARING PHASE --
 
 	// Transform all inflight closures defined in preflight into single-method resources
 	let mut asts = asts
 		.into_iter()
 		.map(|(path, scope)| {
+			if cache.contains(&path) {
+				return (path, scope);
+			}
 			let mut inflight_transformer = ClosureTransformer::new();
 			let scope = inflight_transformer.fold_scope(scope);
 			(path, scope)
@@ -328,21 +462,35 @@ pub fn compile(
 
 	// Create universal types collection (need to keep this alive during entire compilation)
 	let mut types = Types::new();
-	let mut jsii_types = TypeSystem::new();
 
 	// Create a universal JSII import spec (need to keep this alive during entire compilation)
 	let mut jsii_imports = vec![];
 
+	// Classes of cached files keep their uids
+	types.class_counter = types.class_counter.max(cache.next_class_uid());
+
 	// Type check all files in topological order (start with files that don't bring any other
 	// Wing files, then move on to files that depend on those, and repeat)
 	for file in &topo_sorted_files {
+		if cache.contains(&file.path) {
+			continue;
+		}
+		// Cached files are only restored when a file that's compiled brings them
+		for dep in file_graph.dependencies_of(file) {
+			cache
+				.restore(dep, &mut asts, &mut types, &file_graph, jsii_types, &mut jsii_imports)
+				.map_err(ProjectError::InvalidCache)?;
+		}
+
+		let class_uids_start = types.class_counter;
+		let diagnostics_start = diagnostics_count();
 		let mut scope = asts.swap_remove(&file.path).expect("matching AST not found");
 		type_check(
 			&mut scope,
 			&mut types,
 			&file,
 			&file_graph,
-			&mut jsii_types,
+			jsii_types,
 			&mut jsii_imports,
 		);
 
@@ -359,8 +507,29 @@ pub fn compile(
 		json_checker.check(&scope);
 
 		asts.insert(file.path.to_owned(), scope);
+		cache.add_compiled_file(&file.path, class_uids_start..types.class_counter);
+		cache.add_diagnostics(&file.path, get_diagnostics_since(diagnostics_start));
 	}
 
+	// Cached files that declare functions in the same extern files as compiled files are needed to generate their
+	// type declarations
+	let extern_files = types
+		.source_file_envs
+		.keys()
+		.filter(|path| is_extern_file(path))
+		.cloned()
+		.collect::<Vec<_>>();
+	cache
+		.restore_extern_declarations(
+			&extern_files,
+			&mut asts,
+			&mut types,
+			&file_graph,
+			jsii_types,
+			&mut jsii_imports,
+		)
+		.map_err(ProjectError::InvalidCache)?;
+
 	// Verify that the project dir is absolute
 	if !is_absolute_path(&project_dir) {
 		report_diagnostic(Diagnostic {
@@ -370,25 +539,30 @@ pub fn compile(
 			hints: vec![],
 			severity: DiagnosticSeverity::Error,
 		});
-		return Err(());
+		return Err(ProjectError::Diagnostics);
 	}
 
 	let mut jsifier = JSifier::new(&mut types, &files, &file_graph, &source_path, &out_dir);
+	for (_, js) in cache.cached_js() {
+		jsifier.reserve_file_ids(js);
+	}
 
 	// -- LIFTING PHASE --
 
 	let mut asts = asts
 		.into_iter()
 		.map(|(path, scope)| {
-			let mut lift = LiftVisitor::new(&jsifier);
-			lift.visit_scope(&scope);
+			if !cache.contains(&path) {
+				let mut lift = LiftVisitor::new(&jsifier);
+				lift.visit_scope(&scope);
+			}
 			(path, scope)
 		})
 		.collect::<IndexMap<Utf8PathBuf, Scope>>();
 
 	// bail out now (before jsification) if there are errors (no point in jsifying)
 	if found_errors() {
-		return Err(());
+		return Err(ProjectError::Diagnostics);
 	}
 
 	// -- STRUCT SCHEMA GENERATION PHASE --
@@ -396,8 +570,10 @@ pub fn compile(
 	asts = asts
 		.into_iter()
 		.map(|(path, scope)| {
-			let mut reference_visitor = StructSchemaVisitor::new(&path, &jsifier);
-			reference_visitor.visit_scope(&scope);
+			if !cache.contains(&path) {
+				let mut reference_visitor = StructSchemaVisitor::new(&path, &jsifier);
+				reference_visitor.visit_scope(&scope);
+			}
 			(path, scope)
 		})
 		.collect::<IndexMap<Utf8PathBuf, Scope>>();
@@ -405,8 +581,21 @@ pub fn compile(
 	// -- JSIFICATION PHASE --
 
 	for file in &topo_sorted_files {
+		if let Some((_, js)) = cache.cached_js().find(|(path, _)| *path == file.path) {
+			jsifier.add_cached_files(&file.path, js);
+			continue;
+		}
+		let previous_paths = jsifier
+			.output_files
+			.borrow()
+			.paths()
+			.map(Utf8Path::to_path_buf)
+			.collect::<HashSet<_>>();
 		let scope = asts.get_mut(&file.path).expect("matching AST not found");
 		jsifier.jsify(&file, &scope);
+		let output_files = jsifier.output_files.borrow();
+		let new_paths = output_files.paths().filter(|path| !previous_paths.contains(*path));
+		cache.add_js(&file.path, jsifier.cached_files(&file.path, new_paths));
 	}
 	if !found_errors() {
 		match jsifier.output_files.borrow().emit_files(out_dir) {
@@ -420,8 +609,17 @@ pub fn compile(
 		let preflight_file_map = jsifier.preflight_file_map.borrow();
 		let dtsifier = dtsify::DTSifier::new(&mut types, &preflight_file_map, &mut file_graph);
 		for file in &topo_sorted_files {
+			// The type declarations of cached files are emitted with their JS files
+			if cache.contains(&file.path) {
+				continue;
+			}
 			let scope = asts.get_mut(&file.path).expect("matching AST not found");
 			dtsifier.dtsify(&file, &scope);
+			let preflight_file_name = Utf8Path::new(&preflight_file_map[&file.path]);
+			let dts_file_name = preflight_file_name.with_extension(dtsify::EMIT_TYPE_FILE_EXTENSION);
+			if let Some(dts) = dtsifier.output_files.borrow().get_file(&dts_file_name) {
+				cache.add_dts(&file.path, &dts_file_name, dts);
+			}
 		}
 		if !found_errors() {
 			let output_files = dtsifier.output_files.borrow();
@@ -445,19 +643,35 @@ pub fn compile(
 		}
 	}
 
+	// -- LINTING PHASE --
+	// Lint rules assume the program is otherwise valid, so only run them if nothing else failed
+	if !found_errors() {
+		let lint_config = LintConfig::load(project_dir);
+		for file in &topo_sorted_files {
+			// Don't lint code from other packages (e.g. Wing libraries), or files whose warnings were cached
+			if file.package != source_file.package || cache.contains(&file.path) {
+				continue;
+			}
+			let scope = asts.get(&file.path).expect("matching AST not found");
+			let diagnostics_start = diagnostics_count();
+			lint(scope, &types, &lint_config);
+			cache.add_diagnostics(&file.path, get_diagnostics_since(diagnostics_start));
+		}
+	}
+
 	if found_errors() {
-		return Err(());
+		return Err(ProjectError::Diagnostics);
 	}
 
-	let imported_namespaces = types
-		.source_file_envs
+	// Directories are type checked into namespaces (cached ones may not be type checked at all)
+	let imported_namespaces = topo_sorted_files
 		.iter()
-		.filter_map(|(k, v)| match v {
-			SymbolEnvOrNamespace::Namespace(_) => Some(k.to_string()),
-			_ => None,
-		})
+		.filter(|file| file.path.is_dir())
+		.map(|file| file.path.to_string())
 		.collect::<Vec<String>>();
 
+	cache.save(project_dir, &topo_sorted_files, &file_graph, &files, &types, jsii_types);
+
 	Ok(CompilerOutput { imported_namespaces })
 }
 
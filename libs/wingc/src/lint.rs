@@ -0,0 +1,375 @@
+use std::{cell::OnceCell, collections::HashMap, fs};
+
+use camino::Utf8Path;
+use wingii::util::package_json::find_up;
+
+use crate::{
+	ast::Scope,
+	diagnostic::{report_diagnostic, Diagnostic, DiagnosticSeverity, WingSpan},
+	type_check::Types,
+};
+
+mod shadowed_variable;
+mod unreachable_code;
+mod unsafe_any;
+mod unused;
+
+/// The key in `package.json` under which lint rule levels can be configured, e.g.
+/// ```json
+/// {
+///   "wingLint": {
+///     "unused-variable": "error",
+///     "shadowed-variable": "off"
+///   }
+/// }
+/// ```
+pub const LINT_CONFIG_KEY: &str = "wingLint";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+	Off,
+	Warning,
+	Error,
+}
+
+impl LintLevel {
+	fn parse(level: &str) -> Option<Self> {
+		match level {
+			"off" => Some(LintLevel::Off),
+			"warning" | "warn" => Some(LintLevel::Warning),
+			"error" => Some(LintLevel::Error),
+			_ => None,
+		}
+	}
+}
+
+/// Something a lint rule found in the code
+pub struct LintFinding {
+	pub message: String,
+	pub span: WingSpan,
+	pub hints: Vec<String>,
+}
+
+impl LintFinding {
+	pub fn new(message: impl ToString, span: WingSpan) -> Self {
+		Self {
+			message: message.to_string(),
+			span,
+			hints: vec![],
+		}
+	}
+
+	pub fn hint(mut self, hint: impl ToString) -> Self {
+		self.hints.push(hint.to_string());
+		self
+	}
+}
+
+/// A lint rule checks a type checked AST for code that is valid, but likely not what the user intended.
+pub trait LintRule {
+	/// A stable identifier for the rule, used to configure it
+	fn id(&self) -> &'static str;
+
+	/// The level used when the project doesn't configure this rule
+	fn default_level(&self) -> LintLevel {
+		LintLevel::Warning
+	}
+
+	fn check(&self, ctx: &LintContext) -> Vec<LintFinding>;
+}
+
+/// The type checked scope being linted. Analyses shared by several rules are computed once, when
+/// the first rule that needs them asks for them.
+pub struct LintContext<'a> {
+	pub scope: &'a Scope,
+	pub types: &'a Types,
+	usage: OnceCell<unused::Usage>,
+}
+
+impl<'a> LintContext<'a> {
+	pub fn new(scope: &'a Scope, types: &'a Types) -> Self {
+		Self {
+			scope,
+			types,
+			usage: OnceCell::new(),
+		}
+	}
+
+	/// The `let` and `bring` symbols declared in the scope and which of them are referenced
+	fn usage(&self) -> &unused::Usage {
+		self
+			.usage
+			.get_or_init(|| unused::Usage::collect(self.scope, self.types))
+	}
+}
+
+/// All registered lint rules
+fn rules() -> Vec<Box<dyn LintRule>> {
+	vec![
+		Box::new(unused::UnusedVariable),
+		Box::new(unused::UnusedBring),
+		Box::new(unreachable_code::UnreachableCode),
+		Box::new(shadowed_variable::ShadowedVariable),
+		Box::new(unsafe_any::UnsafeAny),
+	]
+}
+
+/// The lint levels configured by the project
+#[derive(Debug, Default)]
+pub struct LintConfig {
+	levels: HashMap<String, LintLevel>,
+}
+
+impl LintConfig {
+	/// Loads the lint configuration from the closest `package.json` to `dir`.
+	/// Invalid entries are reported as warnings and otherwise ignored.
+	pub fn load(dir: &Utf8Path) -> Self {
+		let Some(package_dir) = find_up(dir.to_owned(), |d| d.join("package.json").exists()) else {
+			return Self::default();
+		};
+		let package_json_path = package_dir.join("package.json");
+		let Ok(package_json) = fs::read_to_string(&package_json_path) else {
+			return Self::default();
+		};
+		let Ok(package_json) = serde_json::from_str::<serde_json::Value>(&package_json) else {
+			return Self::default();
+		};
+		match package_json.get(LINT_CONFIG_KEY) {
+			Some(config) => Self::from_json(config, package_json_path.as_str()),
+			None => Self::default(),
+		}
+	}
+
+	fn from_json(config: &serde_json::Value, source: &str) -> Self {
+		let mut levels = HashMap::new();
+		let Some(config) = config.as_object() else {
+			report_config_warning(format!("Expected \"{LINT_CONFIG_KEY}\" in {source} to be an object"));
+			return Self { levels };
+		};
+
+		let known_rules = rules().iter().map(|r| r.id()).collect::<Vec<_>>();
+		for (rule_id, level) in config {
+			if !known_rules.contains(&rule_id.as_str()) {
+				report_config_warning(format!("Unknown lint rule \"{rule_id}\" in {source}"));
+				continue;
+			}
+			match level.as_str().and_then(LintLevel::parse) {
+				Some(level) => {
+					levels.insert(rule_id.clone(), level);
+				}
+				None => report_config_warning(format!(
+					"Invalid level {level} for lint rule \"{rule_id}\" in {source} (expected \"off\", \"warning\" or \"error\")"
+				)),
+			}
+		}
+
+		Self { levels }
+	}
+
+	pub fn level(&self, rule: &dyn LintRule) -> LintLevel {
+		self.levels.get(rule.id()).copied().unwrap_or(rule.default_level())
+	}
+}
+
+fn report_config_warning(message: String) {
+	report_diagnostic(Diagnostic {
+		message,
+		span: None,
+		annotations: vec![],
+		hints: vec![],
+		severity: DiagnosticSeverity::Warning,
+	});
+}
+
+/// Runs all enabled lint rules on a type checked scope and reports their findings as diagnostics.
+pub fn lint(scope: &Scope, types: &Types, config: &LintConfig) {
+	let ctx = LintContext::new(scope, types);
+	for rule in rules() {
+		let severity = match config.level(rule.as_ref()) {
+			LintLevel::Off => continue,
+			LintLevel::Warning => DiagnosticSeverity::Warning,
+			LintLevel::Error => DiagnosticSeverity::Error,
+		};
+		for finding in rule.check(&ctx) {
+			let mut hints = finding.hints;
+			hints.push(format!(
+				"Reported by the \"{}\" lint rule, configure it under \"{LINT_CONFIG_KEY}\" in package.json",
+				rule.id()
+			));
+			report_diagnostic(Diagnostic {
+				message: finding.message,
+				span: Some(finding.span),
+				annotations: vec![],
+				hints,
+				severity: severity.clone(),
+			});
+		}
+	}
+}
+
+/// Symbols starting with an underscore are considered intentionally unused (this also covers
+/// compiler generated symbols like `__parent_this`)
+fn is_ignored_name(name: &str) -> bool {
+	name.starts_with('_') || name.starts_with('$')
+}
+
+#[cfg(test)]
+mod tests {
+	use camino::Utf8Path;
+
+	use super::*;
+	use crate::{
+		compile,
+		diagnostic::{get_diagnostics, reset_diagnostics},
+	};
+
+	fn lint_warnings(code: &str) -> Vec<(String, usize)> {
+		lint_warnings_with_config(code, None)
+	}
+
+	fn lint_warnings_with_config(code: &str, config: Option<serde_json::Value>) -> Vec<(String, usize)> {
+		reset_diagnostics();
+		let project_dir = tempfile::tempdir().unwrap();
+		let project_dir = Utf8Path::from_path(project_dir.path()).unwrap();
+		if let Some(config) = config {
+			let package_json = serde_json::json!({ LINT_CONFIG_KEY: config });
+			fs::write(project_dir.join("package.json"), package_json.to_string()).unwrap();
+		}
+		let out_dir = project_dir.join("target/main.out/.wing");
+		let code = code.replace("\t", "  ");
+		compile(project_dir, &project_dir.join("main.w"), Some(code), &out_dir).expect("compilation failed");
+		get_diagnostics()
+			.into_iter()
+			.filter(|d| d.severity == DiagnosticSeverity::Warning)
+			.filter_map(|d| d.span.map(|s| (d.message, s.start.line as usize)))
+			.collect()
+	}
+
+	#[test]
+	fn unused_let() {
+		let warnings = lint_warnings(
+			r#"
+let x = 1;
+let _y = 2;
+let z = 3;
+log("{z}");
+"#,
+		);
+		assert_eq!(warnings, vec![("Unused variable \"x\"".to_string(), 1)]);
+	}
+
+	#[test]
+	fn unused_let_shadowed_by_own_initializer() {
+		let warnings = lint_warnings(
+			r#"
+let x = 1;
+if true {
+	let x = x + 1;
+	log("{x}");
+}
+"#,
+		);
+		assert_eq!(
+			warnings,
+			vec![("Variable \"x\" shadows a variable in an outer scope".to_string(), 3)]
+		);
+	}
+
+	#[test]
+	fn unused_bring() {
+		let warnings = lint_warnings(
+			r#"
+bring cloud;
+bring util;
+bring math;
+new cloud.Bucket();
+log("{math.PI}");
+"#,
+		);
+		assert_eq!(warnings, vec![("Unused bring \"util\"".to_string(), 2)]);
+	}
+
+	#[test]
+	fn unreachable_code() {
+		let warnings = lint_warnings(
+			r#"
+let f = (): num => {
+	return 1;
+	log("never");
+};
+let g = () => {
+	throw "oops";
+	log("never");
+};
+f();
+g();
+"#,
+		);
+		assert_eq!(
+			warnings,
+			vec![("Unreachable code".to_string(), 3), ("Unreachable code".to_string(), 7)]
+		);
+	}
+
+	#[test]
+	fn shadowed_variable() {
+		let warnings = lint_warnings(
+			r#"
+let x = 1;
+let f = (x: num) => {
+	log("{x}");
+};
+f(x);
+"#,
+		);
+		assert_eq!(
+			warnings,
+			vec![("Variable \"x\" shadows a variable in an outer scope".to_string(), 2)]
+		);
+	}
+
+	#[test]
+	fn unsafe_any() {
+		let code = r#"
+let f = (x: str): str => {
+	let y: any = x;
+	return y;
+};
+let a: any = "hello";
+let b: str = a;
+let c: any = a;
+f(a);
+f(b);
+log("{c}");
+"#;
+		// Disabled by default
+		assert_eq!(lint_warnings(code), vec![]);
+
+		let warnings = lint_warnings_with_config(code, Some(serde_json::json!({ "unsafe-any": "warning" })));
+		assert_eq!(
+			warnings,
+			vec![
+				(
+					"Value of type \"any\" is used as \"the function's return type\"".to_string(),
+					3
+				),
+				("Value of type \"any\" is used as \"str\"".to_string(), 6),
+				("Value of type \"any\" is used as \"str\"".to_string(), 8),
+			]
+		);
+	}
+
+	#[test]
+	fn configured_levels() {
+		let config = LintConfig::from_json(
+			&serde_json::json!({
+				"unused-variable": "error",
+				"shadowed-variable": "off",
+			}),
+			"package.json",
+		);
+		assert_eq!(config.level(&unused::UnusedVariable), LintLevel::Error);
+		assert_eq!(config.level(&shadowed_variable::ShadowedVariable), LintLevel::Off);
+		assert_eq!(config.level(&unused::UnusedBring), LintLevel::Warning);
+	}
+}
@@ -0,0 +1,98 @@
+use crate::{
+	ast::{FunctionBody, FunctionDefinition, Scope, Stmt, StmtKind, Symbol},
+	type_check::{
+		symbol_env::{LookupResult, SymbolEnvKind, SymbolEnvRef},
+		SymbolKind, Types,
+	},
+	visit::{self, Visit},
+};
+
+use super::{is_ignored_name, LintContext, LintFinding, LintRule};
+
+/// Reports variables (`let` bindings, function parameters and loop iterators) that hide a
+/// variable with the same name from an outer scope
+pub struct ShadowedVariable;
+
+impl LintRule for ShadowedVariable {
+	fn id(&self) -> &'static str {
+		"shadowed-variable"
+	}
+
+	fn check(&self, ctx: &LintContext) -> Vec<LintFinding> {
+		let mut visitor = ShadowedVariableVisitor {
+			types: ctx.types,
+			envs: vec![],
+			findings: vec![],
+		};
+		visitor.visit_scope(ctx.scope);
+		visitor.findings
+	}
+}
+
+struct ShadowedVariableVisitor<'a> {
+	types: &'a Types,
+	envs: Vec<SymbolEnvRef>,
+	findings: Vec<LintFinding>,
+}
+
+impl ShadowedVariableVisitor<'_> {
+	/// Checks whether `symbol`, which is defined in `env`, hides a variable from an outer scope
+	fn check_symbol(&mut self, symbol: &Symbol, env: SymbolEnvRef) {
+		if is_ignored_name(&symbol.name) {
+			return;
+		}
+		let Some(parent) = &env.parent else {
+			return;
+		};
+		let LookupResult::Found(SymbolKind::Variable(_), info) = parent.lookup_ext(symbol, Some(env.statement_idx)) else {
+			return;
+		};
+		// Class members are always accessed through `this` so they can't be shadowed, and builtins
+		// have no span to point at
+		if matches!(info.env.kind, SymbolEnvKind::Type(_)) || info.span.is_default() {
+			return;
+		}
+		self.findings.push(
+			LintFinding::new(
+				format!("Variable \"{}\" shadows a variable in an outer scope", symbol.name),
+				symbol.span.clone(),
+			)
+			.hint(format!("\"{}\" is first defined at {}", symbol.name, info.span)),
+		);
+	}
+}
+
+impl<'a> Visit<'a> for ShadowedVariableVisitor<'_> {
+	fn visit_scope(&mut self, node: &'a Scope) {
+		self.envs.push(self.types.get_scope_env(node));
+		visit::visit_scope(self, node);
+		self.envs.pop();
+	}
+
+	fn visit_stmt(&mut self, node: &'a Stmt) {
+		match &node.kind {
+			StmtKind::Let { var_name, .. } => {
+				if let Some(env) = self.envs.last() {
+					self.check_symbol(var_name, *env);
+				}
+			}
+			StmtKind::ForLoop {
+				iterator, statements, ..
+			} => {
+				self.check_symbol(iterator, self.types.get_scope_env(statements));
+			}
+			_ => {}
+		}
+		visit::visit_stmt(self, node);
+	}
+
+	fn visit_function_definition(&mut self, node: &'a FunctionDefinition) {
+		if let FunctionBody::Statements(scope) = &node.body {
+			let env = self.types.get_scope_env(scope);
+			for param in &node.signature.parameters {
+				self.check_symbol(&param.name, env);
+			}
+		}
+		visit::visit_function_definition(self, node);
+	}
+}
@@ -0,0 +1,45 @@
+use crate::{
+	ast::{Scope, StmtKind},
+	visit::{self, Visit},
+};
+
+use super::{LintContext, LintFinding, LintRule};
+
+/// Reports statements that follow a `return`, `throw`, `break` or `continue` in the same block
+pub struct UnreachableCode;
+
+impl LintRule for UnreachableCode {
+	fn id(&self) -> &'static str {
+		"unreachable-code"
+	}
+
+	fn check(&self, ctx: &LintContext) -> Vec<LintFinding> {
+		let mut visitor = UnreachableCodeVisitor { findings: vec![] };
+		visitor.visit_scope(ctx.scope);
+		visitor.findings
+	}
+}
+
+struct UnreachableCodeVisitor {
+	findings: Vec<LintFinding>,
+}
+
+impl<'a> Visit<'a> for UnreachableCodeVisitor {
+	fn visit_scope(&mut self, node: &'a Scope) {
+		let exit = node.statements.iter().position(|s| {
+			matches!(
+				s.kind,
+				StmtKind::Return(_) | StmtKind::Throw(_) | StmtKind::Break | StmtKind::Continue
+			)
+		});
+		if let Some(exit) = exit {
+			if let (Some(first), Some(last)) = (node.statements.get(exit + 1), node.statements.last()) {
+				self
+					.findings
+					.push(LintFinding::new("Unreachable code", first.span.merge(&last.span)));
+			}
+		}
+
+		visit::visit_scope(self, node);
+	}
+}
@@ -0,0 +1,128 @@
+use crate::{
+	ast::{ArgList, CalleeKind, Expr, ExprKind, FunctionBody, FunctionDefinition, Stmt, StmtKind, TypeAnnotationKind},
+	type_check::{ClassLike, FunctionSignature, Types, CLASS_INIT_NAME},
+	visit::{self, Visit},
+};
+
+use super::{LintContext, LintFinding, LintLevel, LintRule};
+
+/// Reports values of type `any` that are used where a specific type is expected, since the type checker can't
+/// verify them. Disabled unless the project opts in.
+pub struct UnsafeAny;
+
+impl LintRule for UnsafeAny {
+	fn id(&self) -> &'static str {
+		"unsafe-any"
+	}
+
+	fn default_level(&self) -> LintLevel {
+		LintLevel::Off
+	}
+
+	fn check(&self, ctx: &LintContext) -> Vec<LintFinding> {
+		let mut visitor = UnsafeAnyVisitor {
+			types: ctx.types,
+			returns_typed_value: vec![],
+			findings: vec![],
+		};
+		visitor.visit_scope(ctx.scope);
+		visitor.findings
+	}
+}
+
+struct UnsafeAnyVisitor<'a> {
+	types: &'a Types,
+	/// For each function we're in, whether it declares a (non `any`) return type
+	returns_typed_value: Vec<bool>,
+	findings: Vec<LintFinding>,
+}
+
+impl UnsafeAnyVisitor<'_> {
+	fn is_any(&self, expr: &Expr) -> bool {
+		self.types.try_get_expr_type(expr.id).map_or(false, |t| t.is_anything())
+	}
+
+	fn report(&mut self, expr: &Expr, expected: impl std::fmt::Display) {
+		self.findings.push(
+			LintFinding::new(
+				format!("Value of type \"any\" is used as \"{expected}\""),
+				expr.span.clone(),
+			)
+			.hint("The type of \"any\" values isn't checked, consider validating the value or casting it explicitly"),
+		);
+	}
+
+	fn check_args(&mut self, arg_list: &ArgList, sig: Option<&FunctionSignature>) {
+		let Some(sig) = sig else {
+			return;
+		};
+		for (arg, param) in arg_list.pos_args.iter().zip(sig.parameters.iter()) {
+			if self.is_any(arg) && !param.typeref.is_anything() {
+				self.report(arg, param.typeref);
+			}
+		}
+	}
+}
+
+impl<'a> Visit<'a> for UnsafeAnyVisitor<'_> {
+	fn visit_stmt(&mut self, node: &'a Stmt) {
+		match &node.kind {
+			StmtKind::Let {
+				type_: Some(type_),
+				initial_value,
+				..
+			} if !matches!(type_.kind, TypeAnnotationKind::Any) => {
+				if self.is_any(initial_value) {
+					self.report(initial_value, type_);
+				}
+			}
+			StmtKind::Return(Some(value)) => {
+				if self.returns_typed_value.last().copied().unwrap_or(false) && self.is_any(value) {
+					self.report(value, "the function's return type");
+				}
+			}
+			_ => {}
+		}
+		visit::visit_stmt(self, node);
+	}
+
+	fn visit_expr(&mut self, node: &'a Expr) {
+		match &node.kind {
+			ExprKind::Call {
+				callee: CalleeKind::Expr(callee),
+				arg_list,
+			} => {
+				let callee_type = self.types.try_get_expr_type(callee.id);
+				self.check_args(
+					arg_list,
+					callee_type
+						.as_ref()
+						.and_then(|t| t.maybe_unwrap_option().as_deep_function_sig()),
+				);
+			}
+			ExprKind::New(new_expr) => {
+				let class_type = self.types.try_get_expr_type(node.id);
+				let init = class_type
+					.as_ref()
+					.and_then(|t| t.as_class())
+					.and_then(|c| c.get_method(&CLASS_INIT_NAME.into()))
+					.map(|v| v.type_);
+				self.check_args(&new_expr.arg_list, init.as_ref().and_then(|t| t.as_function_sig()));
+			}
+			_ => {}
+		}
+		visit::visit_expr(self, node);
+	}
+
+	fn visit_function_definition(&mut self, node: &'a FunctionDefinition) {
+		let FunctionBody::Statements(_) = &node.body else {
+			return visit::visit_function_definition(self, node);
+		};
+		self.returns_typed_value.push(!matches!(
+			node.signature.return_type.kind,
+			TypeAnnotationKind::Any | TypeAnnotationKind::Inferred | TypeAnnotationKind::Void
+		));
+		visit::visit_function_definition(self, node);
+		self.returns_typed_value.pop();
+	}
+}
@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+
+use crate::{
+	ast::{AccessModifier, BringSource, Reference, Scope, Stmt, StmtKind, Symbol, UserDefinedType},
+	diagnostic::WingSpan,
+	type_check::{symbol_env::LookupResult, Types},
+	visit::{self, Visit},
+	visit_context::{VisitContext, VisitorWithContext},
+};
+
+use super::{is_ignored_name, LintContext, LintFinding, LintRule};
+
+/// Reports `let` bindings that are never referenced
+pub struct UnusedVariable;
+
+impl LintRule for UnusedVariable {
+	fn id(&self) -> &'static str {
+		"unused-variable"
+	}
+
+	fn check(&self, ctx: &LintContext) -> Vec<LintFinding> {
+		let usage = ctx.usage();
+		usage
+			.lets
+			.iter()
+			.filter(|s| !usage.used.contains(&s.span))
+			.map(|s| {
+				LintFinding::new(format!("Unused variable \"{}\"", s.name), s.span.clone()).hint(format!(
+					"Prefix the name with an underscore (\"_{}\") to ignore this",
+					s.name
+				))
+			})
+			.collect()
+	}
+}
+
+/// Reports `bring` statements whose namespace is never referenced
+pub struct UnusedBring;
+
+impl LintRule for UnusedBring {
+	fn id(&self) -> &'static str {
+		"unused-bring"
+	}
+
+	fn check(&self, ctx: &LintContext) -> Vec<LintFinding> {
+		let usage = ctx.usage();
+		usage
+			.brings
+			.iter()
+			.filter(|(s, _)| !usage.used.contains(&s.span))
+			.map(|(s, stmt_span)| LintFinding::new(format!("Unused bring \"{}\"", s.name), stmt_span.clone()))
+			.collect()
+	}
+}
+
+/// The `let` and `bring` symbols declared in a scope, and the definition spans of every symbol
+/// that is referenced, so we can tell which declarations are never used.
+pub(super) struct Usage {
+	lets: Vec<Symbol>,
+	/// Brought symbol and the span of the bring statement
+	brings: Vec<(Symbol, WingSpan)>,
+	used: HashSet<WingSpan>,
+}
+
+impl Usage {
+	pub(super) fn collect(scope: &Scope, types: &Types) -> Self {
+		let mut collector = UsageCollector::new(types);
+		collector.visit_scope(scope);
+		Self {
+			lets: collector.lets,
+			brings: collector.brings,
+			used: collector.used,
+		}
+	}
+}
+
+/// Walks a scope to collect its `Usage`
+struct UsageCollector<'a> {
+	types: &'a Types,
+	ctx: VisitContext,
+	lets: Vec<Symbol>,
+	/// Brought symbol and the span of the bring statement
+	brings: Vec<(Symbol, WingSpan)>,
+	used: HashSet<WingSpan>,
+	/// The variable currently being defined, references to it from its own initializer refer
+	/// to an outer variable with the same name
+	defining: Option<WingSpan>,
+}
+
+impl<'a> UsageCollector<'a> {
+	fn new(types: &'a Types) -> Self {
+		Self {
+			types,
+			ctx: VisitContext::new(),
+			lets: vec![],
+			brings: vec![],
+			used: HashSet::new(),
+			defining: None,
+		}
+	}
+
+	fn mark_used(&mut self, symbol: &Symbol) {
+		let Some(env) = self.ctx.current_env() else {
+			return;
+		};
+		let stmt_idx = self.ctx.current_stmt_idx();
+		let result = env.lookup_ext(symbol, Some(stmt_idx));
+		// a symbol referenced while it's being defined refers to the one it shadows
+		let shadowed_env = match &result {
+			LookupResult::Found(_, info) if Some(&info.span) == self.defining.as_ref() => info.env.parent,
+			_ => None,
+		};
+		let result = match &shadowed_env {
+			Some(parent) => parent.lookup_ext(symbol, None),
+			None => result,
+		};
+		if let LookupResult::Found(_, info) = result {
+			self.used.insert(info.span);
+		}
+	}
+}
+
+impl VisitorWithContext for UsageCollector<'_> {
+	fn ctx(&mut self) -> &mut VisitContext {
+		&mut self.ctx
+	}
+}
+
+impl<'a> Visit<'a> for UsageCollector<'_> {
+	fn visit_scope(&mut self, node: &'a Scope) {
+		self.ctx.push_env(self.types.get_scope_env(node));
+		visit::visit_scope(self, node);
+		self.ctx.pop_env();
+	}
+
+	fn visit_stmt(&mut self, node: &'a Stmt) {
+		match &node.kind {
+			StmtKind::Let {
+				var_name,
+				initial_value,
+				type_,
+				access,
+				..
+			} => {
+				// Exported variables can be used by other modules
+				if *access == AccessModifier::Private && !is_ignored_name(&var_name.name) {
+					self.lets.push(var_name.clone());
+				}
+				self.with_stmt(node, |v| {
+					if let Some(type_) = type_ {
+						v.visit_type_annotation(type_);
+					}
+					let outer = v.defining.replace(var_name.span.clone());
+					v.visit_expr(initial_value);
+					v.defining = outer;
+				});
+				return;
+			}
+			StmtKind::Bring { source, identifier } => {
+				let brought = match source {
+					BringSource::BuiltinModule(name)
+					| BringSource::WingLibrary(name, _)
+					| BringSource::TrustedModule(name, _) => Some(identifier.as_ref().unwrap_or(name)),
+					BringSource::JsiiModule(_) | BringSource::WingFile(_) | BringSource::Directory(_) => identifier.as_ref(),
+				};
+				if let Some(brought) = brought {
+					if !is_ignored_name(&brought.name) {
+						self.brings.push((brought.clone(), node.span.clone()));
+					}
+				}
+			}
+			_ => {}
+		}
+
+		self.with_stmt(node, |v| visit::visit_stmt(v, node));
+	}
+
+	fn visit_reference(&mut self, node: &'a Reference) {
+		if let Reference::Identifier(symbol) = node {
+			self.mark_used(symbol);
+		}
+		visit::visit_reference(self, node);
+	}
+
+	fn visit_user_defined_type(&mut self, node: &'a UserDefinedType) {
+		self.mark_used(&node.root);
+		visit::visit_user_defined_type(self, node);
+	}
+}
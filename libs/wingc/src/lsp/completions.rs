@@ -372,6 +372,7 @@ pub fn on_completion(params: lsp_types::CompletionParams) -> CompletionResponse
 									let udt = UserDefinedType {
 										root: Symbol::global(parts[0].to_string()),
 										fields: parts[1..].iter().map(|s| Symbol::global(s.to_string())).collect(),
+										type_arguments: vec![],
 										span: WingSpan::default(),
 									};
 									// Import all types in the namespace by trying to load the "dummy type"
@@ -1118,6 +1119,7 @@ fn format_symbol_kind_as_completion(name: &str, symbol_kind: &SymbolKind) -> Opt
 				| Type::MutMap(_)
 				| Type::Set(_)
 				| Type::MutSet(_)
+				| Type::Promise(_)
 				| Type::Class(_) => CompletionItemKind::CLASS,
 				Type::Anything
 				| Type::Number
@@ -1133,7 +1135,8 @@ fn format_symbol_kind_as_completion(name: &str, symbol_kind: &SymbolKind) -> Opt
 				| Type::Nil
 				| Type::Unresolved
 				| Type::Inferred(_)
-				| Type::Optional(_) => CompletionItemKind::CONSTANT,
+				| Type::Optional(_)
+				| Type::Union(_) => CompletionItemKind::CONSTANT,
 				Type::Function(_) => CompletionItemKind::FUNCTION,
 				Type::Struct(_) => CompletionItemKind::STRUCT,
 				Type::Enum(_) => CompletionItemKind::ENUM,
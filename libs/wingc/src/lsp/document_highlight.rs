@@ -0,0 +1,88 @@
+use lsp_types::{DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams};
+
+use crate::lsp::references::find_symbol_references;
+use crate::lsp::sync::PROJECT_DATA;
+use crate::wasm_util::extern_json_fn;
+
+use super::sync::{check_utf8, WING_TYPES};
+
+#[no_mangle]
+pub unsafe extern "C" fn wingc_on_document_highlight(ptr: u32, len: u32) -> u64 {
+	extern_json_fn(ptr, len, on_document_highlight)
+}
+
+pub fn on_document_highlight(params: DocumentHighlightParams) -> Vec<DocumentHighlight> {
+	WING_TYPES.with(|types| {
+		let types = types.borrow();
+		PROJECT_DATA.with(|project_data| -> Vec<DocumentHighlight> {
+			let project_data = project_data.borrow();
+			let uri = params.text_document_position_params.text_document.uri;
+			let file = check_utf8(uri.to_file_path().expect("LSP only works on real filesystems"));
+			let position = params.text_document_position_params.position;
+
+			let Some((declaration, references)) = find_symbol_references(&types, &project_data, &file, position) else {
+				return vec![];
+			};
+
+			// Only the occurrences in the current document are highlighted (the declaration might be in another file)
+			std::iter::once(declaration)
+				.chain(references)
+				.filter(|span| span.file_id == file.as_str())
+				.map(|span| DocumentHighlight {
+					range: span.into(),
+					kind: Some(DocumentHighlightKind::TEXT),
+				})
+				.collect()
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use lsp_types::*;
+
+	use crate::lsp::document_highlight::*;
+	use crate::lsp::sync::test_utils::*;
+
+	fn highlight(code: &str) -> Vec<Range> {
+		let text_document_position_params = load_file_with_contents(code);
+		on_document_highlight(DocumentHighlightParams {
+			text_document_position_params,
+			work_done_progress_params: Default::default(),
+			partial_result_params: Default::default(),
+		})
+		.into_iter()
+		.map(|h| h.range)
+		.collect()
+	}
+
+	#[test]
+	fn highlights_variable() {
+		let code = r#"
+let var count = 0;
+      //-----
+  count = count + 1;
+//----- //----^
+"#;
+		let mut expected = get_ranges(code);
+		expected.sort_by_key(|r| (r.start.line, r.start.character));
+		let mut ranges = highlight(code);
+		ranges.sort_by_key(|r| (r.start.line, r.start.character));
+		assert_eq!(ranges, expected);
+	}
+
+	#[test]
+	fn highlights_struct_field() {
+		let code = r#"
+struct User {
+  name: str;
+//----
+}
+let u: User = {name: "a"};
+             //----
+log(u.name);
+    //---^
+"#;
+		assert_eq!(highlight(code), get_ranges(code));
+	}
+}
@@ -0,0 +1,83 @@
+use lsp_types::{DocumentFormattingParams, Position, Range, TextEdit};
+
+use crate::format::format_tree;
+use crate::lsp::sync::PROJECT_DATA;
+use crate::wasm_util::extern_json_fn;
+
+use super::sync::check_utf8;
+
+#[no_mangle]
+pub unsafe extern "C" fn wingc_on_formatting(ptr: u32, len: u32) -> u64 {
+	extern_json_fn(ptr, len, on_document_formatting)
+}
+
+pub fn on_document_formatting(params: DocumentFormattingParams) -> Vec<TextEdit> {
+	PROJECT_DATA.with(|project_data| {
+		let project_data = project_data.borrow();
+		let uri = params.text_document.uri;
+		let file = check_utf8(uri.to_file_path().expect("LSP only works on real filesystems"));
+
+		let (Some(source), Some(tree)) = (project_data.files.get_file(&file), project_data.trees.get(&file)) else {
+			return vec![];
+		};
+
+		// Files with syntax errors are left untouched
+		let Ok(formatted) = format_tree(source, tree) else {
+			return vec![];
+		};
+		if formatted == *source {
+			return vec![];
+		}
+
+		vec![TextEdit {
+			range: Range {
+				start: Position::new(0, 0),
+				end: end_position(source),
+			},
+			new_text: formatted,
+		}]
+	})
+}
+
+/// The position right after the last character of `text` (LSP positions count UTF-16 code units)
+fn end_position(text: &str) -> Position {
+	let line = text.matches('\n').count();
+	let last_line = text.rsplit('\n').next().unwrap_or("");
+	Position::new(line as u32, last_line.encode_utf16().count() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+	use lsp_types::*;
+
+	use crate::lsp::formatting::*;
+	use crate::lsp::sync::test_utils::*;
+
+	fn format_document(code: &str) -> Vec<TextEdit> {
+		let text_document_position_params = load_file_with_contents(code);
+		on_document_formatting(DocumentFormattingParams {
+			text_document: text_document_position_params.text_document,
+			options: FormattingOptions::default(),
+			work_done_progress_params: Default::default(),
+		})
+	}
+
+	#[test]
+	fn formats_whole_document() {
+		let edits = format_document("let x=1;\nif x>0 {\nlog(\"{x}\");}");
+		assert_eq!(edits.len(), 1);
+		assert_eq!(edits[0].range.start, Position::new(0, 0));
+		assert_eq!(edits[0].range.end, Position::new(2, 12));
+		assert_eq!(edits[0].new_text, "let x = 1;\nif x > 0 {\n  log(\"{x}\");\n}\n");
+	}
+
+	#[test]
+	fn formatted_document_has_no_edits() {
+		assert!(format_document("let x = 1;\n").is_empty());
+	}
+
+	#[test]
+	fn syntax_errors_have_no_edits() {
+		assert!(format_document("let x = ;\n").is_empty());
+	}
+}
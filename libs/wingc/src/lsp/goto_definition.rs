@@ -1,12 +1,19 @@
+use std::fs;
+
 use crate::lsp::symbol_locator::SymbolLocator;
 use crate::lsp::sync::PROJECT_DATA;
-use crate::type_check::symbol_env::LookupResult;
+use crate::type_check::symbol_env::{LookupResult, SymbolEnvKind, SymbolLookupInfo};
+use crate::type_check::{SymbolKind, Type};
 use crate::visit::Visit;
 use crate::wasm_util::extern_json_fn;
+use camino::{Utf8Path, Utf8PathBuf};
 use lsp_types::{GotoDefinitionParams, LocationLink, Position, Range, Url};
+use regex::Regex;
 use tree_sitter::Point;
+use wingii::fqn::FQN;
+use wingii::type_system::TypeSystem;
 
-use super::sync::{check_utf8, WING_TYPES};
+use super::sync::{check_utf8, JSII_TYPES, WING_TYPES};
 
 #[no_mangle]
 pub unsafe extern "C" fn wingc_on_goto_definition(ptr: u32, len: u32) -> u64 {
@@ -27,14 +34,21 @@ pub fn on_goto_definition(params: GotoDefinitionParams) -> Vec<LocationLink> {
 			symbol_finder.visit_scope(scope);
 
 			if let Some(lookup) = symbol_finder.lookup_located_symbol() {
-				if let LookupResult::Found(_, info) = &lookup {
-					if let Ok(target_uri) = Url::from_file_path(&info.span.file_id) {
-						let origin_span = &info.span;
+				if let LookupResult::Found(kind, info) | LookupResult::NotPublic(kind, info) = &lookup {
+					// Symbols imported from JSII have no span, so we look for their declaration in the package's typings instead
+					let target = if info.span.is_default() {
+						JSII_TYPES.with(|jsii_types| find_jsii_declaration(&jsii_types.borrow(), kind, info))
+					} else {
+						Url::from_file_path(&info.span.file_id)
+							.ok()
+							.map(|uri| (uri, (&info.span).into()))
+					};
+					if let Some((target_uri, target_range)) = target {
 						return vec![LocationLink {
 							origin_selection_range: symbol_finder.located_span().map(|span| span.clone().into()),
 							target_uri,
-							target_range: origin_span.into(),
-							target_selection_range: origin_span.into(),
+							target_range,
+							target_selection_range: target_range,
 						}];
 					}
 				}
@@ -52,7 +66,7 @@ pub fn on_goto_definition(params: GotoDefinitionParams) -> Vec<LocationLink> {
 				.descendant_for_point_range(point, point)
 				.expect("There is always at-least one tree-sitter node");
 
-			// we only support goto definition for extern right now
+			// otherwise, check if this is the path of an extern or a bring statement
 			match node.kind() {
 				"string" => {
 					let parent = node.parent().unwrap();
@@ -98,8 +112,171 @@ pub fn on_goto_definition(params: GotoDefinitionParams) -> Vec<LocationLink> {
 	})
 }
 
+/// Finds where a JSII type, or a member of one, is declared in the `.d.ts` typings of its package
+fn find_jsii_declaration(jsii_types: &TypeSystem, kind: &SymbolKind, info: &SymbolLookupInfo) -> Option<(Url, Range)> {
+	let (type_, member) = match kind {
+		SymbolKind::Type(type_) => (*type_, None),
+		_ => {
+			let SymbolEnvKind::Type(type_) = &info.env.kind else {
+				return None;
+			};
+			let (member, ..) = info.env.iter(false).find(|(_, k, _)| std::ptr::eq(*k, kind))?;
+			(*type_, Some(member))
+		}
+	};
+	let fqn = match &*type_ {
+		Type::Class(c) => c.fqn.as_ref(),
+		Type::Interface(i) => i.fqn.as_ref(),
+		Type::Struct(s) => s.fqn.as_ref(),
+		_ => None,
+	}?;
+	let fqn = FQN::from(fqn.as_str());
+
+	let location = match jsii_types.find_class(&fqn) {
+		Some(class) => class.location_in_module.as_ref(),
+		None => jsii_types.find_interface(&fqn)?.location_in_module.as_ref(),
+	}?;
+	let package_dir = jsii_types.find_assembly_directory(fqn.assembly())?;
+	let typings_file = find_typings_file(package_dir, &location.filename)?;
+	let typings = fs::read_to_string(&typings_file).ok()?;
+	let range = find_declaration_range(&typings, fqn.type_name(), member.as_deref())?;
+
+	Some((Url::from_file_path(typings_file).ok()?, range))
+}
+
+/// Finds the `.d.ts` file generated for a TypeScript source file of a JSII package.
+/// `source_file` is relative to the package directory (e.g. "src/cloud/bucket.ts"). It is either compiled
+/// next to the source, or into the directory of the package's "types" entry (e.g. "lib/cloud/bucket.d.ts").
+fn find_typings_file(package_dir: &Utf8Path, source_file: &str) -> Option<Utf8PathBuf> {
+	let typings_file = Utf8Path::new(source_file).with_extension("d.ts");
+
+	let types_dir = fs::read_to_string(package_dir.join("package.json"))
+		.ok()
+		.and_then(|package_json| serde_json::from_str::<serde_json::Value>(&package_json).ok())
+		.and_then(|package_json| {
+			package_json
+				.get("types")
+				.and_then(|t| t.as_str())
+				.map(Utf8PathBuf::from)
+		})
+		.and_then(|types| types.parent().map(|dir| dir.to_owned()))
+		.unwrap_or_else(|| Utf8PathBuf::from("lib"));
+	let mut components = typings_file.components();
+	components.next();
+
+	[
+		package_dir.join(&typings_file),
+		package_dir.join(types_dir).join(components.as_path()),
+	]
+	.into_iter()
+	.find(|candidate| candidate.is_file())
+}
+
+/// Finds the range of a type's name in its `.d.ts` file, or of the name of one of its members
+fn find_declaration_range(typings: &str, type_name: &str, member: Option<&str>) -> Option<Range> {
+	let type_regex = Regex::new(&format!(r"\b(class|interface)\s+({})\b", regex::escape(type_name))).unwrap();
+	let member_regex = member.map(|member| {
+		Regex::new(&format!(
+			r"^\s*(?:(?:public|protected|private|static|readonly|abstract|get)\s+)*({})\??\s*[(:<]",
+			regex::escape(member)
+		))
+		.unwrap()
+	});
+
+	// Names in comments and strings aren't declarations
+	let code = blank_comments_and_strings(typings);
+	let mut lines = code.lines().enumerate();
+	let (line, name) = lines.find_map(|(idx, line)| Some((idx, type_regex.captures(line)?.get(2)?)))?;
+	let (line, name) = match &member_regex {
+		Some(member_regex) => lines
+			// the members of the type end at the first line that closes its body
+			.take_while(|(_, line)| !line.starts_with('}'))
+			.find_map(|(idx, line)| Some((idx, member_regex.captures(line)?.get(1)?)))?,
+		None => (line, name),
+	};
+
+	// LSP positions count UTF-16 code units, not bytes
+	let line_text = typings.lines().nth(line)?;
+	let utf16_column = |byte_idx: usize| line_text[..byte_idx].encode_utf16().count() as u32;
+	Some(Range {
+		start: Position::new(line as u32, utf16_column(name.start())),
+		end: Position::new(line as u32, utf16_column(name.end())),
+	})
+}
+
+/// Replaces the contents of comments and string literals with spaces, keeping line breaks and byte offsets intact
+fn blank_comments_and_strings(text: &str) -> String {
+	#[derive(PartialEq)]
+	enum State {
+		Code,
+		LineComment,
+		BlockComment,
+		String(char),
+	}
+
+	let mut result = String::with_capacity(text.len());
+	let mut state = State::Code;
+	let mut chars = text.chars().peekable();
+	while let Some(c) = chars.next() {
+		let blank = match state {
+			State::Code => match c {
+				'/' if chars.peek() == Some(&'/') => {
+					state = State::LineComment;
+					true
+				}
+				'/' if chars.peek() == Some(&'*') => {
+					chars.next();
+					result.push_str("  ");
+					state = State::BlockComment;
+					continue;
+				}
+				'"' | '\'' | '`' => {
+					state = State::String(c);
+					true
+				}
+				_ => false,
+			},
+			State::LineComment => {
+				if c == '\n' {
+					state = State::Code;
+				}
+				true
+			}
+			State::BlockComment => {
+				if c == '*' && chars.peek() == Some(&'/') {
+					chars.next();
+					result.push(' ');
+					state = State::Code;
+				}
+				true
+			}
+			State::String(quote) => {
+				if c == '\\' {
+					if let Some(escaped) = chars.next() {
+						result.push(' ');
+						result.extend(std::iter::repeat(' ').take(escaped.len_utf8()));
+						continue;
+					}
+				} else if c == quote || (c == '\n' && quote != '`') {
+					state = State::Code;
+				}
+				true
+			}
+		};
+
+		if !blank || c == '\n' || c == '\r' {
+			result.push(c);
+		} else {
+			result.extend(std::iter::repeat(' ').take(c.len_utf8()));
+		}
+	}
+	result
+}
+
 #[cfg(test)]
 mod tests {
+	use lsp_types::{TextDocumentIdentifier, TextDocumentPositionParams};
+
 	use crate::lsp::goto_definition::*;
 	use crate::lsp::sync::test_utils::*;
 
@@ -317,4 +494,146 @@ bring "./blah.w" as blah;
 		assert!(goto_module_path.len() == 1)
 		assert_eq!(goto_module_path[0].target_uri.to_file_path().unwrap().file_name().unwrap(), "blah.w")
 	);
+
+	test_goto_definition!(
+		function_parameter,
+		r#"
+let f = (value: num) => {
+       //-----
+  return value + 1;
+       //^
+};
+"#
+	);
+
+	test_goto_definition!(
+		instance_method,
+		r#"
+class Cat {
+  pub sleep() {}
+    //-----
+}
+let c = new Cat();
+c.sleep();
+ //^
+"#
+	);
+
+	#[test]
+	fn symbols_from_brought_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let dir = Utf8Path::from_path(dir.path()).unwrap();
+
+		let lib_uri = open_file(&dir.join("lib.w"), "pub class Cat {\n  pub sleep() {}\n}\n");
+		let main_uri = open_file(
+			&dir.join("main.w"),
+			"bring \"./lib.w\" as lib;\nlet c = new lib.Cat();\nc.sleep();\nlet d: lib.Cat = c;\n",
+		);
+
+		let goto = |line: u32, character: u32| {
+			on_goto_definition(GotoDefinitionParams {
+				text_document_position_params: TextDocumentPositionParams {
+					text_document: TextDocumentIdentifier { uri: main_uri.clone() },
+					position: Position::new(line, character),
+				},
+				work_done_progress_params: Default::default(),
+				partial_result_params: Default::default(),
+			})
+		};
+
+		let method = goto(2, 3);
+		assert_eq!(method.len(), 1);
+		assert_eq!(method[0].target_uri, lib_uri);
+		assert_eq!(
+			method[0].target_range,
+			Range::new(Position::new(1, 6), Position::new(1, 11))
+		);
+
+		let class = goto(3, 12);
+		assert_eq!(class.len(), 1);
+		assert_eq!(class[0].target_uri, lib_uri);
+		assert_eq!(
+			class[0].target_range,
+			Range::new(Position::new(0, 10), Position::new(0, 13))
+		);
+	}
+
+	#[test]
+	fn jsii_declaration_range() {
+		let typings = r#"export declare class Bucket extends Resource {
+    readonly name: string;
+    put(key: string): void;
+}
+export interface BucketProps {
+    readonly public?: boolean;
+}
+"#;
+		assert_eq!(
+			find_declaration_range(typings, "Bucket", None),
+			Some(Range::new(Position::new(0, 21), Position::new(0, 27)))
+		);
+		assert_eq!(
+			find_declaration_range(typings, "Bucket", Some("put")),
+			Some(Range::new(Position::new(2, 4), Position::new(2, 7)))
+		);
+		assert_eq!(
+			find_declaration_range(typings, "BucketProps", Some("public")),
+			Some(Range::new(Position::new(5, 13), Position::new(5, 19)))
+		);
+		assert_eq!(find_declaration_range(typings, "BucketProps", Some("put")), None);
+		assert_eq!(find_declaration_range(typings, "Queue", None), None);
+	}
+
+	#[test]
+	fn jsii_declaration_range_skips_comments_and_strings() {
+		let typings = r#"/**
+ * Unlike `class Bucket {}`, a queue
+ * has no put(key) method.
+ */
+export declare class Queue {
+    // put(key: string): void;
+    readonly kind: "class Bucket";
+    /* put(key: string) */ push(value: string): void;
+    put(key: string): void;
+}
+export declare class Bucket {
+}
+"#;
+		assert_eq!(
+			find_declaration_range(typings, "Bucket", None),
+			Some(Range::new(Position::new(10, 21), Position::new(10, 27)))
+		);
+		assert_eq!(
+			find_declaration_range(typings, "Queue", Some("put")),
+			Some(Range::new(Position::new(8, 4), Position::new(8, 7)))
+		);
+	}
+
+	#[test]
+	fn jsii_declaration_range_in_utf16() {
+		let typings = "export declare class Café { readonly name: string; }\n/** 🪣 */ export declare class Bucket {\n}\n";
+		assert_eq!(
+			find_declaration_range(typings, "Café", None),
+			Some(Range::new(Position::new(0, 21), Position::new(0, 25)))
+		);
+		assert_eq!(
+			find_declaration_range(typings, "Bucket", None),
+			Some(Range::new(Position::new(1, 31), Position::new(1, 37)))
+		);
+	}
+
+	#[test]
+	fn jsii_typings_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let dir = Utf8Path::from_path(dir.path()).unwrap();
+		std::fs::write(dir.join("package.json"), r#"{ "types": "lib/index.d.ts" }"#).unwrap();
+		std::fs::create_dir_all(dir.join("lib/cloud")).unwrap();
+		std::fs::write(dir.join("lib/cloud/bucket.d.ts"), "").unwrap();
+
+		assert_eq!(
+			find_typings_file(dir, "src/cloud/bucket.ts"),
+			Some(dir.join("lib/cloud/bucket.d.ts"))
+		);
+		assert_eq!(find_typings_file(dir, "src/cloud/queue.ts"), None);
+	}
 }
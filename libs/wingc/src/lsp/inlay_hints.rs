@@ -0,0 +1,253 @@
+use itertools::Itertools;
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, InlayHintParams};
+
+use crate::ast::{Expr, ExprKind, FunctionBody, FunctionDefinition, Scope, Stmt, StmtKind, Symbol, TypeAnnotationKind};
+use crate::closure_transform::CLOSURE_CLASS_PREFIX;
+use crate::lsp::sync::PROJECT_DATA;
+use crate::type_check::symbol_env::SymbolEnvRef;
+use crate::type_check::{SymbolKind, Types, CLOSURE_CLASS_HANDLE_METHOD};
+use crate::visit::{self, Visit};
+use crate::wasm_util::extern_json_fn;
+
+use super::sync::{check_utf8, WING_TYPES};
+
+#[no_mangle]
+pub unsafe extern "C" fn wingc_on_inlay_hint(ptr: u32, len: u32) -> u64 {
+	extern_json_fn(ptr, len, on_inlay_hint)
+}
+
+pub fn on_inlay_hint(params: InlayHintParams) -> Vec<InlayHint> {
+	WING_TYPES.with(|types| {
+		let types = types.borrow();
+		PROJECT_DATA.with(|project_data| {
+			let project_data = project_data.borrow();
+			let uri = params.text_document.uri;
+			let file = check_utf8(uri.to_file_path().expect("LSP only works on real filesystems"));
+			let (Some(source), Some(scope)) = (project_data.files.get_file(&file), project_data.asts.get(&file)) else {
+				return vec![];
+			};
+
+			let mut visitor = InlayHintVisitor {
+				types: &types,
+				source,
+				file_id: file.as_str(),
+				envs: vec![],
+				hints: vec![],
+			};
+			visitor.visit_scope(scope);
+
+			let range = params.range;
+			let mut hints = visitor.hints;
+			hints.retain(|h| h.position >= range.start && h.position <= range.end);
+			hints.sort_by(|a, b| a.position.cmp(&b.position));
+			hints
+		})
+	})
+}
+
+struct InlayHintVisitor<'a> {
+	types: &'a Types,
+	source: &'a str,
+	file_id: &'a str,
+	envs: Vec<SymbolEnvRef>,
+	hints: Vec<InlayHint>,
+}
+
+impl InlayHintVisitor<'_> {
+	/// Whether the symbol was written by the user (symbols generated by the compiler don't match the source text)
+	fn is_in_source(&self, symbol: &Symbol) -> bool {
+		let span = &symbol.span;
+		span.file_id == self.file_id && self.source.get(span.start_offset..span.end_offset) == Some(symbol.name.as_str())
+	}
+
+	/// Adds a `: <type>` hint after a symbol, using the type of the variable it declares in `env`
+	fn add_type_hint(&mut self, symbol: &Symbol, env: SymbolEnvRef) {
+		if !self.is_in_source(symbol) {
+			return;
+		}
+		let Some(SymbolKind::Variable(info)) = env.lookup(symbol, None) else {
+			return;
+		};
+		if info.type_.is_inferred() || info.type_.is_unresolved() {
+			return;
+		}
+		self.hints.push(InlayHint {
+			position: symbol.span.end.into(),
+			label: InlayHintLabel::String(format!(": {}", info.type_)),
+			kind: Some(InlayHintKind::TYPE),
+			text_edits: None,
+			tooltip: None,
+			padding_left: None,
+			padding_right: None,
+			data: None,
+		});
+	}
+
+	/// Adds a hint listing the preflight objects (and the operations on them) an inflight closure lifts.
+	/// Inflight closures are transformed into classes, the `new` expression that replaces the closure keeps its span.
+	fn add_lifts_hint(&mut self, expr: &Expr) {
+		if expr.span.file_id != self.file_id {
+			return;
+		}
+		let type_ = self.types.get_expr_type(expr);
+		let Some(lifts) = type_.as_class().and_then(|c| c.lifts.as_ref()) else {
+			return;
+		};
+		let Some(qualifications) = lifts.lifts_qualifications.get(CLOSURE_CLASS_HANDLE_METHOD) else {
+			return;
+		};
+		if qualifications.is_empty() {
+			return;
+		}
+
+		let lifted = qualifications
+			.iter()
+			.map(|(code, qualification)| {
+				if qualification.ops.is_empty() {
+					code.clone()
+				} else {
+					// Each qualification is stored as the JS array of ops emitted in the lift map, e.g. `["put"]`
+					let ops = qualification
+						.ops
+						.iter()
+						.flat_map(|op| serde_json::from_str::<Vec<String>>(op).unwrap_or_else(|_| vec![op.clone()]))
+						.unique()
+						.join(", ");
+					format!("{code} ({ops})")
+				}
+			})
+			.collect::<Vec<_>>();
+		self.hints.push(InlayHint {
+			position: expr.span.start.into(),
+			label: InlayHintLabel::String(format!("lifts: {}", lifted.join(", "))),
+			kind: None,
+			text_edits: None,
+			tooltip: None,
+			padding_left: None,
+			padding_right: Some(true),
+			data: None,
+		});
+	}
+}
+
+impl<'a> Visit<'a> for InlayHintVisitor<'_> {
+	fn visit_scope(&mut self, node: &'a Scope) {
+		self.envs.push(self.types.get_scope_env(node));
+		visit::visit_scope(self, node);
+		self.envs.pop();
+	}
+
+	fn visit_stmt(&mut self, node: &'a Stmt) {
+		if let StmtKind::Let {
+			var_name, type_: None, ..
+		} = &node.kind
+		{
+			if let Some(env) = self.envs.last().copied() {
+				self.add_type_hint(var_name, env);
+			}
+		}
+		visit::visit_stmt(self, node);
+	}
+
+	fn visit_function_definition(&mut self, node: &'a FunctionDefinition) {
+		// Only closure parameters can omit their type annotation, their types are inferred from the call site
+		if let FunctionBody::Statements(scope) = &node.body {
+			let env = self.types.get_scope_env(scope);
+			for param in &node.signature.parameters {
+				if matches!(param.type_annotation.kind, TypeAnnotationKind::Inferred) {
+					self.add_type_hint(&param.name, env);
+				}
+			}
+		}
+		visit::visit_function_definition(self, node);
+	}
+
+	fn visit_expr(&mut self, node: &'a Expr) {
+		if let ExprKind::New(new_expr) = &node.kind {
+			if new_expr.class.root.name.starts_with(CLOSURE_CLASS_PREFIX) {
+				self.add_lifts_hint(node);
+			}
+		}
+		visit::visit_expr(self, node);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use lsp_types::*;
+
+	use crate::lsp::inlay_hints::*;
+	use crate::lsp::sync::test_utils::*;
+
+	fn inlay_hints(code: &str) -> Vec<(Position, String)> {
+		let text_document = load_file_with_contents(code).text_document;
+		on_inlay_hint(InlayHintParams {
+			text_document,
+			range: Range::new(Position::new(0, 0), Position::new(u32::MAX, 0)),
+			work_done_progress_params: Default::default(),
+		})
+		.into_iter()
+		.map(|h| match h.label {
+			InlayHintLabel::String(label) => (h.position, label),
+			InlayHintLabel::LabelParts(_) => panic!("expected a string label"),
+		})
+		.collect()
+	}
+
+	#[test]
+	fn inferred_let_types() {
+		let hints = inlay_hints(
+			r#"
+let x = 1;
+let y: str = "hello";
+let var z = "world";
+"#,
+		);
+		assert_eq!(
+			hints,
+			vec![
+				(Position::new(1, 5), ": num".to_string()),
+				(Position::new(3, 9), ": str".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn inferred_closure_parameters() {
+		let hints = inlay_hints(
+			r#"
+let apply = (f: (num): num) => { return f(1); };
+apply((n) => { return n; });
+"#,
+		);
+		let hints = hints.into_iter().filter(|(p, _)| p.line == 2).collect::<Vec<_>>();
+		assert_eq!(hints, vec![(Position::new(2, 8), ": num".to_string())]);
+	}
+
+	#[test]
+	fn lifted_captures() {
+		let hints = inlay_hints(
+			r#"
+bring cloud;
+let bucket = new cloud.Bucket();
+let handler = inflight () => {
+  bucket.put("a", "b");
+  bucket.get("a");
+};
+"#,
+		);
+		assert!(hints.contains(&(Position::new(3, 14), "lifts: bucket (put, get)".to_string())));
+	}
+
+	#[test]
+	fn hints_outside_range() {
+		let text_document = load_file_with_contents("let x = 1;\nlet y = 2;\n").text_document;
+		let hints = on_inlay_hint(InlayHintParams {
+			text_document,
+			range: Range::new(Position::new(1, 0), Position::new(1, 10)),
+			work_done_progress_params: Default::default(),
+		});
+		assert_eq!(hints.len(), 1);
+		assert_eq!(hints[0].position, Position::new(1, 5));
+	}
+}
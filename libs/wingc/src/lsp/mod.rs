@@ -1,11 +1,16 @@
 mod code_actions;
 mod completions;
+mod document_highlight;
 mod document_symbols;
+mod formatting;
 mod goto_definition;
 mod hover;
+mod inlay_hints;
+mod references;
 mod rename_prepare;
 mod rename_request;
 mod rename_visitor;
+mod semantic_tokens;
 mod signature;
 mod symbol_locator;
 mod sync;
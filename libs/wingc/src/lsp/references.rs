@@ -0,0 +1,172 @@
+use camino::Utf8Path;
+use lsp_types::{Location, Position, ReferenceParams, Url};
+
+use crate::diagnostic::WingSpan;
+use crate::lsp::sync::{ProjectData, PROJECT_DATA};
+use crate::type_check::Types;
+use crate::visit::Visit;
+use crate::wasm_util::extern_json_fn;
+
+use super::rename_visitor::RenameVisitor;
+use super::sync::{check_utf8, WING_TYPES};
+
+#[no_mangle]
+pub unsafe extern "C" fn wingc_on_references(ptr: u32, len: u32) -> u64 {
+	extern_json_fn(ptr, len, on_references)
+}
+
+pub fn on_references(params: ReferenceParams) -> Vec<Location> {
+	WING_TYPES.with(|types| {
+		let types = types.borrow();
+		PROJECT_DATA.with(|project_data| -> Vec<Location> {
+			let project_data = project_data.borrow();
+			let uri = params.text_document_position.text_document.uri;
+			let file = check_utf8(uri.to_file_path().expect("LSP only works on real filesystems"));
+			let position = params.text_document_position.position;
+
+			let Some((declaration, references)) = find_symbol_references(&types, &project_data, &file, position) else {
+				return vec![];
+			};
+
+			let spans = if params.context.include_declaration {
+				std::iter::once(declaration).chain(references).collect::<Vec<_>>()
+			} else {
+				references
+			};
+			spans
+				.into_iter()
+				.filter_map(|span| {
+					let uri = Url::from_file_path(&span.file_id).ok()?;
+					Some(Location::new(uri, span.into()))
+				})
+				.collect()
+		})
+	})
+}
+
+/// Finds the symbol at the given position and returns the span of its declaration and the spans of all
+/// its references, in all files of the project.
+pub fn find_symbol_references(
+	types: &Types,
+	project_data: &ProjectData,
+	file: &Utf8Path,
+	position: Position,
+) -> Option<(WingSpan, Vec<WingSpan>)> {
+	let mut reference_visitor = RenameVisitor::new(types);
+	for scope in project_data.asts.values() {
+		reference_visitor.visit_scope(scope);
+	}
+
+	let linked_symbol = reference_visitor.find_linked_symbol(file.as_str(), position)?;
+	let declaration = linked_symbol.declaration_span().clone();
+	let references = linked_symbol.spans().skip(1).cloned().collect();
+	Some((declaration, references))
+}
+
+#[cfg(test)]
+mod tests {
+	use camino::Utf8Path;
+	use lsp_types::*;
+
+	use crate::lsp::references::*;
+	use crate::lsp::sync::test_utils::*;
+
+	/// Creates a test for the references of the symbol at a given position in a wing program
+	/// In the wing program, place a comment "//^" into the text where the "^" is pointing to the desired character position
+	/// Place a comment "//-" below each expected reference (including the declaration), with additional "-" characters to extend the range
+	macro_rules! test_references {
+		($name:ident, $code:literal) => {
+			#[test]
+			fn $name() {
+				// NOTE: this is needed for debugging to work regardless of where you run the test
+				std::env::set_current_dir(env!("CARGO_MANIFEST_DIR")).unwrap();
+
+				let text_document_position_params = load_file_with_contents($code);
+
+				let locations = on_references(ReferenceParams {
+					text_document_position: text_document_position_params,
+					work_done_progress_params: Default::default(),
+					partial_result_params: Default::default(),
+					context: ReferenceContext {
+						include_declaration: true,
+					},
+				});
+
+				assert_eq!(
+					get_ranges($code),
+					locations.into_iter().map(|l| l.range).collect::<Vec<_>>(),
+					"references did not match"
+				);
+			}
+		};
+	}
+
+	test_references!(
+		variable,
+		r#"
+let thing = "thing";
+  //----^
+let thing2 = thing;
+           //-----
+log(thing);
+  //-----
+"#
+	);
+
+	test_references!(
+		class_method,
+		r#"
+class Cat {
+  pub sleep() {}
+    //-----
+}
+
+let a = new Cat();
+a.sleep();
+//----^
+"#
+	);
+
+	test_references!(
+		no_symbol,
+		r#"
+let thing = "thing";
+               //^
+"#
+	);
+
+	#[test]
+	fn references_across_files() {
+		let dir = tempfile::tempdir().unwrap();
+		let dir = Utf8Path::from_path(dir.path()).unwrap();
+
+		let lib_uri = open_file(
+			&dir.join("lib.w"),
+			"pub class Cat {\n  pub name: str;\n  new() { this.name = \"cat\"; }\n}\n",
+		);
+		let main_uri = open_file(
+			&dir.join("main.w"),
+			"bring \"./lib.w\" as lib;\nlet c = new lib.Cat();\nlog(c.name);\n",
+		);
+
+		let locations = on_references(ReferenceParams {
+			text_document_position: TextDocumentPositionParams {
+				text_document: TextDocumentIdentifier { uri: main_uri.clone() },
+				position: Position::new(2, 7),
+			},
+			work_done_progress_params: Default::default(),
+			partial_result_params: Default::default(),
+			context: ReferenceContext {
+				include_declaration: true,
+			},
+		});
+		let mut locations = locations
+			.into_iter()
+			.map(|l| (l.uri, l.range.start.line, l.range.start.character))
+			.collect::<Vec<_>>();
+		locations.sort();
+		let mut expected = vec![(lib_uri.clone(), 1, 6), (lib_uri, 2, 15), (main_uri, 2, 6)];
+		expected.sort();
+		assert_eq!(locations, expected);
+	}
+}
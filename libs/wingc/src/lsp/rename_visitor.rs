@@ -1,6 +1,6 @@
 use lsp_types::{Position, PrepareRenameResponse, Range, TextEdit};
 
-use crate::diagnostic::WingLocation;
+use crate::diagnostic::{WingLocation, WingSpan};
 use crate::type_check::symbol_env::{LookupResult, SymbolEnv};
 use crate::type_check::{SymbolKind, Types, UnsafeRef, CLASS_INFLIGHT_INIT_NAME, CLASS_INIT_NAME};
 use crate::visit::{visit_scope, Visit};
@@ -10,6 +10,18 @@ pub struct LinkedSymbol<'a> {
 	symbol: Symbol,
 	references: Vec<&'a Symbol>,
 }
+
+impl LinkedSymbol<'_> {
+	/// The span of the symbol's declaration followed by the spans of all its references
+	pub fn spans(&self) -> impl Iterator<Item = &WingSpan> {
+		std::iter::once(&self.symbol.span).chain(self.references.iter().map(|r| &r.span))
+	}
+
+	pub fn declaration_span(&self) -> &WingSpan {
+		&self.symbol.span
+	}
+}
+
 pub struct RenameVisitor<'a> {
 	types: &'a Types,
 	linked_symbols: Vec<LinkedSymbol<'a>>,
@@ -76,6 +88,30 @@ impl<'a> RenameVisitor<'a> {
 		}
 	}
 
+	/// Adds a symbol declaration, unless it was already added through a reference visited earlier
+	/// (which happens when the references come from a file that was visited before the declaring file)
+	fn add_declaration_symbol(&mut self, symbol: &Symbol) {
+		if self.linked_symbols.iter().any(|s| s.symbol.span == symbol.span) {
+			return;
+		}
+		self.linked_symbols.push(LinkedSymbol {
+			symbol: symbol.clone(),
+			references: vec![],
+		});
+	}
+
+	/// Finds the linked symbol that has its declaration or one of its references at the given position in the given file
+	pub fn find_linked_symbol(&self, file_id: &str, position: Position) -> Option<&LinkedSymbol<'a>> {
+		let location = WingLocation {
+			line: position.line,
+			col: position.character,
+		};
+		self.linked_symbols.iter().find(|s| {
+			s.spans()
+				.any(|span| span.file_id == file_id && span.contains_location(&location))
+		})
+	}
+
 	pub fn create_text_edits(&mut self, position: Position, new_text: String) -> Vec<TextEdit> {
 		let location = WingLocation {
 			line: position.line,
@@ -193,14 +229,8 @@ impl<'a> Visit<'a> for RenameVisitor<'a> {
 
 	fn visit_stmt(&mut self, stmt: &'a Stmt) {
 		match &stmt.kind {
-			StmtKind::IfLet(IfLet { var_name, .. }) => self.linked_symbols.push(LinkedSymbol {
-				symbol: var_name.clone(),
-				references: vec![],
-			}),
-			StmtKind::Let { var_name, .. } => self.linked_symbols.push(LinkedSymbol {
-				symbol: var_name.clone(),
-				references: vec![],
-			}),
+			StmtKind::IfLet(IfLet { var_name, .. }) => self.add_declaration_symbol(var_name),
+			StmtKind::Let { var_name, .. } => self.add_declaration_symbol(var_name),
 			//TODO: to be handled in a following PR, renaming interface fields is not supported yet
 			// StmtKind::Interface(c) => {
 			// for field in &c.methods {
@@ -212,10 +242,7 @@ impl<'a> Visit<'a> for RenameVisitor<'a> {
 			// }
 			StmtKind::Struct(s) => {
 				for field in &s.fields {
-					self.linked_symbols.push(LinkedSymbol {
-						symbol: field.name.clone(),
-						references: vec![],
-					})
+					self.add_declaration_symbol(&field.name);
 				}
 			}
 			StmtKind::Class(c) => {
@@ -223,16 +250,10 @@ impl<'a> Visit<'a> for RenameVisitor<'a> {
 					if m.name == CLASS_INIT_NAME || m.name == CLASS_INFLIGHT_INIT_NAME {
 						continue;
 					}
-					self.linked_symbols.push(LinkedSymbol {
-						symbol: m.clone(),
-						references: vec![],
-					})
+					self.add_declaration_symbol(m);
 				}
 				for f in &c.fields {
-					self.linked_symbols.push(LinkedSymbol {
-						symbol: f.name.clone(),
-						references: vec![],
-					})
+					self.add_declaration_symbol(&f.name);
 				}
 			}
 			_ => {}
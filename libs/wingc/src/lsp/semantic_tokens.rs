@@ -0,0 +1,433 @@
+use lsp_types::{
+	Range, SemanticToken, SemanticTokenType, SemanticTokens, SemanticTokensParams, SemanticTokensRangeParams, Url,
+};
+
+use crate::ast::{
+	Class, Enum, FunctionDefinition, Interface, Phase, Reference, Scope, Stmt, StmtKind, Struct, Symbol, UserDefinedType,
+};
+use crate::diagnostic::WingSpan;
+use crate::lsp::sync::PROJECT_DATA;
+use crate::type_check::symbol_env::{LookupResult, SymbolEnvRef};
+use crate::type_check::{SymbolKind, Type, TypeRef, Types, VariableInfo, VariableKind};
+use crate::visit::{self, Visit};
+use crate::wasm_util::extern_json_fn;
+
+use super::sync::{check_utf8, WING_TYPES};
+
+/// The token types reported by the language server, the index of each type is used to encode it.
+/// The client receives the same legend in `apps/wing/src/commands/lsp.ts`, so the two must be kept in sync.
+pub const TOKEN_TYPES: &[SemanticTokenType] = &[
+	SemanticTokenType::NAMESPACE,
+	SemanticTokenType::TYPE,
+	SemanticTokenType::CLASS,
+	SemanticTokenType::ENUM,
+	SemanticTokenType::INTERFACE,
+	SemanticTokenType::STRUCT,
+	SemanticTokenType::PARAMETER,
+	SemanticTokenType::VARIABLE,
+	SemanticTokenType::PROPERTY,
+	SemanticTokenType::ENUM_MEMBER,
+	SemanticTokenType::FUNCTION,
+	SemanticTokenType::METHOD,
+];
+
+// The token modifiers reported by the language server, each modifier is encoded as a bit (the client's legend lists
+// them in the same order). Besides the standard modifiers, phases and reassignable variables have custom modifiers.
+const DECLARATION: u32 = 1 << 0;
+const STATIC: u32 = 1 << 1;
+const PREFLIGHT: u32 = 1 << 2;
+const INFLIGHT: u32 = 1 << 3;
+const REASSIGNABLE: u32 = 1 << 4;
+
+#[no_mangle]
+pub unsafe extern "C" fn wingc_on_semantic_tokens(ptr: u32, len: u32) -> u64 {
+	extern_json_fn(ptr, len, on_semantic_tokens_full)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wingc_on_semantic_tokens_range(ptr: u32, len: u32) -> u64 {
+	extern_json_fn(ptr, len, on_semantic_tokens_range)
+}
+
+pub fn on_semantic_tokens_full(params: SemanticTokensParams) -> SemanticTokens {
+	semantic_tokens(&params.text_document.uri, None)
+}
+
+pub fn on_semantic_tokens_range(params: SemanticTokensRangeParams) -> SemanticTokens {
+	semantic_tokens(&params.text_document.uri, Some(params.range))
+}
+
+fn semantic_tokens(uri: &Url, range: Option<Range>) -> SemanticTokens {
+	WING_TYPES.with(|types| {
+		let types = types.borrow();
+		PROJECT_DATA.with(|project_data| {
+			let project_data = project_data.borrow();
+			let file = check_utf8(uri.to_file_path().expect("LSP only works on real filesystems"));
+			let (Some(source), Some(scope)) = (project_data.files.get_file(&file), project_data.asts.get(&file)) else {
+				return SemanticTokens::default();
+			};
+
+			let mut visitor = SemanticTokenVisitor {
+				types: &types,
+				source,
+				file_id: file.as_str(),
+				envs: vec![],
+				tokens: vec![],
+			};
+			visitor.visit_scope(scope);
+
+			let mut tokens = visitor.tokens;
+			if let Some(range) = range {
+				tokens.retain(|t| t.span.start.line >= range.start.line && t.span.end.line <= range.end.line);
+			}
+			encode_tokens(tokens)
+		})
+	})
+}
+
+struct Token {
+	span: WingSpan,
+	token_type: SemanticTokenType,
+	modifiers: u32,
+}
+
+/// Encodes tokens in the relative format of the LSP spec (each token is positioned relative to the previous one)
+fn encode_tokens(mut tokens: Vec<Token>) -> SemanticTokens {
+	tokens.sort_by(|a, b| a.span.start.cmp(&b.span.start));
+	tokens.dedup_by(|a, b| a.span.start == b.span.start);
+
+	let mut data = vec![];
+	let mut prev_line = 0;
+	let mut prev_col = 0;
+	for token in tokens {
+		let line = token.span.start.line;
+		let col = token.span.start.col;
+		data.push(SemanticToken {
+			delta_line: line - prev_line,
+			delta_start: if line == prev_line { col - prev_col } else { col },
+			length: token.span.end.col - col,
+			token_type: TOKEN_TYPES.iter().position(|t| *t == token.token_type).unwrap() as u32,
+			token_modifiers_bitset: token.modifiers,
+		});
+		prev_line = line;
+		prev_col = col;
+	}
+
+	SemanticTokens { result_id: None, data }
+}
+
+fn phase_modifier(phase: Phase) -> u32 {
+	match phase {
+		Phase::Preflight => PREFLIGHT,
+		Phase::Inflight => INFLIGHT,
+		Phase::Independent => 0,
+	}
+}
+
+struct SemanticTokenVisitor<'a> {
+	types: &'a Types,
+	source: &'a str,
+	file_id: &'a str,
+	envs: Vec<SymbolEnvRef>,
+	tokens: Vec<Token>,
+}
+
+impl SemanticTokenVisitor<'_> {
+	fn add_token(&mut self, symbol: &Symbol, token_type: SemanticTokenType, modifiers: u32) {
+		let span = &symbol.span;
+		// Skip symbols generated by the compiler (e.g. by the closure transform), they don't match the source text
+		if span.file_id != self.file_id
+			|| span.start.line != span.end.line
+			|| self.source.get(span.start_offset..span.end_offset) != Some(symbol.name.as_str())
+		{
+			return;
+		}
+		self.tokens.push(Token {
+			span: span.clone(),
+			token_type,
+			modifiers,
+		});
+	}
+
+	fn add_type_token(&mut self, symbol: &Symbol, type_: TypeRef, modifiers: u32) {
+		let (token_type, modifiers) = match &*type_ {
+			Type::Class(c) => (SemanticTokenType::CLASS, modifiers | phase_modifier(c.phase)),
+			Type::Interface(_) => (SemanticTokenType::INTERFACE, modifiers),
+			Type::Struct(_) => (SemanticTokenType::STRUCT, modifiers),
+			Type::Enum(_) => (SemanticTokenType::ENUM, modifiers),
+			_ => (SemanticTokenType::TYPE, modifiers),
+		};
+		self.add_token(symbol, token_type, modifiers);
+	}
+
+	fn add_variable_token(&mut self, symbol: &Symbol, info: &VariableInfo, modifiers: u32) {
+		let mut modifiers = modifiers;
+		if info.reassignable {
+			modifiers |= REASSIGNABLE;
+		}
+		if info.kind == VariableKind::StaticMember {
+			modifiers |= STATIC;
+		}
+		let is_member = matches!(info.kind, VariableKind::InstanceMember | VariableKind::StaticMember);
+		let token_type = match info.type_.maybe_unwrap_option().as_function_sig() {
+			Some(sig) => {
+				modifiers |= phase_modifier(sig.phase);
+				if is_member {
+					SemanticTokenType::METHOD
+				} else {
+					SemanticTokenType::FUNCTION
+				}
+			}
+			None if is_member => SemanticTokenType::PROPERTY,
+			None => SemanticTokenType::VARIABLE,
+		};
+		self.add_token(symbol, token_type, modifiers);
+	}
+
+	fn add_lookup_token(&mut self, symbol: &Symbol, lookup: LookupResult, modifiers: u32) {
+		match lookup {
+			LookupResult::Found(SymbolKind::Type(t), _) => self.add_type_token(symbol, *t, modifiers),
+			LookupResult::Found(SymbolKind::Variable(info), _) => self.add_variable_token(symbol, info, modifiers),
+			LookupResult::Found(SymbolKind::Namespace(_), _) => {
+				self.add_token(symbol, SemanticTokenType::NAMESPACE, modifiers)
+			}
+			_ => {}
+		}
+	}
+
+	/// Adds a token for a symbol defined in the current environment (or looked up from it)
+	fn add_symbol_token(&mut self, symbol: &Symbol, modifiers: u32) {
+		if let Some(env) = self.envs.last().copied() {
+			self.add_lookup_token(symbol, env.lookup_ext(symbol, None), modifiers);
+		}
+	}
+
+	fn lookup_user_defined_type(&self, udt: &UserDefinedType) -> Option<TypeRef> {
+		let env = self.envs.last()?;
+		let mut path = vec![&udt.root];
+		path.extend(udt.fields.iter());
+		match env.lookup_nested(&path, None) {
+			LookupResult::Found(SymbolKind::Type(t), _) => Some(*t),
+			_ => None,
+		}
+	}
+
+	/// Adds a token for a member declared in a type (class, interface or struct)
+	fn add_member_token(&mut self, type_name: &Symbol, member: &Symbol) {
+		let Some(env) = self.envs.last().copied() else {
+			return;
+		};
+		let Some(type_) = env.lookup(type_name, None).and_then(|k| k.as_type()) else {
+			return;
+		};
+		if let Some(type_env) = type_.as_env() {
+			self.add_lookup_token(member, type_env.lookup_ext(member, None), DECLARATION);
+		}
+	}
+}
+
+impl<'a> Visit<'a> for SemanticTokenVisitor<'_> {
+	fn visit_scope(&mut self, node: &'a Scope) {
+		self.envs.push(self.types.get_scope_env(node));
+		visit::visit_scope(self, node);
+		self.envs.pop();
+	}
+
+	fn visit_stmt(&mut self, node: &'a Stmt) {
+		match &node.kind {
+			StmtKind::Bring { identifier, .. } => {
+				if let Some(identifier) = identifier {
+					self.add_token(identifier, SemanticTokenType::NAMESPACE, DECLARATION);
+				}
+			}
+			StmtKind::Let { var_name, .. } => self.add_symbol_token(var_name, DECLARATION),
+			StmtKind::ForLoop {
+				iterator, statements, ..
+			} => {
+				let env = self.types.get_scope_env(statements);
+				self.add_lookup_token(iterator, env.lookup_ext(iterator, None), DECLARATION);
+			}
+			_ => {}
+		}
+		visit::visit_stmt(self, node);
+	}
+
+	fn visit_class(&mut self, node: &'a Class) {
+		self.add_symbol_token(&node.name, DECLARATION);
+		for field in &node.fields {
+			self.add_member_token(&node.name, &field.name);
+		}
+		for (name, _) in &node.methods {
+			self.add_member_token(&node.name, name);
+		}
+		visit::visit_class(self, node);
+	}
+
+	fn visit_interface(&mut self, node: &'a Interface) {
+		self.add_symbol_token(&node.name, DECLARATION);
+		for (name, ..) in &node.methods {
+			self.add_member_token(&node.name, name);
+		}
+		for field in &node.fields {
+			self.add_member_token(&node.name, &field.name);
+		}
+		visit::visit_interface(self, node);
+	}
+
+	fn visit_struct(&mut self, node: &'a Struct) {
+		self.add_symbol_token(&node.name, DECLARATION);
+		for field in &node.fields {
+			self.add_member_token(&node.name, &field.name);
+		}
+		visit::visit_struct(self, node);
+	}
+
+	fn visit_enum(&mut self, node: &'a Enum) {
+		self.add_symbol_token(&node.name, DECLARATION);
+		for value in node.values.keys() {
+			self.add_token(value, SemanticTokenType::ENUM_MEMBER, DECLARATION | STATIC);
+		}
+		visit::visit_enum(self, node);
+	}
+
+	fn visit_function_definition(&mut self, node: &'a FunctionDefinition) {
+		for param in &node.signature.parameters {
+			let modifiers = if param.reassignable {
+				DECLARATION | REASSIGNABLE
+			} else {
+				DECLARATION
+			};
+			self.add_token(&param.name, SemanticTokenType::PARAMETER, modifiers);
+		}
+		visit::visit_function_definition(self, node);
+	}
+
+	fn visit_reference(&mut self, node: &'a Reference) {
+		match node {
+			Reference::Identifier(symbol) => self.add_symbol_token(symbol, 0),
+			Reference::InstanceMember { object, property, .. } => {
+				let object_type = *self.types.get_expr_type(object).maybe_unwrap_option();
+				if let Some(env) = object_type.as_env() {
+					self.add_lookup_token(property, env.lookup_ext(property, None), 0);
+				}
+			}
+			Reference::TypeMember { type_name, property } => {
+				if let Some(type_) = self.lookup_user_defined_type(type_name) {
+					if let Type::Enum(_) = &*type_ {
+						self.add_token(property, SemanticTokenType::ENUM_MEMBER, STATIC);
+					} else if let Some(env) = type_.as_env() {
+						self.add_lookup_token(property, env.lookup_ext(property, None), 0);
+					}
+				}
+			}
+			Reference::ElementAccess { .. } => {}
+		}
+		visit::visit_reference(self, node);
+	}
+
+	fn visit_user_defined_type(&mut self, node: &'a UserDefinedType) {
+		if let Some(env) = self.envs.last().copied() {
+			// Each part of the name is looked up on its own, so `cloud.Bucket` is a namespace followed by a class
+			let mut path = vec![&node.root];
+			self.add_lookup_token(&node.root, env.lookup_nested(&path, None), 0);
+			for field in &node.fields {
+				path.push(field);
+				self.add_lookup_token(field, env.lookup_nested(&path, None), 0);
+			}
+		}
+		visit::visit_user_defined_type(self, node);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use lsp_types::*;
+
+	use crate::lsp::semantic_tokens::*;
+	use crate::lsp::sync::test_utils::*;
+
+	const CODE: &str = r#"
+class Store {
+  pub inflight get(): str { return "a"; }
+  pub set(value: str) {}
+}
+struct Options { name: str; }
+enum Color { RED }
+let var count = 0;
+let store = new Store();
+store.set("{Color.RED}");
+"#;
+
+	/// Decodes the relative token positions and returns the text, type and modifiers of each token
+	fn decode(code: &str, tokens: SemanticTokens) -> Vec<(String, SemanticTokenType, u32)> {
+		let lines = code.lines().collect::<Vec<_>>();
+		let mut line = 0;
+		let mut col = 0;
+		let mut decoded = vec![];
+		for token in tokens.data {
+			if token.delta_line > 0 {
+				col = 0;
+			}
+			line += token.delta_line;
+			col += token.delta_start;
+			let text = &lines[line as usize][col as usize..(col + token.length) as usize];
+			decoded.push((
+				text.to_string(),
+				TOKEN_TYPES[token.token_type as usize].clone(),
+				token.token_modifiers_bitset,
+			));
+		}
+		decoded
+	}
+
+	fn token(text: &str, token_type: SemanticTokenType, modifiers: u32) -> (String, SemanticTokenType, u32) {
+		(text.to_string(), token_type, modifiers)
+	}
+
+	#[test]
+	fn full_document() {
+		let text_document = load_file_with_contents(CODE).text_document;
+		let tokens = on_semantic_tokens_full(SemanticTokensParams {
+			text_document,
+			work_done_progress_params: Default::default(),
+			partial_result_params: Default::default(),
+		});
+
+		assert_eq!(
+			decode(CODE, tokens),
+			vec![
+				token("Store", SemanticTokenType::CLASS, DECLARATION | PREFLIGHT),
+				token("get", SemanticTokenType::METHOD, DECLARATION | INFLIGHT),
+				token("set", SemanticTokenType::METHOD, DECLARATION | PREFLIGHT),
+				token("value", SemanticTokenType::PARAMETER, DECLARATION),
+				token("Options", SemanticTokenType::STRUCT, DECLARATION),
+				token("name", SemanticTokenType::PROPERTY, DECLARATION),
+				token("Color", SemanticTokenType::ENUM, DECLARATION),
+				token("RED", SemanticTokenType::ENUM_MEMBER, DECLARATION | STATIC),
+				token("count", SemanticTokenType::VARIABLE, DECLARATION | REASSIGNABLE),
+				token("store", SemanticTokenType::VARIABLE, DECLARATION),
+				token("Store", SemanticTokenType::CLASS, PREFLIGHT),
+				token("store", SemanticTokenType::VARIABLE, 0),
+				token("set", SemanticTokenType::METHOD, PREFLIGHT),
+				token("Color", SemanticTokenType::ENUM, 0),
+				token("RED", SemanticTokenType::ENUM_MEMBER, STATIC),
+			]
+		);
+	}
+
+	#[test]
+	fn range() {
+		let text_document = load_file_with_contents(CODE).text_document;
+		let tokens = on_semantic_tokens_range(SemanticTokensRangeParams {
+			text_document,
+			range: Range::new(Position::new(7, 0), Position::new(7, 18)),
+			work_done_progress_params: Default::default(),
+			partial_result_params: Default::default(),
+		});
+
+		assert_eq!(
+			decode(CODE, tokens),
+			vec![token("count", SemanticTokenType::VARIABLE, DECLARATION | REASSIGNABLE)]
+		);
+	}
+}
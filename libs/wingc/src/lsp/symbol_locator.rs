@@ -127,6 +127,7 @@ impl<'a> SymbolLocator<'a> {
 			| Type::Inferred(_)
 			| Type::Function(_)
 			| Type::Enum(_)
+			| Type::Union(_)
 			| Type::Stringable => None,
 
 			Type::Array(_)
@@ -135,6 +136,7 @@ impl<'a> SymbolLocator<'a> {
 			| Type::MutMap(_)
 			| Type::Set(_)
 			| Type::MutSet(_)
+			| Type::Promise(_)
 			| Type::Json(_)
 			| Type::MutJson
 			| Type::Number
@@ -254,6 +256,9 @@ impl<'a> Visit<'a> for SymbolLocator<'a> {
 				for method in &interface.methods {
 					self.visit_symbol(&method.0);
 				}
+				for field in &interface.fields {
+					self.visit_symbol(&field.name);
+				}
 
 				self.ctx.pop_env();
 			}
@@ -291,6 +296,18 @@ impl<'a> Visit<'a> for SymbolLocator<'a> {
 					}
 				}
 			}
+			StmtKind::Match(match_) => {
+				// Variables bound by a pattern are defined in the env of the arm's block
+				for arm in &match_.arms {
+					if let MatchArmBody::Statements(statements) = &arm.body {
+						self.push_scope_env(statements);
+						for pattern in &arm.patterns {
+							self.visit_match_pattern(pattern);
+						}
+						self.ctx.pop_env();
+					}
+				}
+			}
 			_ => {}
 		}
 
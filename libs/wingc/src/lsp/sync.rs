@@ -4,17 +4,19 @@ use lsp_types::{DidChangeTextDocumentParams, DidOpenTextDocumentParams};
 use wingii::type_system::TypeSystem;
 
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use tree_sitter::Tree;
 
 use crate::closure_transform::ClosureTransformer;
-use crate::diagnostic::{found_errors, reset_diagnostics};
+use crate::diagnostic::{found_errors, get_diagnostics, report_diagnostic, reset_diagnostics, Diagnostic};
 use crate::file_graph::{File, FileGraph};
 use crate::files::Files;
 use crate::fold::Fold;
 use crate::jsify::JSifier;
 use crate::lifting::LiftVisitor;
-use crate::parser::{normalize_path, parse_wing_project};
+use crate::lint::{lint, LintConfig};
+use crate::parser::{normalize_path, parse_wing_project, reparse_wing_file};
 use crate::type_check::jsii_importer::JsiiImportSpec;
 use crate::type_check::type_reference_transform::TypeReferenceTransformer;
 use crate::type_check_assert::TypeCheckAssert;
@@ -37,6 +39,11 @@ pub struct ProjectData {
 	/// The JSII imports for the file. This is saved so we can load JSII types (for autocompletion for example)
 	/// which don't exist explicitly in the source.
 	pub jsii_imports: Vec<JsiiImportSpec>,
+	/// Diagnostics of the last compilation of each file, keyed by file path. These are reported again
+	/// when a compilation doesn't check the file.
+	pub diagnostics: IndexMap<String, Vec<Diagnostic>>,
+	/// Number of compilations that reused type information since it was last reset
+	pub compilations_since_reset: usize,
 }
 
 impl ProjectData {
@@ -47,6 +54,8 @@ impl ProjectData {
 			trees: IndexMap::new(),
 			asts: IndexMap::new(),
 			jsii_imports: Vec::new(),
+			diagnostics: IndexMap::new(),
+			compilations_since_reset: 0,
 		}
 	}
 
@@ -60,6 +69,9 @@ impl ProjectData {
 	}
 }
 
+/// Number of compilations after which all type information is reset and the whole project is checked again
+const MAX_INCREMENTAL_COMPILATIONS: usize = 100;
+
 thread_local! {
 	/// When consumed as a WASM library, wingc is not in control of the process/memory in which it is running.
 	/// This means that it cannot reliably manage stateful data like this between function calls.
@@ -126,6 +138,9 @@ pub fn on_document_did_change(params: DidChangeTextDocumentParams) {
 
 /// Runs several phases of the wing compiler on a file, including: parsing, type checking, and lifting
 /// `ProjectData` is passed with results from previous compilations, and is updated with the results of this compilation.
+///
+/// Only the changed file and the files that (transitively) depend on it are type checked again. Type
+/// information of all other files is kept from previous compilations.
 fn partial_compile(
 	source_path: &Path,
 	source_text: String,
@@ -161,25 +176,64 @@ fn partial_compile(
 		&mut project_data.asts,
 	);
 
+	// The type arena only grows, so every once in a while we start over to free the types of old compilations.
+	// Cyclic dependencies are always checked from scratch, since the files in the cycle can't be checked in order.
+	project_data.compilations_since_reset += 1;
+	let full_recheck =
+		project_data.compilations_since_reset > MAX_INCREMENTAL_COMPILATIONS || project_data.file_graph.toposort().is_err();
+	let previously_checked_files = types.source_file_envs.keys().cloned().collect::<HashSet<_>>();
+	if full_recheck {
+		*types = Types::new();
+		project_data.jsii_imports.clear();
+		project_data.diagnostics.clear();
+		project_data.compilations_since_reset = 0;
+	}
+
+	// Files that need to be type checked: the changed file, files that depend on it, and files we haven't checked yet
+	let dependents = project_data
+		.file_graph
+		.dependents_of(&source_file)
+		.into_iter()
+		.cloned()
+		.collect::<HashSet<_>>();
+	let dirty_files = topo_sorted_files
+		.into_iter()
+		.filter(|file| {
+			full_recheck
+				|| *file == source_file
+				|| dependents.contains(file)
+				|| !types.source_file_envs.contains_key(&file.path)
+		})
+		.collect::<Vec<_>>();
+
+	// Files that were compiled before have already been transformed and type checked, so we need fresh ASTs for them
+	for file in &dirty_files {
+		if *file != source_file && previously_checked_files.contains(&file.path) {
+			reparse_wing_file(
+				file,
+				&mut project_data.files,
+				&mut project_data.file_graph,
+				&mut project_data.trees,
+				&mut project_data.asts,
+			);
+		}
+	}
+
 	// -- DESUGARING PHASE --
 
 	// Transform all inflight closures defined in preflight into single-method resources
-	for file in &topo_sorted_files {
+	for file in &dirty_files {
 		let mut inflight_transformer = ClosureTransformer::new();
 		let scope = project_data.asts.swap_remove(&file.path).unwrap();
 		let new_scope = inflight_transformer.fold_scope(scope);
 		project_data.asts.insert(file.path.clone(), new_scope);
 	}
 
-	// Reset all type information
-	*types = Types::new();
-	project_data.jsii_imports.clear();
-
 	// -- TYPECHECKING PHASE --
 
 	// Type check all files in topological order (start with files that don't require any other
 	// Wing files, then move on to files that depend on those, etc.)
-	for file in &topo_sorted_files {
+	for file in &dirty_files {
 		let mut scope = project_data
 			.asts
 			.swap_remove(&file.path)
@@ -218,7 +272,7 @@ fn partial_compile(
 		// out_dir will not be used
 		&source_file.path,
 	);
-	for file in &topo_sorted_files {
+	for file in &dirty_files {
 		let mut lift = LiftVisitor::new(&jsifier);
 		let scope = project_data
 			.asts
@@ -228,7 +282,49 @@ fn partial_compile(
 		project_data.asts.insert(file.path.clone(), scope);
 	}
 
+	// -- LINTING PHASE --
+
+	if !found_errors() {
+		let lint_dir = source_file.path.parent().unwrap_or(&source_file.path);
+		let lint_config = LintConfig::load(lint_dir);
+		for file in &dirty_files {
+			if file.package != source_file.package {
+				continue;
+			}
+			let scope = project_data.asts.get(&file.path).expect("matching AST not found");
+			lint(scope, &types, &lint_config);
+		}
+	}
+
 	// no need to JSify in the LSP
+
+	update_diagnostics(project_data, &dirty_files);
+}
+
+/// Stores the diagnostics of this compilation for the files that were checked, and reports the stored
+/// diagnostics of all other files again (the client expects the diagnostics of the whole project after
+/// each compilation).
+fn update_diagnostics(project_data: &mut ProjectData, checked_files: &[File]) {
+	for file in checked_files {
+		project_data.diagnostics.swap_remove(file.path.as_str());
+	}
+	// Diagnostics without a location are never kept between compilations
+	project_data.diagnostics.swap_remove("");
+
+	let new_diagnostics = get_diagnostics();
+	for diagnostic in project_data.diagnostics.values().flatten() {
+		if !new_diagnostics.contains(diagnostic) {
+			report_diagnostic(diagnostic.clone());
+		}
+	}
+
+	for diagnostic in new_diagnostics {
+		let file_id = diagnostic.span.as_ref().map(|s| s.file_id.clone()).unwrap_or_default();
+		let file_diagnostics = project_data.diagnostics.entry(file_id).or_default();
+		if !file_diagnostics.contains(&diagnostic) {
+			file_diagnostics.push(diagnostic);
+		}
+	}
 }
 
 pub fn check_utf8(path: PathBuf) -> Utf8PathBuf {
@@ -237,6 +333,7 @@ pub fn check_utf8(path: PathBuf) -> Utf8PathBuf {
 
 #[cfg(test)]
 pub mod test_utils {
+	use camino::Utf8Path;
 	use regex::Regex;
 	use std::{fs, str::FromStr};
 	use uuid::Uuid;
@@ -312,6 +409,26 @@ pub mod test_utils {
 		};
 	}
 
+	/// Writes a file to disk and opens it in the language server
+	///
+	/// Returns the URI of the file
+	pub fn open_file(path: &Utf8Path, content: &str) -> Url {
+		fs::write(path, content).expect("Failed to write file");
+		let uri = Url::from_file_path(path).unwrap();
+		on_document_did_open(DidOpenTextDocumentParams {
+			text_document: lsp_types::TextDocumentItem {
+				uri: uri.clone(),
+				language_id: LANGUAGE_ID.to_string(),
+				version: 0,
+				text: content.to_string(),
+			},
+		});
+
+		assert_no_panics();
+
+		uri
+	}
+
 	/// Finds all ranges in the document starting with `//-`
 	pub fn get_ranges(content: &str) -> Vec<Range> {
 		let lines = content.lines();
@@ -342,3 +459,77 @@ pub mod test_utils {
 		ranges
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use lsp_types::*;
+
+	use super::test_utils::open_file;
+	use super::*;
+	use crate::diagnostic::{assert_no_panics, DiagnosticSeverity};
+
+	fn change_file(uri: &Url, text: &str) {
+		on_document_did_change(DidChangeTextDocumentParams {
+			text_document: VersionedTextDocumentIdentifier {
+				uri: uri.clone(),
+				version: 1,
+			},
+			content_changes: vec![TextDocumentContentChangeEvent {
+				range: None,
+				range_length: None,
+				text: text.to_string(),
+			}],
+		});
+		assert_no_panics();
+	}
+
+	fn error_files() -> Vec<String> {
+		get_diagnostics()
+			.into_iter()
+			.filter(|d| d.severity == DiagnosticSeverity::Error)
+			.filter_map(|d| {
+				d.span
+					.map(|s| Utf8Path::new(&s.file_id).file_name().unwrap().to_string())
+			})
+			.collect()
+	}
+
+	#[test]
+	fn dependents_are_checked_again() {
+		let dir = tempfile::tempdir().unwrap();
+		let dir = Utf8Path::from_path(dir.path()).unwrap();
+
+		let lib_uri = open_file(
+			&dir.join("lib.w"),
+			"pub class Foo {\n  pub static bar(): num { return 1; }\n}\n",
+		);
+		open_file(
+			&dir.join("main.w"),
+			"bring \"./lib.w\" as lib;\nlet x: str = lib.Foo.bar();\n",
+		);
+		assert_eq!(error_files(), ["main.w"]);
+
+		change_file(
+			&lib_uri,
+			"pub class Foo {\n  pub static bar(): str { return \"1\"; }\n}\n",
+		);
+		assert!(error_files().is_empty());
+	}
+
+	#[test]
+	fn diagnostics_of_unchanged_files_are_kept() {
+		let dir = tempfile::tempdir().unwrap();
+		let dir = Utf8Path::from_path(dir.path()).unwrap();
+
+		open_file(&dir.join("main.w"), "let x: str = 1;\n");
+		assert_eq!(error_files(), ["main.w"]);
+
+		let other_uri = open_file(&dir.join("other.main.w"), "let y = 1;\n");
+		assert_eq!(error_files(), ["main.w"]);
+
+		change_file(&other_uri, "let y: bool = 1;\n");
+		let mut files = error_files();
+		files.sort();
+		assert_eq!(files, ["main.w", "other.main.w"]);
+	}
+}
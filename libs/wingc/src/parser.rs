@@ -13,8 +13,9 @@ use crate::ast::{
 	AccessModifier, ArgList, AssignmentKind, BinaryOperator, BringSource, CalleeKind, CatchBlock, Class, ClassField,
 	ElseIfBlock, ElseIfLetBlock, ElseIfs, Enum, ExplicitLift, Expr, ExprKind, FunctionBody, FunctionDefinition,
 	FunctionParameter, FunctionSignature, IfLet, Interface, InterpolatedString, InterpolatedStringPart, Intrinsic,
-	IntrinsicKind, LiftQualification, Literal, New, Phase, Reference, Scope, Spanned, Stmt, StmtKind, Struct,
-	StructField, Symbol, TypeAnnotation, TypeAnnotationKind, UnaryOperator, UserDefinedType,
+	IntrinsicKind, LiftQualification, Literal, Match, MatchArm, MatchArmBody, MatchPattern, MatchPatternKind, New, Phase,
+	Reference, Scope, Spanned, Stmt, StmtKind, Struct, StructField, Symbol, TypeAnnotation, TypeAnnotationKind,
+	UnaryOperator, UserDefinedType,
 };
 use crate::comp_ctx::{CompilationContext, CompilationPhase};
 use crate::diagnostic::{
@@ -32,11 +33,7 @@ use crate::{
 // this is meant to serve as a bandaide to be removed once wing is further developed.
 // k=grammar, v=optional_message, example: ("generic", "targed impl: 1.0.0")
 static UNIMPLEMENTED_GRAMMARS: phf::Map<&'static str, &'static str> = phf_map! {
-	"any" => "https://github.com/winglang/wing/issues/434",
-	"Promise" => "https://github.com/winglang/wing/issues/529",
 	"internal" => "https://github.com/winglang/wing/issues/4156",
-	"await_expression" => "https://github.com/winglang/wing/issues/116",
-	"defer_expression" => "https://github.com/winglang/wing/issues/116",
 };
 
 static RESERVED_WORDS: phf::Set<&'static str> = phf_set! {
@@ -242,6 +239,22 @@ fn parse_wing_file(
 		None => fs::read_to_string(&source_file.path).expect("read_to_string call failed"),
 	};
 
+	// In the LSP we might have a tree from a previous compilation of this file. If so, tell tree-sitter
+	// which part of the text was changed so it can reuse the unchanged parts of the old tree.
+	let old_tree = match (
+		files.get_file(&source_file.path),
+		tree_sitter_trees.get(&source_file.path),
+	) {
+		(Some(old_text), Some(old_tree)) => {
+			let mut old_tree = old_tree.clone();
+			if let Some(edit) = compute_input_edit(old_text, &source_text) {
+				old_tree.edit(&edit);
+			}
+			Some(old_tree)
+		}
+		_ => None,
+	};
+
 	// Update our files collection with the new source text. On a fresh compilation,
 	// this will be the first time we've seen this file. In the LSP we might already have
 	// text from a previous compilation, so we'll replace the contents.
@@ -251,7 +264,7 @@ fn parse_wing_file(
 	let mut tree_sitter_parser = tree_sitter::Parser::new();
 	tree_sitter_parser.set_language(&language).unwrap();
 
-	let tree_sitter_tree = match tree_sitter_parser.parse(&source_text.as_bytes(), None) {
+	let tree_sitter_tree = match tree_sitter_parser.parse(&source_text.as_bytes(), old_tree.as_ref()) {
 		Some(tree) => tree,
 		None => {
 			panic!("Error parsing source file with tree-sitter: {}", source_file);
@@ -272,6 +285,72 @@ fn parse_wing_file(
 	dependent_wing_paths
 }
 
+/// Parses a file or directory that was parsed before again, using the source text from the previous
+/// parse. This is used to get a fresh AST for a file whose dependencies changed (ASTs are modified
+/// by the compiler passes that run after parsing, so they can't be type checked twice).
+pub fn reparse_wing_file(
+	source_file: &File,
+	files: &mut Files,
+	file_graph: &mut FileGraph,
+	tree_sitter_trees: &mut IndexMap<Utf8PathBuf, tree_sitter::Tree>,
+	asts: &mut IndexMap<Utf8PathBuf, Scope>,
+) {
+	if source_file.path.is_dir() {
+		parse_wing_directory(
+			source_file,
+			&WingSpan::for_file(source_file.to_string()),
+			files,
+			file_graph,
+			tree_sitter_trees,
+			asts,
+		);
+	} else {
+		let source_text = files.get_file(&source_file.path).cloned();
+		parse_wing_file(source_file, source_text, files, file_graph, tree_sitter_trees, asts);
+	}
+}
+
+/// Describes the difference between two versions of a file as a single tree-sitter edit, replacing
+/// everything between the common prefix and the common suffix of the two texts.
+/// Returns `None` if the texts are identical.
+fn compute_input_edit(old_text: &str, new_text: &str) -> Option<tree_sitter::InputEdit> {
+	if old_text == new_text {
+		return None;
+	}
+	let old_bytes = old_text.as_bytes();
+	let new_bytes = new_text.as_bytes();
+
+	let prefix_len = old_bytes.iter().zip(new_bytes).take_while(|(a, b)| a == b).count();
+	let suffix_len = old_bytes[prefix_len..]
+		.iter()
+		.rev()
+		.zip(new_bytes[prefix_len..].iter().rev())
+		.take_while(|(a, b)| a == b)
+		.count();
+
+	let old_end_byte = old_bytes.len() - suffix_len;
+	let new_end_byte = new_bytes.len() - suffix_len;
+	Some(tree_sitter::InputEdit {
+		start_byte: prefix_len,
+		old_end_byte,
+		new_end_byte,
+		start_position: byte_to_point(old_bytes, prefix_len),
+		old_end_position: byte_to_point(old_bytes, old_end_byte),
+		new_end_position: byte_to_point(new_bytes, new_end_byte),
+	})
+}
+
+/// Converts a byte offset into a tree-sitter point (row and byte column)
+fn byte_to_point(bytes: &[u8], offset: usize) -> tree_sitter::Point {
+	let before = &bytes[..offset];
+	let row = before.iter().filter(|b| **b == b'\n').count();
+	let column = match before.iter().rposition(|b| *b == b'\n') {
+		Some(newline) => offset - newline - 1,
+		None => offset,
+	};
+	tree_sitter::Point { row, column }
+}
+
 /// Returns true if the directory contains any Wing source files (.w), either directly
 /// in the directory or in any subdirectories.
 fn dir_contains_wing_file_recursive(dir_path: &Utf8Path) -> bool {
@@ -442,7 +521,7 @@ impl<'s> Parser<'s> {
 			for stmt in &scope.statements {
 				if !is_valid_module_statement(&stmt) {
 					Diagnostic::new(
-						"Module files cannot have statements besides classes, interfaces, enums, and structs. Rename the file to end with `.main.w` or `.test.w` to make this an entrypoint file.",
+						"Module files cannot have statements besides classes, interfaces, enums, structs, immutable variables and tests. Rename the file to end with `.main.w` or `.test.w` to make this an entrypoint file.",
 						stmt,
 					).report();
 				}
@@ -639,6 +718,7 @@ impl<'s> Parser<'s> {
 			"block" => StmtKind::Scope(self.build_scope(statement_node, phase)),
 			"if_statement" => self.build_if_statement(statement_node, phase)?,
 			"if_let_statement" => self.build_if_let_statement(statement_node, phase)?,
+			"match_statement" => StmtKind::Match(self.build_match(statement_node, phase)?),
 			"for_in_loop" => self.build_for_statement(statement_node, phase)?,
 			"while_statement" => self.build_while_statement(statement_node, phase)?,
 			"break_statement" => self.build_break_statement(statement_node)?,
@@ -808,6 +888,7 @@ impl<'s> Parser<'s> {
 		let reassignable = statement_node.child_by_field_name("reassignable").is_some();
 		let value = self.build_expression(&statement_node.child_by_field_name("value").unwrap(), phase)?;
 		let name = self.check_reserved_symbol(&statement_node.child_by_field_name("name").unwrap())?;
+		let type_ = self.build_if_let_type(statement_node, phase)?;
 
 		let mut else_if_vec = vec![];
 		let mut cursor = statement_node.walk();
@@ -822,6 +903,7 @@ impl<'s> Parser<'s> {
 						statements: statements,
 						value: value,
 						var_name: name,
+						type_: self.build_if_let_type(&node, phase)?,
 					});
 					else_if_vec.push(else_if);
 				}
@@ -846,6 +928,7 @@ impl<'s> Parser<'s> {
 		Ok(StmtKind::IfLet(IfLet {
 			var_name: name,
 			reassignable,
+			type_,
 			value,
 			statements: if_block,
 			else_if_statements: else_if_vec,
@@ -853,6 +936,14 @@ impl<'s> Parser<'s> {
 		}))
 	}
 
+	/// The type an `if let` (or `else if let`) block narrows its value to, if one is given
+	fn build_if_let_type(&self, node: &Node, phase: Phase) -> DiagnosticResult<Option<TypeAnnotation>> {
+		match get_actual_child_by_field_name(*node, "type") {
+			Some(type_node) => Ok(Some(self.build_type_annotation(Some(type_node), phase)?)),
+			None => Ok(None),
+		}
+	}
+
 	fn build_if_statement(&self, statement_node: &Node, phase: Phase) -> DiagnosticResult<StmtKind> {
 		let if_block = self.build_scope(&statement_node.child_by_field_name("block").unwrap(), phase);
 		let mut else_if_vec = vec![];
@@ -879,6 +970,87 @@ impl<'s> Parser<'s> {
 		})
 	}
 
+	fn build_match(&self, match_node: &Node, phase: Phase) -> DiagnosticResult<Match> {
+		let value = self.build_expression(&match_node.child_by_field_name("value").unwrap(), phase)?;
+
+		let mut arms = vec![];
+		let mut cursor = match_node.walk();
+		for arm_node in match_node.children_by_field_name("arm", &mut cursor) {
+			let mut patterns = vec![];
+			let mut patterns_cursor = arm_node.walk();
+			for pattern_node in arm_node.children_by_field_name("pattern", &mut patterns_cursor) {
+				patterns.push(self.build_match_pattern(&pattern_node, phase)?);
+			}
+
+			// Arms of a match statement have a block, arms of a match expression have a value
+			let body = if let Some(block_node) = arm_node.child_by_field_name("block") {
+				MatchArmBody::Statements(self.build_scope(&block_node, phase))
+			} else {
+				MatchArmBody::Expression(self.build_expression(&arm_node.child_by_field_name("value").unwrap(), phase)?)
+			};
+
+			arms.push(MatchArm {
+				patterns,
+				body,
+				span: self.node_span(&arm_node),
+			});
+		}
+
+		Ok(Match {
+			value: Box::new(value),
+			arms,
+		})
+	}
+
+	/// Patterns are parsed as expressions: identifiers are bindings (or `_`), Json object literals destructure
+	/// structs and any other expression is a value the matched value is compared to.
+	fn build_match_pattern(&self, pattern_node: &Node, phase: Phase) -> DiagnosticResult<MatchPattern> {
+		let pattern_node = self.check_error(*pattern_node, "pattern")?;
+		let span = self.node_span(&pattern_node);
+		let kind = match pattern_node.kind() {
+			"parenthesized_expression" => return self.build_match_pattern(&pattern_node.named_child(0).unwrap(), phase),
+			"reference" if pattern_node.named_child(0).unwrap().kind() == "reference_identifier" => {
+				let symbol = self.node_symbol(&pattern_node.named_child(0).unwrap())?;
+				if symbol.name == "_" {
+					MatchPatternKind::Wildcard
+				} else {
+					MatchPatternKind::Binding(symbol)
+				}
+			}
+			"json_literal" if pattern_node.child_by_field_name("type").is_none() => {
+				return self.build_match_pattern(&pattern_node.child_by_field_name("element").unwrap(), phase);
+			}
+			"json_map_literal" => {
+				let mut fields: Vec<(Symbol, MatchPattern)> = vec![];
+				let mut cursor = pattern_node.walk();
+				for field_node in pattern_node.children_by_field_name("member", &mut cursor) {
+					let key_node = field_node.named_child(0).unwrap();
+					if key_node.kind() != "identifier" {
+						return self.with_error("Expected a struct field name", &key_node);
+					}
+					let key = self.node_symbol(&key_node)?;
+					let field_pattern = if let Some(value_node) = field_node.named_child(1) {
+						self.build_match_pattern(&value_node, phase)?
+					} else {
+						MatchPattern {
+							kind: MatchPatternKind::Binding(key.clone()),
+							span: key.span(),
+						}
+					};
+					if fields.iter().any(|(k, _)| k == &key) {
+						self.add_error(format!("Duplicate field \"{}\" in pattern", key), &key_node);
+					} else {
+						fields.push((key, field_pattern));
+					}
+				}
+				MatchPatternKind::Struct(fields)
+			}
+			_ => MatchPatternKind::Value(self.build_expression(&pattern_node, phase)?),
+		};
+
+		Ok(MatchPattern { kind, span })
+	}
+
 	fn build_assignment_statement(
 		&self,
 		statement_node: &Node,
@@ -953,6 +1125,7 @@ impl<'s> Parser<'s> {
 
 		Ok(StmtKind::Struct(Struct {
 			name,
+			type_parameters: self.build_type_parameters(statement_node)?,
 			extends,
 			fields: members,
 			access,
@@ -965,7 +1138,16 @@ impl<'s> Parser<'s> {
 		} else {
 			None
 		};
+		let access_modifier_node = statement_node.child_by_field_name("access_modifier");
+		let access = self.build_access_modifier(&access_modifier_node);
+		if access == AccessModifier::Protected {
+			self.with_error::<Node>(
+				"Variables must be public (\"pub\"), internal or private",
+				&access_modifier_node.expect("access modifier node"),
+			)?;
+		}
 		Ok(StmtKind::Let {
+			access,
 			reassignable: statement_node.child_by_field_name("reassignable").is_some(),
 			var_name: self.check_reserved_symbol(&statement_node.child_by_field_name("name").unwrap())?,
 			initial_value: self.build_expression(&statement_node.child_by_field_name("value").unwrap(), phase)?,
@@ -1301,6 +1483,7 @@ impl<'s> Parser<'s> {
 		let mut initializer = None;
 		let mut inflight_initializer = None;
 		let name = self.check_reserved_symbol(&statement_node.child_by_field_name("name").unwrap())?;
+		let type_parameters = self.build_type_parameters(statement_node)?;
 
 		let mut doc_builder = DocBuilder::new(self);
 
@@ -1387,6 +1570,7 @@ impl<'s> Parser<'s> {
 						kind: TypeAnnotationKind::UserDefined(UserDefinedType {
 							root: name.clone(),
 							fields: vec![],
+							type_arguments: type_parameters_as_arguments(&type_parameters),
 							span: name.span.clone(),
 						}),
 						span: self.node_span(&class_element),
@@ -1401,6 +1585,7 @@ impl<'s> Parser<'s> {
 								self.build_scope(&class_element.child_by_field_name("block").unwrap(), Phase::Inflight),
 							),
 							signature: FunctionSignature {
+								type_parameters: vec![],
 								parameters,
 								return_type: init_return_type,
 								phase: Phase::Inflight,
@@ -1418,6 +1603,7 @@ impl<'s> Parser<'s> {
 							),
 							is_static: false,
 							signature: FunctionSignature {
+								type_parameters: vec![],
 								parameters,
 								return_type: init_return_type,
 								phase: Phase::Preflight,
@@ -1455,11 +1641,13 @@ impl<'s> Parser<'s> {
 			None => FunctionDefinition {
 				name: Some(CLASS_INIT_NAME.into()),
 				signature: FunctionSignature {
+					type_parameters: vec![],
 					parameters: vec![],
 					return_type: Box::new(TypeAnnotation {
 						kind: TypeAnnotationKind::UserDefined(UserDefinedType {
 							root: name.clone(),
 							fields: vec![],
+							type_arguments: type_parameters_as_arguments(&type_parameters),
 							span: name.span(),
 						}),
 						span: name.span(),
@@ -1481,11 +1669,13 @@ impl<'s> Parser<'s> {
 			None => FunctionDefinition {
 				name: Some(CLASS_INFLIGHT_INIT_NAME.into()),
 				signature: FunctionSignature {
+					type_parameters: vec![],
 					parameters: vec![],
 					return_type: Box::new(TypeAnnotation {
 						kind: TypeAnnotationKind::UserDefined(UserDefinedType {
 							root: name.clone(),
 							fields: vec![],
+							type_arguments: type_parameters_as_arguments(&type_parameters),
 							span: name.span(),
 						}),
 						span: name.span(),
@@ -1545,6 +1735,7 @@ impl<'s> Parser<'s> {
 
 		Ok(StmtKind::Class(Class {
 			name,
+			type_parameters,
 			span,
 			fields,
 			methods,
@@ -1566,15 +1757,6 @@ impl<'s> Parser<'s> {
 	) -> Result<ClassField, ()> {
 		let modifiers = class_element.child_by_field_name("modifiers");
 		let is_static = self.get_modifier("static", &modifiers)?.is_some();
-		if is_static {
-			report_diagnostic(Diagnostic {
-				message: "Static class fields not supported yet, see https://github.com/winglang/wing/issues/1668".to_string(),
-				span: Some(self.node_span(&class_element)),
-				annotations: vec![],
-				hints: vec![],
-				severity: DiagnosticSeverity::Error,
-			});
-		}
 
 		let phase = match self.get_phase_specifier(&modifiers)? {
 			Some(phase) => phase,
@@ -1586,6 +1768,27 @@ impl<'s> Parser<'s> {
 				.err();
 		}
 
+		// Static fields have no constructor to initialize them, so they're initialized where they're declared
+		let initial_value = match (class_element.child_by_field_name("initializer"), is_static) {
+			(Some(initializer), true) => Some(self.build_expression(&initializer, phase)?),
+			(None, true) => {
+				self
+					.with_error::<Node>("Static class fields must be initialized", &class_element)
+					.err();
+				None
+			}
+			(Some(initializer), false) => {
+				self
+					.with_error::<Node>(
+						"Only static class fields can be initialized in their declaration, initialize instance fields in the constructor",
+						&initializer,
+					)
+					.err();
+				None
+			}
+			(None, false) => None,
+		};
+
 		Ok(ClassField {
 			name: self.node_symbol(&class_element.child_by_field_name("name").unwrap())?,
 			member_type: self.build_type_annotation(get_actual_child_by_field_name(class_element, "type"), phase)?,
@@ -1594,6 +1797,7 @@ impl<'s> Parser<'s> {
 			phase,
 			access: self.get_access_modifier(&class_element.child_by_field_name("modifiers"))?,
 			doc,
+			initial_value,
 		})
 	}
 
@@ -1601,6 +1805,7 @@ impl<'s> Parser<'s> {
 		let mut cursor = statement_node.walk();
 		let mut extends = vec![];
 		let mut methods = vec![];
+		let mut fields = vec![];
 
 		let interface_modifiers = statement_node.child_by_field_name("modifiers");
 
@@ -1651,18 +1856,9 @@ impl<'s> Parser<'s> {
 					}
 				}
 				"class_field" => {
-					// Interface method signatures can't have access modifiers like 'pub'
-					if let Some(first_child_node) = interface_element.child(0) {
-						if let "field_modifiers" = first_child_node.kind() {
-							self
-								.with_error::<Node>("Access modifiers are not allowed in interfaces", &interface_element)
-								.err();
-							continue;
-						}
+					if let Ok(field) = self.build_interface_field(interface_element, interface_phase, doc) {
+						fields.push(field);
 					}
-					self
-						.with_error::<Node>("Properties are not supported in interfaces", &interface_element)
-						.err();
 				}
 				"ERROR" => {
 					self
@@ -1692,7 +1888,7 @@ impl<'s> Parser<'s> {
 			if let Ok(TypeAnnotation {
 				kind: TypeAnnotationKind::UserDefined(interface_type),
 				..
-			}) = self.build_udt_annotation(&extend)
+			}) = self.build_udt_annotation(&extend, interface_phase)
 			{
 				extends.push(interface_type);
 			}
@@ -1708,13 +1904,60 @@ impl<'s> Parser<'s> {
 
 		Ok(StmtKind::Interface(Interface {
 			name,
+			type_parameters: self.build_type_parameters(statement_node)?,
 			methods,
+			fields,
 			extends,
 			access,
 			phase: interface_phase,
 		}))
 	}
 
+	fn build_interface_field(
+		&self,
+		interface_element: Node,
+		interface_phase: Phase,
+		doc: Option<String>,
+	) -> DiagnosticResult<ClassField> {
+		let modifiers = interface_element.child_by_field_name("modifiers");
+		if let Some(access_modifier) = self.get_modifier("access_modifier", &modifiers)? {
+			self
+				.with_error::<Node>("Access modifiers are not allowed in interfaces", &access_modifier)
+				.err();
+		}
+		if let Some(static_modifier) = self.get_modifier("static", &modifiers)? {
+			self
+				.with_error::<Node>("Interface fields cannot be static", &static_modifier)
+				.err();
+		}
+		if let Some(initializer) = interface_element.child_by_field_name("initializer") {
+			self
+				.with_error::<Node>("Interface fields cannot have an initial value", &initializer)
+				.err();
+		}
+
+		let phase = match self.get_phase_specifier(&modifiers)? {
+			Some(phase) => phase,
+			None => interface_phase,
+		};
+		if phase == Phase::Independent {
+			self
+				.with_error::<Node>("Unphased fields on interfaces are not supported", &interface_element)
+				.err();
+		}
+
+		Ok(ClassField {
+			name: self.node_symbol(&interface_element.child_by_field_name("name").unwrap())?,
+			member_type: self.build_type_annotation(get_actual_child_by_field_name(interface_element, "type"), phase)?,
+			reassignable: self.get_modifier("reassignable", &modifiers)?.is_some(),
+			is_static: false,
+			phase,
+			access: AccessModifier::Public,
+			doc,
+			initial_value: None,
+		})
+	}
+
 	fn build_interface_method(
 		&self,
 		interface_element: Node,
@@ -1766,12 +2009,44 @@ impl<'s> Parser<'s> {
 		};
 
 		Ok(FunctionSignature {
+			type_parameters: self.build_type_parameters(func_sig_node)?,
 			parameters,
 			return_type: Box::new(return_type),
 			phase,
 		})
 	}
 
+	/// Builds the type parameters (e.g. `<T, U>`) of a generic class, interface, struct or function
+	fn build_type_parameters(&self, node: &Node) -> DiagnosticResult<Vec<Symbol>> {
+		let Some(type_parameters_node) = node.child_by_field_name("type_parameters") else {
+			return Ok(vec![]);
+		};
+
+		let mut cursor = type_parameters_node.walk();
+		let mut type_parameters: Vec<Symbol> = vec![];
+		for name_node in type_parameters_node.children_by_field_name("name", &mut cursor) {
+			let name = self.check_reserved_symbol(&name_node)?;
+			if type_parameters.iter().any(|p| p.name == name.name) {
+				self.add_error(format!("Duplicate type parameter \"{}\"", name.name), &name_node);
+				continue;
+			}
+			type_parameters.push(name);
+		}
+		Ok(type_parameters)
+	}
+
+	/// Builds the type arguments (e.g. `<num, str>`) of a reference to a generic type
+	fn build_type_arguments(&self, type_node: &Node, phase: Phase) -> DiagnosticResult<Vec<TypeAnnotation>> {
+		let Some(type_arguments_node) = type_node.child_by_field_name("type_arguments") else {
+			return Ok(vec![]);
+		};
+
+		get_actual_children_by_field_name(type_arguments_node, "type")
+			.into_iter()
+			.map(|n| self.build_type_annotation(Some(n), phase))
+			.collect()
+	}
+
 	fn build_anonymous_closure(&self, anon_closure_node: &Node, scope_phase: Phase) -> DiagnosticResult<Expr> {
 		Ok(Expr::new(
 			ExprKind::FunctionClosure(self.build_function_definition(None, anon_closure_node, scope_phase, false, None)?),
@@ -1872,7 +2147,7 @@ impl<'s> Parser<'s> {
 		Ok(res)
 	}
 
-	fn build_udt(&self, type_node: &Node) -> DiagnosticResult<UserDefinedType> {
+	fn build_udt(&self, type_node: &Node, phase: Phase) -> DiagnosticResult<UserDefinedType> {
 		match type_node.kind() {
 			"custom_type" => {
 				// check if last node is a "."
@@ -1892,6 +2167,7 @@ impl<'s> Parser<'s> {
 						.children_by_field_name("fields", &mut cursor)
 						.map(|n| self.node_symbol(&n).unwrap())
 						.collect(),
+					type_arguments: self.build_type_arguments(type_node, phase)?,
 					span: self.node_span(&type_node),
 				};
 
@@ -1908,6 +2184,7 @@ impl<'s> Parser<'s> {
 								name: builtin.to_string(),
 								span: self.node_span(&type_node),
 							}],
+							type_arguments: vec![],
 							span: self.node_span(&type_node),
 						};
 						Ok(udt)
@@ -1995,6 +2272,10 @@ impl<'s> Parser<'s> {
 					kind: TypeAnnotationKind::Void,
 					span,
 				}),
+				"any" => Ok(TypeAnnotation {
+					kind: TypeAnnotationKind::Any,
+					span,
+				}),
 				"ERROR" => self.with_error("Expected builtin type", type_node),
 				other => return self.report_unimplemented_grammar(other, "builtin", type_node),
 			},
@@ -2005,7 +2286,22 @@ impl<'s> Parser<'s> {
 					span,
 				})
 			}
-			"custom_type" => Ok(self.build_udt_annotation(&type_node)?),
+			"custom_type" => Ok(self.build_udt_annotation(&type_node, scope_phase)?),
+			"union_type" => {
+				// Flatten `A | B | C` (parsed as `(A | B) | C`) into a single union
+				let mut members = vec![];
+				for member_node in get_actual_children_by_field_name(*type_node, "type") {
+					let member = self.build_type_annotation(Some(member_node), scope_phase)?;
+					match member.kind {
+						TypeAnnotationKind::Union(inner) => members.extend(inner),
+						_ => members.push(member),
+					}
+				}
+				Ok(TypeAnnotation {
+					kind: TypeAnnotationKind::Union(members),
+					span,
+				})
+			}
 			"function_type" => {
 				let param_type_list_node = type_node.child_by_field_name("parameter_types").unwrap();
 				let mut cursor = param_type_list_node.walk();
@@ -2038,6 +2334,7 @@ impl<'s> Parser<'s> {
 
 				Ok(TypeAnnotation {
 					kind: TypeAnnotationKind::Function(FunctionSignature {
+						type_parameters: vec![],
 						parameters,
 						return_type: Box::new(self.build_type_annotation(
 							Some(get_actual_child_by_field_name(*type_node, "return_type").unwrap()),
@@ -2090,6 +2387,10 @@ impl<'s> Parser<'s> {
 						kind: TypeAnnotationKind::MutSet(Box::new(self.build_type_annotation(element_type, scope_phase)?)),
 						span,
 					}),
+					"Promise" => Ok(TypeAnnotation {
+						kind: TypeAnnotationKind::Promise(Box::new(self.build_type_annotation(element_type, scope_phase)?)),
+						span,
+					}),
 					"ERROR" => self.with_error("Expected builtin container type", type_node)?,
 					other => self.report_unimplemented_grammar(other, "builtin container type", type_node),
 				}
@@ -2113,6 +2414,7 @@ impl<'s> Parser<'s> {
 						type_name: UserDefinedType {
 							root: Symbol::global(WINGSDK_STD_MODULE),
 							fields: vec![self.node_symbol(&object_expr)?],
+							type_arguments: vec![],
 							span: self.node_span(&object_expr),
 						},
 						property: self.node_symbol(&property)?,
@@ -2147,7 +2449,7 @@ impl<'s> Parser<'s> {
 		}
 	}
 
-	fn build_udt_annotation(&self, nested_node: &Node) -> DiagnosticResult<TypeAnnotation> {
+	fn build_udt_annotation(&self, nested_node: &Node, phase: Phase) -> DiagnosticResult<TypeAnnotation> {
 		// check if last node is a "."
 		let last_child = nested_node
 			.child(nested_node.child_count() - 1)
@@ -2164,6 +2466,7 @@ impl<'s> Parser<'s> {
 				.children_by_field_name("fields", &mut cursor)
 				.map(|n| self.node_symbol(&n).unwrap())
 				.collect(),
+			type_arguments: self.build_type_arguments(nested_node, phase)?,
 			span: self.node_span(&nested_node),
 		});
 		Ok(TypeAnnotation {
@@ -2268,12 +2571,28 @@ impl<'s> Parser<'s> {
 			"json_literal" => self.build_json_literal(&expression_node, phase),
 			"struct_literal" => self.build_struct_literal(&expression_node, phase),
 			"optional_unwrap" => self.build_optional_unwrap_expression(&expression_node, phase),
+			"match_expression" => Ok(Expr::new(
+				ExprKind::Match(self.build_match(&expression_node, phase)?),
+				expression_span,
+			)),
+			"await_expression" => Ok(Expr::new(
+				ExprKind::Await(Box::new(
+					self.build_expression(&expression_node.named_child(0).unwrap(), phase)?,
+				)),
+				expression_span,
+			)),
+			"defer_expression" => Ok(Expr::new(
+				ExprKind::Defer(Box::new(
+					self.build_expression(&expression_node.named_child(0).unwrap(), phase)?,
+				)),
+				expression_span,
+			)),
 			other => self.report_unimplemented_grammar(other, "expression", expression_node),
 		}
 	}
 
 	fn build_new_expression(&self, expression_node: &Node, phase: Phase) -> Result<Expr, ()> {
-		let class_udt = self.build_udt(&expression_node.child_by_field_name("class").unwrap())?;
+		let class_udt = self.build_udt(&expression_node.child_by_field_name("class").unwrap(), phase)?;
 
 		let arg_list = if let Ok(args_node) = self.get_child_field(expression_node, "args") {
 			self.build_arg_list(&args_node, phase)
@@ -2826,11 +3145,18 @@ impl<'s> Parser<'s> {
 	fn build_test_statement(&self, statement_node: &Node) -> Result<StmtKind, ()> {
 		let name_node = statement_node.child_by_field_name("name").unwrap();
 		let name_text = self.node_text(&name_node);
+		let name = &name_text[1..name_text.len() - 1];
+		// Tests of modules are all created in the entrypoint's scope, so their names are qualified by the module's file name
+		let test_id = if is_entrypoint_file(&self.source_file.path) {
+			format!("\"test:{name}\"")
+		} else {
+			format!(
+				"\"test:{}: {name}\"",
+				self.source_file.path.file_name().unwrap_or_default()
+			)
+		};
 		let test_id = Box::new(Expr::new(
-			ExprKind::Literal(Literal::String(format!(
-				"\"test:{}\"",
-				&name_text[1..name_text.len() - 1]
-			))),
+			ExprKind::Literal(Literal::String(test_id)),
 			self.node_span(&name_node),
 		));
 		let statements = self.build_scope(&statement_node.child_by_field_name("block").unwrap(), Phase::Inflight);
@@ -2842,6 +3168,7 @@ impl<'s> Parser<'s> {
 				name: None,
 				body: FunctionBody::Statements(statements),
 				signature: FunctionSignature {
+					type_parameters: vec![],
 					parameters: vec![],
 					return_type: Box::new(TypeAnnotation {
 						kind: TypeAnnotationKind::Void,
@@ -2863,6 +3190,7 @@ impl<'s> Parser<'s> {
 				class: UserDefinedType {
 					root: Symbol::global(WINGSDK_STD_MODULE),
 					fields: vec![Symbol::global(WINGSDK_TEST_CLASS_NAME)],
+					type_arguments: vec![],
 					span: type_span.clone(),
 				},
 				obj_id: Some(test_id),
@@ -2942,6 +3270,23 @@ fn get_actual_children_by_field_name<'a>(node: Node<'a>, field_name: &str) -> Ve
 	children
 }
 
+/// References to the type parameters of a generic declaration, used to refer to the declared type from within
+/// its own declaration (e.g. `Box<T>` as the return type of the constructor of `class Box<T>`).
+fn type_parameters_as_arguments(type_parameters: &[Symbol]) -> Vec<TypeAnnotation> {
+	type_parameters
+		.iter()
+		.map(|p| TypeAnnotation {
+			kind: TypeAnnotationKind::UserDefined(UserDefinedType {
+				root: p.clone(),
+				fields: vec![],
+				type_arguments: vec![],
+				span: p.span(),
+			}),
+			span: p.span(),
+		})
+		.collect()
+}
+
 /// Check if the package.json in the given directory has a `wing` field.
 /// If so, return the name of the library.
 pub fn as_wing_library(module_dir: &Utf8Path) -> Option<String> {
@@ -2975,10 +3320,28 @@ pub fn as_wing_library(module_dir: &Utf8Path) -> Option<String> {
 }
 
 pub fn is_entrypoint_file(path: &Utf8Path) -> bool {
-	path
-		.file_name()
-		.map(|s| s == "main.w" || s.ends_with(".main.w") || s.ends_with(".test.w"))
-		.unwrap_or(false)
+	is_test_file(path)
+		|| path
+			.file_name()
+			.map(|s| s == "main.w" || s.ends_with(".main.w"))
+			.unwrap_or(false)
+}
+
+/// Whether `path` is a test entrypoint (a `.test.w` file)
+pub fn is_test_file(path: &Utf8Path) -> bool {
+	path.file_name().map(|s| s.ends_with(".test.w")).unwrap_or(false)
+}
+
+/// Whether the statement was created from a `test` block (see `build_test_statement`)
+pub fn is_test_statement(stmt: &Stmt) -> bool {
+	let StmtKind::Expression(Expr {
+		kind: ExprKind::New(New { class, .. }),
+		..
+	}) = &stmt.kind
+	else {
+		return false;
+	};
+	class.root.name == WINGSDK_STD_MODULE && class.fields.len() == 1 && class.fields[0].name == WINGSDK_TEST_CLASS_NAME
 }
 
 fn is_valid_module_statement(stmt: &Stmt) -> bool {
@@ -2989,9 +3352,12 @@ fn is_valid_module_statement(stmt: &Stmt) -> bool {
 		StmtKind::Interface(_) => true,
 		StmtKind::Struct { .. } => true,
 		StmtKind::Enum { .. } => true,
+		StmtKind::Let { reassignable, .. } => !reassignable,
+		StmtKind::Expression(_) => is_test_statement(stmt),
 		// --- these are all uncool ---
 		StmtKind::SuperConstructor { .. } => false,
 		StmtKind::If { .. } => false,
+		StmtKind::Match(_) => false,
 		StmtKind::ForLoop { .. } => false,
 		StmtKind::While { .. } => false,
 		StmtKind::IfLet { .. } => false,
@@ -2999,14 +3365,10 @@ fn is_valid_module_statement(stmt: &Stmt) -> bool {
 		StmtKind::Continue => false,
 		StmtKind::Return(_) => false,
 		StmtKind::Throw(_) => false,
-		StmtKind::Expression(_) => false,
 		StmtKind::Assignment { .. } => false,
 		StmtKind::Scope(_) => false,
 		StmtKind::TryCatch { .. } => false,
 		StmtKind::ExplicitLift(_) => false,
-		// TODO: support constants https://github.com/winglang/wing/issues/3606
-		// TODO: support test statements https://github.com/winglang/wing/issues/3571
-		StmtKind::Let { .. } => false,
 	}
 }
 
@@ -3148,6 +3510,14 @@ mod tests {
 		assert_eq!(normalize_path(file_path, Some(relative_to)), Utf8Path::new("foo.w"));
 	}
 
+	#[test]
+	fn test_is_test_file() {
+		assert!(is_test_file(Utf8Path::new("tests/hello.test.w")));
+		assert!(!is_test_file(Utf8Path::new("hello.main.w")));
+		assert!(!is_test_file(Utf8Path::new("test.w")));
+		assert!(is_entrypoint_file(Utf8Path::new("tests/hello.test.w")));
+	}
+
 	#[test]
 	fn test_contains_non_symbolic() {
 		assert_eq!(true, contains_non_symbolic("wow%zer"));
@@ -3158,4 +3528,23 @@ mod tests {
 		assert_eq!(false, contains_non_symbolic("_wowzer"));
 		assert_eq!(false, contains_non_symbolic("wowzer"));
 	}
+
+	#[test]
+	fn input_edit_for_changed_text() {
+		assert_eq!(compute_input_edit("let x = 1;", "let x = 1;"), None);
+
+		let edit = compute_input_edit("let x = 1;\nlog(x);", "let x = 1;\nlog(xy);").unwrap();
+		assert_eq!(edit.start_byte, 16);
+		assert_eq!(edit.old_end_byte, 16);
+		assert_eq!(edit.new_end_byte, 17);
+		assert_eq!(edit.start_position, tree_sitter::Point { row: 1, column: 5 });
+		assert_eq!(edit.new_end_position, tree_sitter::Point { row: 1, column: 6 });
+
+		let edit = compute_input_edit("let x = 1;\nlet y = 2;", "let x = 1;").unwrap();
+		assert_eq!(edit.start_byte, 10);
+		assert_eq!(edit.old_end_byte, 21);
+		assert_eq!(edit.new_end_byte, 10);
+		assert_eq!(edit.old_end_position, tree_sitter::Point { row: 1, column: 10 });
+		assert_eq!(edit.new_end_position, tree_sitter::Point { row: 0, column: 10 });
+	}
 }
@@ -1,8 +1,12 @@
+pub(crate) mod cached_exports;
 mod class_fields_init;
+mod dts_importer;
 mod has_type_stmt;
 mod inference_visitor;
 pub(crate) mod jsii_importer;
 pub mod lifts;
+mod preflight_expr;
+mod requires_scope;
 pub mod symbol_env;
 pub(crate) mod type_reference_transform;
 
@@ -12,14 +16,15 @@ use crate::ast::{
 };
 use crate::ast::{
 	ArgList, BinaryOperator, Class as AstClass, ElseIfs, Enum as AstEnum, Expr, ExprKind, FunctionBody,
-	FunctionParameter as AstFunctionParameter, Interface as AstInterface, InterpolatedStringPart, Literal, Phase,
-	Reference, Scope, Spanned, Stmt, StmtKind, Struct as AstStruct, Symbol, TypeAnnotation, UnaryOperator,
-	UserDefinedType,
+	FunctionParameter as AstFunctionParameter, Interface as AstInterface, InterpolatedStringPart, Literal, Match,
+	MatchArmBody, MatchPattern, MatchPatternKind, Phase, Reference, Scope, Spanned, Stmt, StmtKind, Struct as AstStruct,
+	Symbol, TypeAnnotation, UnaryOperator, UserDefinedType,
 };
 use crate::comp_ctx::{CompilationContext, CompilationPhase};
 use crate::diagnostic::{report_diagnostic, Diagnostic, DiagnosticAnnotation, DiagnosticSeverity, TypeError, WingSpan};
 use crate::docs::Docs;
 use crate::file_graph::{File, FileGraph};
+use crate::parser::is_entrypoint_file;
 use crate::type_check::has_type_stmt::HasStatementVisitor;
 use crate::type_check::symbol_env::SymbolEnvKind;
 use crate::visit::Visit;
@@ -35,9 +40,11 @@ use crate::{
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use derivative::Derivative;
+use dts_importer::DtsImporter;
 use indexmap::IndexMap;
 use itertools::{izip, Itertools};
 use jsii_importer::JsiiImporter;
+use serde::{Deserialize, Serialize};
 
 use std::cmp;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -51,6 +58,8 @@ use self::class_fields_init::VisitClassInit;
 use self::inference_visitor::{InferenceCounterVisitor, InferenceVisitor};
 use self::jsii_importer::JsiiImportSpec;
 use self::lifts::Lifts;
+use self::preflight_expr::PreflightExprVisitor;
+use self::requires_scope::RequiresScopeVisitor;
 use self::symbol_env::{LookupResult, LookupResultMut, SymbolEnvIter, SymbolEnvRef};
 
 pub struct UnsafeRef<T>(*const T);
@@ -100,7 +109,7 @@ pub enum SymbolKind {
 	Namespace(NamespaceRef),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VariableKind {
 	/// a free variable not associated with a specific type
 	Free,
@@ -247,6 +256,10 @@ pub enum Type {
 	MutMap(TypeRef),
 	Set(TypeRef),
 	MutSet(TypeRef),
+	/// The pending result of a deferred inflight call
+	Promise(TypeRef),
+	/// A value of any one of the member types (`A | B`). Members are never unions themselves, see `Types::make_union`.
+	Union(Vec<TypeRef>),
 	Function(FunctionSignature),
 	Class(Class),
 	Interface(Interface),
@@ -356,6 +369,27 @@ impl Class {
 		self.lifts = Some(lifts);
 	}
 
+	/// The type of the method `name` in the JSII class this class extends or the JSII interfaces it implements.
+	/// JSII code calls this class's implementation of the method with JSII values (see `Types::jsii_date`).
+	pub fn jsii_method_type(&self, name: &Symbol) -> Option<TypeRef> {
+		if let Some(parent) = self.parent.as_ref().and_then(|p| p.as_class()) {
+			let parent_method = if parent.fqn.is_some() {
+				parent.get_method(name).map(|m| m.type_)
+			} else {
+				parent.jsii_method_type(name)
+			};
+			if parent_method.is_some() {
+				return parent_method;
+			}
+		}
+		self
+			.implements
+			.iter()
+			.filter_map(|i| i.as_interface())
+			.filter(|i| i.fqn.is_some())
+			.find_map(|i| i.get_method(name).map(|m| m.type_))
+	}
+
 	/// Returns the type of the "handle" method of a closure class or `None` if this is not a closure
 	/// class.
 	pub fn get_closure_method(&self) -> Option<TypeRef> {
@@ -378,6 +412,40 @@ pub struct Interface {
 	pub env: SymbolEnv,
 }
 
+impl Interface {
+	/// Whether the interface only declares fields (like JSII interfaces with properties). Such interfaces are typed
+	/// structurally, so struct values and object literals with the right fields can be used as them.
+	pub fn is_structural(&self) -> bool {
+		self.fields(true).next().is_some() && self.methods(true).next().is_none() && self.extends_only_fields()
+	}
+
+	/// Interfaces can extend interfaces with methods whose members aren't in their env (like the implicit `std.IResource`)
+	fn extends_only_fields(&self) -> bool {
+		self.extends.iter().all(|parent| {
+			parent.as_interface().map_or(false, |parent| {
+				parent.methods(true).next().is_none() && parent.extends_only_fields()
+			})
+		})
+	}
+
+	/// Whether a class or struct has all the fields of this interface, with compatible types. This is how a class
+	/// implementing the interface is checked in `type_check_class`.
+	fn fields_implemented_by(&self, implementation: &impl ClassLike) -> bool {
+		self.fields(true).all(|(name, field)| {
+			let Some(implementation_field) = implementation.get_field(&name.as_str().into()) else {
+				return false;
+			};
+			matches!(implementation_field.kind, VariableKind::InstanceMember)
+				&& implementation_field.access == AccessModifier::Public
+				&& (implementation_field.phase == field.phase || implementation_field.phase == Phase::Independent)
+				&& implementation_field.type_.is_subtype_of(&field.type_)
+				// Values of any type the interface accepts can be assigned to reassignable fields
+				&& (!field.reassignable
+					|| (implementation_field.reassignable && field.type_.is_subtype_of(&implementation_field.type_)))
+		})
+	}
+}
+
 impl Display for Interface {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		if let LookupResult::Found(method, _) = self.get_env().lookup_ext(&CLOSURE_CLASS_HANDLE_METHOD.into(), None) {
@@ -385,10 +453,10 @@ impl Display for Interface {
 			if method.phase == Phase::Inflight {
 				write!(f, "{}", method.type_) // show signature of inflight closure
 			} else {
-				write!(f, "{}", self.name.name)
+				write!(f, "{}{}", self.name.name, fmt_type_arguments(&self.env, &self.fqn))
 			}
 		} else {
-			write!(f, "{}", self.name.name)
+			write!(f, "{}{}", self.name.name, fmt_type_arguments(&self.env, &self.fqn))
 		}
 	}
 }
@@ -478,7 +546,7 @@ pub struct Struct {
 
 impl Display for Struct {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}", self.name.name)
+		write!(f, "{}{}", self.name.name, fmt_type_arguments(&self.env, &self.fqn))
 	}
 }
 
@@ -560,6 +628,21 @@ impl Subtype for Type {
 					true
 				}
 			}
+			(Self::Union(l0), _) => {
+				// A union is a subtype of another type if all of its members are subtypes of it
+				l0.iter().all(|member| {
+					let member: &Type = member;
+					member.is_subtype_of(other)
+				})
+			}
+			(_, Self::Union(r0)) => {
+				// A type is a subtype of a union if it's a subtype of any of its members
+				// e.g. `str` is a subtype of `str | num`
+				r0.iter().any(|member| {
+					let member: &Type = member;
+					self.is_subtype_of(member)
+				})
+			}
 			(Self::Function(l0), Self::Interface(r0)) => {
 				// TODO: Hack to make functions compatible with interfaces
 				// Remove this after https://github.com/winglang/wing/issues/1448
@@ -673,8 +756,8 @@ impl Subtype for Type {
 					return false;
 				};
 
-				// Finally check if they're subtypes
-				res_handle_type.is_subtype_of(&handler_method_var.type_)
+				// Finally check if they're subtypes, and that the class has the interface's fields (if any)
+				res_handle_type.is_subtype_of(&handler_method_var.type_) && iface.fields_implemented_by(class)
 			}
 			(Self::Class(res), Self::Function(_)) => {
 				// To support flexible inflight closures, we say that any
@@ -691,6 +774,10 @@ impl Subtype for Type {
 				// Finally check if they're subtypes
 				(*res_handle_type).is_subtype_of(other)
 			}
+			(Self::Struct(st), Self::Interface(iface)) if iface.is_structural() => {
+				// A struct is a subtype of an interface that only declares fields if it has all of them (structural typing)
+				iface.fields_implemented_by(st)
+			}
 			(_, Self::Interface(_)) => {
 				// TODO - for now only resources can implement interfaces
 				// https://github.com/winglang/wing/issues/2111
@@ -742,6 +829,12 @@ impl Subtype for Type {
 				let r: &Type = r0;
 				l.is_subtype_of(r)
 			}
+			(Self::Promise(l0), Self::Promise(r0)) => {
+				// A Promise type is a subtype of another Promise type if the value type is a subtype of the other value type
+				let l: &Type = l0;
+				let r: &Type = r0;
+				l.is_subtype_of(r)
+			}
 			(Self::Enum(e0), Self::Enum(e1)) => {
 				// An enum type is a subtype of another Enum type only if they are the exact same
 				e0.name == e1.name
@@ -766,6 +859,7 @@ impl Subtype for Type {
 			(Self::String, Self::String) => true,
 			(Self::Boolean, Self::Boolean) => true,
 			(Self::Duration, Self::Duration) => true,
+			(Self::Datetime, Self::Datetime) => true,
 			(Self::Void, Self::Void) => true,
 			// These types are stringable
 			(Self::String, Self::Stringable) => true,
@@ -794,6 +888,8 @@ pub struct FunctionSignature {
 	/// The type of "this" inside the function, if any. This should be None for
 	/// static or anonymous functions.
 	pub this_type: Option<TypeRef>,
+	/// The type parameters of a generic function, their types are inferred from the arguments at each call site.
+	pub type_parameters: Vec<TypeRef>,
 	/// The parameters of the function.
 	pub parameters: Vec<FunctionParameter>,
 	/// The return type of the function.
@@ -865,7 +961,7 @@ impl Display for Class {
 		if let Some(closure) = self.get_closure_method() {
 			std::fmt::Display::fmt(&closure, f)
 		} else {
-			write!(f, "{}", self.name.name)
+			write!(f, "{}{}", self.name.name, fmt_type_arguments(&self.env, &self.fqn))
 		}
 	}
 }
@@ -888,23 +984,34 @@ impl Display for Type {
 			Type::Unresolved => write!(f, "unresolved"),
 			Type::Inferred(_) => write!(f, "unknown"),
 			Type::Optional(v) => {
-				if v.is_closure() {
+				if v.is_closure() || matches!(**v, Type::Union(_)) {
 					write!(f, "({})?", v)
 				} else {
 					write!(f, "{}?", v)
 				}
 			}
+			Type::Union(members) => {
+				let members = members.iter().map(|member| {
+					if member.is_closure() {
+						format!("({})", member)
+					} else {
+						member.to_string()
+					}
+				});
+				write!(f, "{}", members.format(" | "))
+			}
 			Type::Function(sig) => write!(f, "{}", sig),
 			Type::Class(class) => write!(f, "{}", class),
 
 			Type::Interface(iface) => write!(f, "{}", iface),
-			Type::Struct(s) => write!(f, "{}", s.name.name),
+			Type::Struct(s) => write!(f, "{}", s),
 			Type::Array(v) => write!(f, "Array<{v}>"),
 			Type::MutArray(v) => write!(f, "MutArray<{}>", v),
 			Type::Map(v) => write!(f, "Map<{}>", v),
 			Type::MutMap(v) => write!(f, "MutMap<{}>", v),
 			Type::Set(v) => write!(f, "Set<{}>", v),
 			Type::MutSet(v) => write!(f, "MutSet<{}>", v),
+			Type::Promise(v) => write!(f, "Promise<{}>", v),
 			Type::Enum(s) => write!(f, "{}", s.name.name),
 		}
 	}
@@ -931,8 +1038,14 @@ impl Display for FunctionSignature {
 			.collect::<Vec<String>>()
 			.join(", ");
 
+		let type_params_str = if self.type_parameters.is_empty() {
+			"".to_string()
+		} else {
+			format!("<{}>", self.type_parameters.iter().join(", "))
+		};
+
 		let ret_type_str = self.return_type.to_string();
-		write!(f, "{phase_str}({params_str}): {ret_type_str}")
+		write!(f, "{phase_str}{type_params_str}({params_str}): {ret_type_str}")
 	}
 }
 
@@ -1128,6 +1241,11 @@ impl TypeRef {
 		matches!(**self, Type::Struct(_))
 	}
 
+	/// See `Interface::is_structural`
+	pub fn is_structural_interface(&self) -> bool {
+		self.as_interface().map_or(false, Interface::is_structural)
+	}
+
 	pub fn is_stringable_struct(&self) -> bool {
 		if let Type::Struct(ref s) = **self {
 			s.fields(true).map(|(_, v)| v.type_).all(|t| t.is_stringable())
@@ -1179,6 +1297,7 @@ impl TypeRef {
 			**self,
 			Type::String | Type::Number | Type::Boolean | Type::Json(_) | Type::MutJson | Type::Enum(_) | Type::Anything
 		) || self.is_stringable_struct()
+			|| matches!(**self, Type::Union(ref members) if members.iter().all(|t| t.is_stringable()))
 	}
 
 	/// If this is a function and its last argument is a struct, return that struct.
@@ -1245,6 +1364,7 @@ impl TypeRef {
 			Type::Nil => true,
 			Type::Unresolved => true,
 			Type::Optional(t) => t.is_serializable(),
+			Type::Union(members) => members.iter().all(|t| t.is_serializable()),
 			Type::Array(t) => t.is_serializable(),
 			Type::MutArray(t) => t.is_serializable(),
 			Type::Map(t) => t.is_serializable(),
@@ -1258,6 +1378,7 @@ impl TypeRef {
 			Type::Inferred(_) => false,
 			Type::Set(_) => false,
 			Type::MutSet(_) => false,
+			Type::Promise(_) => false,
 			Type::Function(_) => false,
 			Type::Class(_) => false,
 			Type::Interface(_) => false,
@@ -1296,6 +1417,7 @@ impl TypeRef {
 				true
 			}
 			Type::Optional(v) => v.is_json_legal_value(),
+			Type::Union(members) => members.iter().all(|t| t.is_json_legal_value()),
 			Type::Json(Some(v)) => match &v.kind {
 				JsonDataKind::Type(SpannedTypeInfo { type_, .. }) => type_.is_json_legal_value(),
 				JsonDataKind::Fields(fields) => {
@@ -1335,6 +1457,7 @@ impl TypeRef {
 			}
 			Type::Enum(_) => true,
 			Type::Optional(t) | Type::Array(t) | Type::Set(t) | Type::Map(t) => t.has_json_representation(),
+			Type::Union(members) => members.iter().all(|t| t.has_json_representation()),
 			_ => self.is_json_legal_value(),
 		}
 	}
@@ -1404,6 +1527,8 @@ pub struct Types {
 	bool_idx: usize,
 	duration_idx: usize,
 	datetime_idx: usize,
+	/// A separate `Datetime` type for dates imported from JSII, so jsify can tell where values cross the JSII boundary
+	jsii_date_idx: usize,
 	regex_idx: usize,
 	anything_idx: usize,
 	void_idx: usize,
@@ -1427,6 +1552,13 @@ pub struct Types {
 	pub append_empty_struct_to_arglist: HashSet<ArgListId>,
 	/// Class counter, used to generate unique ids for class types
 	pub class_counter: usize,
+	/// The types hydrated from each generic type: their type arguments and the hydrated type.
+	/// Used so the same generic type with the same type arguments always resolves to the same type.
+	hydrated_types: HashMap<*const Type, Vec<(Vec<TypeRef>, TypeRef)>>,
+	/// The generic type each hydrated type was created from
+	generic_origins: HashMap<*const Type, TypeRef>,
+	/// The types of the type parameters of generic declarations
+	type_parameters: HashSet<*const Type>,
 }
 
 impl Types {
@@ -1442,6 +1574,8 @@ impl Types {
 		let duration_idx = types.len() - 1;
 		types.push(Box::new(Type::Datetime));
 		let datetime_idx = types.len() - 1;
+		types.push(Box::new(Type::Datetime));
+		let jsii_date_idx = types.len() - 1;
 		types.push(Box::new(Type::Regex));
 		let regex_idx = types.len() - 1;
 		types.push(Box::new(Type::Anything));
@@ -1469,6 +1603,7 @@ impl Types {
 			bool_idx,
 			duration_idx,
 			datetime_idx,
+			jsii_date_idx,
 			regex_idx,
 			anything_idx,
 			void_idx,
@@ -1500,6 +1635,9 @@ impl Types {
 			// 1 based to avoid conflict with imported JSII classes. This isn't strictly needed since brought JSII classes are never accessed
 			// through their unique ID, but still good to avoid confusion.
 			class_counter: 1,
+			hydrated_types: HashMap::new(),
+			generic_origins: HashMap::new(),
+			type_parameters: HashSet::new(),
 		}
 	}
 
@@ -1531,6 +1669,21 @@ impl Types {
 		self.get_typeref(self.datetime_idx)
 	}
 
+	/// The type of JSII `Date` values. It's a `datetime` for the type checker, but the value is a JS `Date`
+	/// and needs to be converted when it's passed to or returned from JSII code.
+	pub fn jsii_date(&self) -> TypeRef {
+		self.get_typeref(self.jsii_date_idx)
+	}
+
+	/// Whether values of type `t` can hold JSII `Date`s (see `jsii_date`): `t` is a JSII `Date`, or an optional,
+	/// array or map of them
+	pub fn has_jsii_date(&self, t: TypeRef) -> bool {
+		match &*t {
+			Type::Optional(t) | Type::Array(t) | Type::MutArray(t) | Type::Map(t) | Type::MutMap(t) => self.has_jsii_date(*t),
+			_ => std::ptr::eq(&*t, &*self.types[self.jsii_date_idx]),
+		}
+	}
+
 	pub fn regex(&self) -> TypeRef {
 		self.get_typeref(self.regex_idx)
 	}
@@ -1647,6 +1800,46 @@ impl Types {
 		}
 	}
 
+	/// Get the union of the given types.
+	///
+	/// Nested unions are flattened and members that are the same type are only kept once. If any of the members is
+	/// optional (or nil) the whole union is made optional instead (`str? | num` is `(str | num)?`). If a single
+	/// member is left it's returned as-is.
+	pub fn make_union(&mut self, members: Vec<TypeRef>) -> TypeRef {
+		let mut is_optional = false;
+		let mut flat_members: Vec<TypeRef> = vec![];
+		let mut pending = members;
+		pending.reverse();
+		while let Some(member) = pending.pop() {
+			match &*member {
+				Type::Nil => is_optional = true,
+				Type::Optional(t) => {
+					is_optional = true;
+					pending.push(*t);
+				}
+				Type::Union(inner) => pending.extend(inner.iter().rev()),
+				// Any value is already an `any`
+				Type::Anything => return member,
+				_ => {
+					if !flat_members.iter().any(|m| is_same_type_argument(*m, member)) {
+						flat_members.push(member);
+					}
+				}
+			}
+		}
+
+		let union = match flat_members.len() {
+			0 => self.nil(),
+			1 => flat_members[0],
+			_ => self.add_type(Type::Union(flat_members)),
+		};
+		if is_optional && !union.is_nil() {
+			self.make_option(union)
+		} else {
+			union
+		}
+	}
+
 	fn get_typeref(&self, idx: usize) -> TypeRef {
 		let t = &self.types[idx];
 		UnsafeRef::<Type>(&**t as *const Type)
@@ -1816,7 +2009,9 @@ impl Types {
 
 			Type::Optional(t) => return self.get_std_class(t),
 
-			Type::Function(_)
+			Type::Promise(_)
+			| Type::Union(_)
+			| Type::Function(_)
 			| Type::Class(_)
 			| Type::Interface(_)
 			| Type::Enum(_)
@@ -1832,6 +2027,42 @@ impl Types {
 
 		self.libraries.lookup_nested_str(fqn.as_str(), None).ok()
 	}
+
+	/// Returns the hydrated type of `generic` with the given type arguments, if it was already hydrated
+	fn get_hydrated_type(&self, generic: TypeRef, type_arguments: &[TypeRef]) -> Option<TypeRef> {
+		self
+			.hydrated_types
+			.get(&(&*generic as *const Type))?
+			.iter()
+			.find(|(args, _)| {
+				args.len() == type_arguments.len()
+					&& args
+						.iter()
+						.zip(type_arguments)
+						.all(|(a, b)| is_same_type_argument(*a, *b))
+			})
+			.map(|(_, hydrated)| *hydrated)
+	}
+
+	/// Returns the generic type a hydrated type (e.g. `Box<num>`) was created from
+	pub fn get_generic_origin(&self, hydrated: TypeRef) -> Option<TypeRef> {
+		self.generic_origins.get(&(&*hydrated as *const Type)).copied()
+	}
+
+	/// Whether the type is the type parameter of a generic declaration (e.g. `T` inside `class Box<T>`)
+	pub fn is_type_parameter(&self, t: TypeRef) -> bool {
+		self.type_parameters.contains(&(&*t as *const Type))
+	}
+
+	/// Records that `hydrated` is `generic` with the given type arguments
+	fn add_hydrated_type(&mut self, generic: TypeRef, type_arguments: Vec<TypeRef>, hydrated: TypeRef) {
+		self
+			.hydrated_types
+			.entry(&*generic as *const Type)
+			.or_default()
+			.push((type_arguments, hydrated));
+		self.generic_origins.insert(&*hydrated as *const Type, generic);
+	}
 }
 
 /// Enum of builtin functions, this are defined as hard coded AST nodes in `add_builtins`
@@ -1971,6 +2202,16 @@ impl<'a> TypeChecker<'a> {
 		});
 	}
 
+	fn spanned_warning<S: Into<String>>(&self, spanned: &impl Spanned, message: S) {
+		report_diagnostic(Diagnostic {
+			message: message.into(),
+			span: Some(spanned.span()),
+			annotations: vec![],
+			hints: vec![],
+			severity: DiagnosticSeverity::Warning,
+		});
+	}
+
 	fn spanned_error_with_hints<S: ToString, H: ToString>(&self, spanned: &impl Spanned, message: S, hints: &[H]) {
 		report_diagnostic(Diagnostic {
 			message: message.to_string(),
@@ -2088,6 +2329,7 @@ impl<'a> TypeChecker<'a> {
 			UtilityFunctions::Log.to_string().as_str(),
 			Type::Function(FunctionSignature {
 				this_type: None,
+				type_parameters: vec![],
 				parameters: vec![FunctionParameter {
 					name: "value".into(),
 					typeref: self.types.stringable(),
@@ -2107,6 +2349,7 @@ impl<'a> TypeChecker<'a> {
 			UtilityFunctions::Assert.to_string().as_str(),
 			Type::Function(FunctionSignature {
 				this_type: None,
+				type_parameters: vec![],
 				parameters: vec![
 					FunctionParameter {
 						name: "condition".into(),
@@ -2134,6 +2377,7 @@ impl<'a> TypeChecker<'a> {
 			UtilityFunctions::UnsafeCast.to_string().as_str(),
 			Type::Function(FunctionSignature {
 				this_type: None,
+				type_parameters: vec![],
 				parameters: vec![FunctionParameter {
 					name: "value".into(),
 					typeref: self.types.anything(),
@@ -2163,6 +2407,7 @@ impl<'a> TypeChecker<'a> {
 			UtilityFunctions::Nodeof.to_string().as_str(),
 			Type::Function(FunctionSignature {
 				this_type: None,
+				type_parameters: vec![],
 				parameters: vec![FunctionParameter {
 					name: "construct".into(),
 					typeref: self.types.construct_interface(),
@@ -2232,6 +2477,9 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			ExprKind::JsonLiteral { is_mut, element } => self.type_check_json_lit(is_mut, element, env, exp),
 			ExprKind::JsonMapLiteral { fields } => self.type_check_json_map_lit(fields, env, exp),
 			ExprKind::FunctionClosure(func_def) => self.type_check_closure(func_def, env),
+			ExprKind::Match(match_) => self.type_check_match(match_, env),
+			ExprKind::Await(awaited) => self.type_check_await(awaited, env, exp),
+			ExprKind::Defer(deferred) => self.type_check_defer(deferred, env, exp),
 		};
 
 		// If we're inflight but the expression is a lifted (preflight) expression then make it immutable
@@ -2353,6 +2601,45 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		}
 	}
 
+	fn type_check_await(&mut self, awaited: &Expr, env: &mut SymbolEnv, exp: &Expr) -> (TypeRef, Phase) {
+		let (awaited_type, _) = self.type_check_exp(awaited, env);
+		if env.phase != Phase::Inflight {
+			self.spanned_error(exp, "\"await\" can only be used in inflight code");
+			return self.resolved_error();
+		}
+		if awaited_type.is_unresolved() {
+			return (awaited_type, Phase::Inflight);
+		}
+
+		if let Type::Promise(value_type) = &*awaited_type {
+			(*value_type, Phase::Inflight)
+		} else {
+			self.spanned_error(
+				awaited,
+				format!("Expected a \"Promise\" to await, but got \"{awaited_type}\" instead"),
+			);
+			(awaited_type, Phase::Inflight)
+		}
+	}
+
+	fn type_check_defer(&mut self, deferred: &Expr, env: &mut SymbolEnv, exp: &Expr) -> (TypeRef, Phase) {
+		let (value_type, _) = self.type_check_exp(deferred, env);
+		if env.phase != Phase::Inflight {
+			self.spanned_error(exp, "\"defer\" can only be used in inflight code");
+			return self.resolved_error();
+		}
+		// Only calls are awaited implicitly, so they're the only expressions that can be deferred
+		if !matches!(deferred.kind, ExprKind::Call { .. }) {
+			self.spanned_error(deferred, "Only function calls can be deferred");
+			return self.resolved_error();
+		}
+		if value_type.is_unresolved() {
+			return (value_type, Phase::Inflight);
+		}
+
+		(self.types.add_type(Type::Promise(value_type)), Phase::Inflight)
+	}
+
 	fn type_check_unary_op(&mut self, unary_exp: &Expr, env: &mut SymbolEnv, op: &UnaryOperator) -> (TypeRef, Phase) {
 		let (type_, phase) = self.type_check_exp(unary_exp, env);
 
@@ -2753,7 +3040,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		}
 
 		// Make sure this is a function signature type
-		let func_sig = if let Some(func_sig) = func_type.as_deep_function_sig() {
+		let mut func_sig = if let Some(func_sig) = func_type.as_deep_function_sig() {
 			func_sig.clone()
 		} else if func_type.is_closure_class() {
 			let handle_type = func_type.as_class().unwrap().get_closure_method().unwrap();
@@ -2766,6 +3053,11 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			return self.resolved_error();
 		};
 
+		// Calls to generic functions are checked against the signature with the inferred type arguments
+		if !func_sig.type_parameters.is_empty() {
+			func_sig = self.instantiate_generic_function_sig(&func_sig, &arg_list_types, env, exp);
+		}
+
 		if !env.phase.can_call_to(&func_sig.phase) {
 			self.spanned_error(
 				exp,
@@ -2877,13 +3169,24 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			obj_scope,
 		} = new_expr;
 		// Type check everything
-		let class_type = self
+		let mut class_type = self
 			.resolve_user_defined_type(class, env, self.ctx.current_stmt_idx())
 			.unwrap_or_else(|e| self.type_error(e));
 		let obj_scope_type = obj_scope.as_ref().map(|x| self.type_check_exp(x, env).0);
 		let obj_id_type = obj_id.as_ref().map(|x| self.type_check_exp(x, env).0);
 		let arg_list_types = self.type_check_arg_list(arg_list, env);
 
+		// Instantiating a generic class without type arguments, infer them from the constructor arguments
+		if class.type_arguments.is_empty() && class_type.as_class().is_some() && self.is_unapplied_generic(class_type, env)
+		{
+			class_type = self.infer_class_type_arguments(class_type, &arg_list_types, env, exp);
+		}
+
+		if self.types.is_type_parameter(class_type) {
+			self.spanned_error(exp, format!("Cannot instantiate type parameter \"{}\"", class_type));
+			return self.resolved_error();
+		}
+
 		// Lookup the class's type in the env
 		let (class_env, class_symbol) = match *class_type {
 			Type::Class(ref class) => {
@@ -3185,6 +3488,11 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			self.ctx.current_stmt_idx(),
 			self.source_file.package.clone(),
 		));
+		self.add_type_parameters_to_env(
+			&func_def.signature.type_parameters,
+			&sig.type_parameters,
+			&mut function_env,
+		);
 		self.add_arguments_to_env(&func_def.signature.parameters, &sig, &mut function_env);
 
 		self.with_function_def(None, &func_def.signature, func_def.is_static, function_env, |tc| {
@@ -3208,18 +3516,24 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		expected_type: &TypeRef,
 		value: &impl Spanned,
 	) {
-		let expected_struct = if let Some(expected_struct) = expected_type.maybe_unwrap_option().as_struct() {
-			expected_struct
-		} else {
-			self.spanned_error(value, format!("{expected_type} is not a struct so it has no fields"));
-			return;
+		let expected_type_unwrapped = expected_type.maybe_unwrap_option();
+		let (expected_name, expected_env) = match &**expected_type_unwrapped {
+			Type::Struct(expected_struct) => (&expected_struct.name, expected_struct.get_env()),
+			// Interfaces that only declare fields are checked like structs
+			Type::Interface(expected_iface) if expected_iface.is_structural() => {
+				(&expected_iface.name, expected_iface.get_env())
+			}
+			_ => {
+				self.spanned_error(value, format!("{expected_type} is not a struct so it has no fields"));
+				return;
+			}
 		};
 
 		// Verify that there are no extraneous fields
 		// Also map original field names to the ones in the struct type
 		let mut field_map = IndexMap::new();
 		for (k, _) in object_types.iter() {
-			let field = expected_struct.env.lookup(k, None);
+			let field = expected_env.lookup(k, None);
 			if let Some(field) = field {
 				let field_type = field
 					.as_variable()
@@ -3232,7 +3546,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		}
 
 		// Verify that all non-optional fields are present and are of the right type
-		for (k, v) in expected_struct.env.iter(true).map(|(k, v, _)| {
+		for (k, v) in expected_env.iter(true).map(|(k, v, _)| {
 			(
 				k,
 				v.as_variable()
@@ -3255,10 +3569,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			} else if !v.is_option() {
 				self.spanned_error(
 					value,
-					format!(
-						"Missing required field \"{}\" from \"{}\"",
-						k, expected_struct.name.name
-					),
+					format!("Missing required field \"{}\" from \"{}\"", k, expected_name.name),
 				);
 			}
 		}
@@ -3336,6 +3647,15 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			return false;
 		}
 
+		// Json literals are checked against the member of a union they can be used as, e.g. a list of struct literals
+		// against the `Array<S>` of `Array<S> | IResolvable`
+		if let Type::Union(members) = &**expected_type_unwrapped {
+			let Some(member) = members.iter().find(|member| json_literal_fits(actual_type, **member)) else {
+				return false;
+			};
+			return self.validate_type_json(actual_type, *member, span);
+		}
+
 		let inner_expected = expected_type_unwrapped.collection_item_type();
 		if let Some(inner_expected) = inner_expected {
 			if let Some(inner_actual) = actual_type.collection_item_type() {
@@ -3360,6 +3680,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		};
 
 		if expected_type_unwrapped.is_struct()
+			|| expected_type_unwrapped.is_structural_interface()
 			|| expected_type_unwrapped.is_immutable_collection()
 			|| expected_type_unwrapped.is_json_legal_value()
 		{
@@ -3374,7 +3695,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				true
 			}
 			JsonDataKind::Fields(fields) => {
-				if expected_type_unwrapped.is_struct() {
+				if expected_type_unwrapped.is_struct() || expected_type_unwrapped.is_structural_interface() {
 					self.validate_structural_type(fields, expected_type_unwrapped, span);
 					true
 				} else if let (Some(inner_expected), true) = (inner_expected, expected_type_unwrapped.is_map()) {
@@ -3895,7 +4216,11 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 
 	fn hoist_struct_definition(&mut self, st: &AstStruct, env: &mut SymbolEnv, doc: &Option<String>) {
 		let AstStruct {
-			name, extends, access, ..
+			name,
+			type_parameters,
+			extends,
+			access,
+			..
 		} = st;
 
 		// Structs can't be defined in preflight or inflight contexts, only at the top-level of a program
@@ -3906,22 +4231,26 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			);
 		}
 
-		// Create environment representing this struct, for now it'll be empty just so we can support referencing it
-		let dummy_env = SymbolEnv::new(
+		// Create environment representing this struct, for now it'll be empty just so we can support referencing it.
+		// It holds the struct's type parameters until the struct's fields are type checked.
+		let mut dummy_env = SymbolEnv::new(
 			None,
 			SymbolEnvKind::Type(self.types.void()),
 			env.phase,
 			0,
 			self.source_file.package.clone(),
 		);
+		let type_parameter_types = self.make_type_parameters(type_parameters);
+		let type_parameters_env = self.make_type_parameters_env(type_parameters, &type_parameter_types, env);
+		if !type_parameters.is_empty() {
+			dummy_env.type_parameters = Some(type_parameter_types);
+		}
 
 		// Collect types this struct extends
 		let extends_types = extends
 			.iter()
 			.filter_map(|ext| {
-				let t = self
-					.resolve_user_defined_type(ext, &env, self.ctx.current_stmt_idx())
-					.unwrap_or_else(|e| self.type_error(e));
+				let t = self.resolve_user_defined_type_annotation(ext, &type_parameters_env, self.ctx.current_stmt_idx());
 				if t.as_struct().is_some() {
 					Some(t)
 				} else {
@@ -3951,13 +4280,19 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 	fn hoist_interface_definition(&mut self, iface: &AstInterface, env: &mut SymbolEnv, doc: &Option<String>) {
 		// Create environment representing this interface, for now it'll be empty just so we can support referencing ourselves
 		// from the interface definition or by other type definitions that come before the interface statement.
-		let dummy_env = SymbolEnv::new(
+		// It holds the interface's type parameters until the interface's members are type checked.
+		let mut dummy_env = SymbolEnv::new(
 			None,
 			SymbolEnvKind::Type(self.types.void()),
 			env.phase,
 			self.ctx.current_stmt_idx(),
 			self.source_file.package.clone(),
 		);
+		let type_parameter_types = self.make_type_parameters(&iface.type_parameters);
+		let type_parameters_env = self.make_type_parameters_env(&iface.type_parameters, &type_parameter_types, env);
+		if !iface.type_parameters.is_empty() {
+			dummy_env.type_parameters = Some(type_parameter_types);
+		}
 
 		// Interfaces can only be declared only at the top-level of a program
 		if let Some(_) = env.parent {
@@ -3973,9 +4308,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			.extends
 			.iter()
 			.filter_map(|i| {
-				let t = self
-					.resolve_user_defined_type(i, env, self.ctx.current_stmt_idx())
-					.unwrap_or_else(|e| self.type_error(e));
+				let t = self.resolve_user_defined_type_annotation(i, &type_parameters_env, self.ctx.current_stmt_idx());
 				if t.as_interface().is_some() {
 					Some(t)
 				} else {
@@ -4051,6 +4384,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			TypeAnnotationKind::Datetime => self.types.datetime(),
 			TypeAnnotationKind::Regex => self.types.regex(),
 			TypeAnnotationKind::Void => self.types.void(),
+			TypeAnnotationKind::Any => self.types.anything(),
 			TypeAnnotationKind::Json => self.types.json(),
 			TypeAnnotationKind::MutJson => self.types.mut_json(),
 			TypeAnnotationKind::Optional(v) => {
@@ -4058,6 +4392,11 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				self.types.add_type(Type::Optional(value_type))
 			}
 			TypeAnnotationKind::Function(ast_sig) => {
+				// Generic functions resolve their parameter and return types in an environment with their type parameters
+				let type_parameters = self.make_type_parameters(&ast_sig.type_parameters);
+				let type_parameters_env = self.make_type_parameters_env(&ast_sig.type_parameters, &type_parameters, env);
+				let env: &SymbolEnv = &type_parameters_env;
+
 				let last_non_optional_index = ast_sig.parameters.iter().rposition(|p| match p.type_annotation.kind {
 					TypeAnnotationKind::Optional(_) => false,
 					_ => !p.variadic,
@@ -4103,6 +4442,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				}
 				let sig = FunctionSignature {
 					this_type: None,
+					type_parameters,
 					parameters,
 					return_type: self.resolve_type_annotation(ast_sig.return_type.as_ref(), env),
 					phase: ast_sig.phase,
@@ -4114,9 +4454,9 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				// TODO: avoid creating a new type for each function_sig resolution
 				self.types.add_type(Type::Function(sig))
 			}
-			TypeAnnotationKind::UserDefined(user_defined_type) => self
-				.resolve_user_defined_type(user_defined_type, env, self.ctx.current_stmt_idx())
-				.unwrap_or_else(|e| self.type_error(e)),
+			TypeAnnotationKind::UserDefined(user_defined_type) => {
+				self.resolve_user_defined_type_annotation(user_defined_type, env, self.ctx.current_stmt_idx())
+			}
 			TypeAnnotationKind::Array(v) => {
 				let value_type = self.resolve_type_annotation(v, env);
 				// TODO: avoid creating a new type for each array resolution
@@ -4137,6 +4477,17 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				// TODO: avoid creating a new type for each set resolution
 				self.types.add_type(Type::MutSet(value_type))
 			}
+			TypeAnnotationKind::Promise(v) => {
+				let value_type = self.resolve_type_annotation(v, env);
+				self.types.add_type(Type::Promise(value_type))
+			}
+			TypeAnnotationKind::Union(members) => {
+				let members = members
+					.iter()
+					.map(|member| self.resolve_type_annotation(member, env))
+					.collect();
+				self.types.make_union(members)
+			}
 			TypeAnnotationKind::Map(v) => {
 				let value_type = self.resolve_type_annotation(v, env);
 				// TODO: avoid creating a new type for each map resolution
@@ -4199,8 +4550,9 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				var_name,
 				initial_value,
 				type_,
+				access,
 			} => {
-				tc.type_check_let(type_, initial_value, var_name, reassignable, env);
+				tc.type_check_let(type_, initial_value, var_name, reassignable, *access, env);
 			}
 			StmtKind::ForLoop {
 				iterator,
@@ -4224,6 +4576,9 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			} => {
 				tc.type_check_if(condition, statements, else_if_statements, else_statements, env);
 			}
+			StmtKind::Match(match_) => {
+				tc.type_check_match(match_, env);
+			}
 			StmtKind::Expression(e) => {
 				tc.type_check_exp(e, env);
 			}
@@ -4341,6 +4696,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 	fn type_check_struct(&mut self, st: &AstStruct, env: &mut SymbolEnv) {
 		let AstStruct {
 			name,
+			type_parameters,
 			extends: _,
 			fields,
 			access: _,
@@ -4370,9 +4726,14 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			self.source_file.package.clone(),
 		);
 
+		// The type parameters were created when the struct was hoisted, field types are resolved in an environment with them
+		struct_env.type_parameters = struct_type.as_struct().unwrap().env.type_parameters.clone();
+		let type_parameter_types = struct_env.type_parameters.clone().unwrap_or_default();
+		let type_parameters_env = self.make_type_parameters_env(type_parameters, &type_parameter_types, env);
+
 		// Add fields to the struct env
 		for field in fields.iter() {
-			let field_type = self.resolve_type_annotation(&field.member_type, env);
+			let field_type = self.resolve_type_annotation(&field.member_type, &type_parameters_env);
 			if field_type.is_mutable() {
 				self.spanned_error(&field.name, "Struct fields must have immutable types");
 			}
@@ -4404,6 +4765,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 
 		// Replace the dummy struct environment with the real one
 		struct_type.as_struct_mut().unwrap().env = struct_env;
+		self.update_hydrated_types(env, struct_type);
 	}
 
 	fn type_check_interface(&mut self, ast_iface: &AstInterface, env: &mut SymbolEnv) {
@@ -4427,9 +4789,14 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			self.source_file.package.clone(),
 		);
 
+		// The type parameters were created when the interface was hoisted, members are resolved in an environment with them
+		interface_env.type_parameters = interface_type.as_interface().unwrap().env.type_parameters.clone();
+		let type_parameter_types = interface_env.type_parameters.clone().unwrap_or_default();
+		let type_parameters_env = self.make_type_parameters_env(&ast_iface.type_parameters, &type_parameter_types, env);
+
 		// Add methods to the interface env
 		for (method_name, sig, doc) in ast_iface.methods.iter() {
-			let mut method_type = self.resolve_type_annotation(&sig.to_type_annotation(), env);
+			let mut method_type = self.resolve_type_annotation(&sig.to_type_annotation(), &type_parameters_env);
 			// use the interface type as the function's "this" type
 			if let Type::Function(ref mut f) = *method_type {
 				f.this_type = Some(interface_type);
@@ -4458,6 +4825,27 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			};
 		}
 
+		// Add fields to the interface env
+		for field in ast_iface.fields.iter() {
+			let field_type = self.resolve_type_annotation(&field.member_type, &type_parameters_env);
+			if let Err(type_error) = interface_env.define(
+				&field.name,
+				SymbolKind::make_member_variable(
+					field.name.clone(),
+					field_type,
+					field.reassignable,
+					false,
+					field.phase,
+					AccessModifier::Public,
+					field.doc.as_ref().map(|s| Docs::with_summary(s)),
+				),
+				AccessModifier::Public,
+				StatementIdx::Top,
+			) {
+				self.type_error(type_error);
+			}
+		}
+
 		let extend_interfaces = &interface_type.as_interface().unwrap().extends;
 
 		// If this is a preflight interface and it doesn't extend any other preflight interfaces then implicitly make it extend
@@ -4494,6 +4882,8 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			let base_resource = self.types.resource_base_interface();
 			interface_type.as_interface_mut().unwrap().extends.push(base_resource);
 		}
+
+		self.update_hydrated_types(env, interface_type);
 	}
 
 	fn type_check_class(&mut self, stmt: &Stmt, ast_class: &AstClass, env: &mut SymbolEnv) {
@@ -4520,26 +4910,35 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				format!("Cannot declare a {} class in {} scope", ast_class.phase, env.phase),
 			);
 		}
+		// The types of a generic class's members (and the types it extends) are resolved in an environment with its type parameters
+		let type_parameters = self.make_type_parameters(&ast_class.type_parameters);
+		let mut type_parameters_env = self.make_type_parameters_env(&ast_class.type_parameters, &type_parameters, env);
+
 		// Verify parent is a known class and get their env
-		let (parent_class, parent_class_env) =
-			self.extract_parent_class(ast_class.parent.as_ref(), ast_class.phase, &ast_class.name, env);
+		let (parent_class, parent_class_env) = self.extract_parent_class(
+			ast_class.parent.as_ref(),
+			ast_class.phase,
+			&ast_class.name,
+			&mut type_parameters_env,
+		);
 
 		// Create environment representing this class, for now it'll be empty just so we can support referencing ourselves from the class definition.
-		let dummy_env = SymbolEnv::new(
+		let mut dummy_env = SymbolEnv::new(
 			None,
 			SymbolEnvKind::Type(self.types.void()),
 			env.phase,
 			stmt.idx,
 			self.source_file.package.clone(),
 		);
+		if !type_parameters.is_empty() {
+			dummy_env.type_parameters = Some(type_parameters.clone());
+		}
 
 		let impl_interfaces = ast_class
 			.implements
 			.iter()
 			.filter_map(|i| {
-				let t = self
-					.resolve_user_defined_type(i, env, stmt.idx)
-					.unwrap_or_else(|e| self.type_error(e));
+				let t = self.resolve_user_defined_type_annotation(i, &type_parameters_env, stmt.idx);
 				if t.as_interface().is_some() {
 					Some(t)
 				} else {
@@ -4599,10 +4998,13 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			stmt.idx,
 			self.source_file.package.clone(),
 		);
+		if !type_parameters.is_empty() {
+			class_env.type_parameters = Some(type_parameters);
+		}
 
 		// Add fields to the class env
 		for field in ast_class.fields.iter() {
-			let field_type = self.resolve_type_annotation(&field.member_type, env);
+			let field_type = self.resolve_type_annotation(&field.member_type, &type_parameters_env);
 			match class_env.define(
 				&field.name,
 				SymbolKind::make_member_variable(
@@ -4627,7 +5029,8 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		// Add methods to the class env
 		let mut method_types: BTreeMap<&Symbol, TypeRef> = BTreeMap::new();
 		for (method_name, method_def) in ast_class.methods.iter() {
-			let mut method_type = self.resolve_type_annotation(&method_def.signature.to_type_annotation(), env);
+			let mut method_type =
+				self.resolve_type_annotation(&method_def.signature.to_type_annotation(), &type_parameters_env);
 			self.add_method_to_class_env(
 				&mut method_type,
 				method_def,
@@ -4645,7 +5048,10 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			span: ast_class.initializer.span.clone(),
 		};
 
-		let mut init_func_type = self.resolve_type_annotation(&ast_class.initializer.signature.to_type_annotation(), env);
+		let mut init_func_type = self.resolve_type_annotation(
+			&ast_class.initializer.signature.to_type_annotation(),
+			&type_parameters_env,
+		);
 		self.add_method_to_class_env(
 			&mut init_func_type,
 			&ast_class.initializer,
@@ -4662,8 +5068,10 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		};
 
 		// Add the inflight initializer to the class env
-		let mut inflight_init_func_type =
-			self.resolve_type_annotation(&ast_class.inflight_initializer.signature.to_type_annotation(), env);
+		let mut inflight_init_func_type = self.resolve_type_annotation(
+			&ast_class.inflight_initializer.signature.to_type_annotation(),
+			&type_parameters_env,
+		);
 		self.add_method_to_class_env(
 			&mut inflight_init_func_type,
 			&ast_class.inflight_initializer,
@@ -4680,6 +5088,13 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		} else {
 			panic!("Expected class type");
 		}
+		self.update_hydrated_types(env, class_type);
+
+		for field in ast_class.fields.iter() {
+			if let Some(initial_value) = &field.initial_value {
+				self.type_check_static_field_value(field, initial_value, &type_parameters_env, stmt.idx);
+			}
+		}
 
 		if let FunctionBody::Statements(scope) = &ast_class.inflight_initializer.body {
 			self.check_class_field_initialization(&scope, &ast_class.fields, Phase::Inflight);
@@ -4690,7 +5105,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			class_type,
 			&init_symb,
 			&mut init_func_type,
-			env,
+			&type_parameters_env,
 			stmt.idx,
 			&ast_class.initializer,
 		);
@@ -4773,7 +5188,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			class_type,
 			&inflight_init_symb,
 			&mut inflight_init_func_type,
-			env,
+			&type_parameters_env,
 			stmt.idx,
 			&ast_class.inflight_initializer,
 		);
@@ -4784,7 +5199,14 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		// Type check methods
 		for (method_name, method_def) in ast_class.methods.iter() {
 			let mut method_type = *method_types.get(&method_name).unwrap();
-			self.type_check_method(class_type, method_name, &mut method_type, env, stmt.idx, method_def);
+			self.type_check_method(
+				class_type,
+				method_name,
+				&mut method_type,
+				&type_parameters_env,
+				stmt.idx,
+				method_def,
+			);
 		}
 
 		// Check that the class satisfies all of its interfaces
@@ -4829,6 +5251,67 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 					);
 				}
 			}
+
+			// Check all fields are implemented
+			for (field_name, interface_field) in interface_type.fields(true) {
+				let class_field = class_type
+					.as_class()
+					.unwrap()
+					.get_field(&field_name.as_str().into())
+					.filter(|v| matches!(v.kind, VariableKind::InstanceMember))
+					.cloned();
+				let Some(class_field) = class_field else {
+					self.spanned_error(
+						&ast_class.name,
+						format!(
+							"Class \"{}\" does not implement field \"{}\" of interface \"{}\"",
+							&ast_class.name, field_name, interface_type.name.name
+						),
+					);
+					continue;
+				};
+
+				self.validate_type(class_field.type_, interface_field.type_, &class_field.name);
+				if class_field.access != AccessModifier::Public {
+					self.spanned_error(
+						&class_field.name,
+						format!(
+							"Field \"{field_name}\" is {} in \"{}\" but it's an implementation of \"{interface_type}\". Interface members must be public.",
+							class_field.access, ast_class.name,
+						),
+					);
+				}
+				if class_field.phase != interface_field.phase {
+					self.spanned_error(
+						&class_field.name,
+						format!(
+							"Field \"{field_name}\" is {} in \"{}\" but {} in \"{interface_type}\"",
+							class_field.phase, ast_class.name, interface_field.phase
+						),
+					);
+				}
+				// Values of any type the interface accepts can be assigned to reassignable fields, so the types must match exactly
+				if interface_field.reassignable {
+					if !class_field.reassignable {
+						self.spanned_error_with_hints(
+							&class_field.name,
+							format!(
+								"Field \"{field_name}\" of \"{}\" must be reassignable to implement \"{interface_type}\"",
+								ast_class.name
+							),
+							&["Declare the field with \"var\""],
+						);
+					} else if !interface_field.type_.is_subtype_of(&class_field.type_) {
+						self.spanned_error(
+							&class_field.name,
+							format!(
+								"Field \"{field_name}\" is reassignable in \"{interface_type}\" so its type must be \"{}\", but got \"{}\" instead",
+								interface_field.type_, class_field.type_
+							),
+						);
+					}
+				}
+			}
 		}
 
 		// Check that if the class implements sim.IResource, then the
@@ -5017,6 +5500,8 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				| Type::Optional(_)
 				| Type::Set(_)
 				| Type::MutSet(_)
+				| Type::Promise(_)
+				| Type::Union(_)
 				| Type::Function(_)
 				| Type::Class(_)
 				| Type::Interface(_)
@@ -5090,6 +5575,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			&iflet.statements,
 			&iflet.reassignable,
 			&iflet.var_name,
+			&iflet.type_,
 			env,
 		);
 
@@ -5104,6 +5590,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 						&else_if_let_block.statements,
 						&else_if_let_block.reassignable,
 						&else_if_let_block.var_name,
+						&else_if_let_block.type_,
 						env,
 					);
 				}
@@ -5186,10 +5673,33 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		initial_value: &Expr,
 		var_name: &Symbol,
 		reassignable: &bool,
+		access: AccessModifier,
 		env: &mut SymbolEnv,
 	) {
 		let explicit_type = type_.as_ref().map(|t| self.resolve_type_annotation(t, env));
 		let (mut inferred_type, _) = self.type_check_exp(initial_value, env);
+
+		// Only the top-level variables of module files are exported, they're evaluated when the module is brought, outside
+		// of any construct scope
+		let is_module_variable = env.is_root() && !is_entrypoint_file(&self.source_file.path);
+		if access != AccessModifier::Private && !is_module_variable {
+			self.spanned_error(
+				var_name,
+				format!("Only top-level variables in module files can be {access}"),
+			);
+		}
+		if is_module_variable {
+			let mut requires_scope = RequiresScopeVisitor::new(self.types);
+			requires_scope.visit_expr(initial_value);
+			if let Some(span) = requires_scope.found {
+				self.spanned_error_with_hints(
+					&span,
+					"Top-level variables in module files cannot create preflight objects",
+					&["Create the object inside a class instead"],
+				);
+			}
+		}
+
 		if inferred_type.is_void() {
 			self.spanned_error(
 				var_name,
@@ -5213,7 +5723,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			match env.define(
 				var_name,
 				SymbolKind::make_free_variable(var_name.clone(), final_type, *reassignable, env.phase),
-				AccessModifier::Private,
+				access,
 				StatementIdx::Index(self.ctx.current_stmt_idx()),
 			) {
 				Err(type_error) => {
@@ -5231,7 +5741,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			match env.define(
 				var_name,
 				SymbolKind::make_free_variable(var_name.clone(), inferred_type, *reassignable, env.phase),
-				AccessModifier::Private,
+				access,
 				StatementIdx::Index(self.ctx.current_stmt_idx()),
 			) {
 				Err(type_error) => {
@@ -5248,32 +5758,19 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		statements: &Scope,
 		reassignable: &bool,
 		var_name: &Symbol,
+		type_: &Option<TypeAnnotation>,
 		env: &mut SymbolEnv,
 	) {
-		let (mut cond_type, _) = self.type_check_exp(value, env);
-
-		if let Type::Inferred(n) = *cond_type {
-			// If the type is inferred and unlinked, we must make sure that the type is also optional
-			// So let's make a new inference, but this time optional
-			if self.types.get_inference_by_id(n).is_none() {
-				let new_inference = self.types.make_inference();
-				cond_type = self.types.make_option(new_inference);
-				self.types.update_inferred_type(n, cond_type, &value.span);
-			}
-		}
+		let (cond_type, _) = self.type_check_exp(value, env);
 
-		if !cond_type.is_option() {
-			self.spanned_error(
-				value,
-				format!("Expected type to be optional, but got \"{}\" instead", cond_type),
-			)
-		}
-
-		// Technically we only allow if let statements to be used with optionals
-		// and above validate_type_is_optional method will attach a diagnostic error if it is not.
-		// However for the sake of verbose diagnostics we'll allow the code to continue if the type is not an optional
-		// and complete the type checking process for additional errors.
-		let var_type = *cond_type.maybe_unwrap_option();
+		let var_type = if let Some(type_) = type_ {
+			// `if let x: T = value` narrows the value to `T` (with a runtime type check) instead of unwrapping an optional
+			let narrowed_type = self.resolve_type_annotation(type_, env);
+			self.validate_narrowing(narrowed_type, cond_type, type_);
+			narrowed_type
+		} else {
+			self.unwrap_if_let_value(value, cond_type)
+		};
 
 		let mut stmt_env = self.types.add_symbol_env(SymbolEnv::new(
 			Some(env.get_ref()),
@@ -5300,21 +5797,328 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		self.inner_scopes.push((statements, self.ctx.clone()));
 	}
 
-	fn type_check_if_statement(&mut self, condition: &Expr, statements: &Scope, env: &mut SymbolEnv) {
-		let (cond_type, _) = self.type_check_exp(condition, env);
-		self.validate_type(cond_type, self.types.bool(), condition);
-
-		let if_scope_env = self.types.add_symbol_env(SymbolEnv::new(
-			Some(env.get_ref()),
-			SymbolEnvKind::Scope,
-			env.phase,
-			self.ctx.current_stmt_idx(),
-			self.source_file.package.clone(),
-		));
+	/// The type of the variable of an `if let` statement without a type annotation, which unwraps an optional value
+	fn unwrap_if_let_value(&mut self, value: &Expr, mut cond_type: TypeRef) -> TypeRef {
+		if let Type::Inferred(n) = *cond_type {
+			// If the type is inferred and unlinked, we must make sure that the type is also optional
+			// So let's make a new inference, but this time optional
+			if self.types.get_inference_by_id(n).is_none() {
+				let new_inference = self.types.make_inference();
+				cond_type = self.types.make_option(new_inference);
+				self.types.update_inferred_type(n, cond_type, &value.span);
+			}
+		}
+
+		if !cond_type.is_option() {
+			self.spanned_error(
+				value,
+				format!("Expected type to be optional, but got \"{}\" instead", cond_type),
+			)
+		}
+
+		// Technically we only allow if let statements to be used with optionals
+		// and above validate_type_is_optional method will attach a diagnostic error if it is not.
+		// However for the sake of verbose diagnostics we'll allow the code to continue if the type is not an optional
+		// and complete the type checking process for additional errors.
+		*cond_type.maybe_unwrap_option()
+	}
+
+	/// Validates that a value of type `value_type` can be narrowed to `narrowed_type` by checking its type at runtime.
+	/// Only `str`, `num`, `bool` and arrays can be told apart at runtime, and only if no other type the value may have
+	/// passes the same check (e.g. `Array<str>` can't be narrowed from `Array<str> | Array<num>`, and `str` can't be
+	/// narrowed from `str | SomeEnum` since enum values are strings).
+	fn validate_narrowing(&mut self, narrowed_type: TypeRef, value_type: TypeRef, type_annotation: &TypeAnnotation) {
+		if narrowed_type.is_unresolved() || value_type.is_unresolved() {
+			return;
+		}
+
+		let Some(kind) = narrowing_kind(&narrowed_type) else {
+			self.spanned_error(
+				type_annotation,
+				format!(
+					"Cannot narrow to \"{narrowed_type}\", only str, num, bool, Array and MutArray can be checked at runtime"
+				),
+			);
+			return;
+		};
+
+		let value_type = *value_type.maybe_unwrap_option();
+		if value_type.is_anything() {
+			return;
+		}
+		let members = match &*value_type {
+			Type::Union(members) => members.clone(),
+			_ => vec![value_type],
+		};
+		if !members.iter().any(|member| narrowed_type.is_subtype_of(member)) {
+			self.spanned_error(
+				type_annotation,
+				format!("\"{narrowed_type}\" is not one of the types of \"{value_type}\""),
+			);
+			return;
+		}
+
+		let ambiguous_member = members.iter().find(|member| {
+			!narrowed_type.is_same_type_as(member)
+				&& (narrowing_kind(member) == Some(kind) || member.is_json() || (kind == "string" && member.is_enum()))
+		});
+		if let Some(member) = ambiguous_member {
+			self.spanned_error(
+				type_annotation,
+				format!("Cannot narrow \"{value_type}\" to \"{narrowed_type}\", values of type \"{member}\" can't be told apart from it at runtime"),
+			);
+		}
+	}
+
+	fn type_check_if_statement(&mut self, condition: &Expr, statements: &Scope, env: &mut SymbolEnv) {
+		let (cond_type, _) = self.type_check_exp(condition, env);
+		self.validate_type(cond_type, self.types.bool(), condition);
+
+		let if_scope_env = self.types.add_symbol_env(SymbolEnv::new(
+			Some(env.get_ref()),
+			SymbolEnvKind::Scope,
+			env.phase,
+			self.ctx.current_stmt_idx(),
+			self.source_file.package.clone(),
+		));
 		self.types.set_scope_env(statements, if_scope_env);
 		self.inner_scopes.push((statements, self.ctx.clone()));
 	}
 
+	/// Type checks a `match` statement or expression. For a match expression, returns the type of its arms' values.
+	fn type_check_match(&mut self, match_: &Match, env: &mut SymbolEnv) -> (TypeRef, Phase) {
+		let (value_type, value_phase) = self.type_check_exp(&match_.value, env);
+
+		let mut result_type: Option<TypeRef> = None;
+		let mut result_phase = value_phase;
+		// Once an arm matched `nil`, optional values bound by the following arms are known to be set
+		let mut nil_matched = false;
+		for arm in &match_.arms {
+			if arm.patterns.len() > 1 && arm.patterns.iter().any(pattern_has_bindings) {
+				self.spanned_error(&arm.span, "Alternative patterns cannot bind variables");
+			}
+
+			let mut arm_env = self.types.add_symbol_env(SymbolEnv::new(
+				Some(env.get_ref()),
+				SymbolEnvKind::Scope,
+				env.phase,
+				self.ctx.current_stmt_idx(),
+				self.source_file.package.clone(),
+			));
+			for pattern in &arm.patterns {
+				self.type_check_match_pattern(pattern, value_type, nil_matched, &mut arm_env);
+			}
+			if arm.patterns.iter().any(is_nil_pattern) {
+				nil_matched = true;
+			}
+
+			match &arm.body {
+				MatchArmBody::Statements(scope) => {
+					self.types.set_scope_env(scope, arm_env);
+					self.inner_scopes.push((scope, self.ctx.clone()));
+				}
+				MatchArmBody::Expression(arm_value) => {
+					let (arm_type, arm_phase) = self.type_check_exp(arm_value, &mut arm_env);
+					result_phase = combine_phases(result_phase, arm_phase);
+					if let Some(result_type) = result_type {
+						self.validate_type(arm_type, result_type, arm_value);
+					} else if !arm_type.is_unresolved() {
+						result_type = Some(arm_type);
+					}
+				}
+			}
+		}
+
+		let patterns = match_
+			.arms
+			.iter()
+			.flat_map(|arm| arm.patterns.iter())
+			.collect::<Vec<_>>();
+		let missing = self.missing_match_patterns(value_type, &patterns);
+		if !missing.is_empty() {
+			self.spanned_error_with_hints(
+				&*match_.value,
+				format!("Match is not exhaustive, values not covered: {}", missing.join(", ")),
+				&["Add arms for the missing values, or a \"_\" arm to match any value"],
+			);
+		}
+
+		(result_type.unwrap_or(self.types.void()), result_phase)
+	}
+
+	/// Type checks a pattern of a match arm against the type of the matched value and defines its bindings in `env`.
+	/// If `nil_matched` is set, a previous arm matched `nil` so a binding of an optional value has the unwrapped type.
+	fn type_check_match_pattern(
+		&mut self,
+		pattern: &MatchPattern,
+		value_type: TypeRef,
+		nil_matched: bool,
+		env: &mut SymbolEnv,
+	) {
+		match &pattern.kind {
+			MatchPatternKind::Wildcard => {}
+			MatchPatternKind::Binding(name) => {
+				let var_type = if nil_matched {
+					*value_type.maybe_unwrap_option()
+				} else {
+					value_type
+				};
+				if let Err(type_error) = env.define(
+					name,
+					SymbolKind::make_free_variable(name.clone(), var_type, false, env.phase),
+					AccessModifier::Private,
+					StatementIdx::Top,
+				) {
+					self.type_error(type_error);
+				}
+			}
+			MatchPatternKind::Value(value) => {
+				let (pattern_type, _) = self.type_check_exp(value, env);
+				if pattern_type.is_nil() {
+					if !value_type.is_option() && !value_type.is_unresolved() {
+						self.spanned_error(
+							pattern,
+							format!("Cannot match nil against non-optional type \"{value_type}\""),
+						);
+					}
+					return;
+				}
+				if !matches!(
+					*pattern_type,
+					Type::Number | Type::String | Type::Boolean | Type::Enum(_) | Type::Unresolved
+				) {
+					self.spanned_error(pattern, "Expected a literal, nil or an enum variant as a pattern");
+					return;
+				}
+				// Values other than nil only match the non-nil values of an optional
+				self.validate_type(pattern_type, *value_type.maybe_unwrap_option(), value);
+			}
+			MatchPatternKind::Struct(fields) => {
+				let struct_type = *value_type.maybe_unwrap_option();
+				if struct_type.is_unresolved() {
+					return;
+				}
+				let Some(st) = struct_type.as_struct() else {
+					self.spanned_error(
+						pattern,
+						format!("Expected a struct to destructure, but got \"{value_type}\" instead"),
+					);
+					return;
+				};
+				for (field_name, field_pattern) in fields {
+					let Some(field_type) = st
+						.env
+						.lookup(field_name, None)
+						.and_then(|kind| kind.as_variable())
+						.map(|var| var.type_)
+					else {
+						self.spanned_error(
+							field_name,
+							format!("Struct \"{struct_type}\" does not have a field named \"{field_name}\""),
+						);
+						continue;
+					};
+					self.type_check_match_pattern(field_pattern, field_type, false, env);
+				}
+			}
+		}
+	}
+
+	/// Returns the values of `type_` that none of the patterns match, for reporting non-exhaustive matches.
+	/// Values that can't be listed (like numbers) are described as "_".
+	fn missing_match_patterns(&self, type_: TypeRef, patterns: &[&MatchPattern]) -> Vec<String> {
+		if type_.is_unresolved()
+			|| patterns
+				.iter()
+				.any(|p| matches!(p.kind, MatchPatternKind::Wildcard | MatchPatternKind::Binding(_)))
+		{
+			return vec![];
+		}
+
+		match &*type_ {
+			Type::Optional(inner) => {
+				let mut missing = vec![];
+				if !patterns.iter().copied().any(is_nil_pattern) {
+					missing.push("nil".to_string());
+				}
+				let non_nil_patterns = patterns
+					.iter()
+					.copied()
+					.filter(|p| !is_nil_pattern(p))
+					.collect::<Vec<_>>();
+				missing.extend(self.missing_match_patterns(*inner, &non_nil_patterns));
+				missing
+			}
+			Type::Enum(enu) => enu
+				.values
+				.keys()
+				.filter(|variant| {
+					!patterns
+						.iter()
+						.any(|p| enum_variant_of_pattern(p).is_some_and(|v| v.name == variant.name))
+				})
+				.map(|variant| format!("{}.{}", enu.name, variant.name))
+				.collect(),
+			Type::Boolean => [true, false]
+				.into_iter()
+				.filter(|b| {
+					!patterns.iter().any(|p| match &p.kind {
+						MatchPatternKind::Value(Expr {
+							kind: ExprKind::Literal(Literal::Boolean(v)),
+							..
+						}) => v == b,
+						_ => false,
+					})
+				})
+				.map(|b| b.to_string())
+				.collect(),
+			Type::Struct(st) => {
+				let struct_patterns = patterns
+					.iter()
+					.filter_map(|p| match &p.kind {
+						MatchPatternKind::Struct(fields) => Some(fields),
+						_ => None,
+					})
+					.collect::<Vec<_>>();
+				let field_type = |name: &Symbol| {
+					st.env
+						.lookup(name, None)
+						.and_then(|kind| kind.as_variable())
+						.map(|var| var.type_)
+						.unwrap_or(self.types.error())
+				};
+
+				// The struct patterns cover all values if they all restrict the same (single) field, and the patterns
+				// of that field cover all of its values
+				let covered = st.fields(true).any(|(name, field)| {
+					let mut field_patterns = vec![];
+					for fields in &struct_patterns {
+						for (field_name, field_pattern) in fields.iter() {
+							if field_name.name == name {
+								field_patterns.push(field_pattern);
+							} else if !self
+								.missing_match_patterns(field_type(field_name), &[field_pattern])
+								.is_empty()
+							{
+								return false;
+							}
+						}
+						if !fields.iter().any(|(field_name, _)| field_name.name == name) {
+							return true;
+						}
+					}
+					!struct_patterns.is_empty() && self.missing_match_patterns(field.type_, &field_patterns).is_empty()
+				});
+				if covered {
+					vec![]
+				} else {
+					vec!["_".to_string()]
+				}
+			}
+			_ => vec!["_".to_string()],
+		}
+	}
+
 	fn type_check_super_constructor_against_parent_initializer(
 		&mut self,
 		super_constructor_call: &Stmt,
@@ -5400,6 +6204,46 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 	/// * `fields` - All fields of a class
 	/// * `phase` - initializer phase
 	///
+	/// Static fields are initialized when their class is defined (the inflight client class for inflight fields),
+	/// there's no `this` and no construct scope available at that point
+	fn type_check_static_field_value(
+		&mut self,
+		field: &ClassField,
+		initial_value: &Expr,
+		class_parent_env: &SymbolEnv,
+		stmt_idx: usize,
+	) {
+		let field_type = self.resolve_type_annotation(&field.member_type, class_parent_env);
+		let mut field_env = self.types.add_symbol_env(SymbolEnv::new(
+			Some(class_parent_env.get_ref()),
+			SymbolEnvKind::Scope,
+			field.phase,
+			stmt_idx,
+			self.source_file.package.clone(),
+		));
+		let (value_type, _) = self.type_check_exp(initial_value, &mut field_env);
+		self.validate_type(value_type, field_type, initial_value);
+
+		if field.phase == Phase::Preflight {
+			let mut requires_scope = RequiresScopeVisitor::new(&self.types);
+			requires_scope.visit_expr(initial_value);
+			if let Some(span) = requires_scope.found {
+				self.spanned_error_with_hints(
+					&span,
+					"Static fields cannot create preflight objects",
+					&["Create the object in the constructor and store it in an instance field instead"],
+				);
+			}
+		} else if class_parent_env.phase == Phase::Preflight {
+			// Inflight clients of classes defined preflight only receive the values lifted by their methods
+			let mut preflight_expr = PreflightExprVisitor::new(&self.types);
+			preflight_expr.visit_expr(initial_value);
+			if let Some(span) = preflight_expr.found {
+				self.spanned_error(&span, "Inflight static fields cannot reference preflight values");
+			}
+		}
+	}
+
 	fn check_class_field_initialization(&mut self, scope: &Scope, fields: &[ClassField], phase: Phase) {
 		// Traverse the AST of the constructor (preflight or inflight) to find all initialized fields
 		// that were initialized during its execution.
@@ -5487,6 +6331,11 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				)
 				.expect("Expected `this` to be added to constructor env");
 		}
+		self.add_type_parameters_to_env(
+			&method_def.signature.type_parameters,
+			&method_sig.type_parameters,
+			&mut method_env,
+		);
 		self.add_arguments_to_env(&method_def.signature.parameters, method_sig, &mut method_env);
 
 		self.with_function_def(
@@ -5707,10 +6556,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 					Err(type_error) => {
 						self.spanned_error(
 							&stmt.map(|s| s.span.clone()).unwrap_or_default(),
-							format!(
-								"Cannot find jsii module \"{}\" in source directory: {}",
-								library_name, type_error
-							),
+							format!("Cannot load jsii module \"{}\": {}", library_name, type_error),
 						);
 						return;
 					}
@@ -5718,6 +6564,10 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				assembly_name
 			};
 
+			for warning in self.jsii_types.take_warnings() {
+				self.spanned_warning(&stmt.map(|s| s.span.clone()).unwrap_or_default(), warning);
+			}
+
 			debug!("Loaded JSII assembly {}", assembly_name);
 
 			self.jsii_imports.push(JsiiImportSpec {
@@ -5736,6 +6586,9 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		// check if we've already defined the given alias in the current scope
 		if env.lookup(&jsii.alias.name.as_str().into(), None).is_some() {
 			self.spanned_error(alias, format!("\"{}\" is already defined", alias.name));
+		} else if let Some(typings) = self.jsii_types.find_typings(&jsii.assembly_name) {
+			// npm packages without a JSII assembly are imported from their TypeScript declarations
+			DtsImporter::new(typings, self.types).import_to_env(&jsii.alias, env);
 		} else {
 			let mut importer = JsiiImporter::new(&jsii, self.types, self.jsii_types);
 
@@ -5815,10 +6668,9 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			return self.types.error();
 		}
 
-		// map from original_type_params to type_params
-		let mut types_map = HashMap::new();
-		for (o, n) in original_type_params.iter().zip(type_params.iter()) {
-			types_map.insert(format!("{o}"), (*o, *n));
+		// Hydrating the same generic type with the same type arguments always results in the same type
+		if let Some(hydrated_type) = self.types.get_hydrated_type(original_type, &type_params) {
+			return hydrated_type;
 		}
 
 		let dummy_env = SymbolEnv::new(
@@ -5861,8 +6713,37 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			_ => panic!("Expected type to be a class, interface, or struct"),
 		};
 
-		// TODO: here we add a new type regardless whether we already "hydrated" `original_type` with these `type_params`. Cache!
-		let mut new_type = self.types.add_type(tt);
+		let new_type = self.types.add_type(tt);
+		// Cache the new type before adding its members, so members referring to the hydrated type itself resolve to it
+		self
+			.types
+			.add_hydrated_type(original_type, type_params.clone(), new_type);
+		self.hydrate_members(env, original_type, new_type, type_params);
+
+		return new_type;
+	}
+
+	/// Fills the environment of a hydrated type with the members of its generic type, replacing the generic's type
+	/// parameters with the given type arguments.
+	fn hydrate_members(
+		&mut self,
+		env: &SymbolEnv,
+		original_type: TypeRef,
+		mut new_type: TypeRef,
+		type_params: Vec<TypeRef>,
+	) {
+		let original_type_env = original_type.as_env().expect("generic type to have an environment");
+		let original_type_params = original_type_env
+			.type_parameters
+			.clone()
+			.expect("generic type to have type parameters");
+
+		// map from original_type_params to type_params
+		let mut types_map = HashMap::new();
+		for (o, n) in original_type_params.iter().zip(type_params.iter()) {
+			types_map.insert(format!("{o}"), (*o, *n));
+		}
+
 		let new_env = SymbolEnv::new_with_type_params(
 			None,
 			SymbolEnvKind::Type(new_type),
@@ -5872,16 +6753,38 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			type_params,
 		);
 
-		// Update the types's env to point to the new env
+		// Update the types's env to point to the new env, and replace type parameters in the types it extends
+		// (types imported from JSII are kept as they are)
+		let (parent, extends, fqn) = match &*original_type {
+			Type::Class(c) => (c.parent, c.implements.clone(), &c.fqn),
+			Type::Interface(iface) => (None, iface.extends.clone(), &iface.fqn),
+			Type::Struct(s) => (None, s.extends.clone(), &s.fqn),
+			_ => (None, vec![], &None),
+		};
+		let (parent, extends) = if fqn.is_some() {
+			(parent, extends)
+		} else {
+			(
+				parent.map(|p| self.get_concrete_type_for_generic(env, p, &types_map)),
+				extends
+					.into_iter()
+					.map(|t| self.get_concrete_type_for_generic(env, t, &types_map))
+					.collect::<Vec<_>>(),
+			)
+		};
 		match *new_type {
 			Type::Class(ref mut class) => {
 				class.env = new_env;
+				class.parent = parent;
+				class.implements = extends;
 			}
 			Type::Interface(ref mut iface) => {
 				iface.env = new_env;
+				iface.extends = extends;
 			}
 			Type::Struct(ref mut s) => {
 				s.env = new_env;
+				s.extends = extends;
 			}
 			_ => {}
 		}
@@ -5924,8 +6827,242 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				}
 			}
 		}
+	}
 
-		return new_type;
+	/// Generic types can be hydrated while they are still being declared (e.g. a method of `Box<T>` returning a
+	/// `Box<str>`), before their members are known. Once the generic's real environment is in place, this fills in
+	/// the members of all the types hydrated from it so far.
+	fn update_hydrated_types(&mut self, env: &SymbolEnv, generic_type: TypeRef) {
+		let hydrated_types = self
+			.types
+			.hydrated_types
+			.get(&(&*generic_type as *const Type))
+			.cloned()
+			.unwrap_or_default();
+		for (type_arguments, hydrated_type) in hydrated_types {
+			self.hydrate_members(env, generic_type, hydrated_type, type_arguments);
+		}
+	}
+
+	/// Creates the types of the type parameters of a generic declaration. Each type parameter is an opaque class,
+	/// similar to the `std.T1` placeholder used by the generic types of the standard library.
+	fn make_type_parameters(&mut self, names: &[Symbol]) -> Vec<TypeRef> {
+		names
+			.iter()
+			.map(|name| {
+				let mut type_parameter = self.types.add_type(Type::Class(Class {
+					name: name.clone(),
+					env: SymbolEnv::new(
+						None,
+						SymbolEnvKind::Type(self.types.void()),
+						Phase::Independent,
+						0,
+						self.source_file.package.clone(),
+					),
+					fqn: None,
+					parent: None,
+					implements: vec![],
+					// Type parameters can't be instantiated
+					is_abstract: true,
+					phase: Phase::Independent,
+					defined_in_phase: Phase::Independent,
+					docs: Docs::default(),
+					std_construct_args: false,
+					lifts: None,
+					uid: 0,
+				}));
+				type_parameter.as_class_mut().unwrap().env = SymbolEnv::new(
+					None,
+					SymbolEnvKind::Type(type_parameter),
+					Phase::Independent,
+					0,
+					self.source_file.package.clone(),
+				);
+				self.types.type_parameters.insert(&*type_parameter as *const Type);
+				type_parameter
+			})
+			.collect()
+	}
+
+	/// Returns an environment in which the given type parameters are defined, for resolving the types used by a
+	/// generic declaration. If there are no type parameters, this is the given environment.
+	fn make_type_parameters_env(&mut self, names: &[Symbol], types: &[TypeRef], env: &SymbolEnv) -> SymbolEnvRef {
+		if names.is_empty() {
+			return env.get_ref();
+		}
+
+		let mut type_parameters_env = self.types.add_symbol_env(SymbolEnv::new(
+			Some(env.get_ref()),
+			SymbolEnvKind::Scope,
+			env.phase,
+			self.ctx.current_stmt_idx(),
+			self.source_file.package.clone(),
+		));
+		self.add_type_parameters_to_env(names, types, &mut type_parameters_env);
+		type_parameters_env
+	}
+
+	fn add_type_parameters_to_env(&mut self, names: &[Symbol], types: &[TypeRef], env: &mut SymbolEnv) {
+		for (name, type_parameter) in names.iter().zip(types.iter()) {
+			if let Err(type_error) = env.define(
+				name,
+				SymbolKind::Type(*type_parameter),
+				AccessModifier::Private,
+				StatementIdx::Top,
+			) {
+				self.type_error(type_error);
+			}
+		}
+	}
+
+	/// Binds the type parameters of a generic function (or class) to the types of the arguments passed to it.
+	/// `param_type` is the declared type of a parameter and `arg_type` the type of the argument passed for it.
+	/// Each type parameter is bound to the first argument type found in its position.
+	fn infer_type_arguments(&self, param_type: TypeRef, arg_type: TypeRef, bindings: &mut [(TypeRef, Option<TypeRef>)]) {
+		if arg_type.is_unresolved() || arg_type.is_inferred() || arg_type.is_nil() {
+			return;
+		}
+
+		if let Some((_, binding)) = bindings.iter_mut().find(|(p, _)| std::ptr::eq(&**p, &*param_type)) {
+			if binding.is_none() {
+				*binding = Some(arg_type);
+			}
+			return;
+		}
+
+		match (&*param_type, &*arg_type) {
+			(Type::Optional(p), Type::Optional(a))
+			| (Type::Array(p), Type::Array(a))
+			| (Type::MutArray(p), Type::MutArray(a))
+			| (Type::Map(p), Type::Map(a))
+			| (Type::MutMap(p), Type::MutMap(a))
+			| (Type::Set(p), Type::Set(a))
+			| (Type::MutSet(p), Type::MutSet(a))
+			| (Type::Promise(p), Type::Promise(a)) => self.infer_type_arguments(*p, *a, bindings),
+			(Type::Optional(p), _) => self.infer_type_arguments(*p, arg_type, bindings),
+			(Type::Function(param_sig), _) => {
+				let Some(arg_sig) = arg_type.as_deep_function_sig() else {
+					return;
+				};
+				for (p, a) in param_sig.parameters.iter().zip(arg_sig.parameters.iter()) {
+					self.infer_type_arguments(p.typeref, a.typeref, bindings);
+				}
+				self.infer_type_arguments(param_sig.return_type, arg_sig.return_type, bindings);
+			}
+			(Type::Class(_), Type::Class(_))
+			| (Type::Interface(_), Type::Interface(_))
+			| (Type::Struct(_), Type::Struct(_)) => {
+				// Both are hydrations of the same generic type (e.g. `Box<T>` and `Box<num>`), bind their type arguments
+				let param_origin = self.types.get_generic_origin(param_type).unwrap_or(param_type);
+				let Some(arg_origin) = self.types.get_generic_origin(arg_type) else {
+					return;
+				};
+				if !std::ptr::eq(&*param_origin, &*arg_origin) {
+					return;
+				}
+				let (Some(param_args), Some(arg_args)) = (
+					param_type.as_env().and_then(|e| e.type_parameters.clone()),
+					arg_type.as_env().and_then(|e| e.type_parameters.clone()),
+				) else {
+					return;
+				};
+				for (p, a) in param_args.into_iter().zip(arg_args.into_iter()) {
+					self.infer_type_arguments(p, a, bindings);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Infers the type arguments of a call to a generic function from the types of the arguments and returns the
+	/// function signature with its type parameters replaced by them. Type parameters that can't be inferred are
+	/// reported as errors.
+	fn instantiate_generic_function_sig(
+		&mut self,
+		func_sig: &FunctionSignature,
+		arg_list_types: &ArgListTypes,
+		env: &SymbolEnv,
+		call_span: &impl Spanned,
+	) -> FunctionSignature {
+		let mut bindings = func_sig
+			.type_parameters
+			.iter()
+			.map(|p| (*p, None))
+			.collect::<Vec<(TypeRef, Option<TypeRef>)>>();
+
+		for (param, arg_type) in func_sig.parameters.iter().zip(arg_list_types.pos_args.iter()) {
+			if param.variadic {
+				break;
+			}
+			self.infer_type_arguments(param.typeref, *arg_type, &mut bindings);
+		}
+		if let Some(variadic_param) = func_sig.parameters.iter().find(|p| p.variadic) {
+			let variadic_index = func_sig.parameters.len() - 1;
+			if let Some(item_type) = variadic_param.typeref.collection_item_type() {
+				for arg_type in arg_list_types.pos_args.iter().skip(variadic_index) {
+					self.infer_type_arguments(item_type, *arg_type, &mut bindings);
+				}
+			}
+		}
+
+		let mut types_map = HashMap::new();
+		for (type_parameter, binding) in bindings {
+			let type_argument = binding.unwrap_or_else(|| {
+				self.spanned_error(
+					call_span,
+					format!(
+						"Unable to infer type argument \"{}\" of \"{}\", add type annotations to the arguments",
+						type_parameter, func_sig
+					),
+				);
+				self.types.error()
+			});
+			types_map.insert(format!("{type_parameter}"), (type_parameter, type_argument));
+		}
+
+		let generic_sig = self.types.add_type(Type::Function(FunctionSignature {
+			type_parameters: vec![],
+			..func_sig.clone()
+		}));
+		let mut sig = self
+			.get_concrete_type_for_generic(env, generic_sig, &types_map)
+			.as_function_sig()
+			.expect("a function signature")
+			.clone();
+		sig.phase = func_sig.phase;
+		sig
+	}
+
+	/// Infers the type arguments of a generic class from the arguments passed to its constructor and returns the
+	/// class hydrated with them.
+	fn infer_class_type_arguments(
+		&mut self,
+		class_type: TypeRef,
+		arg_list_types: &ArgListTypes,
+		env: &SymbolEnv,
+		new_span: &impl Spanned,
+	) -> TypeRef {
+		let class = class_type.as_class().expect("a class");
+		let init_method_name = if env.phase == Phase::Preflight || class.env.phase == Phase::Independent {
+			CLASS_INIT_NAME
+		} else {
+			CLASS_INFLIGHT_INIT_NAME
+		};
+		let Some(constructor_sig) = class
+			.env
+			.lookup(&init_method_name.into(), None)
+			.and_then(|k| k.as_variable())
+			.and_then(|v| v.type_.as_function_sig().cloned())
+		else {
+			return class_type;
+		};
+
+		let generic_sig = FunctionSignature {
+			type_parameters: class.env.type_parameters.clone().unwrap_or_default(),
+			..constructor_sig
+		};
+		let instantiated_sig = self.instantiate_generic_function_sig(&generic_sig, arg_list_types, env, new_span);
+		instantiated_sig.return_type
 	}
 
 	fn get_concrete_type_for_generic(
@@ -5966,6 +7103,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 
 				let new_sig = FunctionSignature {
 					this_type: new_this_type,
+					type_parameters: sig.type_parameters.clone(),
 					parameters: new_params,
 					return_type: new_return_type,
 					phase: if new_this_type.is_none() { env.phase } else { sig.phase },
@@ -5979,14 +7117,23 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			}
 
 			if let Some(inner_env) = type_to_maybe_replace.as_env() {
-				if let Some(type_parameters) = &inner_env.type_parameters {
-					// For now all our generics only have a single type parameter so use the first type parameter as our "T1"
-					let t1 = type_parameters[0];
-					let t1_replacement = *types_map
-						.get(&format!("{t1}"))
-						.filter(|(o, _)| t1.is_same_type_as(o))
-						.map(|(_, n)| n)
-						.expect("generic must have a type parameter");
+				if let Some(type_parameters) = inner_env.type_parameters.clone() {
+					let type_arguments = type_parameters
+						.iter()
+						.map(|t| self.get_concrete_type_for_generic(env, *t, types_map))
+						.collect::<Vec<_>>();
+
+					// None of the type arguments changed, so there's nothing to hydrate
+					if type_arguments
+						.iter()
+						.zip(type_parameters.iter())
+						.all(|(a, p)| std::ptr::eq(&**a, &**p))
+					{
+						return type_to_maybe_replace;
+					}
+
+					// The generic types of the standard library only have a single type parameter, "T1"
+					let t1_replacement = type_arguments[0];
 
 					let fqn = match &*type_to_maybe_replace {
 						Type::Class(c) => c.fqn.as_ref(),
@@ -6006,10 +7153,16 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 							WINGSDK_MUT_MAP => self.types.add_type(Type::MutMap(t1_replacement)),
 							WINGSDK_SET => self.types.add_type(Type::Set(t1_replacement)),
 							WINGSDK_MUT_SET => self.types.add_type(Type::MutSet(t1_replacement)),
-							_ => self.hydrate_class_type_arguments(env, type_to_maybe_replace, vec![t1_replacement]),
+							_ => self.hydrate_class_type_arguments(env, type_to_maybe_replace, type_arguments),
 						}
 					} else {
-						self.hydrate_class_type_arguments(env, type_to_maybe_replace, vec![t1_replacement])
+						// Hydrate user defined generics from the generic type itself, even if this type is already a hydration
+						// of it (e.g. `Box<T>` inside of another generic)
+						let generic_type = self
+							.types
+							.get_generic_origin(type_to_maybe_replace)
+							.unwrap_or(type_to_maybe_replace);
+						self.hydrate_class_type_arguments(env, generic_type, type_arguments)
 					};
 				}
 			} else {
@@ -6030,6 +7183,17 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 						let new_inner = self.get_concrete_type_for_generic(env, *inner_t, types_map);
 						return self.types.add_type(Type::MutSet(new_inner));
 					}
+					Type::Promise(inner_t) => {
+						let new_inner = self.get_concrete_type_for_generic(env, *inner_t, types_map);
+						return self.types.add_type(Type::Promise(new_inner));
+					}
+					Type::Union(members) => {
+						let new_members = members
+							.iter()
+							.map(|member| self.get_concrete_type_for_generic(env, *member, types_map))
+							.collect();
+						return self.types.make_union(new_members);
+					}
 					Type::Map(inner_t) => {
 						let new_inner = self.get_concrete_type_for_generic(env, *inner_t, types_map);
 						return self.types.add_type(Type::Map(new_inner));
@@ -6133,6 +7297,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		Some(UserDefinedType {
 			root,
 			fields: path,
+			type_arguments: vec![],
 			span: WingSpan {
 				start,
 				end,
@@ -6183,6 +7348,41 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			.map(|_| base_udt)
 	}
 
+	/// Resolves `module.name` where `module` is a brought Wing module (or a namespace within it) and `name` is a
+	/// variable declared at its top level. Returns `None` if the reference doesn't point into a module.
+	fn resolve_module_variable(&mut self, object: &Expr, property: &Symbol, env: &SymbolEnv) -> Option<VariableInfo> {
+		let ExprKind::Reference(object_reference) = &object.kind else {
+			return None;
+		};
+		let object_udt = self.reference_to_udt(object_reference)?;
+		let mut path = object_udt.full_path();
+		path.push(property.clone());
+
+		// Module variables are declared in another file, so their statement index isn't comparable to ours
+		let variable = match env.lookup_nested(&path.iter().collect::<Vec<_>>(), None) {
+			LookupResult::Found(SymbolKind::Variable(variable), _) => variable.clone(),
+			lookup_result @ LookupResult::NotPublic(SymbolKind::Variable(_), _) => {
+				self.type_error(lookup_result_to_type_error(lookup_result, property));
+				self.make_error_variable_info()
+			}
+			_ => return None,
+		};
+
+		// The module isn't a value, but later passes expect every expression to have a type
+		let mut module_expr = object;
+		loop {
+			self
+				.types
+				.assign_type_to_expr(module_expr, self.types.anything(), Phase::Independent);
+			match &module_expr.kind {
+				ExprKind::Reference(Reference::InstanceMember { object, .. }) => module_expr = object,
+				_ => break,
+			}
+		}
+
+		Some(variable)
+	}
+
 	fn make_immutable(&mut self, type_: TypeRef) -> TypeRef {
 		match *type_ {
 			Type::MutArray(inner) => {
@@ -6241,6 +7441,12 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 				property,
 				optional_accessor,
 			} => {
+				// References to variables exported from a brought module (`module.CONSTANT`)
+				if let Some(module_variable) = self.resolve_module_variable(object, property, env) {
+					let phase = module_variable.phase;
+					return (ResolveReferenceResult::Variable(module_variable), phase);
+				}
+
 				// There's a special case where the object is actually a type and the property is either a static member or an enum variant.
 				// In this case the type might even be namespaced (recursive nested reference). We need to detect this and transform this
 				// reference into a type reference.
@@ -6476,6 +7682,8 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 					| Type::Optional(_)
 					| Type::Set(_)
 					| Type::MutSet(_)
+					| Type::Promise(_)
+					| Type::Union(_)
 					| Type::Function(_)
 					| Type::Class(_)
 					| Type::Interface(_)
@@ -6709,14 +7917,119 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 		statement_idx: usize,
 	) -> Result<TypeRef, TypeError> {
 		// Attempt to resolve the type from the current environment
-		let res = resolve_user_defined_type(user_defined_type, env, statement_idx);
-		if res.is_ok() {
+		let mut res = resolve_user_defined_type(user_defined_type, env, statement_idx);
+		if res.is_err() {
+			// If the type is not found, attempt to import it from a jsii library
+			import_udt_from_jsii(self.types, self.jsii_types, user_defined_type, &self.jsii_imports);
+			res = resolve_user_defined_type(user_defined_type, env, statement_idx);
+		}
+
+		if user_defined_type.type_arguments.is_empty() {
 			return res;
 		}
+		res.map(|generic_type| self.apply_type_arguments(user_defined_type, generic_type, env))
+	}
+
+	/// Resolves a user defined type used as a type annotation (or as a parent or implemented type). Unlike in `new`
+	/// expressions, where they can be inferred, the type arguments of a generic type must be specified in annotations.
+	fn resolve_user_defined_type_annotation(
+		&mut self,
+		user_defined_type: &UserDefinedType,
+		env: &SymbolEnv,
+		statement_idx: usize,
+	) -> TypeRef {
+		let type_ = self
+			.resolve_user_defined_type(user_defined_type, env, statement_idx)
+			.unwrap_or_else(|e| self.type_error(e));
+
+		if user_defined_type.type_arguments.is_empty() && self.is_unapplied_generic(type_, env) {
+			let type_parameters = type_.as_env().unwrap().type_parameters.as_ref().unwrap();
+			self.spanned_error(
+				user_defined_type,
+				format!(
+					"Missing type arguments for generic type \"{}\", expected {}",
+					user_defined_type,
+					type_parameters.len()
+				),
+			);
+			return self.types.error();
+		}
 
-		// If the type is not found, attempt to import it from a jsii library
-		import_udt_from_jsii(self.types, self.jsii_types, user_defined_type, &self.jsii_imports);
-		resolve_user_defined_type(user_defined_type, env, statement_idx)
+		type_
+	}
+
+	/// Hydrates a generic type with the type arguments given in a user defined type (e.g. `Box<num>`)
+	fn apply_type_arguments(
+		&mut self,
+		user_defined_type: &UserDefinedType,
+		generic_type: TypeRef,
+		env: &SymbolEnv,
+	) -> TypeRef {
+		if generic_type.is_unresolved() {
+			return generic_type;
+		}
+
+		let Some(type_parameters) = generic_type.as_env().and_then(|e| e.type_parameters.clone()) else {
+			self.spanned_error(
+				user_defined_type,
+				format!("Type \"{}\" is not generic", user_defined_type.full_path_str()),
+			);
+			return self.types.error();
+		};
+
+		if type_parameters.len() != user_defined_type.type_arguments.len() {
+			self.spanned_error(
+				user_defined_type,
+				format!(
+					"Type \"{}\" has {} type parameters, but {} were provided",
+					user_defined_type.full_path_str(),
+					type_parameters.len(),
+					user_defined_type.type_arguments.len()
+				),
+			);
+			return self.types.error();
+		}
+
+		let type_arguments = user_defined_type
+			.type_arguments
+			.iter()
+			.map(|t| self.resolve_type_annotation(t, env))
+			.collect::<Vec<_>>();
+
+		let types_map = type_parameters
+			.iter()
+			.zip(type_arguments.iter())
+			.map(|(p, a)| (format!("{p}"), (*p, *a)))
+			.collect::<HashMap<_, _>>();
+		self.get_concrete_type_for_generic(env, generic_type, &types_map)
+	}
+
+	/// Whether `type_` is a user defined generic type (not hydrated with type arguments) used outside of its own
+	/// declaration. Inside the declaration the type parameters are in scope, so the raw type refers to itself.
+	fn is_unapplied_generic(&self, type_: TypeRef, env: &SymbolEnv) -> bool {
+		let Some(type_env) = type_.as_env() else {
+			return false;
+		};
+		let fqn = match &*type_ {
+			Type::Class(c) => &c.fqn,
+			Type::Interface(i) => &i.fqn,
+			Type::Struct(s) => &s.fqn,
+			_ => return false,
+		};
+		let Some(type_parameters) = &type_env.type_parameters else {
+			return false;
+		};
+		if fqn.is_some() || self.types.get_generic_origin(type_).is_some() {
+			return false;
+		}
+
+		!type_parameters.iter().all(|p| {
+			let name = &p.as_class().expect("type parameters are classes").name;
+			env
+				.lookup(name, None)
+				.and_then(|k| k.as_type())
+				.is_some_and(|t| std::ptr::eq(&*t, &**p))
+		})
 	}
 
 	fn extract_parent_class(
@@ -6737,12 +8050,7 @@ It should primarily be used in preflight or in inflights that are guaranteed to
 			}
 		};
 
-		let parent_type = self
-			.resolve_user_defined_type(parent, env, self.ctx.current_stmt_idx())
-			.unwrap_or_else(|e| {
-				self.type_error(e);
-				self.types.error()
-			});
+		let parent_type = self.resolve_user_defined_type_annotation(parent, env, self.ctx.current_stmt_idx());
 
 		// bail out if we could not resolve the parent type
 		if parent_type.is_unresolved() {
@@ -6847,6 +8155,120 @@ impl VisitorWithContext for TypeChecker<'_> {
 	}
 }
 
+/// How values of a type are told apart from other values at runtime when narrowing them with `if let x: T = value`,
+/// `None` if they can't be. Types of the same kind pass the same runtime check.
+fn narrowing_kind(t: &Type) -> Option<&'static str> {
+	match t {
+		Type::Number => Some("number"),
+		Type::String => Some("string"),
+		Type::Boolean => Some("boolean"),
+		Type::Array(_) | Type::MutArray(_) => Some("array"),
+		_ => None,
+	}
+}
+
+/// Whether a Json literal of type `actual` has the shape of `expected`: a collection of Json literals fits a collection
+/// of the same kind whose items they fit, an object literal fits a struct, a structural interface or a map, and other
+/// values fit their type.
+fn json_literal_fits(actual: TypeRef, expected: TypeRef) -> bool {
+	let expected = *expected.maybe_unwrap_option();
+	if let (Some(inner_actual), Some(inner_expected)) = (actual.collection_item_type(), expected.collection_item_type()) {
+		return std::mem::discriminant(&*actual) == std::mem::discriminant(&*expected)
+			&& json_literal_fits(inner_actual, inner_expected);
+	}
+	let Type::Json(Some(data)) = &*actual else {
+		return actual.is_subtype_of(&expected);
+	};
+	match &data.kind {
+		JsonDataKind::Type(t) => t.type_.is_subtype_of(&expected),
+		JsonDataKind::Fields(_) => expected.is_struct() || expected.is_structural_interface() || expected.is_map(),
+		// The items of an array literal are kept as a Json list in its element type
+		JsonDataKind::List(items) => items.iter().all(|item| json_literal_fits(item.type_, expected)),
+	}
+}
+
+/// Whether two type arguments of a generic type are the same type. This is stricter than `is_same_type_as`: `any` and
+/// inferred types are only the same as themselves, and classes, interfaces and structs are compared by identity, so
+/// a generic hydrated with one of them is never reused for another type.
+fn is_same_type_argument(a: TypeRef, b: TypeRef) -> bool {
+	if std::ptr::eq(&*a, &*b) {
+		return true;
+	}
+	match (&*a, &*b) {
+		(Type::Optional(a), Type::Optional(b))
+		| (Type::Array(a), Type::Array(b))
+		| (Type::MutArray(a), Type::MutArray(b))
+		| (Type::Map(a), Type::Map(b))
+		| (Type::MutMap(a), Type::MutMap(b))
+		| (Type::Set(a), Type::Set(b))
+		| (Type::MutSet(a), Type::MutSet(b))
+		| (Type::Promise(a), Type::Promise(b)) => is_same_type_argument(*a, *b),
+		(Type::Union(a), Type::Union(b)) => {
+			a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| is_same_type_argument(*a, *b))
+		}
+		(Type::Function(a), Type::Function(b)) => {
+			a.phase == b.phase
+				&& a.parameters.len() == b.parameters.len()
+				&& a
+					.parameters
+					.iter()
+					.zip(b.parameters.iter())
+					.all(|(a, b)| is_same_type_argument(a.typeref, b.typeref))
+				&& is_same_type_argument(a.return_type, b.return_type)
+		}
+		(Type::Enum(a), Type::Enum(b)) => a.name == b.name,
+		(Type::Inferred(_), _)
+		| (_, Type::Inferred(_))
+		| (Type::Class(_), _)
+		| (Type::Interface(_), _)
+		| (Type::Struct(_), _)
+		| (Type::Json(Some(_)), _)
+		| (_, Type::Json(Some(_))) => false,
+		(a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+	}
+}
+
+/// Formats the type arguments of a user defined generic type (e.g. `<num, str>`), or an empty string for non-generic
+/// types. Types imported from JSII (which have an fqn) keep their plain name.
+fn fmt_type_arguments(env: &SymbolEnv, fqn: &Option<String>) -> String {
+	match (&env.type_parameters, fqn) {
+		(Some(type_arguments), None) if !type_arguments.is_empty() => {
+			format!("<{}>", type_arguments.iter().join(", "))
+		}
+		_ => "".to_string(),
+	}
+}
+
+fn is_nil_pattern(pattern: &MatchPattern) -> bool {
+	matches!(
+		&pattern.kind,
+		MatchPatternKind::Value(Expr {
+			kind: ExprKind::Literal(Literal::Nil),
+			..
+		})
+	)
+}
+
+fn pattern_has_bindings(pattern: &MatchPattern) -> bool {
+	match &pattern.kind {
+		MatchPatternKind::Binding(_) => true,
+		MatchPatternKind::Struct(fields) => fields.iter().any(|(_, p)| pattern_has_bindings(p)),
+		MatchPatternKind::Wildcard | MatchPatternKind::Value(_) => false,
+	}
+}
+
+/// The variant an enum pattern (`MyEnum.A`) matches
+fn enum_variant_of_pattern(pattern: &MatchPattern) -> Option<&Symbol> {
+	let MatchPatternKind::Value(value) = &pattern.kind else {
+		return None;
+	};
+	match &value.kind {
+		ExprKind::Reference(Reference::InstanceMember { property, .. })
+		| ExprKind::Reference(Reference::TypeMember { property, .. }) => Some(property),
+		_ => None,
+	}
+}
+
 /**
  * Given two phases (typically from two sub expressions of an expression) will return a valid phase
  * for the top level expression.
@@ -7317,6 +8739,7 @@ mod tests {
 	fn make_function(params: Vec<FunctionParameter>, ret: TypeRef, phase: Phase) -> Type {
 		Type::Function(FunctionSignature {
 			this_type: None,
+			type_parameters: vec![],
 			parameters: params,
 			return_type: ret,
 			phase,
@@ -7342,6 +8765,116 @@ mod tests {
 		assert!(opt_string.is_subtype_of(&opt_string));
 	}
 
+	#[test]
+	fn union_subtyping() {
+		let mut types = Types::new();
+		let string = types.string();
+		let num = types.number();
+		let boolean = types.bool();
+		let str_or_num = types.make_union(vec![string, num]);
+		let str_num_or_bool = types.make_union(vec![str_or_num, boolean]);
+
+		// members are subtypes of the union (but not vice versa)
+		assert!(string.is_subtype_of(&str_or_num));
+		assert!(!str_or_num.is_subtype_of(&string));
+		assert!(!boolean.is_subtype_of(&str_or_num));
+
+		// a union is a subtype of a union with more members
+		assert!(str_or_num.is_subtype_of(&str_num_or_bool));
+		assert!(!str_num_or_bool.is_subtype_of(&str_or_num));
+
+		// nested unions are flattened and the order of members doesn't matter
+		let num_or_str = types.make_union(vec![num, string]);
+		assert!(num_or_str.is_same_type_as(&str_or_num));
+		assert_eq!(str_num_or_bool.to_string(), "str | num | bool");
+
+		// optional members make the whole union optional
+		let opt_string = types.make_option(string);
+		let opt_union = types.make_union(vec![opt_string, num]);
+		assert_eq!(opt_union.to_string(), "(str | num)?");
+		assert!(types.nil().is_subtype_of(&opt_union));
+		assert!(str_or_num.is_subtype_of(&opt_union));
+
+		// a union of a single type is that type
+		assert_eq!(types.make_union(vec![string, string]).to_string(), "str");
+	}
+
+	fn make_fields_env(types: &Types, phase: Phase, fields: &[(&str, TypeRef, bool)]) -> SymbolEnv {
+		let mut env = SymbolEnv::new(None, SymbolEnvKind::Type(types.void()), phase, 0, "".to_string());
+		for (name, type_, reassignable) in fields {
+			env
+				.define(
+					&(*name).into(),
+					SymbolKind::make_member_variable(
+						(*name).into(),
+						*type_,
+						*reassignable,
+						false,
+						phase,
+						AccessModifier::Public,
+						None,
+					),
+					AccessModifier::Public,
+					StatementIdx::Top,
+				)
+				.unwrap();
+		}
+		env
+	}
+
+	fn make_struct(types: &mut Types, name: &str, fields: &[(&str, TypeRef, bool)]) -> TypeRef {
+		let env = make_fields_env(types, Phase::Independent, fields);
+		types.add_type(Type::Struct(Struct {
+			name: name.into(),
+			fqn: None,
+			docs: Docs::default(),
+			extends: vec![],
+			env,
+		}))
+	}
+
+	fn make_interface(types: &mut Types, name: &str, fields: &[(&str, TypeRef, bool)]) -> TypeRef {
+		let env = make_fields_env(types, Phase::Preflight, fields);
+		types.add_type(Type::Interface(Interface {
+			name: name.into(),
+			fqn: None,
+			docs: Docs::default(),
+			extends: vec![],
+			phase: Phase::Preflight,
+			env,
+		}))
+	}
+
+	#[test]
+	fn structural_interface_subtyping() {
+		let mut types = Types::new();
+		let string = types.string();
+		let num = types.number();
+		let iface = make_interface(&mut types, "IProps", &[("name", string, false)]);
+		let mutable_iface = make_interface(&mut types, "IMutableProps", &[("name", string, true)]);
+
+		// interfaces that only declare fields are typed structurally
+		assert!(iface.is_structural_interface());
+
+		// a struct with all the interface's fields is a subtype of it (extra fields are fine)
+		let with_fields = make_struct(
+			&mut types,
+			"WithFields",
+			&[("name", string, false), ("size", num, false)],
+		);
+		assert!(with_fields.is_subtype_of(&iface));
+		assert!(!iface.is_subtype_of(&with_fields));
+
+		// missing fields or fields of the wrong type aren't
+		let missing_field = make_struct(&mut types, "MissingField", &[("size", num, false)]);
+		assert!(!missing_field.is_subtype_of(&iface));
+		let wrong_type = make_struct(&mut types, "WrongType", &[("name", num, false)]);
+		assert!(!wrong_type.is_subtype_of(&iface));
+
+		// struct fields can't be reassigned, so structs can't be used where reassignable fields are expected
+		assert!(!with_fields.is_subtype_of(&mutable_iface));
+	}
+
 	#[test]
 	fn function_subtyping_across_phases() {
 		let void = UnsafeRef::<Type>(&Type::Void as *const Type);
@@ -0,0 +1,924 @@
+use std::collections::{BTreeMap, HashMap};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use indexmap::{IndexMap, IndexSet};
+use serde::{Deserialize, Serialize};
+use wingii::{fqn::FQN, type_system::TypeSystem};
+
+use crate::{
+	ast::{AccessModifier, Expr, Phase, Symbol},
+	diagnostic::WingSpan,
+	docs::Docs,
+	dtsify::extern_dtsify::is_extern_file,
+};
+
+use super::{
+	jsii_importer::{JsiiImportSpec, JsiiImporter},
+	lifts::{Capture, LiftQualification, Lifts},
+	symbol_env::{StatementIdx, SymbolEnvKind, SymbolEnvRef},
+	Class, Enum, FunctionParameter, FunctionSignature, Interface, JsonData, JsonDataKind, SpannedTypeInfo, Struct,
+	SymbolEnv, SymbolEnvOrNamespace, SymbolKind, Type, TypeChecker, TypeRef, Types, VariableInfo, VariableKind,
+};
+
+/// The symbols a Wing file defines at its top level (and the extern functions it declares), saved by the compile cache
+/// so a later compilation can restore them without type checking the file again.
+///
+/// Types are saved by value if the file defines them, and by name otherwise: types imported from JSII by their FQN
+/// and types of other Wing files by the file and the name they have there (those files are restored first).
+#[derive(Serialize, Deserialize)]
+pub struct CachedExports {
+	symbols: Vec<CachedSymbol>,
+	/// Functions declared in extern files by this file
+	externs: IndexMap<String, Vec<CachedSymbol>>,
+	/// The types defined by the file, referred to by their index
+	types: Vec<CachedType>,
+	/// Directories of the JSII assemblies the saved types are imported from
+	assemblies: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedTypeRef {
+	Anything,
+	Number,
+	String,
+	Duration,
+	Datetime,
+	JsiiDate,
+	Regex,
+	Boolean,
+	Void,
+	Json,
+	MutJson,
+	Nil,
+	Stringable,
+	/// A type imported from a JSII assembly, by its FQN
+	Jsii(String),
+	/// A type defined at the top level of another Wing file
+	Wing {
+		file: String,
+		name: String,
+	},
+	/// A type in `CachedExports::types`
+	Local(usize),
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedType {
+	Optional(CachedTypeRef),
+	Array(CachedTypeRef),
+	MutArray(CachedTypeRef),
+	Map(CachedTypeRef),
+	MutMap(CachedTypeRef),
+	Set(CachedTypeRef),
+	MutSet(CachedTypeRef),
+	Promise(CachedTypeRef),
+	Union(Vec<CachedTypeRef>),
+	Json(CachedJsonData),
+	Function(CachedSignature),
+	Class(CachedClass),
+	Interface(CachedInterface),
+	Struct(CachedStruct),
+	Enum(CachedEnum),
+	/// A generic type hydrated with type arguments (e.g. `Box<num>`)
+	Hydrated {
+		generic: CachedTypeRef,
+		type_arguments: Vec<CachedTypeRef>,
+		hydrated: Box<CachedType>,
+	},
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedJsonData {
+	Type(CachedTypeRef, WingSpan),
+	Fields(Vec<(Symbol, CachedTypeRef, WingSpan)>),
+	List(Vec<(CachedTypeRef, WingSpan)>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSignature {
+	this_type: Option<CachedTypeRef>,
+	type_parameters: Vec<CachedTypeRef>,
+	parameters: Vec<CachedParameter>,
+	return_type: CachedTypeRef,
+	phase: Phase,
+	implicit_scope_param: bool,
+	js_override: Option<String>,
+	is_macro: bool,
+	docs: Docs,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedParameter {
+	name: String,
+	typeref: CachedTypeRef,
+	docs: Docs,
+	variadic: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedClass {
+	name: Symbol,
+	parent: Option<CachedTypeRef>,
+	implements: Vec<CachedTypeRef>,
+	env: CachedEnv,
+	/// Whether the class's environment inherits the members of its parent's environment
+	inherits_parent_env: bool,
+	is_abstract: bool,
+	phase: Phase,
+	docs: Docs,
+	lifts: Option<CachedLifts>,
+	defined_in_phase: Phase,
+	std_construct_args: bool,
+	uid: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLifts {
+	lifts_qualifications: BTreeMap<String, BTreeMap<String, LiftQualification>>,
+	captures: BTreeMap<String, Capture>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedInterface {
+	name: Symbol,
+	docs: Docs,
+	extends: Vec<CachedTypeRef>,
+	phase: Phase,
+	env: CachedEnv,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedStruct {
+	name: Symbol,
+	docs: Docs,
+	extends: Vec<CachedTypeRef>,
+	env: CachedEnv,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedEnum {
+	name: Symbol,
+	docs: Docs,
+	values: Vec<(Symbol, Option<String>)>,
+}
+
+/// The environment of a class, interface or struct
+#[derive(Serialize, Deserialize)]
+struct CachedEnv {
+	phase: Phase,
+	statement_idx: usize,
+	source_package: String,
+	type_parameters: Option<Vec<CachedTypeRef>>,
+	symbols: Vec<CachedSymbol>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSymbol {
+	symbol: Symbol,
+	access: AccessModifier,
+	kind: CachedSymbolKind,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedSymbolKind {
+	Type(CachedTypeRef),
+	Variable(CachedVariable),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedVariable {
+	name: Symbol,
+	type_: CachedTypeRef,
+	reassignable: bool,
+	phase: Phase,
+	kind: VariableKind,
+	access: AccessModifier,
+	docs: Option<Docs>,
+}
+
+impl CachedExports {
+	/// Saves the symbols of a type checked file. Returns `None` if they refer to types that can't be restored by name
+	/// (like types imported from TypeScript declarations).
+	pub fn new(types: &Types, jsii_types: &TypeSystem, file: &Utf8Path) -> Option<Self> {
+		let Some(SymbolEnvOrNamespace::SymbolEnv(env)) = types.source_file_envs.get(file) else {
+			return None;
+		};
+
+		let mut writer = ExportsWriter {
+			types,
+			jsii_types,
+			file,
+			local_types: HashMap::new(),
+			cached_types: vec![],
+			assemblies: IndexSet::new(),
+			jsii_fqns: None,
+		};
+		let symbols = writer.symbols(env)?;
+		let mut externs = IndexMap::new();
+		for (path, extern_env) in &types.source_file_envs {
+			let SymbolEnvOrNamespace::SymbolEnv(extern_env) = extern_env else {
+				continue;
+			};
+			if is_extern_file(path) {
+				let symbols = writer.symbols(extern_env)?;
+				if !symbols.is_empty() {
+					externs.insert(path.to_string(), symbols);
+				}
+			}
+		}
+
+		Some(Self {
+			symbols,
+			externs,
+			types: writer.cached_types.into_iter().collect::<Option<_>>()?,
+			assemblies: writer.assemblies.into_iter().collect(),
+		})
+	}
+
+	/// The extern files the file declares functions in
+	pub fn extern_files(&self) -> impl Iterator<Item = &Utf8Path> {
+		self.externs.keys().map(Utf8Path::new)
+	}
+}
+
+struct ExportsWriter<'a> {
+	types: &'a Types,
+	jsii_types: &'a TypeSystem,
+	file: &'a Utf8Path,
+	/// Index of each type defined by the file in `cached_types`
+	local_types: HashMap<*const Type, usize>,
+	/// The types defined by the file (`None` while a type is being saved)
+	cached_types: Vec<Option<CachedType>>,
+	assemblies: IndexSet<String>,
+	/// The FQN of every type imported from JSII so far, computed when it's first needed
+	jsii_fqns: Option<HashMap<*const Type, String>>,
+}
+
+impl ExportsWriter<'_> {
+	/// Saves the symbols of a top level (or extern) environment that were defined by the file
+	fn symbols(&mut self, env: &SymbolEnv) -> Option<Vec<CachedSymbol>> {
+		env
+			.symbol_map
+			.iter()
+			.filter(|(_, entry)| entry.span.file_id == self.file.as_str() && entry.kind.as_namespace_ref().is_none())
+			.map(|(name, entry)| self.symbol(&Symbol::new(name, entry.span.clone()), entry.access, &entry.kind))
+			.collect()
+	}
+
+	fn symbol(&mut self, symbol: &Symbol, access: AccessModifier, kind: &SymbolKind) -> Option<CachedSymbol> {
+		let kind = match kind {
+			SymbolKind::Type(t) => CachedSymbolKind::Type(self.type_ref(*t)?),
+			SymbolKind::Variable(v) => CachedSymbolKind::Variable(CachedVariable {
+				name: v.name.clone(),
+				type_: self.type_ref(v.type_)?,
+				reassignable: v.reassignable,
+				phase: v.phase,
+				kind: v.kind.clone(),
+				access: v.access,
+				docs: v.docs.clone(),
+			}),
+			SymbolKind::Namespace(_) => return None,
+		};
+		Some(CachedSymbol {
+			symbol: symbol.clone(),
+			access,
+			kind,
+		})
+	}
+
+	fn type_refs(&mut self, types: &[TypeRef]) -> Option<Vec<CachedTypeRef>> {
+		types.iter().map(|t| self.type_ref(*t)).collect()
+	}
+
+	fn optional_type_ref(&mut self, t: Option<TypeRef>) -> Option<Option<CachedTypeRef>> {
+		match t {
+			Some(t) => Some(Some(self.type_ref(t)?)),
+			None => Some(None),
+		}
+	}
+
+	fn type_ref(&mut self, t: TypeRef) -> Option<CachedTypeRef> {
+		let t = self.types.maybe_unwrap_inference(t);
+		let cached = match &*t {
+			Type::Anything => CachedTypeRef::Anything,
+			Type::Number => CachedTypeRef::Number,
+			Type::String => CachedTypeRef::String,
+			Type::Duration => CachedTypeRef::Duration,
+			Type::Datetime if std::ptr::eq(&*t, &*self.types.jsii_date()) => CachedTypeRef::JsiiDate,
+			Type::Datetime => CachedTypeRef::Datetime,
+			Type::Regex => CachedTypeRef::Regex,
+			Type::Boolean => CachedTypeRef::Boolean,
+			Type::Void => CachedTypeRef::Void,
+			Type::Json(None) => CachedTypeRef::Json,
+			Type::MutJson => CachedTypeRef::MutJson,
+			Type::Nil => CachedTypeRef::Nil,
+			Type::Stringable => CachedTypeRef::Stringable,
+			Type::Unresolved | Type::Inferred(_) => return None,
+			_ => {
+				if let Some(index) = self.local_types.get(&(&*t as *const Type)) {
+					return Some(CachedTypeRef::Local(*index));
+				}
+				if self.types.get_generic_origin(t).is_none() {
+					if let Some(fqn) = self.jsii_fqn(t) {
+						self.assemblies.insert(self.assembly_directory(&fqn)?);
+						return Some(CachedTypeRef::Jsii(fqn));
+					}
+					if let Some(name) = type_name(&t) {
+						if name.span.file_id != self.file.as_str() {
+							return self.wing_type_ref(t, name);
+						}
+					}
+				}
+
+				let index = self.cached_types.len();
+				self.cached_types.push(None);
+				self.local_types.insert(&*t as *const Type, index);
+				self.cached_types[index] = Some(self.cached_type(t)?);
+				CachedTypeRef::Local(index)
+			}
+		};
+		Some(cached)
+	}
+
+	/// Types of other Wing files are saved by name, if they are defined at the top level of the file
+	fn wing_type_ref(&self, t: TypeRef, name: &Symbol) -> Option<CachedTypeRef> {
+		let file = name.span.file_id.clone();
+		let Some(SymbolEnvOrNamespace::SymbolEnv(env)) = self.types.source_file_envs.get(Utf8Path::new(&file)) else {
+			return None;
+		};
+		let defined = env.symbol_map.get(&name.name)?.kind.as_type()?;
+		std::ptr::eq(&*defined, &*t).then(|| CachedTypeRef::Wing {
+			file,
+			name: name.name.clone(),
+		})
+	}
+
+	fn jsii_fqn(&mut self, t: TypeRef) -> Option<String> {
+		let libraries = &self.types.libraries;
+		let fqns = self.jsii_fqns.get_or_insert_with(|| {
+			let mut fqns = HashMap::new();
+			collect_type_fqns(libraries, "", &mut fqns);
+			fqns
+		});
+		fqns.get(&(&*t as *const Type)).cloned()
+	}
+
+	fn assembly_directory(&self, fqn: &str) -> Option<String> {
+		let directory = self.jsii_types.find_assembly_directory(FQN::from(fqn).assembly())?;
+		Some(directory.to_string())
+	}
+
+	fn cached_type(&mut self, t: TypeRef) -> Option<CachedType> {
+		if let Some(generic) = self.types.get_generic_origin(t) {
+			let type_arguments = self.types.hydrated_types[&(&*generic as *const Type)]
+				.iter()
+				.find(|(_, hydrated)| std::ptr::eq(&**hydrated, &*t))
+				.map(|(type_arguments, _)| type_arguments.clone())?;
+			return Some(CachedType::Hydrated {
+				generic: self.type_ref(generic)?,
+				type_arguments: self.type_refs(&type_arguments)?,
+				hydrated: Box::new(self.cached_type_definition(t)?),
+			});
+		}
+		self.cached_type_definition(t)
+	}
+
+	fn cached_type_definition(&mut self, t: TypeRef) -> Option<CachedType> {
+		let cached = match &*t {
+			Type::Optional(t) => CachedType::Optional(self.type_ref(*t)?),
+			Type::Array(t) => CachedType::Array(self.type_ref(*t)?),
+			Type::MutArray(t) => CachedType::MutArray(self.type_ref(*t)?),
+			Type::Map(t) => CachedType::Map(self.type_ref(*t)?),
+			Type::MutMap(t) => CachedType::MutMap(self.type_ref(*t)?),
+			Type::Set(t) => CachedType::Set(self.type_ref(*t)?),
+			Type::MutSet(t) => CachedType::MutSet(self.type_ref(*t)?),
+			Type::Promise(t) => CachedType::Promise(self.type_ref(*t)?),
+			Type::Union(members) => CachedType::Union(self.type_refs(members)?),
+			Type::Json(Some(data)) => CachedType::Json(match &data.kind {
+				JsonDataKind::Type(t) => CachedJsonData::Type(self.type_ref(t.type_)?, t.span.clone()),
+				JsonDataKind::Fields(fields) => CachedJsonData::Fields(
+					fields
+						.iter()
+						.map(|(name, t)| Some((name.clone(), self.type_ref(t.type_)?, t.span.clone())))
+						.collect::<Option<_>>()?,
+				),
+				JsonDataKind::List(items) => CachedJsonData::List(
+					items
+						.iter()
+						.map(|t| Some((self.type_ref(t.type_)?, t.span.clone())))
+						.collect::<Option<_>>()?,
+				),
+			}),
+			Type::Function(sig) => CachedType::Function(self.signature(sig)?),
+			Type::Class(class) => {
+				// A class's environment inherits from its parent class's environment (or none)
+				let parent_env = class.parent.as_ref().and_then(|p| p.as_class()).map(|p| &p.env);
+				let inherits_parent_env = match (class.env.parent, parent_env) {
+					(None, _) => false,
+					(Some(env), Some(parent_env)) if std::ptr::eq(&*env, parent_env) => true,
+					_ => return None,
+				};
+				CachedType::Class(CachedClass {
+					name: class.name.clone(),
+					parent: self.optional_type_ref(class.parent)?,
+					implements: self.type_refs(&class.implements)?,
+					env: self.env(&class.env)?,
+					inherits_parent_env,
+					is_abstract: class.is_abstract,
+					phase: class.phase,
+					docs: class.docs.clone(),
+					lifts: class.lifts.as_ref().map(|lifts| CachedLifts {
+						lifts_qualifications: lifts
+							.lifts_qualifications
+							.iter()
+							.map(|(method, quals)| {
+								let quals = quals
+									.iter()
+									.map(|(code, qual)| (code.clone(), LiftQualification { ops: qual.ops.clone() }))
+									.collect();
+								(method.clone(), quals)
+							})
+							.collect(),
+						captures: lifts
+							.captures
+							.iter()
+							.map(|(token, capture)| {
+								let capture = Capture {
+									is_field: capture.is_field,
+									code: capture.code.clone(),
+								};
+								(token.clone(), capture)
+							})
+							.collect(),
+					}),
+					defined_in_phase: class.defined_in_phase,
+					std_construct_args: class.std_construct_args,
+					uid: class.uid,
+				})
+			}
+			Type::Interface(iface) => CachedType::Interface(CachedInterface {
+				name: iface.name.clone(),
+				docs: iface.docs.clone(),
+				extends: self.type_refs(&iface.extends)?,
+				phase: iface.phase,
+				env: self.env(&iface.env)?,
+			}),
+			Type::Struct(st) => CachedType::Struct(CachedStruct {
+				name: st.name.clone(),
+				docs: st.docs.clone(),
+				extends: self.type_refs(&st.extends)?,
+				env: self.env(&st.env)?,
+			}),
+			Type::Enum(enu) => CachedType::Enum(CachedEnum {
+				name: enu.name.clone(),
+				docs: enu.docs.clone(),
+				values: enu.values.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+			}),
+			_ => return None,
+		};
+		Some(cached)
+	}
+
+	fn signature(&mut self, sig: &FunctionSignature) -> Option<CachedSignature> {
+		Some(CachedSignature {
+			this_type: self.optional_type_ref(sig.this_type)?,
+			type_parameters: self.type_refs(&sig.type_parameters)?,
+			parameters: sig
+				.parameters
+				.iter()
+				.map(|p| {
+					Some(CachedParameter {
+						name: p.name.clone(),
+						typeref: self.type_ref(p.typeref)?,
+						docs: p.docs.clone(),
+						variadic: p.variadic,
+					})
+				})
+				.collect::<Option<_>>()?,
+			return_type: self.type_ref(sig.return_type)?,
+			phase: sig.phase,
+			implicit_scope_param: sig.implicit_scope_param,
+			js_override: sig.js_override.clone(),
+			is_macro: sig.is_macro,
+			docs: sig.docs.clone(),
+		})
+	}
+
+	fn env(&mut self, env: &SymbolEnv) -> Option<CachedEnv> {
+		Some(CachedEnv {
+			phase: env.phase,
+			statement_idx: env.statement_idx,
+			source_package: env.source_package.clone(),
+			type_parameters: match &env.type_parameters {
+				Some(type_parameters) => Some(self.type_refs(type_parameters)?),
+				None => None,
+			},
+			symbols: env
+				.symbol_map
+				.iter()
+				.map(|(name, entry)| self.symbol(&Symbol::new(name, entry.span.clone()), entry.access, &entry.kind))
+				.collect::<Option<_>>()?,
+		})
+	}
+}
+
+fn type_name(t: &Type) -> Option<&Symbol> {
+	match t {
+		Type::Class(class) => Some(&class.name),
+		Type::Interface(iface) => Some(&iface.name),
+		Type::Struct(st) => Some(&st.name),
+		Type::Enum(enu) => Some(&enu.name),
+		_ => None,
+	}
+}
+
+fn collect_type_fqns(env: &SymbolEnv, prefix: &str, fqns: &mut HashMap<*const Type, String>) {
+	for (name, entry) in &env.symbol_map {
+		let fqn = if prefix.is_empty() {
+			name.clone()
+		} else {
+			format!("{prefix}.{name}")
+		};
+		match &entry.kind {
+			SymbolKind::Type(t) => {
+				fqns.insert(&**t as *const Type, fqn);
+			}
+			SymbolKind::Namespace(ns) => {
+				for env in &ns.envs {
+					collect_type_fqns(env, &fqn, fqns);
+				}
+			}
+			SymbolKind::Variable(_) => {}
+		}
+	}
+}
+
+/// The types restored so far
+struct ExportsReader<'a> {
+	exports: &'a CachedExports,
+	types: Vec<Option<TypeRef>>,
+	/// Types that were created but whose definition wasn't restored yet
+	pending: Vec<(usize, TypeRef)>,
+	/// Classes whose environment inherits their parent's environment, set once all types are restored
+	class_parents: Vec<(TypeRef, TypeRef)>,
+}
+
+impl TypeChecker<'_> {
+	/// Defines the saved symbols of the file being checked in `env` and makes it the file's environment, like
+	/// `type_check_file_or_dir` does after type checking the file. Fails if the exports don't match the types they
+	/// refer to (e.g. if the cache is corrupted), in which case `env` is left partially defined.
+	pub fn restore_exports(&mut self, mut env: SymbolEnvRef, exports: &CachedExports) -> Result<(), String> {
+		for directory in &exports.assemblies {
+			if let Err(error) = self.jsii_types.load_module(Utf8Path::new(directory)) {
+				return Err(format!(
+					"Cannot load cached JSII assembly from \"{directory}\": {error}"
+				));
+			}
+		}
+
+		let mut reader = ExportsReader {
+			exports,
+			types: vec![None; exports.types.len()],
+			pending: vec![],
+			class_parents: vec![],
+		};
+		for symbol in &exports.symbols {
+			let kind = self.restore_symbol_kind(&mut reader, &symbol.kind)?;
+			env
+				.define(&symbol.symbol, kind, symbol.access, StatementIdx::Top)
+				.map_err(|_| format!("Cached symbol \"{}\" is defined twice", symbol.symbol))?;
+		}
+		for (path, symbols) in &exports.externs {
+			for symbol in symbols {
+				let kind = self.restore_symbol_kind(&mut reader, &symbol.kind)?;
+				let mut extern_env = match self.types.source_file_envs.get(Utf8Path::new(path)) {
+					Some(SymbolEnvOrNamespace::SymbolEnv(extern_env)) => *extern_env,
+					_ => {
+						let phase = kind.as_variable().map_or(Phase::Preflight, |v| v.phase);
+						let extern_env = self.types.add_symbol_env(SymbolEnv::new(
+							None,
+							SymbolEnvKind::Type(self.types.void()),
+							phase,
+							0,
+							self.source_file.package.clone(),
+						));
+						self
+							.types
+							.source_file_envs
+							.insert(Utf8PathBuf::from(path), SymbolEnvOrNamespace::SymbolEnv(extern_env));
+						extern_env
+					}
+				};
+				// Extern functions declared by more than one file are checked to be the same
+				if !extern_env.symbol_map.contains_key(&symbol.symbol.name) {
+					extern_env
+						.define(&symbol.symbol, kind, symbol.access, StatementIdx::Top)
+						.map_err(|_| format!("Cached extern function \"{}\" is defined twice", symbol.symbol))?;
+				}
+			}
+		}
+
+		while let Some((index, t)) = reader.pending.pop() {
+			let definition = match &exports.types[index] {
+				CachedType::Hydrated { hydrated, .. } => hydrated,
+				definition => definition,
+			};
+			self.restore_type_definition(&mut reader, t, definition)?;
+		}
+		for (mut class, parent) in reader.class_parents {
+			let parent_env = parent
+				.as_class()
+				.ok_or_else(|| format!("Expected parent \"{parent}\" of cached class to be a class"))?
+				.env
+				.get_ref();
+			class.as_class_mut().expect("Expected restored class").env.parent = Some(parent_env);
+		}
+
+		self
+			.types
+			.source_file_envs
+			.insert(self.source_file.path.clone(), SymbolEnvOrNamespace::SymbolEnv(env));
+		Ok(())
+	}
+
+	fn restore_symbol_kind(&mut self, reader: &mut ExportsReader, kind: &CachedSymbolKind) -> Result<SymbolKind, String> {
+		Ok(match kind {
+			CachedSymbolKind::Type(t) => SymbolKind::Type(self.restore_type_ref(reader, t)?),
+			CachedSymbolKind::Variable(v) => SymbolKind::Variable(VariableInfo {
+				name: v.name.clone(),
+				type_: self.restore_type_ref(reader, &v.type_)?,
+				reassignable: v.reassignable,
+				phase: v.phase,
+				kind: v.kind.clone(),
+				access: v.access,
+				docs: v.docs.clone(),
+			}),
+		})
+	}
+
+	fn restore_type_refs(&mut self, reader: &mut ExportsReader, types: &[CachedTypeRef]) -> Result<Vec<TypeRef>, String> {
+		types.iter().map(|t| self.restore_type_ref(reader, t)).collect()
+	}
+
+	fn restore_type_ref(&mut self, reader: &mut ExportsReader, t: &CachedTypeRef) -> Result<TypeRef, String> {
+		Ok(match t {
+			CachedTypeRef::Anything => self.types.anything(),
+			CachedTypeRef::Number => self.types.number(),
+			CachedTypeRef::String => self.types.string(),
+			CachedTypeRef::Duration => self.types.duration(),
+			CachedTypeRef::Datetime => self.types.datetime(),
+			CachedTypeRef::JsiiDate => self.types.jsii_date(),
+			CachedTypeRef::Regex => self.types.regex(),
+			CachedTypeRef::Boolean => self.types.bool(),
+			CachedTypeRef::Void => self.types.void(),
+			CachedTypeRef::Json => self.types.json(),
+			CachedTypeRef::MutJson => self.types.mut_json(),
+			CachedTypeRef::Nil => self.types.nil(),
+			CachedTypeRef::Stringable => self.types.stringable(),
+			CachedTypeRef::Jsii(fqn) => self.restore_jsii_type(fqn)?,
+			CachedTypeRef::Wing { file, name } => {
+				match self.types.source_file_envs.get(Utf8Path::new(file)) {
+					Some(SymbolEnvOrNamespace::SymbolEnv(env)) => env
+						.symbol_map
+						.get(name)
+						.and_then(|entry| entry.kind.as_type())
+						.ok_or_else(|| format!("Expected \"{name}\" to be a type defined in \"{file}\""))?,
+					_ => {
+						return Err(format!(
+							"Expected \"{file}\" to be type checked before the files that bring it"
+						))
+					}
+				}
+			}
+			CachedTypeRef::Local(index) => match reader.types.get(*index) {
+				Some(Some(t)) => *t,
+				Some(None) => self.restore_local_type(reader, *index)?,
+				None => return Err(format!("Cached type {index} is out of range")),
+			},
+		})
+	}
+
+	fn restore_jsii_type(&mut self, fqn: &str) -> Result<TypeRef, String> {
+		let fqn = FQN::from(fqn);
+		let jsii = JsiiImportSpec {
+			assembly_name: fqn.assembly().to_string(),
+			namespace_filter: vec![],
+			alias: Symbol::global(fqn.assembly()),
+		};
+		JsiiImporter::new(&jsii, self.types, self.jsii_types)
+			.import_type(&fqn)
+			.ok_or_else(|| format!("Expected {fqn} to be defined"))
+	}
+
+	/// Types with a name are created first and defined later, so types can refer to each other
+	fn restore_local_type(&mut self, reader: &mut ExportsReader, index: usize) -> Result<TypeRef, String> {
+		let exports = reader.exports;
+		let restored = match &exports.types[index] {
+			CachedType::Class(_) | CachedType::Interface(_) | CachedType::Struct(_) | CachedType::Enum(_) => {
+				let t = self.types.add_type(Type::Unresolved);
+				reader.pending.push((index, t));
+				reader.types[index] = Some(t);
+				return Ok(t);
+			}
+			CachedType::Hydrated {
+				generic,
+				type_arguments,
+				..
+			} => {
+				let generic = self.restore_type_ref(reader, generic)?;
+				let type_arguments = self.restore_type_refs(reader, type_arguments)?;
+				// Another file may have already hydrated the generic type with the same type arguments
+				let t = if let Some(t) = self.types.get_hydrated_type(generic, &type_arguments) {
+					t
+				} else {
+					let t = self.types.add_type(Type::Unresolved);
+					self.types.add_hydrated_type(generic, type_arguments, t);
+					reader.pending.push((index, t));
+					t
+				};
+				reader.types[index] = Some(t);
+				return Ok(t);
+			}
+			CachedType::Optional(t) => Type::Optional(self.restore_type_ref(reader, t)?),
+			CachedType::Array(t) => Type::Array(self.restore_type_ref(reader, t)?),
+			CachedType::MutArray(t) => Type::MutArray(self.restore_type_ref(reader, t)?),
+			CachedType::Map(t) => Type::Map(self.restore_type_ref(reader, t)?),
+			CachedType::MutMap(t) => Type::MutMap(self.restore_type_ref(reader, t)?),
+			CachedType::Set(t) => Type::Set(self.restore_type_ref(reader, t)?),
+			CachedType::MutSet(t) => Type::MutSet(self.restore_type_ref(reader, t)?),
+			CachedType::Promise(t) => Type::Promise(self.restore_type_ref(reader, t)?),
+			CachedType::Union(members) => Type::Union(self.restore_type_refs(reader, members)?),
+			CachedType::Json(data) => {
+				let kind = match data {
+					CachedJsonData::Type(t, span) => JsonDataKind::Type(SpannedTypeInfo {
+						type_: self.restore_type_ref(reader, t)?,
+						span: span.clone(),
+					}),
+					CachedJsonData::Fields(fields) => JsonDataKind::Fields(
+						fields
+							.iter()
+							.map(|(name, t, span)| {
+								let t = SpannedTypeInfo {
+									type_: self.restore_type_ref(reader, t)?,
+									span: span.clone(),
+								};
+								Ok((name.clone(), t))
+							})
+							.collect::<Result<_, String>>()?,
+					),
+					CachedJsonData::List(items) => JsonDataKind::List(
+						items
+							.iter()
+							.map(|(t, span)| {
+								Ok(SpannedTypeInfo {
+									type_: self.restore_type_ref(reader, t)?,
+									span: span.clone(),
+								})
+							})
+							.collect::<Result<_, String>>()?,
+					),
+				};
+				// The Json literal is in another file, so it isn't cast to anything in this compilation
+				Type::Json(Some(JsonData {
+					expression_id: Expr::new_id(),
+					kind,
+				}))
+			}
+			CachedType::Function(sig) => Type::Function(self.restore_signature(reader, sig)?),
+		};
+		let t = self.types.add_type(restored);
+		reader.types[index] = Some(t);
+		Ok(t)
+	}
+
+	fn restore_type_definition(
+		&mut self,
+		reader: &mut ExportsReader,
+		mut t: TypeRef,
+		definition: &CachedType,
+	) -> Result<(), String> {
+		let restored = match definition {
+			CachedType::Class(class) => {
+				let parent = class
+					.parent
+					.as_ref()
+					.map(|p| self.restore_type_ref(reader, p))
+					.transpose()?;
+				if class.inherits_parent_env {
+					let parent = parent.ok_or_else(|| format!("Expected cached class \"{}\" to have a parent", class.name))?;
+					reader.class_parents.push((t, parent));
+				}
+				let lifts = class.lifts.as_ref().map(|lifts| {
+					let mut restored = Lifts::new();
+					for (method, quals) in &lifts.lifts_qualifications {
+						let quals = quals
+							.iter()
+							.map(|(code, qual)| (code.clone(), LiftQualification { ops: qual.ops.clone() }))
+							.collect();
+						restored.lifts_qualifications.insert(method.clone(), quals);
+					}
+					for (token, capture) in &lifts.captures {
+						let capture = Capture {
+							is_field: capture.is_field,
+							code: capture.code.clone(),
+						};
+						restored.captures.insert(token.clone(), capture);
+					}
+					restored
+				});
+				Type::Class(Class {
+					name: class.name.clone(),
+					parent,
+					implements: self.restore_type_refs(reader, &class.implements)?,
+					env: self.restore_env(reader, t, &class.env)?,
+					fqn: None,
+					is_abstract: class.is_abstract,
+					phase: class.phase,
+					docs: class.docs.clone(),
+					lifts,
+					defined_in_phase: class.defined_in_phase,
+					std_construct_args: class.std_construct_args,
+					uid: class.uid,
+				})
+			}
+			CachedType::Interface(iface) => Type::Interface(Interface {
+				name: iface.name.clone(),
+				fqn: None,
+				docs: iface.docs.clone(),
+				extends: self.restore_type_refs(reader, &iface.extends)?,
+				phase: iface.phase,
+				env: self.restore_env(reader, t, &iface.env)?,
+			}),
+			CachedType::Struct(st) => Type::Struct(Struct {
+				name: st.name.clone(),
+				fqn: None,
+				docs: st.docs.clone(),
+				extends: self.restore_type_refs(reader, &st.extends)?,
+				env: self.restore_env(reader, t, &st.env)?,
+			}),
+			CachedType::Enum(enu) => Type::Enum(Enum {
+				name: enu.name.clone(),
+				docs: enu.docs.clone(),
+				values: enu.values.iter().cloned().collect(),
+			}),
+			_ => return Err("Expected a cached class, interface, struct or enum".to_string()),
+		};
+		*t = restored;
+		Ok(())
+	}
+
+	fn restore_env(&mut self, reader: &mut ExportsReader, t: TypeRef, env: &CachedEnv) -> Result<SymbolEnv, String> {
+		let mut restored = SymbolEnv::new(
+			None,
+			SymbolEnvKind::Type(t),
+			env.phase,
+			env.statement_idx,
+			env.source_package.clone(),
+		);
+		restored.type_parameters = env
+			.type_parameters
+			.as_ref()
+			.map(|type_parameters| self.restore_type_refs(reader, type_parameters))
+			.transpose()?;
+		for symbol in &env.symbols {
+			let kind = self.restore_symbol_kind(reader, &symbol.kind)?;
+			restored
+				.define(&symbol.symbol, kind, symbol.access, StatementIdx::Top)
+				.map_err(|_| format!("Cached member \"{}\" is defined twice", symbol.symbol))?;
+		}
+		Ok(restored)
+	}
+
+	fn restore_signature(
+		&mut self,
+		reader: &mut ExportsReader,
+		sig: &CachedSignature,
+	) -> Result<FunctionSignature, String> {
+		Ok(FunctionSignature {
+			this_type: sig
+				.this_type
+				.as_ref()
+				.map(|t| self.restore_type_ref(reader, t))
+				.transpose()?,
+			type_parameters: self.restore_type_refs(reader, &sig.type_parameters)?,
+			parameters: sig
+				.parameters
+				.iter()
+				.map(|p| {
+					Ok(FunctionParameter {
+						name: p.name.clone(),
+						typeref: self.restore_type_ref(reader, &p.typeref)?,
+						docs: p.docs.clone(),
+						variadic: p.variadic,
+					})
+				})
+				.collect::<Result<_, String>>()?,
+			return_type: self.restore_type_ref(reader, &sig.return_type)?,
+			phase: sig.phase,
+			implicit_scope_param: sig.implicit_scope_param,
+			js_override: sig.js_override.clone(),
+			is_macro: sig.is_macro,
+			docs: sig.docs.clone(),
+		})
+	}
+}
@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use wingii::dts::{self, Constructor, DeclarationKind, MemberKind};
+
+use crate::{
+	ast::{AccessModifier, Phase, Symbol},
+	debug,
+	docs::Docs,
+	type_check::{
+		self,
+		symbol_env::{StatementIdx, SymbolEnv, SymbolEnvKind},
+		Class, ClassLike, Enum, FunctionParameter, FunctionSignature, Interface, Namespace, ResolveSource, Struct,
+		SymbolKind, Type, TypeRef, Types, CLASS_INIT_NAME,
+	},
+};
+
+/// Imports the TypeScript declarations of an npm package (that isn't a JSII module) into the Wing type system.
+///
+/// Like JSII assemblies, the package becomes a namespace in `wing_types.libraries` with its exported declarations:
+/// - Interfaces without methods (whose parents are also such interfaces) are imported as structs, other interfaces
+///   as preflight interfaces.
+/// - Classes are imported as phase independent classes.
+/// - Functions and variables are imported as (non-reassignable) variables of the namespace.
+/// - Enums and type aliases are imported as types.
+///
+/// Types that can't be represented in Wing (or aren't declared in the package) are imported as `any`.
+pub struct DtsImporter<'a> {
+	module: &'a dts::Module,
+
+	/// The wing type system: all imported types are added to `wing_types.libraries`.
+	wing_types: &'a mut Types,
+
+	/// Types imported by declaration name, `None` while a type alias is being imported (so cycles become `any`)
+	imported: HashMap<String, Option<TypeRef>>,
+}
+
+impl<'a> DtsImporter<'a> {
+	pub fn new(module: &'a dts::Module, wing_types: &'a mut Types) -> Self {
+		Self {
+			module,
+			wing_types,
+			imported: HashMap::new(),
+		}
+	}
+
+	/// Imports the package's namespace (if it wasn't imported yet) and defines it as `alias` in `env`
+	pub fn import_to_env(&mut self, alias: &Symbol, env: &mut SymbolEnv) {
+		let package = Symbol::global(&self.module.name);
+		let ns = if let Some(ns) = self.wing_types.libraries.lookup(&package, None) {
+			ns.as_namespace_ref()
+				.expect("Expected an npm package to be a namespace")
+		} else {
+			self.import_namespace(&package)
+		};
+
+		env
+			.define(
+				alias,
+				SymbolKind::Namespace(ns),
+				AccessModifier::Private,
+				StatementIdx::Top,
+			)
+			.unwrap();
+	}
+
+	fn import_namespace(&mut self, package: &Symbol) -> type_check::NamespaceRef {
+		debug!("Importing TypeScript declarations of {}", package.name);
+		let mut ns_env = self.wing_types.add_symbol_env(SymbolEnv::new(
+			None,
+			SymbolEnvKind::Scope,
+			Phase::Preflight,
+			0,
+			package.name.clone(),
+		));
+		let ns = self.wing_types.add_namespace(Namespace {
+			name: package.name.clone(),
+			envs: vec![ns_env],
+			source_package: package.name.clone(),
+			module_path: ResolveSource::ExternalModule(package.name.clone()),
+		});
+		self
+			.wing_types
+			.libraries
+			.define(
+				package,
+				SymbolKind::Namespace(ns),
+				AccessModifier::Public,
+				StatementIdx::Top,
+			)
+			.expect("Failed to define npm package namespace");
+
+		for declaration in self.module.declarations.iter().filter(|d| d.exported) {
+			let Some(symbol_kind) = self.import_declaration(declaration) else {
+				continue;
+			};
+			// Declarations merged by TypeScript (like a function and a namespace of the same name) are only imported once
+			let _ = ns_env.define(
+				&Symbol::global(&declaration.name),
+				symbol_kind,
+				AccessModifier::Public,
+				StatementIdx::Top,
+			);
+		}
+		ns
+	}
+
+	fn import_declaration(&mut self, declaration: &dts::Declaration) -> Option<SymbolKind> {
+		let name = Symbol::global(&declaration.name);
+		match &declaration.kind {
+			DeclarationKind::Function(function) => {
+				let docs = docs_of(&declaration.docs);
+				let function_type = self.function_type_to_wing_type(function, Phase::Independent, docs);
+				Some(SymbolKind::make_free_variable(
+					name,
+					function_type,
+					false,
+					Phase::Independent,
+				))
+			}
+			DeclarationKind::Variable(type_) => {
+				let type_ = self.type_ref_to_wing_type(type_);
+				Some(SymbolKind::make_free_variable(name, type_, false, Phase::Independent))
+			}
+			DeclarationKind::Alias(target) => {
+				let target = self.module.find(target)?;
+				self.import_declaration(target)
+			}
+			DeclarationKind::Interface { .. }
+			| DeclarationKind::Class { .. }
+			| DeclarationKind::Enum(_)
+			| DeclarationKind::TypeAlias(_) => Some(SymbolKind::Type(self.import_named_type(&declaration.name))),
+		}
+	}
+
+	fn import_named_type(&mut self, name: &str) -> TypeRef {
+		match self.imported.get(name) {
+			Some(Some(t)) => return *t,
+			Some(None) => return self.wing_types.anything(),
+			None => {}
+		}
+		let Some(declaration) = self.module.find(name) else {
+			// Types from other packages (or builtin types like `Promise` and `Error`)
+			return self.wing_types.anything();
+		};
+
+		match &declaration.kind {
+			DeclarationKind::Interface { extends, members } => self.import_interface(declaration, extends, members),
+			DeclarationKind::Class {
+				extends,
+				implements,
+				is_abstract,
+				constructor,
+				members,
+			} => self.import_class(declaration, extends, implements, *is_abstract, constructor, members),
+			DeclarationKind::Enum(members) => self.import_enum(declaration, members),
+			DeclarationKind::TypeAlias(target) => {
+				self.imported.insert(name.to_string(), None);
+				let t = self.type_ref_to_wing_type(target);
+				self.imported.insert(name.to_string(), Some(t));
+				t
+			}
+			DeclarationKind::Alias(target) => {
+				self.imported.insert(name.to_string(), None);
+				let t = self.import_named_type(target);
+				self.imported.insert(name.to_string(), Some(t));
+				t
+			}
+			DeclarationKind::Function(_) | DeclarationKind::Variable(_) => self.wing_types.anything(),
+		}
+	}
+
+	/// Whether an interface can be imported as a struct: it has no methods and all its parents are structs too
+	fn is_struct(&self, declaration: &dts::Declaration) -> bool {
+		let DeclarationKind::Interface { extends, members } = &declaration.kind else {
+			return false;
+		};
+		members.iter().all(|m| matches!(m.kind, MemberKind::Property { .. }))
+			&& extends
+				.iter()
+				.all(|parent| self.module.find(parent).is_some_and(|parent| self.is_struct(parent)))
+	}
+
+	fn import_interface(
+		&mut self,
+		declaration: &dts::Declaration,
+		extends: &[String],
+		members: &[dts::Member],
+	) -> TypeRef {
+		debug!("Importing interface {}", declaration.name);
+		let name = Symbol::global(&declaration.name);
+		let fqn = Some(format!("{}.{}", self.module.name, declaration.name));
+		let is_struct = self.is_struct(declaration);
+		let phase = if is_struct {
+			Phase::Independent
+		} else {
+			Phase::Preflight
+		};
+		let dummy_env = SymbolEnv::new(
+			None,
+			SymbolEnvKind::Type(self.wing_types.void()),
+			phase,
+			0,
+			self.module.name.clone(),
+		);
+		let docs = docs_of(&declaration.docs);
+
+		let mut wing_type = if is_struct {
+			self.wing_types.add_type(Type::Struct(Struct {
+				name: name.clone(),
+				fqn,
+				// Will be replaced below
+				extends: vec![],
+				docs,
+				// Will be replaced below
+				env: dummy_env,
+			}))
+		} else {
+			self.wing_types.add_type(Type::Interface(Interface {
+				name: name.clone(),
+				fqn,
+				// Will be replaced below
+				extends: vec![],
+				docs,
+				// Will be replaced below
+				env: dummy_env,
+				phase,
+			}))
+		};
+		self.imported.insert(declaration.name.clone(), Some(wing_type));
+
+		let parents = extends
+			.iter()
+			.map(|parent| self.import_named_type(parent))
+			.filter(|parent| {
+				if is_struct {
+					parent.as_struct().is_some()
+				} else {
+					parent.as_interface().is_some()
+				}
+			})
+			.collect::<Vec<_>>();
+
+		let mut iface_env = SymbolEnv::new(None, SymbolEnvKind::Type(wing_type), phase, 0, self.module.name.clone());
+		self.add_members_to_env(members, Phase::Independent, &mut iface_env, wing_type);
+
+		// Add properties from our parents to the new type's env
+		if is_struct {
+			if let Err(type_error) = type_check::add_parent_members_to_struct_env(&parents, &name, &mut iface_env) {
+				debug!(
+					"Failed to add parent members to struct {}: {}",
+					name, type_error.message
+				);
+			}
+		} else if let Err(type_error) = type_check::add_parent_members_to_iface_env(&parents, &name, &mut iface_env) {
+			debug!(
+				"Failed to add parent members to interface {}: {}",
+				name, type_error.message
+			);
+		}
+
+		match *wing_type {
+			Type::Struct(Struct {
+				ref mut extends,
+				ref mut env,
+				..
+			})
+			| Type::Interface(Interface {
+				ref mut extends,
+				ref mut env,
+				..
+			}) => {
+				*extends = parents;
+				*env = iface_env;
+			}
+			_ => panic!("Expected {} to be an interface or struct", name),
+		};
+		wing_type
+	}
+
+	fn import_class(
+		&mut self,
+		declaration: &dts::Declaration,
+		extends: &Option<String>,
+		implements: &[String],
+		is_abstract: bool,
+		constructor: &Constructor,
+		members: &[dts::Member],
+	) -> TypeRef {
+		debug!("Importing class {}", declaration.name);
+		let name = Symbol::global(&declaration.name);
+		let phase = Phase::Independent;
+
+		let parent = extends
+			.as_ref()
+			.map(|parent| self.import_named_type(parent))
+			.filter(|parent| parent.as_class().is_some());
+
+		let mut new_type = self.wing_types.add_type(Type::Class(Class {
+			name: name.clone(),
+			// Will be replaced below
+			env: SymbolEnv::new(
+				None,
+				SymbolEnvKind::Type(self.wing_types.void()),
+				phase,
+				0,
+				self.module.name.clone(),
+			),
+			fqn: Some(format!("{}.{}", self.module.name, declaration.name)),
+			parent,
+			// Will be replaced below
+			implements: vec![],
+			is_abstract,
+			phase,
+			defined_in_phase: Phase::Preflight,
+			docs: docs_of(&declaration.docs),
+			std_construct_args: false,
+			lifts: None,
+			// Imported types aren't code generated, so they don't need a unique id
+			uid: 0,
+		}));
+		self.imported.insert(declaration.name.clone(), Some(new_type));
+
+		new_type.as_class_mut().unwrap().implements = implements
+			.iter()
+			.map(|i| self.import_named_type(i))
+			.filter(|i| i.as_interface().is_some())
+			.collect();
+
+		let mut class_env = SymbolEnv::new(
+			parent.map(|p| p.as_class().unwrap().env.get_ref()),
+			SymbolEnvKind::Type(new_type),
+			phase,
+			0,
+			self.module.name.clone(),
+		);
+
+		// A class without a constructor is created like its parent class
+		let init_parameters = match constructor {
+			Constructor::Public(parameters) => Some(self.parameters_to_wing_types(parameters)),
+			Constructor::Private => None,
+			Constructor::Implicit => match parent {
+				Some(parent) => parent
+					.as_class()
+					.unwrap()
+					.get_method(&Symbol::global(CLASS_INIT_NAME))
+					.map(|init| init.type_.as_function_sig().unwrap().parameters.clone()),
+				None => Some(vec![]),
+			},
+		};
+		if let Some(parameters) = init_parameters {
+			let init_sig = self.wing_types.add_type(Type::Function(FunctionSignature {
+				this_type: None,
+				type_parameters: vec![],
+				parameters,
+				return_type: new_type,
+				phase,
+				js_override: None,
+				is_macro: false,
+				docs: Docs::default(),
+				implicit_scope_param: false,
+			}));
+			let sym = Symbol::global(CLASS_INIT_NAME);
+			class_env
+				.define(
+					&sym,
+					SymbolKind::make_member_variable(sym.clone(), init_sig, false, true, phase, AccessModifier::Public, None),
+					AccessModifier::Public,
+					StatementIdx::Top,
+				)
+				.expect("Failed to define class init");
+		}
+
+		self.add_members_to_env(members, phase, &mut class_env, new_type);
+
+		match *new_type {
+			Type::Class(ref mut class) => class.env = class_env,
+			_ => panic!("Expected {} to be a class", name),
+		};
+		new_type
+	}
+
+	fn import_enum(&mut self, declaration: &dts::Declaration, members: &[String]) -> TypeRef {
+		let enum_type = self.wing_types.add_type(Type::Enum(Enum {
+			name: Symbol::global(&declaration.name),
+			docs: docs_of(&declaration.docs),
+			values: members
+				.iter()
+				.map(|m| (Symbol::global(m), None))
+				.collect::<IndexMap<_, _>>(),
+		}));
+		self.imported.insert(declaration.name.clone(), Some(enum_type));
+		enum_type
+	}
+
+	fn add_members_to_env(
+		&mut self,
+		members: &[dts::Member],
+		member_phase: Phase,
+		env: &mut SymbolEnv,
+		wing_type: TypeRef,
+	) {
+		for member in members {
+			let docs = docs_of(&member.docs);
+			let (type_, reassignable) = match &member.kind {
+				MemberKind::Property {
+					type_,
+					optional,
+					readonly,
+				} => {
+					let mut type_ = self.type_ref_to_wing_type(type_);
+					if *optional {
+						type_ = self.wing_types.make_option(type_);
+					}
+					(type_, !readonly)
+				}
+				MemberKind::Method(function) => {
+					let mut sig = self.function_signature(function, member_phase, docs.clone());
+					sig.this_type = if member.is_static { None } else { Some(wing_type) };
+					(self.wing_types.add_type(Type::Function(sig)), false)
+				}
+			};
+			let sym = Symbol::global(&member.name);
+			// Members that conflict with the type's other members (or its parents) are skipped
+			let _ = env.define(
+				&sym,
+				SymbolKind::make_member_variable(
+					sym.clone(),
+					type_,
+					reassignable,
+					member.is_static,
+					member_phase,
+					AccessModifier::Public,
+					Some(docs),
+				),
+				AccessModifier::Public,
+				StatementIdx::Top,
+			);
+		}
+	}
+
+	fn type_ref_to_wing_type(&mut self, type_ref: &dts::TypeRef) -> TypeRef {
+		match type_ref {
+			dts::TypeRef::String => self.wing_types.string(),
+			dts::TypeRef::Number => self.wing_types.number(),
+			dts::TypeRef::Boolean => self.wing_types.bool(),
+			dts::TypeRef::Void => self.wing_types.void(),
+			dts::TypeRef::Any => self.wing_types.anything(),
+			dts::TypeRef::Nil => self.wing_types.nil(),
+			dts::TypeRef::Date => self.wing_types.jsii_date(),
+			dts::TypeRef::Array(element) => {
+				let element = self.type_ref_to_wing_type(element);
+				self.wing_types.add_type(Type::Array(element))
+			}
+			dts::TypeRef::Map(value) => {
+				let value = self.type_ref_to_wing_type(value);
+				self.wing_types.add_type(Type::Map(value))
+			}
+			dts::TypeRef::Union(members) => {
+				let members = members.iter().map(|m| self.type_ref_to_wing_type(m)).collect();
+				self.wing_types.make_union(members)
+			}
+			// TODO These should technically be "phase independent", but wing currently doesn't have a way to create
+			// a phase independent function, so it's more useful to pick one for now (like JSII callables).
+			dts::TypeRef::Function(function) => self.function_type_to_wing_type(function, Phase::Preflight, Docs::default()),
+			dts::TypeRef::Named(name) => self.import_named_type(name),
+		}
+	}
+
+	fn function_type_to_wing_type(&mut self, function: &dts::FunctionType, phase: Phase, docs: Docs) -> TypeRef {
+		let sig = self.function_signature(function, phase, docs);
+		self.wing_types.add_type(Type::Function(sig))
+	}
+
+	fn function_signature(&mut self, function: &dts::FunctionType, phase: Phase, docs: Docs) -> FunctionSignature {
+		FunctionSignature {
+			this_type: None,
+			type_parameters: vec![],
+			parameters: self.parameters_to_wing_types(&function.parameters),
+			return_type: self.type_ref_to_wing_type(&function.returns),
+			phase,
+			js_override: None,
+			is_macro: false,
+			docs,
+			implicit_scope_param: false,
+		}
+	}
+
+	fn parameters_to_wing_types(&mut self, parameters: &[dts::Parameter]) -> Vec<FunctionParameter> {
+		parameters
+			.iter()
+			.map(|parameter| {
+				let mut typeref = self.type_ref_to_wing_type(&parameter.type_);
+				// Rest parameters are typed as the array of arguments, unless their type can't be represented
+				if parameter.variadic && !matches!(*typeref, Type::Array(_)) {
+					typeref = self.wing_types.add_type(Type::Array(typeref));
+				}
+				if parameter.optional {
+					typeref = self.wing_types.make_option(typeref);
+				}
+				FunctionParameter {
+					name: parameter.name.clone(),
+					typeref,
+					docs: Docs::default(),
+					variadic: parameter.variadic,
+				}
+			})
+			.collect()
+	}
+}
+
+fn docs_of(docs: &Option<String>) -> Docs {
+	docs.as_deref().map(Docs::with_summary).unwrap_or_default()
+}
@@ -87,7 +87,8 @@ impl<'a> crate::visit_types::VisitType<'_> for InferenceVisitor<'a> {
 				| Type::Map(t)
 				| Type::MutMap(t)
 				| Type::Set(t)
-				| Type::MutSet(t) =>
+				| Type::MutSet(t)
+				| Type::Promise(t) =>
 				// If the type we're looking at is also a wrapper type, then we need to unwrap it
 				{
 					match &**node {
@@ -97,7 +98,8 @@ impl<'a> crate::visit_types::VisitType<'_> for InferenceVisitor<'a> {
 						| Type::Map(_)
 						| Type::MutMap(_)
 						| Type::Set(_)
-						| Type::MutSet(_) => {
+						| Type::MutSet(_)
+						| Type::Promise(_) => {
 							self.expected_type = Some(t);
 						}
 						_ => {}
@@ -122,6 +124,7 @@ impl<'a> crate::visit_types::VisitType<'_> for InferenceVisitor<'a> {
 				| Type::Struct(_)
 				| Type::Interface(_)
 				| Type::Enum(_)
+				| Type::Union(_)
 				| Type::Inferred(_) => {}
 			}
 		}
@@ -91,7 +91,7 @@ impl<'a> JsiiImporter<'a> {
 				PrimitiveType::Boolean => self.wing_types.bool(),
 				PrimitiveType::Any => self.wing_types.anything(),
 				PrimitiveType::Json => self.wing_types.json(),
-				PrimitiveType::Date => self.wing_types.anything(), // TODO: https://github.com/winglang/wing/issues/3569
+				PrimitiveType::Date => self.wing_types.jsii_date(),
 			},
 			TypeReference::NamedTypeReference(named_ref) => {
 				let type_fqn = &named_ref.fqn;
@@ -123,11 +123,14 @@ impl<'a> JsiiImporter<'a> {
 					// kind” is only either map or array.
 				}
 			}
-			TypeReference::UnionTypeReference(..) =>
-			// Wing does not support union types, so we'll model it the same way as if
-			// we saw an "any" in a JSII library
-			{
-				self.wing_types.anything()
+			TypeReference::UnionTypeReference(union_ref) => {
+				let members = union_ref
+					.union
+					.types
+					.iter()
+					.map(|member| self.type_ref_to_wing_type(member))
+					.collect();
+				self.wing_types.make_union(members)
 			}
 		}
 	}
@@ -328,6 +331,7 @@ impl<'a> JsiiImporter<'a> {
 		let wing_type = self.wing_types.add_type(Type::Function(FunctionSignature {
 			docs: Docs::from(&jsii_interface.docs),
 			this_type: None,
+			type_parameters: vec![],
 			parameters,
 			return_type,
 			phase,
@@ -581,6 +585,7 @@ impl<'a> JsiiImporter<'a> {
 				let method_sig = self.wing_types.add_type(Type::Function(FunctionSignature {
 					docs: Docs::from(&m.docs),
 					this_type,
+					type_parameters: vec![],
 					parameters: fn_params,
 					return_type,
 					phase: member_phase,
@@ -858,6 +863,7 @@ impl<'a> JsiiImporter<'a> {
 			}
 			let method_sig = self.wing_types.add_type(Type::Function(FunctionSignature {
 				this_type: None, // Initializers are considered static so they have no `this_type`
+				type_parameters: vec![],
 				parameters: fn_params,
 				return_type: new_type,
 				phase: member_phase,
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, HashMap};
 
 use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
 
 use crate::ast::{Symbol, UserDefinedType};
 
@@ -29,14 +30,14 @@ pub enum Liftable {
 }
 
 /// A record that describes a single lift from a method.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LiftQualification {
 	/// The operations that qualify the lift (the property names)
 	pub ops: IndexSet<String>,
 }
 
 /// A record that describes a lift from a class.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Capture {
 	/// Whether this is a field capture (`this.foo`)
 	pub is_field: bool,
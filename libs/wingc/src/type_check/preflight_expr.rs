@@ -0,0 +1,34 @@
+use crate::{
+	ast::{Expr, Phase},
+	diagnostic::WingSpan,
+	type_check::Types,
+	visit::{self, Visit},
+};
+
+/// Finds the first sub-expression that evaluates to a preflight value. Used where inflight code can't lift preflight
+/// values, like the initial values of inflight static fields.
+pub struct PreflightExprVisitor<'a> {
+	types: &'a Types,
+	pub found: Option<WingSpan>,
+}
+
+impl<'a> PreflightExprVisitor<'a> {
+	pub fn new(types: &'a Types) -> Self {
+		Self { types, found: None }
+	}
+}
+
+impl Visit<'_> for PreflightExprVisitor<'_> {
+	fn visit_expr(&mut self, node: &Expr) {
+		if self.found.is_some() {
+			return;
+		}
+
+		if self.types.get_expr_phase(node) == Some(Phase::Preflight) {
+			self.found = Some(node.span.clone());
+			return;
+		}
+
+		visit::visit_expr(self, node);
+	}
+}
@@ -0,0 +1,49 @@
+use crate::{
+	ast::{CalleeKind, Expr, ExprKind},
+	diagnostic::WingSpan,
+	type_check::Types,
+	visit::{self, Visit},
+};
+
+/// Finds the first sub-expression that can only be evaluated inside a construct scope, like creating a preflight object
+/// (or an inflight closure) or calling a static preflight method. The top level of module files has no scope.
+pub struct RequiresScopeVisitor<'a> {
+	types: &'a Types,
+	pub found: Option<WingSpan>,
+}
+
+impl<'a> RequiresScopeVisitor<'a> {
+	pub fn new(types: &'a Types) -> Self {
+		Self { types, found: None }
+	}
+}
+
+impl Visit<'_> for RequiresScopeVisitor<'_> {
+	fn visit_expr(&mut self, node: &Expr) {
+		if self.found.is_some() {
+			return;
+		}
+
+		let requires_scope = match &node.kind {
+			ExprKind::New(_) => self
+				.types
+				.try_get_expr_type(node.id)
+				.map_or(false, |t| t.is_preflight_class()),
+			ExprKind::Call {
+				callee: CalleeKind::Expr(callee),
+				..
+			} => self.types.try_get_expr_type(callee.id).map_or(false, |t| {
+				t.maybe_unwrap_option()
+					.as_deep_function_sig()
+					.map_or(false, |sig| sig.implicit_scope_param)
+			}),
+			_ => false,
+		};
+		if requires_scope {
+			self.found = Some(node.span.clone());
+			return;
+		}
+
+		visit::visit_expr(self, node);
+	}
+}
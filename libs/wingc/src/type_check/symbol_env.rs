@@ -32,7 +32,7 @@ pub struct SymbolEnv {
 
 	pub phase: Phase,
 	pub type_parameters: Option<Vec<TypeRef>>,
-	statement_idx: usize,
+	pub(crate) statement_idx: usize,
 
 	/// The source code package the environment is associated with
 	/// (for example, if the environment is for a class, this would be the package the class is defined in)
@@ -1,7 +1,8 @@
 use crate::ast::{
 	ArgList, BringSource, CalleeKind, Class, ElseIfs, Enum, Expr, ExprKind, FunctionBody, FunctionDefinition,
-	FunctionParameter, FunctionSignature, IfLet, Interface, InterpolatedStringPart, Literal, New, Reference, Scope, Stmt,
-	StmtKind, Struct, Symbol, TypeAnnotation, TypeAnnotationKind, UserDefinedType,
+	FunctionParameter, FunctionSignature, IfLet, Interface, InterpolatedStringPart, Literal, Match, MatchArmBody,
+	MatchPattern, MatchPatternKind, New, Reference, Scope, Stmt, StmtKind, Struct, Symbol, TypeAnnotation,
+	TypeAnnotationKind, UserDefinedType,
 };
 
 /// Visitor pattern inspired by implementation from https://docs.rs/syn/latest/syn/visit/index.html
@@ -54,6 +55,12 @@ pub trait Visit<'ast> {
 	fn visit_new_expr(&mut self, node: &'ast New) {
 		visit_new_expr(self, node);
 	}
+	fn visit_match(&mut self, node: &'ast Match) {
+		visit_match(self, node);
+	}
+	fn visit_match_pattern(&mut self, node: &'ast MatchPattern) {
+		visit_match_pattern(self, node);
+	}
 	fn visit_literal(&mut self, node: &'ast Literal) {
 		visit_literal(self, node);
 	}
@@ -115,6 +122,7 @@ where
 			var_name,
 			initial_value,
 			type_,
+			access: _,
 		} => {
 			v.visit_symbol(var_name);
 			if let Some(type_) = type_ {
@@ -141,10 +149,14 @@ where
 			statements,
 			reassignable: _,
 			var_name,
+			type_,
 			else_if_statements,
 			else_statements,
 		}) => {
 			v.visit_symbol(var_name);
+			if let Some(type_) = type_ {
+				v.visit_type_annotation(type_);
+			}
 			v.visit_expr(value);
 			v.visit_scope(statements);
 			for else_if in else_if_statements {
@@ -155,6 +167,9 @@ where
 					}
 					ElseIfs::ElseIfLetBlock(else_if_let_block) => {
 						v.visit_symbol(&else_if_let_block.var_name);
+						if let Some(type_) = &else_if_let_block.type_ {
+							v.visit_type_annotation(type_);
+						}
 						v.visit_expr(&else_if_let_block.value);
 						v.visit_scope(&else_if_let_block.statements);
 					}
@@ -180,6 +195,7 @@ where
 				v.visit_scope(statements);
 			}
 		}
+		StmtKind::Match(match_) => v.visit_match(match_),
 		StmtKind::Expression(expr) => v.visit_expr(&expr),
 		StmtKind::Assignment {
 			kind: _,
@@ -233,6 +249,9 @@ where
 	V: Visit<'ast> + ?Sized,
 {
 	v.visit_symbol(&node.name);
+	for type_parameter in &node.type_parameters {
+		v.visit_symbol(type_parameter);
+	}
 
 	v.visit_function_definition(&node.initializer);
 	v.visit_function_definition(&node.inflight_initializer);
@@ -240,6 +259,9 @@ where
 	for field in &node.fields {
 		v.visit_symbol(&field.name);
 		v.visit_type_annotation(&field.member_type);
+		if let Some(initial_value) = &field.initial_value {
+			v.visit_expr(initial_value);
+		}
 	}
 
 	for method in &node.methods {
@@ -261,6 +283,9 @@ where
 	V: Visit<'ast> + ?Sized,
 {
 	v.visit_symbol(&node.name);
+	for type_parameter in &node.type_parameters {
+		v.visit_symbol(type_parameter);
+	}
 	for extend in &node.extends {
 		v.visit_user_defined_type(&extend);
 	}
@@ -275,12 +300,20 @@ where
 	V: Visit<'ast> + ?Sized,
 {
 	v.visit_symbol(&node.name);
+	for type_parameter in &node.type_parameters {
+		v.visit_symbol(type_parameter);
+	}
 
 	for method in &node.methods {
 		v.visit_symbol(&method.0);
 		v.visit_function_signature(&method.1);
 	}
 
+	for field in &node.fields {
+		v.visit_symbol(&field.name);
+		v.visit_type_annotation(&field.member_type);
+	}
+
 	for extend in &node.extends {
 		v.visit_user_defined_type(extend);
 	}
@@ -397,6 +430,45 @@ where
 		ExprKind::FunctionClosure(def) => {
 			v.visit_function_definition(def);
 		}
+		ExprKind::Match(match_) => {
+			v.visit_match(match_);
+		}
+		ExprKind::Await(exp) | ExprKind::Defer(exp) => {
+			v.visit_expr(exp);
+		}
+	}
+}
+
+pub fn visit_match<'ast, V>(v: &mut V, node: &'ast Match)
+where
+	V: Visit<'ast> + ?Sized,
+{
+	v.visit_expr(&node.value);
+	for arm in &node.arms {
+		for pattern in &arm.patterns {
+			v.visit_match_pattern(pattern);
+		}
+		match &arm.body {
+			MatchArmBody::Statements(scope) => v.visit_scope(scope),
+			MatchArmBody::Expression(expr) => v.visit_expr(expr),
+		}
+	}
+}
+
+pub fn visit_match_pattern<'ast, V>(v: &mut V, node: &'ast MatchPattern)
+where
+	V: Visit<'ast> + ?Sized,
+{
+	match &node.kind {
+		MatchPatternKind::Wildcard => {}
+		MatchPatternKind::Binding(name) => v.visit_symbol(name),
+		MatchPatternKind::Value(value) => v.visit_expr(value),
+		MatchPatternKind::Struct(fields) => {
+			for (name, pattern) in fields {
+				v.visit_symbol(name);
+				v.visit_match_pattern(pattern);
+			}
+		}
 	}
 }
 
@@ -461,6 +533,9 @@ pub fn visit_function_signature<'ast, V>(v: &mut V, node: &'ast FunctionSignatur
 where
 	V: Visit<'ast> + ?Sized,
 {
+	for type_parameter in &node.type_parameters {
+		v.visit_symbol(type_parameter);
+	}
 	for param in &node.parameters {
 		v.visit_function_parameter(param);
 	}
@@ -501,6 +576,7 @@ where
 		TypeAnnotationKind::Datetime => {}
 		TypeAnnotationKind::Regex => {}
 		TypeAnnotationKind::Void => {}
+		TypeAnnotationKind::Any => {}
 		TypeAnnotationKind::Json => {}
 		TypeAnnotationKind::MutJson => {}
 		TypeAnnotationKind::Inferred => {}
@@ -511,6 +587,12 @@ where
 		TypeAnnotationKind::MutMap(t) => v.visit_type_annotation(t),
 		TypeAnnotationKind::Set(t) => v.visit_type_annotation(t),
 		TypeAnnotationKind::MutSet(t) => v.visit_type_annotation(t),
+		TypeAnnotationKind::Promise(t) => v.visit_type_annotation(t),
+		TypeAnnotationKind::Union(members) => {
+			for t in members {
+				v.visit_type_annotation(t);
+			}
+		}
 		TypeAnnotationKind::Function(f) => {
 			for param in &f.parameters {
 				v.visit_symbol(&param.name);
@@ -530,6 +612,9 @@ where
 	for field in &node.fields {
 		v.visit_symbol(field);
 	}
+	for type_argument in &node.type_arguments {
+		v.visit_type_annotation(type_argument);
+	}
 }
 
 pub fn visit_symbol<'ast, V>(_v: &mut V, _node: &'ast Symbol)
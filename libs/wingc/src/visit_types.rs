@@ -68,10 +68,17 @@ where
 		| Type::Map(node_unwrap([t]))
 		| Type::MutMap(node_unwrap([t]))
 		| Type::Set(node_unwrap([t]))
-		| Type::MutSet(node_unwrap([t])) => {
+		| Type::MutSet(node_unwrap([t]))
+		| Type::Promise(node_unwrap([t])) => {
 			v.visit_typeref(t);
 		}
 
+		Type::Union(node_unwrap([members])) => {
+			for member in members {
+				v.visit_typeref(member);
+			}
+		}
+
 		Type::Json(Some(node_unwrap([data]))) => {
 			v.visit_json(data);
 		}
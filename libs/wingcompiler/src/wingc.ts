@@ -12,10 +12,15 @@ export type WingCompilerFunction =
   | "wingc_on_completion"
   | "wingc_on_signature_help"
   | "wingc_on_goto_definition"
+  | "wingc_on_references"
+  | "wingc_on_document_highlight"
   | "wingc_on_document_symbol"
+  | "wingc_on_formatting"
   | "wingc_on_rename"
   | "wingc_on_prepare_rename"
   | "wingc_on_semantic_tokens"
+  | "wingc_on_semantic_tokens_range"
+  | "wingc_on_inlay_hint"
   | "wingc_on_hover"
   | "wingc_on_code_action";
 
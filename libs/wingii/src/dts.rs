@@ -0,0 +1,1509 @@
+//! Reads the TypeScript declarations (`.d.ts` files) of npm packages that don't come with a JSII assembly, so they
+//! can be brought into Wing like JSII modules.
+//!
+//! Only a subset of TypeScript is understood: interfaces, classes, functions, variables, enums and type aliases,
+//! typed with primitives, arrays, maps (`Record<string, T>` or index signatures), unions and function types.
+//! Declarations using other syntax are skipped, and types that can't be represented are read as `any`. Either way,
+//! a warning is added to the module, so unknown syntax never fails loading a package.
+
+use std::collections::HashSet;
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::Value;
+
+use crate::node_resolve::{resolve_package_export, TYPES_CONDITIONS};
+use crate::Result;
+
+/// The declarations of an npm package
+#[derive(Debug)]
+pub struct Module {
+	/// The name of the npm package
+	pub name: String,
+	pub declarations: Vec<Declaration>,
+	/// Syntax that was skipped or read as `any`, prefixed by its location (`path:line: message`)
+	pub warnings: Vec<String>,
+}
+
+impl Module {
+	pub fn find(&self, name: &str) -> Option<&Declaration> {
+		self.declarations.iter().find(|d| d.name == name)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct Declaration {
+	pub name: String,
+	pub docs: Option<String>,
+	/// Whether the package exports the declaration. Other declarations can only be referenced by exported ones.
+	pub exported: bool,
+	pub kind: DeclarationKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum DeclarationKind {
+	Interface {
+		extends: Vec<String>,
+		members: Vec<Member>,
+	},
+	Class {
+		extends: Option<String>,
+		implements: Vec<String>,
+		is_abstract: bool,
+		constructor: Constructor,
+		members: Vec<Member>,
+	},
+	Function(FunctionType),
+	Variable(TypeRef),
+	Enum(Vec<String>),
+	TypeAlias(TypeRef),
+	/// Another name for a declaration (`export { a as b }`)
+	Alias(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Constructor {
+	/// The class doesn't declare a constructor, so it's created like its parent class (or without arguments)
+	Implicit,
+	Public(Vec<Parameter>),
+	/// The class can't be created with `new`
+	Private,
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+	pub name: String,
+	pub docs: Option<String>,
+	pub is_static: bool,
+	pub kind: MemberKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum MemberKind {
+	Property {
+		type_: TypeRef,
+		optional: bool,
+		readonly: bool,
+	},
+	Method(FunctionType),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionType {
+	pub parameters: Vec<Parameter>,
+	pub returns: TypeRef,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+	pub name: String,
+	pub type_: TypeRef,
+	pub optional: bool,
+	/// A rest parameter (`...args: T[]`), its type is the array of arguments
+	pub variadic: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeRef {
+	String,
+	Number,
+	Boolean,
+	Void,
+	Any,
+	/// `undefined` or `null`
+	Nil,
+	Date,
+	Array(Box<TypeRef>),
+	/// `Record<string, T>` or `{ [key: string]: T }`
+	Map(Box<TypeRef>),
+	Union(Vec<TypeRef>),
+	Function(Box<FunctionType>),
+	/// A type declared in the package
+	Named(String),
+}
+
+/// Loads the declarations of the npm package in `module_directory`, starting from the entry point of its typings:
+/// the `types` (or `typings`) field of its package.json, the declaration file of its `main` file or `index.d.ts`.
+pub fn load_module(module_directory: &Utf8Path, package: &Value) -> Result<Module> {
+	let name = package
+		.get("name")
+		.and_then(|name| name.as_str())
+		.ok_or(format!("Missing package name in {}", module_directory))?;
+	let entrypoint = resolve_package_export(module_directory, ".", &TYPES_CONDITIONS)
+		.ok()
+		.and_then(|path| resolve_declaration_file(&path))
+		.or_else(|| {
+			["types", "typings", "main"]
+				.iter()
+				.filter_map(|field| package.get(field)?.as_str())
+				.chain(["index"])
+				.find_map(|path| resolve_declaration_file(&module_directory.join(path)))
+		})
+		.ok_or(format!(
+			"\"{}\" is not a JSII module and has no TypeScript declarations",
+			name
+		))?;
+
+	let mut visited = HashSet::new();
+	let mut warnings = vec![];
+	Ok(Module {
+		name: name.to_string(),
+		declarations: load_file(&entrypoint, &mut visited, &mut warnings)?,
+		warnings,
+	})
+}
+
+/// Finds the declaration file of a module path, like TypeScript does (`./foo` or `./foo.js` can be declared by
+/// `./foo.d.ts` or `./foo/index.d.ts`)
+fn resolve_declaration_file(path: &Utf8Path) -> Option<Utf8PathBuf> {
+	if path.as_str().ends_with(".d.ts") {
+		return path.is_file().then(|| path.to_path_buf());
+	}
+	let stem = [".js", ".cjs", ".mjs", ".ts"]
+		.iter()
+		.find_map(|ext| path.as_str().strip_suffix(ext))
+		.unwrap_or(path.as_str());
+	[format!("{stem}.d.ts"), format!("{stem}/index.d.ts")]
+		.into_iter()
+		.map(Utf8PathBuf::from)
+		.find(|path| path.is_file())
+}
+
+/// Reads the declarations of a file, and of the files it re-exports
+fn load_file(
+	path: &Utf8Path,
+	visited: &mut HashSet<Utf8PathBuf>,
+	warnings: &mut Vec<String>,
+) -> Result<Vec<Declaration>> {
+	if !visited.insert(path.to_path_buf()) {
+		return Ok(vec![]);
+	}
+	let file = parse(&fs::read_to_string(path)?);
+	let mut declarations = file.declarations;
+	warnings.extend(
+		file
+			.warnings
+			.into_iter()
+			.map(|(line, message)| format!("{}:{}: {}", path, line, message)),
+	);
+
+	let directory = path.parent().expect("a file is in a directory");
+	for reexport in file.reexports {
+		// Re-exports of other packages aren't supported
+		if !reexport.path.starts_with('.') {
+			continue;
+		}
+		// Drops the `.` components of the path, so warnings show the same path for a file however it's exported
+		let reexport_path = directory.join(&reexport.path).components().collect::<Utf8PathBuf>();
+		let Some(reexported_path) = resolve_declaration_file(&reexport_path) else {
+			continue;
+		};
+		let mut reexported = match load_file(&reexported_path, visited, warnings) {
+			Ok(reexported) => reexported,
+			Err(error) => {
+				warnings.push(format!("{}: cannot read \"{}\": {}", path, reexported_path, error));
+				continue;
+			}
+		};
+		if let Some(names) = reexport.names {
+			for declaration in &mut reexported {
+				declaration.exported = false;
+			}
+			export_names(&mut reexported, &names);
+		}
+		declarations.extend(reexported);
+	}
+	Ok(declarations)
+}
+
+/// Marks the declarations named in an export list (`export { a, b as c }`) as exported
+fn export_names(declarations: &mut Vec<Declaration>, names: &[(String, String)]) {
+	for (local, exported) in names {
+		let Some(declaration) = declarations.iter_mut().find(|d| &d.name == local) else {
+			continue;
+		};
+		if local == exported {
+			declaration.exported = true;
+		} else {
+			let alias = Declaration {
+				name: exported.clone(),
+				docs: declaration.docs.clone(),
+				exported: true,
+				kind: DeclarationKind::Alias(local.clone()),
+			};
+			declarations.push(alias);
+		}
+	}
+}
+
+/// The declarations of a single file
+#[derive(Default)]
+struct File {
+	declarations: Vec<Declaration>,
+	reexports: Vec<Reexport>,
+	/// Names exported with an export list (`export { a, b as c }`), as (local name, exported name) pairs
+	exports: Vec<(String, String)>,
+	/// Whether the file has an export list, in which case declarations are only exported if they're listed
+	has_export_list: bool,
+	/// Syntax that was skipped or read as `any`, by line
+	warnings: Vec<(usize, String)>,
+}
+
+/// `export * from "./path"` or `export { a, b as c } from "./path"`
+struct Reexport {
+	path: String,
+	/// The exported names, or `None` for all of them
+	names: Option<Vec<(String, String)>>,
+}
+
+fn parse(source: &str) -> File {
+	let mut parser = Parser {
+		tokens: tokenize(source),
+		pos: 0,
+		type_parameters: vec![],
+		warnings: vec![],
+	};
+	let mut file = File::default();
+	while parser.peek() != &Token::Eof {
+		let start = parser.pos;
+		let warning_count = parser.warnings.len();
+		parser.type_parameters.clear();
+		if parser.parse_statement(&mut file).is_none() {
+			parser.pos = start;
+			parser.warnings.truncate(warning_count);
+			let statement = parser.describe();
+			parser.warn(format!("unsupported declaration `{}` is skipped", statement));
+			parser.skip_statement();
+		}
+	}
+	file.warnings = parser.warnings;
+
+	// Without an export list, all the declarations of a declaration file are exported
+	if file.has_export_list {
+		let exports = std::mem::take(&mut file.exports);
+		export_names(&mut file.declarations, &exports);
+	} else {
+		for declaration in &mut file.declarations {
+			declaration.exported = true;
+		}
+	}
+	file
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	Ident(String),
+	Str(String),
+	Num(String),
+	Punct(char),
+	/// `=>`
+	Arrow,
+	/// `...`
+	Spread,
+	Eof,
+}
+
+/// Splits the source into tokens, each with the summary of the doc comment preceding it (if any) and its line
+fn tokenize(source: &str) -> Vec<(Token, Option<String>, usize)> {
+	let chars = source.chars().collect::<Vec<_>>();
+	let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+	let mut tokens = vec![];
+	let mut docs = None;
+	// Lines are counted up to the start of the last token
+	let mut line = 1;
+	let mut line_counted_to = 0;
+	let mut i = 0;
+	while i < chars.len() {
+		let c = chars[i];
+		let token_start = i;
+		let next = chars.get(i + 1).copied();
+		let token = if c.is_whitespace() {
+			i += 1;
+			continue;
+		} else if c == '/' && next == Some('/') {
+			while i < chars.len() && chars[i] != '\n' {
+				i += 1;
+			}
+			continue;
+		} else if c == '/' && next == Some('*') {
+			let start = i + 2;
+			i = start;
+			while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+				i += 1;
+			}
+			let comment = chars[start..i].iter().collect::<String>();
+			if let Some(doc) = comment.strip_prefix('*') {
+				docs = doc_summary(doc);
+			}
+			i += 2;
+			continue;
+		} else if is_ident_char(c) && !c.is_ascii_digit() || c == '#' {
+			let start = i;
+			i += 1;
+			while i < chars.len() && is_ident_char(chars[i]) {
+				i += 1;
+			}
+			Token::Ident(chars[start..i].iter().collect())
+		} else if c.is_ascii_digit() {
+			let start = i;
+			while i < chars.len() && (is_ident_char(chars[i]) || chars[i] == '.') {
+				i += 1;
+			}
+			Token::Num(chars[start..i].iter().collect())
+		} else if c == '"' || c == '\'' || c == '`' {
+			let start = i + 1;
+			i = start;
+			while i < chars.len() && chars[i] != c {
+				i += if chars[i] == '\\' { 2 } else { 1 };
+			}
+			let value = chars[start..i.min(chars.len())].iter().collect();
+			i += 1;
+			Token::Str(value)
+		} else if c == '=' && next == Some('>') {
+			i += 2;
+			Token::Arrow
+		} else if c == '.' && next == Some('.') && chars.get(i + 2) == Some(&'.') {
+			i += 3;
+			Token::Spread
+		} else {
+			i += 1;
+			Token::Punct(c)
+		};
+		line += chars[line_counted_to..token_start]
+			.iter()
+			.filter(|c| **c == '\n')
+			.count();
+		line_counted_to = token_start;
+		tokens.push((token, docs.take(), line));
+	}
+	tokens
+}
+
+/// The first paragraph of a doc comment's text (without the leading `*` of each line), up to its first tag
+fn doc_summary(comment: &str) -> Option<String> {
+	let mut lines = vec![];
+	for line in comment.lines() {
+		let line = line.trim().trim_start_matches('*').trim();
+		if line.starts_with('@') || (line.is_empty() && !lines.is_empty()) {
+			break;
+		}
+		if !line.is_empty() {
+			lines.push(line);
+		}
+	}
+	(!lines.is_empty()).then(|| lines.join(" "))
+}
+
+/// A recursive descent parser for declaration files. Parsing functions return `None` when they find syntax they
+/// don't understand, and the statement (or class member) being parsed is skipped.
+struct Parser {
+	tokens: Vec<(Token, Option<String>, usize)>,
+	pos: usize,
+	/// Names of the type parameters in scope, references to them are read as `any`
+	type_parameters: Vec<String>,
+	/// Syntax that was skipped or read as `any`, by line
+	warnings: Vec<(usize, String)>,
+}
+
+impl Parser {
+	fn peek_at(&self, offset: usize) -> &Token {
+		self
+			.tokens
+			.get(self.pos + offset)
+			.map_or(&Token::Eof, |(token, _, _)| token)
+	}
+
+	fn peek(&self) -> &Token {
+		self.peek_at(0)
+	}
+
+	fn docs(&self) -> Option<String> {
+		self.tokens.get(self.pos).and_then(|(_, docs, _)| docs.clone())
+	}
+
+	/// The line of the current token
+	fn line(&self) -> usize {
+		self
+			.tokens
+			.get(self.pos)
+			.or(self.tokens.last())
+			.map_or(1, |(_, _, line)| *line)
+	}
+
+	/// Adds a warning at the line of the current token
+	fn warn(&mut self, message: String) {
+		self.warnings.push((self.line(), message));
+	}
+
+	/// Adds a warning for a type that can't be represented (found at `line`), returning `any` for it
+	fn unsupported_type(&mut self, line: usize, kind: &str) -> TypeRef {
+		self.warnings.push((
+			line,
+			format!("{} can't be represented in Wing and is read as `any`", kind),
+		));
+		TypeRef::Any
+	}
+
+	/// The first tokens of the statement or member at the current position, to show where it starts
+	fn describe(&self) -> String {
+		let mut words = vec![];
+		for offset in 0..4 {
+			let word = match self.peek_at(offset) {
+				Token::Ident(word) | Token::Num(word) => word.clone(),
+				Token::Str(value) => format!("\"{}\"", value),
+				Token::Punct(c) if !matches!(c, '{' | '}' | ';') => c.to_string(),
+				Token::Arrow => "=>".to_string(),
+				Token::Spread => "...".to_string(),
+				_ => break,
+			};
+			words.push(word);
+		}
+		words.join(" ")
+	}
+
+	fn next(&mut self) -> Token {
+		let token = self.peek().clone();
+		self.pos = (self.pos + 1).min(self.tokens.len());
+		token
+	}
+
+	fn is_punct(&self, c: char) -> bool {
+		self.peek() == &Token::Punct(c)
+	}
+
+	fn is_keyword(&self, keyword: &str) -> bool {
+		matches!(self.peek(), Token::Ident(word) if word == keyword)
+	}
+
+	fn eat(&mut self, token: Token) -> bool {
+		let found = self.peek() == &token;
+		if found {
+			self.pos += 1;
+		}
+		found
+	}
+
+	fn eat_punct(&mut self, c: char) -> bool {
+		self.eat(Token::Punct(c))
+	}
+
+	fn eat_keyword(&mut self, keyword: &str) -> bool {
+		self.eat(Token::Ident(keyword.to_string()))
+	}
+
+	fn expect_punct(&mut self, c: char) -> Option<()> {
+		self.eat_punct(c).then_some(())
+	}
+
+	fn ident(&mut self) -> Option<String> {
+		match self.next() {
+			Token::Ident(name) => Some(name),
+			_ => None,
+		}
+	}
+
+	/// Skips the rest of a statement: up to its `;`, or the closing brace of its body
+	fn skip_statement(&mut self) {
+		let mut depth = 0;
+		loop {
+			match self.next() {
+				Token::Eof => return,
+				Token::Punct(';') if depth <= 0 => return,
+				Token::Punct('{' | '(' | '[') => depth += 1,
+				Token::Punct(')' | ']') => depth -= 1,
+				Token::Punct('}') => {
+					depth -= 1;
+					if depth <= 0 {
+						self.eat_punct(';');
+						return;
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	/// Skips the rest of a class or interface member, stopping before the closing brace of the body
+	fn skip_member(&mut self) {
+		let mut depth = 0;
+		loop {
+			match self.peek() {
+				Token::Eof => return,
+				Token::Punct('}') if depth <= 0 => return,
+				Token::Punct(';' | ',') if depth <= 0 => {
+					self.pos += 1;
+					return;
+				}
+				Token::Punct('{' | '(' | '[' | '<') => depth += 1,
+				Token::Punct('}' | ')' | ']' | '>') => depth -= 1,
+				_ => {}
+			}
+			self.pos += 1;
+		}
+	}
+
+	/// Skips a group of tokens in brackets (like an object type, a tuple or a destructuring pattern)
+	fn skip_brackets(&mut self) {
+		let mut depth = 0;
+		loop {
+			match self.next() {
+				Token::Eof => return,
+				Token::Punct('{' | '(' | '[' | '<') => depth += 1,
+				Token::Punct('}' | ')' | ']' | '>') => {
+					depth -= 1;
+					if depth <= 0 {
+						return;
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	fn parse_statement(&mut self, file: &mut File) -> Option<()> {
+		let docs = self.docs();
+		if self.eat_punct(';') {
+			return Some(());
+		}
+		if self.is_keyword("import") {
+			self.skip_statement();
+			return Some(());
+		}
+
+		let exported = self.eat_keyword("export");
+		if exported {
+			if self.eat_punct('*') {
+				if !self.eat_keyword("from") {
+					return None;
+				}
+				let Token::Str(path) = self.next() else {
+					return None;
+				};
+				self.eat_punct(';');
+				file.reexports.push(Reexport { path, names: None });
+				return Some(());
+			}
+			if self.eat_punct('{') {
+				let names = self.export_list()?;
+				if self.eat_keyword("from") {
+					let Token::Str(path) = self.next() else {
+						return None;
+					};
+					file.reexports.push(Reexport {
+						path,
+						names: Some(names),
+					});
+				} else {
+					file.has_export_list = true;
+					file.exports.extend(names);
+				}
+				self.eat_punct(';');
+				return Some(());
+			}
+		}
+
+		self.eat_keyword("declare");
+		let line = self.line();
+		let (name, kind) = match self.ident()?.as_str() {
+			"interface" => self.parse_interface()?,
+			"class" => self.parse_class(false)?,
+			"abstract" if self.eat_keyword("class") => self.parse_class(true)?,
+			"function" => self.parse_function()?,
+			"const" | "let" | "var" => self.parse_variable()?,
+			"enum" => self.parse_enum()?,
+			"type" => self.parse_type_alias()?,
+			_ => return None,
+		};
+		let is_overload = matches!(kind, DeclarationKind::Function(_))
+			&& file
+				.declarations
+				.iter()
+				.any(|d| d.name == name && matches!(d.kind, DeclarationKind::Function(_)));
+		if is_overload {
+			self
+				.warnings
+				.push((line, format!("Only the first overload of `{}` is used", name)));
+			return Some(());
+		}
+		file.declarations.push(Declaration {
+			name,
+			docs,
+			exported,
+			kind,
+		});
+		Some(())
+	}
+
+	/// Parses the names of an export list (`a, b as c }`) as (local name, exported name) pairs
+	fn export_list(&mut self) -> Option<Vec<(String, String)>> {
+		let mut names = vec![];
+		while !self.eat_punct('}') {
+			// `export { type Foo }`
+			if self.is_keyword("type") && matches!(self.peek_at(1), Token::Ident(name) if name != "as") {
+				self.pos += 1;
+			}
+			let local = self.ident()?;
+			let exported = if self.eat_keyword("as") {
+				self.ident()?
+			} else {
+				local.clone()
+			};
+			names.push((local, exported));
+			if !self.eat_punct(',') {
+				self.expect_punct('}')?;
+				break;
+			}
+		}
+		Some(names)
+	}
+
+	fn parse_interface(&mut self) -> Option<(String, DeclarationKind)> {
+		let name = self.ident()?;
+		self.type_parameters()?;
+		let extends = if self.eat_keyword("extends") {
+			self.heritage_list()?
+		} else {
+			vec![]
+		};
+		let (_, members) = self.parse_members()?;
+		Some((name, DeclarationKind::Interface { extends, members }))
+	}
+
+	fn parse_class(&mut self, is_abstract: bool) -> Option<(String, DeclarationKind)> {
+		let name = self.ident()?;
+		self.type_parameters()?;
+		let extends = if self.eat_keyword("extends") {
+			self.heritage_list()?.into_iter().next()
+		} else {
+			None
+		};
+		let implements = if self.eat_keyword("implements") {
+			self.heritage_list()?
+		} else {
+			vec![]
+		};
+		let (constructor, members) = self.parse_members()?;
+		Some((
+			name,
+			DeclarationKind::Class {
+				extends,
+				implements,
+				is_abstract,
+				constructor,
+				members,
+			},
+		))
+	}
+
+	/// Parses the names of the types in an `extends` or `implements` clause
+	fn heritage_list(&mut self) -> Option<Vec<String>> {
+		let mut names = vec![];
+		loop {
+			if let TypeRef::Named(name) = self.parse_type()? {
+				names.push(name);
+			}
+			if !self.eat_punct(',') {
+				return Some(names);
+			}
+		}
+	}
+
+	/// Parses the body of a class or interface
+	fn parse_members(&mut self) -> Option<(Constructor, Vec<Member>)> {
+		self.expect_punct('{')?;
+		let mut constructor = Constructor::Implicit;
+		let mut members = vec![];
+		while !self.eat_punct('}') {
+			if self.peek() == &Token::Eof {
+				return None;
+			}
+			let start = self.pos;
+			let type_parameter_count = self.type_parameters.len();
+			let warning_count = self.warnings.len();
+			if self.parse_member(&mut constructor, &mut members).is_none() {
+				self.pos = start;
+				self.warnings.truncate(warning_count);
+				let member = self.describe();
+				self.warn(format!("unsupported member `{}` is skipped", member));
+				self.skip_member();
+			}
+			self.type_parameters.truncate(type_parameter_count);
+		}
+		Some((constructor, members))
+	}
+
+	fn parse_member(&mut self, constructor: &mut Constructor, members: &mut Vec<Member>) -> Option<()> {
+		let docs = self.docs();
+		let line = self.line();
+		let mut is_static = false;
+		let mut readonly = false;
+		let mut private = false;
+		// Modifiers are followed by the member's name (otherwise they're the name, like in `readonly: boolean`)
+		while matches!(self.peek_at(1), Token::Ident(_) | Token::Str(_) | Token::Punct('[')) {
+			match self.peek() {
+				Token::Ident(word) if word == "static" => is_static = true,
+				Token::Ident(word) if word == "readonly" => readonly = true,
+				Token::Ident(word) if word == "private" || word == "protected" => private = true,
+				Token::Ident(word) if ["public", "abstract", "declare", "override", "accessor"].contains(&word.as_str()) => {}
+				_ => break,
+			}
+			self.pos += 1;
+		}
+		let accessor = match (self.peek(), self.peek_at(1)) {
+			(Token::Ident(word), Token::Ident(_) | Token::Str(_)) if word == "get" || word == "set" => self.ident(),
+			_ => None,
+		};
+
+		// Index, call and construct signatures can't be represented
+		let signature = match self.peek() {
+			Token::Punct('[') => Some("An index signature"),
+			Token::Punct('(' | '<') => Some("A call signature"),
+			Token::Ident(word) if word == "new" && matches!(self.peek_at(1), Token::Punct('(' | '<')) => {
+				Some("A construct signature")
+			}
+			_ => None,
+		};
+		if let Some(signature) = signature {
+			self.warn(format!("{} can't be represented in Wing and is skipped", signature));
+			self.skip_member();
+			return Some(());
+		}
+
+		let name = match self.next() {
+			Token::Ident(name) | Token::Str(name) | Token::Num(name) => name,
+			_ => return None,
+		};
+		if name == "constructor" && self.is_punct('(') {
+			let parameters = self.parameters()?;
+			self.eat_punct(';');
+			*constructor = if private {
+				Constructor::Private
+			} else {
+				Constructor::Public(parameters)
+			};
+			return Some(());
+		}
+
+		let optional = self.eat_punct('?');
+		let kind = if self.is_punct('(') || self.is_punct('<') {
+			self.type_parameters()?;
+			let parameters = self.parameters()?;
+			let returns = if self.eat_punct(':') {
+				self.parse_return_type()?
+			} else {
+				TypeRef::Any
+			};
+			match accessor.as_deref() {
+				Some("get") => MemberKind::Property {
+					type_: returns,
+					optional,
+					readonly: true,
+				},
+				Some(_) => MemberKind::Property {
+					type_: parameters.into_iter().next().map_or(TypeRef::Any, |p| p.type_),
+					optional,
+					readonly: false,
+				},
+				None => MemberKind::Method(FunctionType { parameters, returns }),
+			}
+		} else {
+			let type_ = if self.eat_punct(':') {
+				self.parse_type()?
+			} else {
+				TypeRef::Any
+			};
+			MemberKind::Property {
+				type_,
+				optional,
+				readonly,
+			}
+		};
+		if !self.eat_punct(';') {
+			self.eat_punct(',');
+		}
+
+		if private || name.starts_with('#') {
+			return Some(());
+		}
+		if let Some(existing) = members.iter_mut().find(|m| m.name == name && m.is_static == is_static) {
+			// A getter and a setter make a writable property. Otherwise, only the first overload of a method is kept.
+			if let (MemberKind::Property { readonly, .. }, Some(_)) = (&mut existing.kind, &accessor) {
+				*readonly = false;
+			} else {
+				self
+					.warnings
+					.push((line, format!("Only the first overload of `{}` is used", name)));
+			}
+			return Some(());
+		}
+		members.push(Member {
+			name,
+			docs,
+			is_static,
+			kind,
+		});
+		Some(())
+	}
+
+	fn parse_function(&mut self) -> Option<(String, DeclarationKind)> {
+		let name = self.ident()?;
+		self.type_parameters()?;
+		let parameters = self.parameters()?;
+		let returns = if self.eat_punct(':') {
+			self.parse_return_type()?
+		} else {
+			TypeRef::Any
+		};
+		self.eat_punct(';');
+		Some((name, DeclarationKind::Function(FunctionType { parameters, returns })))
+	}
+
+	fn parse_variable(&mut self) -> Option<(String, DeclarationKind)> {
+		let name = self.ident()?;
+		self.expect_punct(':')?;
+		let type_ = self.parse_type()?;
+		self.expect_punct(';')?;
+		Some((name, DeclarationKind::Variable(type_)))
+	}
+
+	fn parse_enum(&mut self) -> Option<(String, DeclarationKind)> {
+		let name = self.ident()?;
+		self.expect_punct('{')?;
+		let mut members = vec![];
+		while !self.eat_punct('}') {
+			match self.next() {
+				Token::Ident(member) | Token::Str(member) => members.push(member),
+				_ => return None,
+			}
+			// Skip the member's value
+			while !self.is_punct(',') && !self.is_punct('}') {
+				if self.next() == Token::Eof {
+					return None;
+				}
+			}
+			self.eat_punct(',');
+		}
+		Some((name, DeclarationKind::Enum(members)))
+	}
+
+	fn parse_type_alias(&mut self) -> Option<(String, DeclarationKind)> {
+		let name = self.ident()?;
+		self.type_parameters()?;
+		self.expect_punct('=')?;
+		let type_ = self.parse_type()?;
+		self.eat_punct(';');
+		Some((name, DeclarationKind::TypeAlias(type_)))
+	}
+
+	/// Parses optional type parameters (`<T, U extends X = Y>`) and brings them into scope
+	fn type_parameters(&mut self) -> Option<()> {
+		if !self.eat_punct('<') {
+			return Some(());
+		}
+		loop {
+			let name = self.ident()?;
+			if self.eat_keyword("extends") {
+				self.parse_type()?;
+			}
+			if self.eat_punct('=') {
+				self.parse_type()?;
+			}
+			self.type_parameters.push(name);
+			if !self.eat_punct(',') {
+				return self.expect_punct('>');
+			}
+		}
+	}
+
+	/// Parses type arguments (`<A, B>`), if any
+	fn type_arguments(&mut self) -> Option<Vec<TypeRef>> {
+		let mut arguments = vec![];
+		if self.eat_punct('<') {
+			loop {
+				arguments.push(self.parse_type()?);
+				if !self.eat_punct(',') {
+					self.expect_punct('>')?;
+					break;
+				}
+			}
+		}
+		Some(arguments)
+	}
+
+	fn parameters(&mut self) -> Option<Vec<Parameter>> {
+		self.expect_punct('(')?;
+		let mut parameters = vec![];
+		while !self.eat_punct(')') {
+			// Modifiers of constructor parameters that declare properties
+			while matches!(self.peek_at(1), Token::Ident(_))
+				&& ["public", "private", "protected", "readonly"]
+					.iter()
+					.any(|modifier| self.is_keyword(modifier))
+			{
+				self.pos += 1;
+			}
+			let variadic = self.eat(Token::Spread);
+			let name = if self.is_punct('{') || self.is_punct('[') {
+				// A destructured parameter has no name
+				self.skip_brackets();
+				format!("arg{}", parameters.len())
+			} else {
+				self.ident()?
+			};
+			let optional = self.eat_punct('?');
+			let type_ = if self.eat_punct(':') {
+				self.parse_type()?
+			} else {
+				TypeRef::Any
+			};
+			// `this` is the type of the object a function is called on, not a parameter
+			if name != "this" {
+				parameters.push(Parameter {
+					name,
+					type_,
+					optional,
+					variadic,
+				});
+			}
+			if !self.eat_punct(',') {
+				self.expect_punct(')')?;
+				break;
+			}
+		}
+		Some(parameters)
+	}
+
+	/// Parses a function's return type, which can also be a type predicate (`x is string`)
+	fn parse_return_type(&mut self) -> Option<TypeRef> {
+		if self.is_keyword("asserts") && matches!(self.peek_at(1), Token::Ident(_)) {
+			self.pos += 2;
+			if self.eat_keyword("is") {
+				self.parse_type()?;
+			}
+			return Some(TypeRef::Void);
+		}
+		if matches!(self.peek(), Token::Ident(_)) && matches!(self.peek_at(1), Token::Ident(word) if word == "is") {
+			self.pos += 2;
+			self.parse_type()?;
+			return Some(TypeRef::Boolean);
+		}
+		self.parse_type()
+	}
+
+	fn parse_type(&mut self) -> Option<TypeRef> {
+		let line = self.line();
+		let type_ = self.parse_union()?;
+		if self.eat_keyword("extends") {
+			self.parse_union()?;
+			self.expect_punct('?')?;
+			self.parse_type()?;
+			self.expect_punct(':')?;
+			self.parse_type()?;
+			return Some(self.unsupported_type(line, "A conditional type"));
+		}
+		Some(type_)
+	}
+
+	fn parse_union(&mut self) -> Option<TypeRef> {
+		self.eat_punct('|');
+		let mut types = vec![self.parse_intersection()?];
+		while self.eat_punct('|') {
+			types.push(self.parse_intersection()?);
+		}
+		Some(if types.len() == 1 {
+			types.remove(0)
+		} else {
+			TypeRef::Union(types)
+		})
+	}
+
+	/// Intersections can't be represented, so only a single type is parsed as is
+	fn parse_intersection(&mut self) -> Option<TypeRef> {
+		let line = self.line();
+		self.eat_punct('&');
+		let type_ = self.parse_postfix()?;
+		if !self.is_punct('&') {
+			return Some(type_);
+		}
+		while self.eat_punct('&') {
+			self.parse_postfix()?;
+		}
+		Some(self.unsupported_type(line, "An intersection type"))
+	}
+
+	fn parse_postfix(&mut self) -> Option<TypeRef> {
+		let mut type_ = self.parse_primary()?;
+		while self.is_punct('[') {
+			if self.peek_at(1) == &Token::Punct(']') {
+				self.pos += 2;
+				type_ = TypeRef::Array(Box::new(type_));
+			} else {
+				let line = self.line();
+				self.skip_brackets();
+				type_ = self.unsupported_type(line, "An indexed access type");
+			}
+		}
+		Some(type_)
+	}
+
+	fn parse_primary(&mut self) -> Option<TypeRef> {
+		match self.peek().clone() {
+			Token::Str(_) => {
+				self.pos += 1;
+				Some(TypeRef::String)
+			}
+			Token::Num(_) => {
+				self.pos += 1;
+				Some(TypeRef::Number)
+			}
+			Token::Punct('-') if matches!(self.peek_at(1), Token::Num(_)) => {
+				self.pos += 2;
+				Some(TypeRef::Number)
+			}
+			Token::Punct('(') if self.is_function_type() => self.parse_function_type(),
+			Token::Punct('(') => {
+				self.pos += 1;
+				let type_ = self.parse_type()?;
+				self.expect_punct(')')?;
+				Some(type_)
+			}
+			Token::Punct('<') => {
+				self.type_parameters()?;
+				self.parse_function_type()
+			}
+			Token::Punct('{') => self.parse_object_type(),
+			Token::Punct('[') => {
+				let line = self.line();
+				self.skip_brackets();
+				Some(self.unsupported_type(line, "A tuple type"))
+			}
+			Token::Ident(word) => {
+				self.pos += 1;
+				self.parse_named_type(word)
+			}
+			_ => None,
+		}
+	}
+
+	/// Whether the parenthesis at the current position starts a function type (rather than a parenthesized type)
+	fn is_function_type(&self) -> bool {
+		match (self.peek_at(1), self.peek_at(2)) {
+			(Token::Punct(')'), _) | (Token::Spread, _) => true,
+			(Token::Ident(_), Token::Punct(':' | ',' | '?')) => true,
+			(Token::Ident(_), Token::Punct(')')) => self.peek_at(3) == &Token::Arrow,
+			_ => false,
+		}
+	}
+
+	fn parse_function_type(&mut self) -> Option<TypeRef> {
+		let parameters = self.parameters()?;
+		if self.next() != Token::Arrow {
+			return None;
+		}
+		let returns = self.parse_return_type()?;
+		Some(TypeRef::Function(Box::new(FunctionType { parameters, returns })))
+	}
+
+	/// Object types are only understood as maps (`{ [key: string]: T }`)
+	fn parse_object_type(&mut self) -> Option<TypeRef> {
+		let start = self.pos;
+		if let (
+			Token::Punct('['),
+			Token::Ident(_),
+			Token::Punct(':'),
+			Token::Ident(key),
+			Token::Punct(']'),
+			Token::Punct(':'),
+		) = (
+			self.peek_at(1),
+			self.peek_at(2),
+			self.peek_at(3),
+			self.peek_at(4),
+			self.peek_at(5),
+			self.peek_at(6),
+		) {
+			if key == "string" {
+				self.pos += 7;
+				let value = self.parse_type()?;
+				if !self.eat_punct(';') {
+					self.eat_punct(',');
+				}
+				if self.eat_punct('}') {
+					return Some(TypeRef::Map(Box::new(value)));
+				}
+			}
+		}
+		self.pos = start;
+		let line = self.line();
+		let kind = if matches!(self.peek_at(3), Token::Ident(word) if word == "in") && self.peek_at(1) == &Token::Punct('[')
+		{
+			"A mapped type"
+		} else {
+			"An object type"
+		};
+		self.skip_brackets();
+		Some(self.unsupported_type(line, kind))
+	}
+
+	fn parse_named_type(&mut self, word: String) -> Option<TypeRef> {
+		let line = self.line();
+		let type_ = match word.as_str() {
+			"string" => TypeRef::String,
+			"number" => TypeRef::Number,
+			"boolean" | "true" | "false" => TypeRef::Boolean,
+			"void" => TypeRef::Void,
+			"undefined" | "null" => TypeRef::Nil,
+			"any" | "unknown" | "object" | "never" | "symbol" | "bigint" | "this" => TypeRef::Any,
+			// `readonly T[]`
+			"readonly" => return self.parse_postfix(),
+			"keyof" | "unique" => {
+				self.parse_postfix()?;
+				self.unsupported_type(line, &format!("A `{}` type", word))
+			}
+			"typeof" => {
+				self.qualified_name(String::new())?;
+				// `typeof import("module")`
+				if self.is_punct('(') {
+					self.skip_brackets();
+				}
+				self.unsupported_type(line, "A `typeof` type")
+			}
+			"infer" => {
+				self.ident()?;
+				TypeRef::Any
+			}
+			"new" => {
+				self.type_parameters()?;
+				self.parse_function_type()?;
+				self.unsupported_type(line, "A constructor type")
+			}
+			_ => {
+				let name = self.qualified_name(word)?;
+				let arguments = self.type_arguments()?;
+				match (name.as_str(), arguments.as_slice()) {
+					_ if self.type_parameters.contains(&name) => TypeRef::Any,
+					("Array" | "ReadonlyArray", [element]) => TypeRef::Array(Box::new(element.clone())),
+					("Record", [TypeRef::String, value]) => TypeRef::Map(Box::new(value.clone())),
+					("Date", []) => TypeRef::Date,
+					("String", []) => TypeRef::String,
+					("Number", []) => TypeRef::Number,
+					("Boolean", []) => TypeRef::Boolean,
+					_ => TypeRef::Named(name),
+				}
+			}
+		};
+		Some(type_)
+	}
+
+	/// Parses the rest of a dotted name (`a.b.c`), starting with `first`. An empty `first` reads the whole name.
+	fn qualified_name(&mut self, first: String) -> Option<String> {
+		let mut name = if first.is_empty() { self.ident()? } else { first };
+		while self.eat_punct('.') {
+			name.push('.');
+			name.push_str(&self.ident()?);
+		}
+		Some(name)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn declaration<'a>(file: &'a File, name: &str) -> &'a Declaration {
+		file
+			.declarations
+			.iter()
+			.find(|d| d.name == name)
+			.expect("declaration not found")
+	}
+
+	#[test]
+	fn test_parse_declarations() {
+		let file = parse(
+			r#"
+import { Other } from "other";
+
+/**
+ * Adds two numbers.
+ *
+ * @param a the first number
+ */
+export declare function add(a: number, b?: number, ...rest: number[]): number;
+export declare function add(a: string): string;
+export declare const version: string;
+export declare enum Color {
+	Red = "red",
+	Green = 1,
+}
+export type Callback = (error: Error | undefined, value?: string) => void;
+export type Names = Record<string, string[]> | { [key: string]: readonly string[] } | null;
+export declare function get<T>(key: keyof T): T | undefined;
+"#,
+		);
+		let DeclarationKind::Function(add) = &declaration(&file, "add").kind else {
+			panic!("expected a function")
+		};
+		assert_eq!(declaration(&file, "add").docs.as_deref(), Some("Adds two numbers."));
+		assert_eq!(add.returns, TypeRef::Number);
+		assert_eq!(add.parameters.len(), 3);
+		assert!(add.parameters[1].optional);
+		assert!(add.parameters[2].variadic);
+		assert_eq!(add.parameters[2].type_, TypeRef::Array(Box::new(TypeRef::Number)));
+
+		assert!(matches!(
+			&declaration(&file, "version").kind,
+			DeclarationKind::Variable(TypeRef::String)
+		));
+		assert!(
+			matches!(&declaration(&file, "Color").kind, DeclarationKind::Enum(members) if members == &["Red", "Green"])
+		);
+
+		let DeclarationKind::TypeAlias(TypeRef::Function(callback)) = &declaration(&file, "Callback").kind else {
+			panic!("expected a function type")
+		};
+		assert_eq!(
+			callback.parameters[0].type_,
+			TypeRef::Union(vec![TypeRef::Named("Error".to_string()), TypeRef::Nil])
+		);
+		assert_eq!(callback.returns, TypeRef::Void);
+
+		let names = TypeRef::Map(Box::new(TypeRef::Array(Box::new(TypeRef::String))));
+		assert!(
+			matches!(&declaration(&file, "Names").kind, DeclarationKind::TypeAlias(TypeRef::Union(types)) if types == &[names.clone(), names, TypeRef::Nil])
+		);
+
+		let DeclarationKind::Function(get) = &declaration(&file, "get").kind else {
+			panic!("expected a function")
+		};
+		assert_eq!(get.returns, TypeRef::Union(vec![TypeRef::Any, TypeRef::Nil]));
+		assert!(file.declarations.iter().all(|d| d.exported));
+	}
+
+	#[test]
+	fn test_parse_classes_and_interfaces() {
+		let file = parse(
+			r#"
+interface Options {
+	readonly name: string;
+	readonly?: boolean;
+	"quoted-name"?: Date;
+	[key: string]: unknown;
+}
+declare class Base {
+	private constructor();
+}
+declare abstract class Greeter extends Base implements IGreeter {
+	#private;
+	constructor(options: Options, { verbose }?: { verbose: boolean });
+	static create(): Greeter;
+	get greeting(): string;
+	set greeting(value: string);
+	get count(): number;
+	protected secret: string;
+	greet(name: string): Promise<string>;
+	greet(names: string[]): Promise<string>;
+	unsupported: typeof import("x");
+	isGreeter(x: unknown): x is Greeter;
+}
+export { Greeter as Hello, Options };
+"#,
+		);
+
+		let DeclarationKind::Interface { members, .. } = &declaration(&file, "Options").kind else {
+			panic!("expected an interface")
+		};
+		let names = members.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+		assert_eq!(names, ["name", "readonly", "quoted-name"]);
+		assert!(matches!(
+			&members[2].kind,
+			MemberKind::Property {
+				type_: TypeRef::Date,
+				optional: true,
+				readonly: false
+			}
+		));
+
+		assert!(matches!(
+			&declaration(&file, "Base").kind,
+			DeclarationKind::Class {
+				constructor: Constructor::Private,
+				..
+			}
+		));
+
+		let DeclarationKind::Class {
+			extends,
+			implements,
+			is_abstract,
+			constructor: Constructor::Public(parameters),
+			members,
+		} = &declaration(&file, "Greeter").kind
+		else {
+			panic!("expected a class with a constructor")
+		};
+		assert_eq!(extends.as_deref(), Some("Base"));
+		assert_eq!(implements, &["IGreeter"]);
+		assert!(is_abstract);
+		assert_eq!(parameters.len(), 2);
+		assert!(parameters[1].optional);
+		let names = members.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+		assert_eq!(
+			names,
+			["create", "greeting", "count", "greet", "unsupported", "isGreeter"]
+		);
+		assert!(members[0].is_static);
+		assert!(matches!(&members[1].kind, MemberKind::Property { readonly: false, .. }));
+		assert!(matches!(&members[2].kind, MemberKind::Property { readonly: true, .. }));
+		let MemberKind::Method(greet) = &members[3].kind else {
+			panic!("expected a method")
+		};
+		assert_eq!(greet.parameters[0].type_, TypeRef::String);
+		assert_eq!(greet.returns, TypeRef::Named("Promise".to_string()));
+		assert!(matches!(&members[5].kind, MemberKind::Method(f) if f.returns == TypeRef::Boolean));
+
+		// Only listed declarations are exported when there's an export list
+		assert!(!declaration(&file, "Greeter").exported);
+		assert!(!declaration(&file, "Base").exported);
+		assert!(declaration(&file, "Options").exported);
+		assert!(matches!(&declaration(&file, "Hello").kind, DeclarationKind::Alias(name) if name == "Greeter"));
+	}
+
+	#[test]
+	fn test_skip_unsupported_syntax() {
+		let file = parse(
+			r#"
+export declare namespace Utils {
+	function helper(): void;
+}
+export type Mapped<T> = { [K in keyof T]: T[K] };
+export default function main(): void;
+export declare function after(x: [string, number], y: A & B): Conditional<string> extends string ? 1 : 2;
+"#,
+		);
+		let names = file.declarations.iter().map(|d| d.name.as_str()).collect::<Vec<_>>();
+		assert_eq!(names, ["Mapped", "after"]);
+		let DeclarationKind::Function(after) = &declaration(&file, "after").kind else {
+			panic!("expected a function")
+		};
+		assert!(after.parameters.iter().all(|p| p.type_ == TypeRef::Any));
+		assert_eq!(after.returns, TypeRef::Any);
+	}
+
+	#[test]
+	fn test_warn_on_unsupported_syntax() {
+		let file = parse(
+			r#"
+export type IsString<T> = T extends string ? true : false;
+export type Partial<T> = { [K in keyof T]?: T[K] };
+declare module "other" {
+	export function hidden(): void;
+}
+export declare function parse(value: string): number;
+export declare function parse(value: number): number;
+export interface Callable {
+	(value: string): void;
+	run(a: string): void;
+	run(a: number): void;
+}
+export declare const after: string;
+"#,
+		);
+		let names = file.declarations.iter().map(|d| d.name.as_str()).collect::<Vec<_>>();
+		assert_eq!(names, ["IsString", "Partial", "parse", "Callable", "after"]);
+		assert!(matches!(
+			&declaration(&file, "IsString").kind,
+			DeclarationKind::TypeAlias(TypeRef::Any)
+		));
+		assert!(matches!(
+			&declaration(&file, "Partial").kind,
+			DeclarationKind::TypeAlias(TypeRef::Any)
+		));
+		let DeclarationKind::Function(parse) = &declaration(&file, "parse").kind else {
+			panic!("expected a function")
+		};
+		assert_eq!(parse.parameters[0].type_, TypeRef::String);
+		let DeclarationKind::Interface { members, .. } = &declaration(&file, "Callable").kind else {
+			panic!("expected an interface")
+		};
+		assert_eq!(members.len(), 1);
+
+		assert_eq!(
+			file.warnings,
+			[
+				(
+					2,
+					"A conditional type can't be represented in Wing and is read as `any`".to_string()
+				),
+				(
+					3,
+					"A mapped type can't be represented in Wing and is read as `any`".to_string()
+				),
+				(
+					4,
+					"unsupported declaration `declare module \"other\"` is skipped".to_string()
+				),
+				(8, "Only the first overload of `parse` is used".to_string()),
+				(
+					10,
+					"A call signature can't be represented in Wing and is skipped".to_string()
+				),
+				(12, "Only the first overload of `run` is used".to_string()),
+			]
+		);
+	}
+
+	#[test]
+	fn test_load_module() {
+		let temp_dir = std::env::temp_dir().join("wingii-test-dts");
+		let _ = fs::remove_dir_all(&temp_dir);
+		let dir = Utf8PathBuf::from_path_buf(temp_dir).expect("invalid utf8");
+		fs::create_dir_all(dir.join("lib/util")).unwrap();
+		fs::write(
+			dir.join("lib/index.d.ts"),
+			"export * from \"./util\";\nexport { b as renamed } from \"./other.js\";\nexport declare const a: number;",
+		)
+		.unwrap();
+		fs::write(
+			dir.join("lib/util/index.d.ts"),
+			"export declare function util(): void;\nexport declare const pair: [string, string];",
+		)
+		.unwrap();
+		fs::write(
+			dir.join("lib/other.d.ts"),
+			"export declare const b: string;\nexport declare const c: string;",
+		)
+		.unwrap();
+
+		let package = serde_json::json!({ "name": "some-lib", "main": "lib/index.js" });
+		let module = load_module(&dir, &package).unwrap();
+		assert_eq!(module.name, "some-lib");
+		let exported = module
+			.declarations
+			.iter()
+			.filter(|d| d.exported)
+			.map(|d| d.name.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(exported, ["a", "util", "pair", "renamed"]);
+		assert_eq!(
+			module.warnings,
+			[format!(
+				"{}:2: A tuple type can't be represented in Wing and is read as `any`",
+				dir.join("lib/util/index.d.ts")
+			)]
+		);
+
+		let package = serde_json::json!({ "name": "some-lib", "types": "missing.d.ts", "main": "missing.js" });
+		assert!(load_module(&dir, &package).is_err());
+
+		// the "types" condition of the package's "exports" comes first
+		fs::write(
+			dir.join("package.json"),
+			r#"{ "name": "some-lib", "exports": { ".": { "types": "./lib/other.d.ts", "default": "./lib/index.js" } } }"#,
+		)
+		.unwrap();
+		let module = load_module(&dir, &package).unwrap();
+		let exported = module.declarations.iter().map(|d| d.name.as_str()).collect::<Vec<_>>();
+		assert_eq!(exported, ["b", "c"]);
+	}
+}
@@ -0,0 +1 @@
+// src/index.js
@@ -0,0 +1 @@
+// src/internal/secret.js
@@ -0,0 +1 @@
+// src/internal/util.js
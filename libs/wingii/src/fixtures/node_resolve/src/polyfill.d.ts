@@ -0,0 +1 @@
+// src/polyfill.d.ts
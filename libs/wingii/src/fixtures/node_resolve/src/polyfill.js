@@ -0,0 +1 @@
+// src/polyfill.js
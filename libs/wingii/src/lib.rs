@@ -11,11 +11,13 @@ extern crate serde_json;
 #[cfg(test)]
 mod test;
 
+pub mod dts;
 pub mod fqn;
 // this is public temporarily until reflection API is finalized
 pub mod jsii;
 
 pub mod node_resolve;
+pub mod semver;
 pub mod util;
 
 pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
@@ -161,12 +163,14 @@ pub mod spec {
 pub mod type_system {
 	type AssemblyName = String;
 
-	use camino::Utf8Path;
+	use camino::{Utf8Path, Utf8PathBuf};
 	use serde_json::Value;
 
+	use crate::dts;
 	use crate::fqn::FQN;
 	use crate::jsii;
 	use crate::jsii::Assembly;
+	use crate::semver::{Version, VersionRange};
 	use crate::spec;
 	use crate::util::package_json;
 	use crate::Result;
@@ -174,6 +178,12 @@ pub mod type_system {
 
 	pub struct TypeSystem {
 		assemblies: HashMap<String, Assembly>,
+		/// The directory of the npm package each assembly was loaded from
+		assembly_directories: HashMap<String, Utf8PathBuf>,
+		/// TypeScript declarations of loaded npm packages that aren't JSII modules
+		typings: HashMap<String, dts::Module>,
+		/// Problems found while loading modules that didn't prevent loading them, see `take_warnings`
+		warnings: Vec<String>,
 	}
 
 	pub trait QueryableType {}
@@ -185,15 +195,34 @@ pub mod type_system {
 		pub fn new() -> TypeSystem {
 			TypeSystem {
 				assemblies: HashMap::new(),
+				assembly_directories: HashMap::new(),
+				typings: HashMap::new(),
+				warnings: vec![],
 			}
 		}
 
+		/// Returns (and clears) the warnings of the modules loaded since the last call
+		pub fn take_warnings(&mut self) -> Vec<String> {
+			std::mem::take(&mut self.warnings)
+		}
+
 		pub fn includes_assembly(&self, name: &str) -> bool {
 			self.assemblies.contains_key(name)
 		}
 		pub fn find_assembly(&self, name: &str) -> Option<&Assembly> {
 			self.assemblies.get(name)
 		}
+		pub fn find_assembly_directory(&self, name: &str) -> Option<&Utf8Path> {
+			self.assembly_directories.get(name).map(|dir| dir.as_path())
+		}
+		/// The directories of the npm packages of all loaded assemblies
+		pub fn assembly_directories(&self) -> impl Iterator<Item = &Utf8Path> {
+			self.assembly_directories.values().map(|dir| dir.as_path())
+		}
+		/// The TypeScript declarations of a package that was loaded without a JSII assembly
+		pub fn find_typings(&self, name: &str) -> Option<&dts::Module> {
+			self.typings.get(name)
+		}
 		fn find_type(&self, fqn: &FQN) -> Option<&jsii::Type> {
 			let assembly = self.assemblies.get(fqn.assembly())?;
 
@@ -245,14 +274,14 @@ pub mod type_system {
 			let file_path = Utf8Path::new(module_directory).join("package.json");
 			let package_json = std::fs::read_to_string(file_path)?;
 			let package: serde_json::Value = serde_json::from_str(&package_json)?;
-			let _ = package
-				.get("jsii")
-				.ok_or(format!("not a jsii module: {}", module_directory))?;
+			if package.get("jsii").is_none() {
+				return self.load_typings(module_directory, &package);
+			}
 			let assembly_file = spec::find_assembly_file(module_directory)?;
 			// in JSII, the assembly name is the NPM package name, so we don't need to load the assembly
-			// in order to check if we've already loaded it. Also, JSII doesn't support multiple version
-			// of the same assembly in the closure, so it is safe to assume that there are no version
-			// conflicts (otherwise we are going to get into semantic versioning checks and stuff).
+			// in order to check if we've already loaded it. JSII doesn't support multiple versions of the
+			// same assembly in the closure, so the version of an assembly that was already loaded is checked
+			// against the dependency ranges of the modules that depend on it (see `load_dependencies`).
 			let name = package.get("name").unwrap().as_str().unwrap();
 			if self.assemblies.contains_key(name) {
 				return Ok(name.to_string());
@@ -268,18 +297,75 @@ pub mod type_system {
 
 			let asm = spec::load_assembly_from_file(name, &assembly_file, None, &module_version)?;
 			let root = self.add_assembly(asm)?;
-			let bundled = package_json::bundled_dependencies_of(&package);
-			let deps = package_json::dependencies_of(&package);
+			self
+				.assembly_directories
+				.insert(root.clone(), module_directory.to_owned());
+			if let Err(error) = self.load_dependencies(&root, &package, module_directory) {
+				// Don't keep an assembly without its dependencies, so loading it again fails too
+				self.assemblies.remove(&root);
+				self.assembly_directories.remove(&root);
+				return Err(error);
+			}
+			Ok(root)
+		}
+
+		/// Loads the (non bundled) dependencies of a JSII module, and checks that the versions that were loaded
+		/// match the module's dependency ranges
+		fn load_dependencies(&mut self, name: &str, package: &Value, module_directory: &Utf8Path) -> Result<()> {
+			let bundled = package_json::bundled_dependencies_of(package);
+			let deps = package_json::dependencies_of(package);
 			for dep in deps {
-				if !bundled.contains(&dep) {
-					let dep_dir = package_json::find_dependency_directory(&dep, &module_directory).ok_or(format!(
-						"Unable to load \"{}\": Module not found from \"{}\"",
-						dep, module_directory
-					))?;
-					self.load_module(&dep_dir).expect("unable to load assembly");
+				if bundled.contains(&dep) {
+					continue;
+				}
+				let dep_dir = package_json::find_dependency_directory(&dep, module_directory).ok_or(format!(
+					"Unable to load \"{}\": Module not found from \"{}\" (required by \"{}\")",
+					dep, module_directory, name
+				))?;
+				let dep_name = self
+					.load_module(&dep_dir)
+					.map_err(|error| format!("Unable to load \"{}\" (required by \"{}\"): {}", dep, name, error))?;
+
+				let Some(range) = package_json::dependency_range_of(package, &dep) else {
+					continue;
+				};
+				let Some(loaded) = self.assemblies.get(&dep_name) else {
+					continue;
+				};
+				// Ranges that can't be checked (like tags or urls) are assumed to match
+				let (Some(parsed_range), Some(version)) = (VersionRange::parse(range), Version::parse(&loaded.version)) else {
+					self.warnings.push(format!(
+						"Cannot check that version {} of \"{}\" matches \"{}\" (required by \"{}\"), assuming it does",
+						loaded.version, dep_name, range, name
+					));
+					continue;
+				};
+				if !parsed_range.matches(&version) {
+					let loaded_directory = self.assembly_directories.get(&dep_name).map_or("", |dir| dir.as_str());
+					return Err(
+						format!(
+							"Conflicting versions of \"{}\": \"{}\" requires \"{}\" but version {} was already loaded from \"{}\"",
+							dep_name, name, range, version, loaded_directory
+						)
+						.into(),
+					);
 				}
 			}
-			Ok(root)
+			Ok(())
+		}
+
+		/// Loads the TypeScript declarations of an npm package that isn't a JSII module
+		fn load_typings(&mut self, module_directory: &Utf8Path, package: &Value) -> Result<AssemblyName> {
+			let mut module = dts::load_module(module_directory, package)?;
+			let name = module.name.clone();
+			if !self.typings.contains_key(&name) {
+				self.warnings.append(&mut module.warnings);
+				self.typings.insert(name.clone(), module);
+				self
+					.assembly_directories
+					.insert(name.clone(), module_directory.to_owned());
+			}
+			Ok(name)
 		}
 	}
 }
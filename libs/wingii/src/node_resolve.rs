@@ -4,15 +4,22 @@
 // See: https://github.com/goto-bus-stop/node-resolve/issues/11
 
 use std::{
+	cmp::Ordering,
 	error::Error,
+	fmt,
 	fs::{self},
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
+use serde::{
+	de::{MapAccess, SeqAccess, Visitor},
+	Deserialize, Deserializer,
+};
 use serde_json::Value;
 
 static ROOT: &str = "/";
 static NODE_WING_FIELD: &str = "wing";
+static NODE_MODULES: &str = "node_modules";
 static NODE_EXTENSIONS: [&str; 5] = [".js", ".cjs", ".mjs", "json", ".node"];
 static NODE_MAIN_FIELDS: [&str; 1] = ["main"];
 static NODE_BUILTINS: [&str; 33] = [
@@ -51,9 +58,24 @@ static NODE_BUILTINS: [&str; 33] = [
 	"zlib",
 ];
 
-/// Resolve a node.js module path relative to `basedir`.
+/// The conditions matched by `require()` in package.json "exports" and "imports" ("default" always matches)
+pub static REQUIRE_CONDITIONS: [&str; 2] = ["node", "require"];
+/// The conditions matched by TypeScript when looking up the declarations of a module
+pub static TYPES_CONDITIONS: [&str; 3] = ["types", "node", "require"];
+
+/// Resolve a node.js module path relative to `basedir`, the way `require()` does.
 /// Returns the path to the module, or an error.
 pub fn resolve_from(target: &str, basedir: &Utf8Path) -> Result<Utf8PathBuf, Box<dyn Error>> {
+	resolve_from_with_conditions(target, basedir, &REQUIRE_CONDITIONS)
+}
+
+/// Resolve a node.js module path relative to `basedir`, matching `conditions` in package.json "exports"
+/// and "imports". Returns the path to the module, or an error.
+pub fn resolve_from_with_conditions(
+	target: &str,
+	basedir: &Utf8Path,
+	conditions: &[&str],
+) -> Result<Utf8PathBuf, Box<dyn Error>> {
 	// 1. If X is a core module
 	if is_core_module(target) {
 		// 1.a. Return the core module
@@ -74,7 +96,32 @@ pub fn resolve_from(target: &str, basedir: &Utf8Path) -> Result<Utf8PathBuf, Box
 		return resolve_as_file(&path).or_else(|_| resolve_as_directory(&path));
 	}
 
-	resolve_node_modules(target, basedir)
+	// 4. If X begins with '#'
+	if target.starts_with('#') {
+		// 4.a. LOAD_PACKAGE_IMPORTS(X, dirname(Y))
+		return resolve_package_imports(target, basedir, conditions);
+	}
+
+	// 5. LOAD_PACKAGE_SELF(X, dirname(Y))
+	if let Some(result) = resolve_package_self(target, basedir, conditions) {
+		return result;
+	}
+
+	resolve_node_modules(target, basedir, conditions)
+}
+
+/// Resolve a subpath (like "." or "./feature") of the package in `package_dir` through its "exports".
+/// Returns the path to the module, or an error if the package has no "exports" or doesn't export the subpath.
+pub fn resolve_package_export(
+	package_dir: &Utf8Path,
+	subpath: &str,
+	conditions: &[&str],
+) -> Result<Utf8PathBuf, Box<dyn Error>> {
+	let pkg = read_package_json(package_dir)?.ok_or("Not Found")?;
+	if pkg.exports.is_null() {
+		return Err(format!("{} does not define \"exports\"", package_dir.join("package.json")).into());
+	}
+	resolve_exports_match(package_dir, subpath, &pkg.exports, conditions)
 }
 
 /// Resolve a path as a file. If `path` refers to a file, it is returned;
@@ -102,9 +149,13 @@ fn resolve_as_file(path: &Utf8Path) -> Result<Utf8PathBuf, Box<dyn Error>> {
 }
 
 /// Resolve by walking up node_modules folders.
-fn resolve_node_modules(target: &str, basedir: &Utf8Path) -> Result<Utf8PathBuf, Box<dyn Error>> {
-	let node_modules = basedir.join("node_modules");
+fn resolve_node_modules(target: &str, basedir: &Utf8Path, conditions: &[&str]) -> Result<Utf8PathBuf, Box<dyn Error>> {
+	let node_modules = basedir.join(NODE_MODULES);
 	let result = if node_modules.is_dir() {
+		// LOAD_PACKAGE_EXPORTS(X, DIR)
+		if let Some(result) = resolve_node_modules_exports(target, &node_modules, conditions) {
+			return result;
+		}
 		let path = node_modules.join(target);
 		resolve_as_file(&path).or_else(|_| resolve_as_directory(&path))
 	} else {
@@ -116,11 +167,318 @@ fn resolve_node_modules(target: &str, basedir: &Utf8Path) -> Result<Utf8PathBuf,
 	}
 
 	match basedir.parent() {
-		Some(parent) => resolve_node_modules(target, parent),
+		Some(parent) => resolve_node_modules(target, parent, conditions),
 		None => result,
 	}
 }
 
+/// Resolve a package in a node_modules folder through its "exports".
+/// Returns `None` if the package isn't there or doesn't define "exports".
+fn resolve_node_modules_exports(
+	target: &str,
+	node_modules: &Utf8Path,
+	conditions: &[&str],
+) -> Option<Result<Utf8PathBuf, Box<dyn Error>>> {
+	let (name, subpath) = split_package_name(target)?;
+	let pkg_dir = node_modules.join(name);
+	let pkg = read_package_json(&pkg_dir).ok()??;
+	if pkg.exports.is_null() {
+		return None;
+	}
+
+	// wing packages don't need to export their main module
+	if subpath == "." && pkg.wing.as_bool().unwrap_or(false) {
+		return None;
+	}
+
+	Some(resolve_exports_match(&pkg_dir, &subpath, &pkg.exports, conditions))
+}
+
+/// Resolve a package that refers to itself by name, through its own "exports".
+fn resolve_package_self(
+	target: &str,
+	basedir: &Utf8Path,
+	conditions: &[&str],
+) -> Option<Result<Utf8PathBuf, Box<dyn Error>>> {
+	let (name, subpath) = split_package_name(target)?;
+	let scope = find_package_scope(basedir)?;
+	let pkg = read_package_json(&scope).ok()??;
+	if pkg.exports.is_null() || pkg.name.as_str() != Some(name) {
+		return None;
+	}
+
+	Some(resolve_exports_match(&scope, &subpath, &pkg.exports, conditions))
+}
+
+/// Resolve a "#" specifier through the "imports" of the closest package.
+fn resolve_package_imports(
+	target: &str,
+	basedir: &Utf8Path,
+	conditions: &[&str],
+) -> Result<Utf8PathBuf, Box<dyn Error>> {
+	if target == "#" || target.starts_with("#/") {
+		return Err(format!("Invalid module specifier \"{}\"", target).into());
+	}
+
+	let scope = find_package_scope(basedir);
+	if let Some(scope) = &scope {
+		if let Some(pkg) = read_package_json(scope)? {
+			if let ExportsTarget::Conditions(imports) = &pkg.imports {
+				match resolve_imports_exports(target, imports, scope, true, conditions)? {
+					Resolution::Resolved(path) if is_core_module(path.as_str()) => return Ok(path),
+					Resolution::Resolved(path) => return resolve_existing_file(path),
+					Resolution::Excluded | Resolution::Unmatched => {}
+				}
+			}
+		}
+	}
+
+	Err(
+		format!(
+			"Package import specifier \"{}\" is not defined in {}",
+			target,
+			scope.unwrap_or_else(|| basedir.to_path_buf()).join("package.json")
+		)
+		.into(),
+	)
+}
+
+/// Resolve a subpath of the package in `pkg_dir` through its "exports", and check that the file exists.
+fn resolve_exports_match(
+	pkg_dir: &Utf8Path,
+	subpath: &str,
+	exports: &ExportsTarget,
+	conditions: &[&str],
+) -> Result<Utf8PathBuf, Box<dyn Error>> {
+	let not_exported = || -> Box<dyn Error> {
+		format!(
+			"Package subpath \"{}\" is not defined by \"exports\" in {}",
+			subpath,
+			pkg_dir.join("package.json")
+		)
+		.into()
+	};
+
+	// an object is either a map of subpaths (all keys start with "."), or of conditions (none of them do)
+	let subpaths = match exports {
+		ExportsTarget::Conditions(entries) => {
+			let subpath_keys = entries.iter().filter(|(key, _)| key.starts_with('.')).count();
+			if subpath_keys != 0 && subpath_keys != entries.len() {
+				return Err(
+					format!(
+						"Invalid \"exports\" in {}: keys must either all start with \".\" or none of them",
+						pkg_dir.join("package.json")
+					)
+					.into(),
+				);
+			}
+			(subpath_keys != 0).then_some(entries)
+		}
+		_ => None,
+	};
+
+	let resolution = match subpaths {
+		Some(entries) => resolve_imports_exports(subpath, entries, pkg_dir, false, conditions)?,
+		// everything else is sugar for { ".": exports }
+		None if subpath == "." => resolve_target(pkg_dir, exports, None, false, conditions)?,
+		None => Resolution::Unmatched,
+	};
+
+	match resolution {
+		Resolution::Resolved(path) => resolve_existing_file(path),
+		Resolution::Excluded | Resolution::Unmatched => Err(not_exported()),
+	}
+}
+
+/// Find the entry of an "exports" or "imports" map matching `match_key`, either exactly or with a
+/// subpath pattern (a key with a single "*"), and resolve its target.
+fn resolve_imports_exports(
+	match_key: &str,
+	entries: &[(String, ExportsTarget)],
+	pkg_dir: &Utf8Path,
+	is_imports: bool,
+	conditions: &[&str],
+) -> Result<Resolution, Box<dyn Error>> {
+	if !match_key.contains('*') {
+		if let Some((_, target)) = entries.iter().find(|(key, _)| key == match_key) {
+			return resolve_target(pkg_dir, target, None, is_imports, conditions);
+		}
+	}
+
+	let mut patterns = entries
+		.iter()
+		.filter(|(key, _)| key.matches('*').count() == 1)
+		.collect::<Vec<_>>();
+	patterns.sort_by(|(a, _), (b, _)| pattern_key_compare(a, b));
+
+	for (key, target) in patterns {
+		let (base, trailer) = key.split_once('*').unwrap();
+		if match_key.starts_with(base)
+			&& match_key != base
+			&& (trailer.is_empty() || (match_key.ends_with(trailer) && match_key.len() >= key.len()))
+		{
+			let pattern_match = &match_key[base.len()..match_key.len() - trailer.len()];
+			return resolve_target(pkg_dir, target, Some(pattern_match), is_imports, conditions);
+		}
+	}
+
+	Ok(Resolution::Unmatched)
+}
+
+/// Order subpath patterns from the most specific to the least specific.
+fn pattern_key_compare(a: &str, b: &str) -> Ordering {
+	let base_length = |key: &str| key.find('*').map(|index| index + 1).unwrap_or(key.len());
+	let (a_base, b_base) = (base_length(a), base_length(b));
+	b_base
+		.cmp(&a_base)
+		.then_with(|| b.contains('*').cmp(&a.contains('*')))
+		.then_with(|| b.len().cmp(&a.len()))
+}
+
+/// Resolve the target of an "exports" or "imports" entry, replacing "*" with `pattern_match`.
+fn resolve_target(
+	pkg_dir: &Utf8Path,
+	target: &ExportsTarget,
+	pattern_match: Option<&str>,
+	is_imports: bool,
+	conditions: &[&str],
+) -> Result<Resolution, Box<dyn Error>> {
+	match target {
+		ExportsTarget::Path(path) => resolve_target_path(pkg_dir, path, pattern_match, is_imports, conditions),
+		ExportsTarget::Conditions(entries) => {
+			for (condition, target) in entries {
+				if condition == "default" || conditions.contains(&condition.as_str()) {
+					match resolve_target(pkg_dir, target, pattern_match, is_imports, conditions)? {
+						Resolution::Unmatched => continue,
+						resolution => return Ok(resolution),
+					}
+				}
+			}
+			Ok(Resolution::Unmatched)
+		}
+		ExportsTarget::Array(targets) => {
+			// invalid targets are skipped, in favor of the next fallback
+			let mut last = Ok(Resolution::Excluded);
+			for target in targets {
+				last = resolve_target(pkg_dir, target, pattern_match, is_imports, conditions);
+				match last {
+					Ok(Resolution::Unmatched) | Err(_) => continue,
+					_ => return last,
+				}
+			}
+			last
+		}
+		ExportsTarget::Null => Ok(Resolution::Excluded),
+		ExportsTarget::Invalid => Err(
+			format!(
+				"Invalid \"{}\" target defined in {}",
+				if is_imports { "imports" } else { "exports" },
+				pkg_dir.join("package.json")
+			)
+			.into(),
+		),
+	}
+}
+
+/// Resolve a target path, which must be relative to the package (or, for "imports", another package).
+fn resolve_target_path(
+	pkg_dir: &Utf8Path,
+	target: &str,
+	pattern_match: Option<&str>,
+	is_imports: bool,
+	conditions: &[&str],
+) -> Result<Resolution, Box<dyn Error>> {
+	let invalid_target = || -> Box<dyn Error> {
+		format!(
+			"Invalid \"{}\" target \"{}\" defined in {}",
+			if is_imports { "imports" } else { "exports" },
+			target,
+			pkg_dir.join("package.json")
+		)
+		.into()
+	};
+	let substitute = |target: &str| match pattern_match {
+		Some(pattern_match) => target.replace('*', pattern_match),
+		None => target.to_string(),
+	};
+
+	let Some(relative_target) = target.strip_prefix("./") else {
+		// "imports" can map to other packages
+		if !is_imports
+			|| target.starts_with('#')
+			|| target.starts_with("../")
+			|| target.starts_with('/')
+			|| target.contains("://")
+		{
+			return Err(invalid_target());
+		}
+		return resolve_from_with_conditions(&substitute(target), pkg_dir, conditions).map(Resolution::Resolved);
+	};
+
+	if has_invalid_segments(relative_target) {
+		return Err(invalid_target());
+	}
+	if let Some(pattern_match) = pattern_match {
+		if has_invalid_segments(pattern_match) {
+			return Err(format!("Invalid module specifier \"{}\"", pattern_match).into());
+		}
+	}
+
+	Ok(Resolution::Resolved(pkg_dir.join(substitute(relative_target))))
+}
+
+/// Check if a path has "", ".", ".." or "node_modules" segments, which would escape the package or point
+/// into its dependencies.
+fn has_invalid_segments(path: &str) -> bool {
+	path
+		.split(['/', '\\'])
+		.any(|segment| matches!(segment.to_lowercase().as_str(), "" | "." | ".." | "node_modules"))
+}
+
+/// Unlike the classic algorithm, "exports" and "imports" must point at an existing file (no extensions are added).
+fn resolve_existing_file(path: Utf8PathBuf) -> Result<Utf8PathBuf, Box<dyn Error>> {
+	if path.is_file() {
+		Ok(path)
+	} else {
+		Err(format!("Cannot find module \"{}\"", path).into())
+	}
+}
+
+/// Split a package specifier into its package name and subpath, like "@scope/pkg/feature" into
+/// ("@scope/pkg", "./feature").
+fn split_package_name(target: &str) -> Option<(&str, String)> {
+	let name_length = if target.starts_with('@') {
+		let (scope, rest) = target.split_once('/')?;
+		scope.len() + 1 + rest.find('/').unwrap_or(rest.len())
+	} else {
+		target.find('/').unwrap_or(target.len())
+	};
+	let (name, subpath) = target.split_at(name_length);
+	if name.is_empty() || name.starts_with('.') || name.contains(['\\', '%']) {
+		return None;
+	}
+	Some((name, format!(".{}", subpath)))
+}
+
+/// Find the closest directory with a package.json, stopping at node_modules folders.
+fn find_package_scope(basedir: &Utf8Path) -> Option<Utf8PathBuf> {
+	basedir
+		.ancestors()
+		.take_while(|dir| dir.file_name() != Some(NODE_MODULES))
+		.find(|dir| dir.join("package.json").is_file())
+		.map(Utf8Path::to_path_buf)
+}
+
+/// Read the fields of a package.json needed to resolve its "exports" and "imports".
+/// Returns `None` if the directory has no package.json.
+fn read_package_json(pkg_dir: &Utf8Path) -> Result<Option<PackageJson>, Box<dyn Error>> {
+	let pkg_path = pkg_dir.join("package.json");
+	if !pkg_path.is_file() {
+		return Ok(None);
+	}
+	Ok(Some(serde_json::from_slice(&fs::read(pkg_path)?)?))
+}
+
 /// Resolve a path as a directory, using the "main" key from a package.json file if it
 /// exists, or resolving to the index.EXT file if it exists.
 fn resolve_as_directory(path: &Utf8Path) -> Result<Utf8PathBuf, Box<dyn Error>> {
@@ -182,6 +540,101 @@ fn resolve_index(path: &Utf8Path) -> Result<Utf8PathBuf, Box<dyn Error>> {
 	Err(String::from("Not Found").into())
 }
 
+#[derive(Deserialize)]
+struct PackageJson {
+	#[serde(default)]
+	name: Value,
+	#[serde(default)]
+	wing: Value,
+	#[serde(default)]
+	exports: ExportsTarget,
+	#[serde(default)]
+	imports: ExportsTarget,
+}
+
+/// The target of an "exports" or "imports" entry. Unlike `serde_json::Value`, objects keep the order of their
+/// keys, since conditions are matched in the order they're declared.
+#[derive(Debug, Default, PartialEq)]
+enum ExportsTarget {
+	#[default]
+	Null,
+	Path(String),
+	Array(Vec<ExportsTarget>),
+	Conditions(Vec<(String, ExportsTarget)>),
+	Invalid,
+}
+
+impl ExportsTarget {
+	fn is_null(&self) -> bool {
+		*self == ExportsTarget::Null
+	}
+}
+
+impl<'de> Deserialize<'de> for ExportsTarget {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(ExportsTargetVisitor)
+	}
+}
+
+struct ExportsTargetVisitor;
+
+impl<'de> Visitor<'de> for ExportsTargetVisitor {
+	type Value = ExportsTarget;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("an \"exports\" or \"imports\" target")
+	}
+
+	fn visit_unit<E>(self) -> Result<Self::Value, E> {
+		Ok(ExportsTarget::Null)
+	}
+
+	fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+		Ok(ExportsTarget::Invalid)
+	}
+
+	fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+		Ok(ExportsTarget::Invalid)
+	}
+
+	fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+		Ok(ExportsTarget::Invalid)
+	}
+
+	fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+		Ok(ExportsTarget::Invalid)
+	}
+
+	fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+		Ok(ExportsTarget::Path(value.to_string()))
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut targets = Vec::new();
+		while let Some(target) = seq.next_element()? {
+			targets.push(target);
+		}
+		Ok(ExportsTarget::Array(targets))
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut entries = Vec::new();
+		while let Some(entry) = map.next_entry()? {
+			entries.push(entry);
+		}
+		Ok(ExportsTarget::Conditions(entries))
+	}
+}
+
+/// The outcome of resolving an "exports" or "imports" target
+enum Resolution {
+	Resolved(Utf8PathBuf),
+	/// The target is `null`, so the subpath is explicitly not exported
+	Excluded,
+	/// None of the conditions matched
+	Unmatched,
+}
+
 /// Check if a string references a core module, such as "events"
 fn is_core_module(target: &str) -> bool {
 	NODE_BUILTINS.iter().any(|builtin| builtin == &target)
@@ -192,3 +645,130 @@ fn is_core_module(target: &str) -> bool {
 pub fn is_path_dependency(dependency_name: &str) -> bool {
 	dependency_name.starts_with("./") || dependency_name.starts_with("../") || dependency_name.starts_with("/")
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fixture_dir() -> Utf8PathBuf {
+		Utf8PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("src")
+			.join("fixtures")
+			.join("node_resolve")
+	}
+
+	fn resolve(target: &str) -> Result<Utf8PathBuf, Box<dyn Error>> {
+		resolve_from(target, &fixture_dir().join("src"))
+	}
+
+	fn relative(path: Utf8PathBuf) -> String {
+		path.strip_prefix(fixture_dir()).unwrap().to_string()
+	}
+
+	#[test]
+	fn test_resolve_exports_conditions() {
+		assert_eq!(relative(resolve("dual").unwrap()), "node_modules/dual/cjs/index.js");
+		let types = resolve_from_with_conditions("dual", &fixture_dir(), &TYPES_CONDITIONS).unwrap();
+		assert_eq!(relative(types), "node_modules/dual/types/index.d.ts");
+		assert_eq!(
+			relative(resolve("dual/package.json").unwrap()),
+			"node_modules/dual/package.json"
+		);
+		// "exports" take precedence over "main", and can be a string
+		assert_eq!(relative(resolve("sugar").unwrap()), "node_modules/sugar/lib/main.js");
+		assert!(resolve("sugar/index.js").is_err());
+	}
+
+	#[test]
+	fn test_resolve_exports_patterns() {
+		assert_eq!(
+			relative(resolve("dual/features/a.js").unwrap()),
+			"node_modules/dual/lib/features/a.js"
+		);
+		// the most specific pattern wins, even if it excludes the subpath
+		assert!(resolve("dual/features/private/secret.js").is_err());
+		assert!(resolve("dual/cjs/index.js").is_err());
+		assert_eq!(
+			relative(resolve("@scope/patterns/widget").unwrap()),
+			"node_modules/@scope/patterns/src/widget.js"
+		);
+		assert_eq!(
+			relative(resolve("@scope/patterns/widget.js").unwrap()),
+			"node_modules/@scope/patterns/src/widget.js"
+		);
+		assert!(resolve("@scope/patterns").is_err());
+	}
+
+	#[test]
+	fn test_resolve_exports_fallbacks() {
+		assert_eq!(
+			relative(resolve("fallbacks").unwrap()),
+			"node_modules/fallbacks/main.js"
+		);
+		assert!(resolve("fallbacks/escape").is_err());
+		assert!(resolve("fallbacks/worker").is_err());
+		assert_eq!(
+			relative(
+				resolve_package_export(&fixture_dir().join("node_modules/fallbacks"), ".", &REQUIRE_CONDITIONS).unwrap()
+			),
+			"node_modules/fallbacks/main.js"
+		);
+	}
+
+	#[test]
+	fn test_resolve_without_exports() {
+		assert_eq!(
+			relative(resolve("classic").unwrap()),
+			"node_modules/classic/lib/entry.js"
+		);
+		assert!(resolve_package_export(&fixture_dir().join("node_modules/classic"), ".", &REQUIRE_CONDITIONS).is_err());
+		assert_eq!(resolve("fs").unwrap(), Utf8PathBuf::from("fs"));
+	}
+
+	#[test]
+	fn test_resolve_imports() {
+		assert_eq!(relative(resolve("#internal/util.js").unwrap()), "src/internal/util.js");
+		assert!(resolve("#internal/secret.js").is_err());
+		assert!(resolve("#internal/missing.js").is_err());
+		assert!(resolve("#unknown").is_err());
+		assert!(resolve("#/util.js").is_err());
+		// imports can map to packages
+		assert_eq!(
+			relative(resolve("#polyfill").unwrap()),
+			"node_modules/dual/cjs/index.js"
+		);
+		let types = resolve_from_with_conditions("#polyfill", &fixture_dir(), &TYPES_CONDITIONS).unwrap();
+		assert_eq!(relative(types), "src/polyfill.d.ts");
+		assert_eq!(resolve("#fs").unwrap(), Utf8PathBuf::from("fs"));
+	}
+
+	#[test]
+	fn test_resolve_self_reference() {
+		assert_eq!(relative(resolve("app").unwrap()), "src/index.js");
+		assert!(resolve("app/src/internal/util.js").is_err());
+	}
+
+	#[test]
+	fn test_pattern_key_compare() {
+		let mut keys = vec!["./*", "./features/*.js", "./features/private/*", "./*.js", "./features"];
+		keys.sort_by(|a, b| pattern_key_compare(a, b));
+		assert_eq!(
+			keys,
+			vec!["./features/private/*", "./features/*.js", "./features", "./*.js", "./*"]
+		);
+	}
+
+	#[test]
+	fn test_split_package_name() {
+		assert_eq!(split_package_name("dual"), Some(("dual", ".".to_string())));
+		assert_eq!(
+			split_package_name("dual/features/a.js"),
+			Some(("dual", "./features/a.js".to_string()))
+		);
+		assert_eq!(
+			split_package_name("@scope/patterns/widget"),
+			Some(("@scope/patterns", "./widget".to_string()))
+		);
+		assert_eq!(split_package_name("@scope"), None);
+	}
+}
@@ -0,0 +1,537 @@
+//! Versions and version ranges of npm packages, following the rules of https://github.com/npm/node-semver
+//! (without its loose parsing mode).
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+
+/// A semantic version (`major.minor.patch[-prerelease][+build]`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+	pub major: u64,
+	pub minor: u64,
+	pub patch: u64,
+	pub prerelease: Vec<Identifier>,
+}
+
+/// An identifier of a prerelease version (`alpha` or `1` in `1.0.0-alpha.1`). Numeric identifiers are lower than
+/// alphanumeric ones.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Identifier {
+	Numeric(u64),
+	AlphaNumeric(String),
+}
+
+impl Version {
+	pub fn parse(version: &str) -> Option<Version> {
+		let partial = PartialVersion::parse(version)?;
+		Some(Version {
+			major: partial.major?,
+			minor: partial.minor?,
+			patch: partial.patch?,
+			prerelease: partial.prerelease,
+		})
+	}
+
+	fn new(major: u64, minor: u64, patch: u64) -> Version {
+		Version {
+			major,
+			minor,
+			patch,
+			prerelease: vec![],
+		}
+	}
+
+	/// The lowest prerelease of this version (`1.0.0-0`), used as an exclusive upper bound that excludes the
+	/// prereleases of the version too
+	fn lowest_prerelease(mut self) -> Version {
+		self.prerelease = vec![Identifier::Numeric(0)];
+		self
+	}
+
+	fn same_release(&self, other: &Version) -> bool {
+		(self.major, self.minor, self.patch) == (other.major, other.minor, other.patch)
+	}
+}
+
+impl Ord for Version {
+	fn cmp(&self, other: &Self) -> Ordering {
+		(self.major, self.minor, self.patch)
+			.cmp(&(other.major, other.minor, other.patch))
+			.then_with(|| match (self.prerelease.is_empty(), other.prerelease.is_empty()) {
+				// A prerelease is lower than its release
+				(true, true) => Ordering::Equal,
+				(true, false) => Ordering::Greater,
+				(false, true) => Ordering::Less,
+				(false, false) => self.prerelease.cmp(&other.prerelease),
+			})
+	}
+}
+
+impl PartialOrd for Version {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Display for Version {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+		for (i, identifier) in self.prerelease.iter().enumerate() {
+			let separator = if i == 0 { '-' } else { '.' };
+			match identifier {
+				Identifier::Numeric(n) => write!(f, "{separator}{n}")?,
+				Identifier::AlphaNumeric(s) => write!(f, "{separator}{s}")?,
+			}
+		}
+		Ok(())
+	}
+}
+
+/// A version where trailing parts can be missing or wildcards (`1`, `1.2`, `1.x`, `*`)
+struct PartialVersion {
+	major: Option<u64>,
+	minor: Option<u64>,
+	patch: Option<u64>,
+	prerelease: Vec<Identifier>,
+}
+
+impl PartialVersion {
+	fn parse(version: &str) -> Option<PartialVersion> {
+		let version = version.strip_prefix('v').unwrap_or(version);
+		// Build metadata doesn't affect precedence
+		let version = version.split_once('+').map_or(version, |(version, _)| version);
+		let (release, prerelease) = match version.split_once('-') {
+			Some((release, prerelease)) => (release, Some(prerelease)),
+			None => (version, None),
+		};
+
+		let mut parts = release.split('.');
+		let mut next_part = || -> Option<Option<u64>> {
+			match parts.next() {
+				None | Some("x" | "X" | "*") => Some(None),
+				Some(part) => parse_number(part).map(Some),
+			}
+		};
+		let major = if release.is_empty() { None } else { next_part()? };
+		let minor = next_part()?;
+		let patch = next_part()?;
+		if parts.next().is_some() {
+			return None;
+		}
+		// Parts after a wildcard must be wildcards too
+		if (major.is_none() && minor.is_some()) || (minor.is_none() && patch.is_some()) {
+			return None;
+		}
+
+		let prerelease = match prerelease {
+			Some(prerelease) => prerelease
+				.split('.')
+				.map(|identifier| {
+					if identifier.is_empty() || !identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+						None
+					} else if identifier.chars().all(|c| c.is_ascii_digit()) {
+						parse_number(identifier).map(Identifier::Numeric)
+					} else {
+						Some(Identifier::AlphaNumeric(identifier.to_string()))
+					}
+				})
+				.collect::<Option<Vec<_>>>()?,
+			None => vec![],
+		};
+		Some(PartialVersion {
+			major,
+			minor,
+			patch,
+			prerelease,
+		})
+	}
+
+	/// The lowest version matching this partial version (`1.2` is `1.2.0`)
+	fn floor(&self) -> Version {
+		Version {
+			major: self.major.unwrap_or(0),
+			minor: self.minor.unwrap_or(0),
+			patch: self.patch.unwrap_or(0),
+			prerelease: self.prerelease.clone(),
+		}
+	}
+
+	/// The lowest version above all the versions matching this partial version (`1.2` is below `1.3.0-0`), or `None`
+	/// for a full version or a wildcard
+	fn ceiling(&self) -> Option<Version> {
+		match (self.major, self.minor, self.patch) {
+			(Some(major), None, _) => Some(Version::new(major + 1, 0, 0).lowest_prerelease()),
+			(Some(major), Some(minor), None) => Some(Version::new(major, minor + 1, 0).lowest_prerelease()),
+			_ => None,
+		}
+	}
+}
+
+/// Parses a number without leading zeros
+fn parse_number(part: &str) -> Option<u64> {
+	if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) || (part.len() > 1 && part.starts_with('0')) {
+		return None;
+	}
+	part.parse().ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+	Less,
+	LessOrEqual,
+	Greater,
+	GreaterOrEqual,
+	Equal,
+}
+
+#[derive(Debug, Clone)]
+struct Comparator {
+	operator: Operator,
+	version: Version,
+}
+
+impl Comparator {
+	fn new(operator: Operator, version: Version) -> Comparator {
+		Comparator { operator, version }
+	}
+
+	fn matches(&self, version: &Version) -> bool {
+		let ordering = version.cmp(&self.version);
+		match self.operator {
+			Operator::Less => ordering == Ordering::Less,
+			Operator::LessOrEqual => ordering != Ordering::Greater,
+			Operator::Greater => ordering == Ordering::Greater,
+			Operator::GreaterOrEqual => ordering != Ordering::Less,
+			Operator::Equal => ordering == Ordering::Equal,
+		}
+	}
+}
+
+/// A version range of an npm dependency (like `^1.2.0`, `>=1.0.0 <2.0.0`, `1.x || 2.x` or `1.0.0 - 1.5.0`)
+#[derive(Debug, Clone)]
+pub struct VersionRange {
+	/// The range matches a version if all the comparators of any of the sets match it
+	comparator_sets: Vec<Vec<Comparator>>,
+}
+
+impl VersionRange {
+	/// Parses a version range. Returns `None` for dependency specifiers that aren't ranges, like tags (`latest`),
+	/// urls or paths.
+	pub fn parse(range: &str) -> Option<VersionRange> {
+		let comparator_sets = range
+			.split("||")
+			.map(parse_comparator_set)
+			.collect::<Option<Vec<_>>>()?;
+		Some(VersionRange { comparator_sets })
+	}
+
+	pub fn matches(&self, version: &Version) -> bool {
+		self.comparator_sets.iter().any(|comparators| {
+			comparators.iter().all(|c| c.matches(version))
+				// Prereleases only match ranges that explicitly include prereleases of the same version
+				&& (version.prerelease.is_empty()
+					|| comparators
+						.iter()
+						.any(|c| !c.version.prerelease.is_empty() && c.version.same_release(version)))
+		})
+	}
+}
+
+fn parse_comparator_set(range: &str) -> Option<Vec<Comparator>> {
+	let mut tokens = range.split_whitespace().collect::<Vec<_>>();
+
+	// Hyphen ranges (`1.2 - 2.3.4`)
+	if let [from, "-", to] = tokens.as_slice() {
+		let from = PartialVersion::parse(from)?;
+		let to = PartialVersion::parse(to)?;
+		let mut comparators = vec![Comparator::new(Operator::GreaterOrEqual, from.floor())];
+		match (to.major, to.ceiling()) {
+			(None, _) => {}
+			(_, Some(ceiling)) => comparators.push(Comparator::new(Operator::Less, ceiling)),
+			(_, None) => comparators.push(Comparator::new(Operator::LessOrEqual, to.floor())),
+		}
+		return Some(comparators);
+	}
+
+	// Operators can be separated from their version with spaces (`>= 1.2.3`)
+	let mut comparators = vec![];
+	while !tokens.is_empty() {
+		let mut token = tokens.remove(0).to_string();
+		if token.chars().all(|c| "<>=~^".contains(c)) && !tokens.is_empty() {
+			token.push_str(tokens.remove(0));
+		}
+		comparators.extend(parse_comparator(&token)?);
+	}
+	if comparators.is_empty() {
+		// An empty range matches any version
+		comparators.push(Comparator::new(Operator::GreaterOrEqual, Version::new(0, 0, 0)));
+	}
+	Some(comparators)
+}
+
+fn parse_comparator(comparator: &str) -> Option<Vec<Comparator>> {
+	let (operator, version) = ["<=", ">=", "<", ">", "=", "^", "~"]
+		.iter()
+		.find_map(|op| Some((*op, comparator.strip_prefix(op)?)))
+		.unwrap_or(("", comparator));
+	// `~>` is an alias of `~`
+	let version = if operator == "~" {
+		version.strip_prefix('>').unwrap_or(version)
+	} else {
+		version
+	};
+	let partial = PartialVersion::parse(version)?;
+	let floor = partial.floor();
+	let ceiling = partial.ceiling();
+	let any = || vec![Comparator::new(Operator::GreaterOrEqual, Version::new(0, 0, 0))];
+
+	let comparators = match operator {
+		"" | "=" => match (partial.major, ceiling) {
+			(None, _) => any(),
+			(_, Some(ceiling)) => vec![
+				Comparator::new(Operator::GreaterOrEqual, floor),
+				Comparator::new(Operator::Less, ceiling),
+			],
+			(_, None) => vec![Comparator::new(Operator::Equal, floor)],
+		},
+		"^" | "~" if partial.major.is_none() => any(),
+		"^" => {
+			// Allows changes that don't modify the left-most non-zero part
+			let ceiling = match (partial.major.unwrap_or(0), partial.minor, partial.patch) {
+				(major, _, _) if major > 0 => Version::new(major + 1, 0, 0),
+				(_, None, _) => Version::new(1, 0, 0),
+				(_, Some(minor), _) if minor > 0 => Version::new(0, minor + 1, 0),
+				(_, Some(_), None) => Version::new(0, 1, 0),
+				(_, Some(_), Some(patch)) => Version::new(0, 0, patch + 1),
+			};
+			vec![
+				Comparator::new(Operator::GreaterOrEqual, floor),
+				Comparator::new(Operator::Less, ceiling.lowest_prerelease()),
+			]
+		}
+		"~" => {
+			// Allows patch-level changes, or minor-level changes if only the major version is given
+			let ceiling = match (partial.major.unwrap_or(0), partial.minor) {
+				(major, None) => Version::new(major + 1, 0, 0),
+				(major, Some(minor)) => Version::new(major, minor + 1, 0),
+			};
+			vec![
+				Comparator::new(Operator::GreaterOrEqual, floor),
+				Comparator::new(Operator::Less, ceiling.lowest_prerelease()),
+			]
+		}
+		">" => match (partial.major, ceiling) {
+			// Nothing is greater than any version
+			(None, _) => vec![Comparator::new(Operator::Less, Version::new(0, 0, 0))],
+			// `>1.2` is `>=1.3.0`, which excludes the prereleases of 1.3.0
+			(_, Some(ceiling)) => vec![Comparator::new(
+				Operator::GreaterOrEqual,
+				Version::new(ceiling.major, ceiling.minor, ceiling.patch),
+			)],
+			(_, None) => vec![Comparator::new(Operator::Greater, floor)],
+		},
+		">=" => vec![Comparator::new(Operator::GreaterOrEqual, floor)],
+		"<" => vec![Comparator::new(
+			Operator::Less,
+			// `<1.2` excludes the prereleases of 1.2.0 too
+			if partial.patch.is_none() {
+				floor.lowest_prerelease()
+			} else {
+				floor
+			},
+		)],
+		"<=" => match (partial.major, ceiling) {
+			(None, _) => any(),
+			(_, Some(ceiling)) => vec![Comparator::new(Operator::Less, ceiling)],
+			(_, None) => vec![Comparator::new(Operator::LessOrEqual, floor)],
+		},
+		_ => unreachable!("unexpected operator {operator}"),
+	};
+	Some(comparators)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn matches(range: &str, version: &str) -> bool {
+		VersionRange::parse(range)
+			.expect("invalid range")
+			.matches(&Version::parse(version).expect("invalid version"))
+	}
+
+	#[test]
+	fn test_parse_version() {
+		let version = Version::parse("v1.2.3-beta.10+build.5").unwrap();
+		assert_eq!((version.major, version.minor, version.patch), (1, 2, 3));
+		assert_eq!(
+			version.prerelease,
+			[Identifier::AlphaNumeric("beta".to_string()), Identifier::Numeric(10)]
+		);
+		assert_eq!(version.to_string(), "1.2.3-beta.10");
+
+		assert!(Version::parse("1.2").is_none());
+		assert!(Version::parse("01.2.3").is_none());
+		assert!(Version::parse("1.2.3.4").is_none());
+		assert!(Version::parse("1.2.3-").is_none());
+	}
+
+	#[test]
+	fn test_compare_versions() {
+		let ordered = [
+			"0.9.9",
+			"1.0.0-0",
+			"1.0.0-alpha",
+			"1.0.0-alpha.1",
+			"1.0.0-alpha.beta",
+			"1.0.0-beta.2",
+			"1.0.0-beta.11",
+			"1.0.0",
+			"1.0.1",
+			"1.10.0",
+			"2.0.0",
+		];
+		for pair in ordered.windows(2) {
+			assert!(
+				Version::parse(pair[0]).unwrap() < Version::parse(pair[1]).unwrap(),
+				"{} < {}",
+				pair[0],
+				pair[1]
+			);
+		}
+	}
+
+	#[test]
+	fn test_match_ranges() {
+		for (range, version) in [
+			("1.2.3", "1.2.3"),
+			("=v1.2.3", "1.2.3"),
+			("", "4.0.0"),
+			("*", "1.2.3"),
+			("1.x", "1.9.0"),
+			("1.2", "1.2.9"),
+			("^1.2.3", "1.9.9"),
+			("^0.2.3", "0.2.9"),
+			("^0.0.3", "0.0.3"),
+			("^1.x", "1.0.0"),
+			("^*", "3.0.0"),
+			("~1.2.3", "1.2.9"),
+			("~1", "1.9.0"),
+			("~>1.2", "1.2.0"),
+			(">=1.2.3", "2.0.0"),
+			(">= 1.2.3 < 2", "1.9.9"),
+			(">1.2", "1.3.0"),
+			("<=1.2", "1.2.9"),
+			("1.2.3 - 2.3", "2.3.9"),
+			("1.x || >=2.5.0", "2.5.1"),
+			("^1.2.3-beta.2", "1.2.3-beta.4"),
+			("^10.0.0", "10.3.0"),
+		] {
+			assert!(matches(range, version), "{version} should match {range}");
+		}
+	}
+
+	#[test]
+	fn test_mismatch_ranges() {
+		for (range, version) in [
+			("1.2.3", "1.2.4"),
+			("1.x", "2.0.0"),
+			("^1.2.3", "2.0.0"),
+			("^1.2.3", "2.0.0-alpha"),
+			("^1.2.3", "1.3.0-beta"),
+			("^0.2.3", "0.3.0"),
+			("^0.0.3", "0.0.4"),
+			("~1.2.3", "1.3.0"),
+			(">1.2", "1.2.9"),
+			("<1.2", "1.2.0-beta"),
+			("<=1.2.3", "1.2.4"),
+			("1.2.3 - 2.3.4", "2.3.5"),
+			("1.x || >=2.5.0", "2.4.0"),
+			("^1.2.3-beta.2", "1.2.3-beta.1"),
+			("^10.0.0", "9.3.0"),
+		] {
+			assert!(!matches(range, version), "{version} shouldn't match {range}");
+		}
+	}
+
+	/// Cases from node-semver's `range-include` and `range-exclude` fixtures (without the loose and
+	/// `includePrerelease` options)
+	#[test]
+	fn test_npm_fixtures() {
+		for (range, version) in [
+			("1.0.0 - 2.0.0", "1.2.3"),
+			("1.2.3-pre+asdf - 2.4.3-pre+asdf", "1.2.3"),
+			("1.2.3-pre+asdf - 2.4.3-pre+asdf", "1.2.3-pre.2"),
+			("1.2.3-pre+asdf - 2.4.3-pre+asdf", "2.4.3-alpha"),
+			("1.2.3+asdf - 2.4.3+asdf", "1.2.3"),
+			("x - 1.0.0", "0.9.7"),
+			("x - 1.x", "0.9.7"),
+			("1.0.0 - x", "1.9.7"),
+			("1.x - x", "1.9.7"),
+			("^1.2.3+build", "1.2.3"),
+			(">=*", "0.2.4"),
+			("0.1.20 || 1.2.4", "1.2.4"),
+			(">=0.2.3 || <0.0.1", "0.0.0"),
+			(">=0.2.3 || <0.0.1", "0.2.3"),
+			(">=0.2.3 || <0.0.1", "0.2.4"),
+			("||", "1.3.4"),
+			("2.x.x", "2.1.3"),
+			("1.2.x || 2.x", "2.1.3"),
+			("1.2.x || 2.x", "1.2.3"),
+			("~1.2.1 >=1.2.3", "1.2.3"),
+			("~1.2.1 =1.2.3", "1.2.3"),
+			(">=1.2.1 1.2.3", "1.2.3"),
+			("^1.2.3-alpha", "1.2.3-pre"),
+			("^1.2.0-alpha", "1.2.0-pre"),
+			("^0.0.1-alpha", "0.0.1-beta"),
+			("^0.1.1-alpha", "0.1.1-beta"),
+			("~1.2.3-beta.2", "1.2.3-beta.4"),
+			("^x", "1.2.3"),
+			("<=7.x", "7.9.9"),
+		] {
+			assert!(matches(range, version), "{version} should match {range}");
+		}
+
+		for (range, version) in [
+			("1.0.0 - 2.0.0", "2.2.3"),
+			("1.2.3+asdf - 2.4.3+asdf", "1.2.3-pre.2"),
+			("1.2.3+asdf - 2.4.3+asdf", "2.4.3-alpha"),
+			("1 - 2", "2.0.0-pre"),
+			("1 - 2", "3.0.0-pre"),
+			("^1.2.3+build", "2.0.0"),
+			("^1.2.3+build", "1.2.0"),
+			("^1.2.3", "1.2.3-pre"),
+			("^1.2", "1.2.0-pre"),
+			(">1.2", "1.3.0-beta"),
+			("<=1.2.3", "1.2.3-beta"),
+			("=0.7.x", "0.7.0-asdf"),
+			(">=0.7.x", "0.7.0-asdf"),
+			("<=0.7.x", "0.7.0-asdf"),
+			("~1.2.3-beta.2", "1.2.4-beta.2"),
+			("^1.0.0", "2.0.0-rc1"),
+			("0.1.20 || 1.2.4", "1.2.3"),
+			(">=0.2.3 || <0.0.1", "0.0.3"),
+			(">=0.2.3 || <0.0.1", "0.2.2"),
+			("2.x.x", "1.1.3"),
+			("2.x.x", "3.1.3"),
+			("1.2.x || 2.x", "3.1.3"),
+			("1.2.x || 2.x", "1.1.3"),
+		] {
+			assert!(!matches(range, version), "{version} shouldn't match {range}");
+		}
+	}
+
+	#[test]
+	fn test_parse_non_ranges() {
+		for specifier in [
+			"latest",
+			"workspace:^",
+			"file:../lib",
+			"github:user/repo",
+			"1.2.3.4",
+			">=x.1",
+		] {
+			assert!(VersionRange::parse(specifier).is_none(), "{specifier} isn't a range");
+		}
+	}
+}
@@ -210,6 +210,124 @@ mod tests {
 		let i_construct = type_system.find_class(&FQN::from("constructs.IConstruct"));
 		assert_eq!(i_construct, None);
 	}
+
+	#[test]
+	fn loads_dependencies_matching_their_ranges() {
+		let root = create_temp_node_modules();
+		create_temp_module(&root, "jsii-test-dep", "1.2.4", json!({}));
+		create_temp_module(&root, "jsii-test-lib", "1.0.0", json!({ "jsii-test-dep": "^1.2.0" }));
+
+		let mut type_system = TypeSystem::new();
+		let name = type_system
+			.load_module(&root.join("node_modules/jsii-test-lib"))
+			.unwrap();
+		assert_eq!(name, "jsii-test-lib");
+		assert_eq!(type_system.find_assembly("jsii-test-dep").unwrap().version, "1.2.4");
+		fs::remove_dir_all(root).unwrap();
+	}
+
+	#[test]
+	fn reports_dependency_version_conflicts() {
+		let root = create_temp_node_modules();
+		create_temp_module(&root, "jsii-test-dep", "1.2.4", json!({}));
+		create_temp_module(&root, "jsii-test-lib", "1.0.0", json!({ "jsii-test-dep": "^2.0.0" }));
+
+		let mut type_system = TypeSystem::new();
+		type_system
+			.load_module(&root.join("node_modules/jsii-test-dep"))
+			.unwrap();
+		let error = type_system
+			.load_module(&root.join("node_modules/jsii-test-lib"))
+			.unwrap_err()
+			.to_string();
+		assert!(error.starts_with(
+			"Conflicting versions of \"jsii-test-dep\": \"jsii-test-lib\" requires \"^2.0.0\" but version 1.2.4 was already loaded"
+		));
+		// The module isn't kept, so loading it again fails too
+		assert!(type_system.find_assembly("jsii-test-lib").is_none());
+		assert!(type_system
+			.load_module(&root.join("node_modules/jsii-test-lib"))
+			.is_err());
+		fs::remove_dir_all(root).unwrap();
+	}
+
+	#[test]
+	fn warns_about_unchecked_dependency_ranges() {
+		let root = create_temp_node_modules();
+		create_temp_module(&root, "jsii-test-dep", "1.2.4", json!({}));
+		create_temp_module(&root, "jsii-test-lib", "1.0.0", json!({ "jsii-test-dep": "latest" }));
+
+		let mut type_system = TypeSystem::new();
+		type_system
+			.load_module(&root.join("node_modules/jsii-test-lib"))
+			.unwrap();
+		assert_eq!(
+			type_system.take_warnings(),
+			["Cannot check that version 1.2.4 of \"jsii-test-dep\" matches \"latest\" (required by \"jsii-test-lib\"), assuming it does"]
+		);
+		assert!(type_system.take_warnings().is_empty());
+		fs::remove_dir_all(root).unwrap();
+	}
+
+	#[test]
+	fn reports_missing_dependencies() {
+		let root = create_temp_node_modules();
+		create_temp_module(
+			&root,
+			"jsii-test-dep",
+			"1.2.4",
+			json!({ "jsii-test-missing": "^1.0.0" }),
+		);
+		create_temp_module(&root, "jsii-test-lib", "1.0.0", json!({ "jsii-test-dep": "^1.0.0" }));
+
+		let mut type_system = TypeSystem::new();
+		let error = type_system
+			.load_module(&root.join("node_modules/jsii-test-lib"))
+			.unwrap_err()
+			.to_string();
+		assert!(error.starts_with(
+			"Unable to load \"jsii-test-dep\" (required by \"jsii-test-lib\"): Unable to load \"jsii-test-missing\""
+		));
+		assert!(type_system.find_assembly("jsii-test-lib").is_none());
+		assert!(type_system.find_assembly("jsii-test-dep").is_none());
+		fs::remove_dir_all(root).unwrap();
+	}
+}
+
+fn create_temp_node_modules() -> Utf8PathBuf {
+	let temp_dir = Utf8PathBuf::from_path_buf(env::temp_dir()).expect("invalid unicode path");
+	let root = temp_dir.join(format!("modules-{}", rand::thread_rng().gen::<u32>()));
+	fs::create_dir_all(root.join("node_modules")).unwrap();
+	root
+}
+
+/// Creates a JSII module in the `node_modules` directory of `root`
+fn create_temp_module(root: &Utf8PathBuf, name: &str, version: &str, dependencies: serde_json::Value) {
+	let module_dir = root.join("node_modules").join(name);
+	fs::create_dir_all(&module_dir).unwrap();
+	let package_json = json!({
+		"name": name,
+		"version": version,
+		"main": "index.js",
+		"jsii": {},
+		"dependencies": dependencies,
+	});
+	fs::write(module_dir.join("package.json"), package_json.to_string()).unwrap();
+	fs::write(module_dir.join("index.js"), "").unwrap();
+	let assembly = json!({
+		"schema": "jsii/0.10.0",
+		"name": name,
+		"version": version,
+		"license": "Apache-2.0",
+		"description": "A test assembly",
+		"homepage": "https://github.com/aws/jsii",
+		"repository": { "type": "git", "url": "git://github.com/aws/jsii.git" },
+		"author": { "name": "Amazon Web Services", "roles": ["author"] },
+		"fingerprint": "F1NG3RPR1N7",
+		"dependencies": dependencies,
+		"jsiiVersion": "1.0.0",
+	});
+	fs::write(module_dir.join(spec::SPEC_FILE_NAME), assembly.to_string()).unwrap();
 }
 
 fn create_temp_assembly() -> (String, Utf8PathBuf) {
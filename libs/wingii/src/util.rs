@@ -8,6 +8,8 @@ pub mod package_json {
 
 	use crate::node_resolve::{is_path_dependency, resolve_from};
 
+	const NODE_MODULES: &str = "node_modules";
+
 	pub fn dependencies_of(package_json: &serde_json::Value) -> HashSet<String> {
 		// merge both dependencies and peerDependencies and return the list of keys
 		let mut deps = HashSet::new();
@@ -20,6 +22,13 @@ pub mod package_json {
 		deps
 	}
 
+	/// The version range of a dependency (or peer dependency) of a package, like `^1.2.0`
+	pub fn dependency_range_of<'a>(package_json: &'a serde_json::Value, dependency_name: &str) -> Option<&'a str> {
+		["dependencies", "peerDependencies"]
+			.iter()
+			.find_map(|field| package_json.get(field)?.get(dependency_name)?.as_str())
+	}
+
 	pub fn bundled_dependencies_of(package_json: &serde_json::Value) -> Vec<String> {
 		// merge both bundledDependencies and bundleDependencies and return the list of keys
 		let mut deps = Vec::new();
@@ -62,7 +71,21 @@ pub mod package_json {
 		})
 	}
 
+	/// Finds the directory of a dependency. Packages are looked up in the `node_modules` directories of
+	/// `search_start` and its parents, without resolving their entrypoint (which `require()` can't resolve for
+	/// ESM-only packages or packages whose "exports" don't include "."), while path dependencies are resolved.
 	pub fn find_dependency_directory(dependency_name: &str, search_start: &Utf8Path) -> Option<Utf8PathBuf> {
+		if !is_path_dependency(dependency_name) {
+			let node_modules = find_up(search_start.to_path_buf(), |dir| {
+				dir
+					.join(NODE_MODULES)
+					.join(dependency_name)
+					.join("package.json")
+					.is_file()
+			})?;
+			return Some(node_modules.join(NODE_MODULES).join(dependency_name));
+		}
+
 		let entrypoint = resolve_from(dependency_name, search_start);
 		let entrypoint = entrypoint.ok()?;
 		let dep_pkg_json_path = find_package_json_up(dependency_name, entrypoint);
@@ -78,6 +101,7 @@ pub mod package_json {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use camino::Utf8PathBuf;
 	use serde_json::json;
 	#[test]
 	fn test_dependencies_of() {
@@ -99,6 +123,40 @@ mod tests {
 		assert!(dependencies.contains("regex"));
 	}
 
+	#[test]
+	fn test_dependency_range_of() {
+		let package_json = json!({
+			"dependencies": {
+				"serde": "^1.0"
+			},
+			"peerDependencies": {
+				"serde": "^2.0",
+				"regex": "~1.2"
+			}
+		});
+
+		assert_eq!(package_json::dependency_range_of(&package_json, "serde"), Some("^1.0"));
+		assert_eq!(package_json::dependency_range_of(&package_json, "regex"), Some("~1.2"));
+		assert_eq!(package_json::dependency_range_of(&package_json, "uuid"), None);
+	}
+
+	#[test]
+	fn test_find_dependency_directory() {
+		let fixture_dir = Utf8PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("src")
+			.join("fixtures")
+			.join("node_resolve");
+		let search_start = fixture_dir.join("src").join("internal");
+		for name in ["classic", "dual", "@scope/patterns", "esm-only", "subpaths-only"] {
+			assert_eq!(
+				package_json::find_dependency_directory(name, &search_start),
+				Some(fixture_dir.join("node_modules").join(name)),
+				"{name} should be found"
+			);
+		}
+		assert_eq!(package_json::find_dependency_directory("missing", &search_start), None);
+	}
+
 	#[test]
 	fn test_bundled_dependencies_of() {
 		let package_json = json!({
@@ -205,6 +205,86 @@ export function bringJs(
   );
 }
 
+/**
+ * Converts a Wing `datetime` passed to JSII code into the JS `Date` JSII expects. Arrays and maps are
+ * converted item by item. Other values (like `nil`, or a `Date` already) are returned as is.
+ */
+export function toJsiiDate(value: any): any {
+  if (Array.isArray(value)) {
+    return value.map(toJsiiDate);
+  }
+  if (isPlainObject(value)) {
+    return mapValues(value, toJsiiDate);
+  }
+  if (value instanceof Date || value?.timestampMs === undefined) {
+    return value;
+  }
+  return new Date(value.timestampMs);
+}
+
+/**
+ * Converts a JS `Date` returned from JSII code into a Wing `datetime`. Arrays and maps are converted
+ * item by item. Other values (like `undefined`) are returned as is.
+ */
+export function fromJsiiDate(value: any): any {
+  if (Array.isArray(value)) {
+    return value.map(fromJsiiDate);
+  }
+  if (isPlainObject(value)) {
+    return mapValues(value, fromJsiiDate);
+  }
+  if (!(value instanceof Date)) {
+    return value;
+  }
+  /* eslint-disable @typescript-eslint/no-require-imports */
+  const { Datetime } = require("./std/datetime");
+  return Datetime.fromDate(value);
+}
+
+/**
+ * Wraps a Wing function passed to JSII code as a callback, so the JS `Date`s JSII calls it with are
+ * converted to `datetime`s, and the `datetime` it returns is converted to a `Date`.
+ * @param fn - the Wing function. Values that aren't functions are returned as is.
+ * @param dateParams - the indices of the parameters to convert.
+ * @param dateReturn - whether to convert the return value.
+ */
+export function toJsiiCallback(
+  fn: any,
+  dateParams: number[],
+  dateReturn: boolean
+): any {
+  if (typeof fn !== "function") {
+    return fn;
+  }
+  return (...args: any[]) => {
+    const result = fn(
+      ...args.map((arg, i) =>
+        dateParams.includes(i) ? fromJsiiDate(arg) : arg
+      )
+    );
+    if (!dateReturn) {
+      return result;
+    }
+    return result instanceof Promise
+      ? result.then(toJsiiDate)
+      : toJsiiDate(result);
+  };
+}
+
+function isPlainObject(value: any): boolean {
+  return (
+    typeof value === "object" &&
+    value !== null &&
+    Object.getPrototypeOf(value) === Object.prototype
+  );
+}
+
+function mapValues(obj: any, fn: (value: any) => any): any {
+  return Object.fromEntries(
+    Object.entries(obj).map(([key, value]) => [key, fn(value)])
+  );
+}
+
 /**
  * Helper function to get a singleton instance of a class defined in preflight.
  * In practice this is used to get the preflight instance of **inflight** classes defined **preflight**.